@@ -1,32 +1,41 @@
 //! CLI command implementations
 
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use dprojc_core::CatalogWatcher;
 use dprojc_docs::{DocsConfig, DocumentationGenerator};
-use dprojc_scanner::ProjectScanner;
-use dprojc_types::{ProjectType, ReportData, StatsData};
+use dprojc_scanner::{CancellationToken, ProjectScanner, ScanControl};
+use dprojc_types::{
+    AutomationFilter, AutomationKind, CatalogChange, DependencyEcosystem, Project, ProjectType,
+    ReportData, ScanConfig, ScanProgress, Staleness, StatsData, ToolchainPin,
+};
+use dprojc_utils::{
+    collapse_workspace_members, find_artifacts, format_file_size, group_workspace_members,
+    read_lockfiles, remove_artifact, AdvisoryDatabase,
+};
 use fuzzy_matcher::FuzzyMatcher;
 use indicatif::{ProgressBar, ProgressStyle};
 
 use crate::output::{OutputFormat, OutputFormatter};
-use crate::CliRunner;
+use crate::{CliRunner, DiskUsageSort};
 
 impl CliRunner {
     /// Run the scan command
     pub async fn run_scan(
         &mut self,
         paths: &[PathBuf],
-        max_depth: Option<usize>,
+        overrides: &ScanOverrides,
         format: &OutputFormat,
         no_save: bool,
+        incremental: bool,
     ) -> anyhow::Result<()> {
-        let mut config = self.config.clone();
-        if let Some(depth) = max_depth {
-            config.max_depth = Some(depth);
-        }
-
-        let scanner = ProjectScanner::with_config(config)?;
+        let scanner = ProjectScanner::with_config(overrides.apply(&self.config))?;
         let mut all_results = Vec::new();
+        let mut unchanged_dirs = 0;
+        let saved_at = chrono::Utc::now();
 
         // Create progress bar
         let progress = ProgressBar::new(paths.len() as u64);
@@ -37,14 +46,60 @@ impl CliRunner {
                 .progress_chars("#>-"),
         );
 
+        // Ctrl-C during the walk stops it and keeps what was found; any
+        // other Ctrl-C exits as usual
+        let cancellation = CancellationToken::new();
+        let scanning = Arc::new(AtomicBool::new(true));
+        tokio::spawn({
+            let cancellation = cancellation.clone();
+            let scanning = Arc::clone(&scanning);
+            async move {
+                while tokio::signal::ctrl_c().await.is_ok() {
+                    if cancellation.is_cancelled() || !scanning.load(Ordering::Relaxed) {
+                        std::process::exit(130);
+                    }
+                    cancellation.cancel();
+                }
+            }
+        });
+
         for path in paths {
+            if cancellation.is_cancelled() {
+                break;
+            }
             progress.set_message(format!("Scanning {}", path.display()));
-            let result = scanner.scan(path).await?;
-            all_results.push(result);
+            let control = ScanControl::new()
+                .with_cancellation(cancellation.clone())
+                .with_progress({
+                    let progress = progress.clone();
+                    // Reported paths are absolute, whatever the root looked like
+                    let root = std::env::current_dir()
+                        .map(|cwd| cwd.join(path))
+                        .unwrap_or_else(|_| path.clone());
+                    move |walked: &ScanProgress| {
+                        progress.set_message(format_scan_progress(&root, walked))
+                    }
+                });
+            if incremental {
+                // Rescans are stored as they complete, against each root's cache
+                let rescan =
+                    dprojc_core::rescan_and_store(&scanner, &mut self.database, path, &control)
+                        .await?;
+                unchanged_dirs += rescan.unchanged_dirs;
+                all_results.push(rescan.scan);
+            } else {
+                all_results.push(scanner.scan_with_control(path, &control).await?);
+            }
             progress.inc(1);
         }
 
-        progress.finish_with_message("Scan complete");
+        scanning.store(false, Ordering::Relaxed);
+        let cancelled = cancellation.is_cancelled();
+        if cancelled {
+            progress.abandon_with_message("Scan cancelled");
+        } else {
+            progress.finish_with_message("Scan complete");
+        }
 
         // Collect all projects
         let mut all_projects = Vec::new();
@@ -58,7 +113,7 @@ impl CliRunner {
         }
 
         // Save to database if requested
-        if !no_save {
+        if !no_save && !incremental {
             let save_progress = ProgressBar::new(all_projects.len() as u64);
             save_progress.set_style(
                 ProgressStyle::default_bar()
@@ -74,12 +129,40 @@ impl CliRunner {
 
             save_progress.finish_with_message("Projects saved to database");
 
-            // Store scan results
-            for result in &all_results {
+            // Keep the stored type registry in step with the configuration
+            self.database
+                .replace_project_types(&self.config.project_types)?;
+
+            // Store scan results; partial ones would read as removals in
+            // the scan history
+            for result in all_results.iter().filter(|result| !result.cancelled) {
                 self.database.store_scan_result(result)?;
             }
         }
 
+        if cancelled {
+            if no_save {
+                eprintln!("Scan interrupted");
+            } else {
+                eprintln!(
+                    "Scan interrupted; saved the {} projects found so far",
+                    all_projects.len()
+                );
+            }
+        }
+
+        if !no_save {
+            for alias in self.database.get_project_aliases()? {
+                if alias.moved_at >= saved_at {
+                    eprintln!(
+                        "Moved {} -> {}",
+                        dprojc_utils::format_path_display(&alias.path),
+                        dprojc_utils::format_path_display(&alias.project_path)
+                    );
+                }
+            }
+        }
+
         // Output results
         let formatter = OutputFormatter::new(format.clone());
 
@@ -90,6 +173,12 @@ impl CliRunner {
                 all_projects.len(),
                 total_errors
             );
+            if incremental {
+                println!(
+                    "{} directories unchanged since the last scan",
+                    unchanged_dirs
+                );
+            }
         }
 
         formatter.format_scan_results(&all_results)?;
@@ -97,6 +186,53 @@ impl CliRunner {
         Ok(())
     }
 
+    /// Run the watch command
+    pub async fn run_watch(&mut self, paths: &[PathBuf]) -> anyhow::Result<()> {
+        let roots = if paths.is_empty() {
+            self.config
+                .watch_roots
+                .iter()
+                .map(|root| dprojc_utils::expand_tilde(&root.to_string_lossy()))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        } else {
+            paths.to_vec()
+        };
+        if roots.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No roots to watch; pass paths or set `watch_roots` in the configuration"
+            ));
+        }
+
+        let verbose = self.verbose > 0;
+        let (mut watcher, changes) =
+            CatalogWatcher::start(self.config.clone(), &roots, &mut self.database).await?;
+        for change in &changes {
+            print_catalog_change(change, verbose);
+        }
+        if !watcher.watched_roots().is_empty() {
+            println!(
+                "Watching {} directories under {} root(s)",
+                watcher.watched_dirs(),
+                watcher.watched_roots().len()
+            );
+        }
+        for root in watcher.polled_roots() {
+            println!(
+                "Rescanning {} every {} minutes instead of watching it",
+                dprojc_utils::format_path_display(root),
+                self.config.rescan_interval_minutes
+            );
+        }
+
+        tokio::select! {
+            result = watcher.run(&mut self.database, |change| print_catalog_change(change, verbose)) => result,
+            _ = tokio::signal::ctrl_c() => {
+                println!("Stopped watching");
+                Ok(())
+            }
+        }
+    }
+
     /// Run the list command
     pub async fn run_list(
         &self,
@@ -104,20 +240,11 @@ impl CliRunner {
         search: Option<&str>,
         format: &OutputFormat,
         limit: Option<usize>,
+        collapse_workspaces: bool,
+        filter: &ProjectFilter,
     ) -> anyhow::Result<()> {
         let mut projects = if let Some(pt_str) = project_type {
-            let project_type = match pt_str.to_lowercase().as_str() {
-                "rust" => ProjectType::Rust,
-                "nodejs" | "node" => ProjectType::NodeJs,
-                "ruby" => ProjectType::Ruby,
-                "python" => ProjectType::Python,
-                "go" => ProjectType::Go,
-                "java" => ProjectType::Java,
-                "git" => ProjectType::Git,
-                "nix" => ProjectType::Nix,
-                "unknown" => ProjectType::Unknown,
-                _ => return Err(anyhow::anyhow!("Unknown project type: {}", pt_str)),
-            };
+            let project_type = self.resolve_project_type(pt_str)?;
             self.database.get_projects_by_type(&project_type)?
         } else {
             self.database.get_all_projects()?
@@ -131,15 +258,30 @@ impl CliRunner {
                 let type_str = format!("{:?}", project.project_type);
                 matcher.fuzzy_match(&path_str, query).is_some()
                     || matcher.fuzzy_match(&type_str, query).is_some()
+                    || project
+                        .manifest
+                        .as_ref()
+                        .is_some_and(|manifest| manifest.matches(query))
             });
         }
 
+        projects.retain(|project| filter.matches(project));
+
+        // Show workspace members beneath their workspace, or fold them into it
+        let collapsed_members = if collapse_workspaces {
+            collapse_workspace_members(&mut projects)
+        } else {
+            group_workspace_members(&mut projects);
+            HashMap::new()
+        };
+
         // Apply limit
         if let Some(limit) = limit {
             projects.truncate(limit);
         }
 
-        let formatter = OutputFormatter::new(format.clone());
+        let formatter =
+            OutputFormatter::new(format.clone()).with_collapsed_members(collapsed_members);
         formatter.format_projects(&projects)?;
 
         Ok(())
@@ -159,7 +301,12 @@ impl CliRunner {
             .filter(|project| {
                 let path_str = project.path.to_string_lossy();
                 let type_str = format!("{:?}", project.project_type);
-                path_str.contains(query) || type_str.contains(query)
+                path_str.contains(query)
+                    || type_str.contains(query)
+                    || project
+                        .manifest
+                        .as_ref()
+                        .is_some_and(|manifest| manifest.matches(query))
             })
             .collect();
 
@@ -239,13 +386,24 @@ impl CliRunner {
     }
 
     /// Run the stats command
-    pub async fn run_stats(&self, format: &OutputFormat) -> anyhow::Result<()> {
+    pub async fn run_stats(
+        &self,
+        format: &OutputFormat,
+        toolchain: Option<&str>,
+    ) -> anyhow::Result<()> {
         let stats = self.database.get_scan_statistics()?;
         let counts = self.database.get_project_counts_by_type()?;
+        let ecosystem_counts = self.database.get_project_counts_by_ecosystem()?;
+        let tool = toolchain.map(ToolchainPin::canonical_tool);
+        let toolchain_counts = self.database.get_toolchain_counts(tool.as_deref())?;
+        let language_counts = self.database.get_language_counts()?;
 
         let stats_data = StatsData {
             statistics: stats,
             project_counts: counts,
+            ecosystem_counts,
+            toolchain_counts,
+            language_counts,
         };
 
         let formatter = OutputFormatter::new(format.clone());
@@ -254,17 +412,392 @@ impl CliRunner {
         Ok(())
     }
 
+    /// Run the deps command
+    pub async fn run_deps(
+        &self,
+        package: Option<&str>,
+        ecosystem: Option<&str>,
+        version: Option<&str>,
+        spread: bool,
+        project: Option<&PathBuf>,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let ecosystem = ecosystem
+            .map(|name| {
+                DependencyEcosystem::from_name(name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown ecosystem: {}", name))
+            })
+            .transpose()?;
+        let formatter = OutputFormatter::new(format.clone());
+
+        if let Some(path) = project {
+            let path = dprojc_utils::normalize_path(path)?;
+            let project = self
+                .database
+                .get_project_by_path(&path)?
+                .ok_or_else(|| anyhow::anyhow!("Project not in catalog: {}", path.display()))?;
+            let dependencies: Vec<_> = project
+                .dependencies
+                .into_iter()
+                .filter(|d| ecosystem.is_none_or(|e| d.ecosystem == e))
+                .collect();
+            return formatter.format_dependencies(&dependencies);
+        }
+
+        if spread {
+            // A single package is shown even when every project agrees on a version
+            let min_versions = if package.is_some() { 1 } else { 2 };
+            let spread = self
+                .database
+                .get_version_spread(ecosystem, package, min_versions)?;
+            return formatter.format_version_spread(&spread);
+        }
+
+        let package = package.ok_or_else(|| anyhow::anyhow!("No package given"))?;
+        let dependents = self.database.get_dependents(package, ecosystem, version)?;
+        formatter.format_dependents(&dependents)
+    }
+
+    /// Run the du command
+    pub async fn run_du(
+        &self,
+        sort: DiskUsageSort,
+        limit: Option<usize>,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let mut projects: Vec<_> = self
+            .database
+            .get_all_projects()?
+            .into_iter()
+            .filter(|project| project.disk_usage.is_some())
+            .collect();
+        projects.sort_by_key(|project| {
+            let bytes = project
+                .disk_usage
+                .as_ref()
+                .map_or(0, |usage| sort.bytes(usage));
+            (std::cmp::Reverse(bytes), project.path.clone())
+        });
+        if let Some(limit) = limit {
+            projects.truncate(limit);
+        }
+
+        OutputFormatter::new(format.clone()).format_disk_usage(&projects)
+    }
+
+    /// Run the stale command
+    pub async fn run_stale(
+        &self,
+        all: bool,
+        abandoned: bool,
+        stale_after: Option<u32>,
+        abandoned_after: Option<u32>,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let mut config = self.config.clone();
+        if let Some(days) = stale_after {
+            config.stale_after_days = days;
+        }
+        if let Some(days) = abandoned_after {
+            config.abandoned_after_days = days;
+        }
+        if config.abandoned_after_days < config.stale_after_days {
+            return Err(anyhow::anyhow!(
+                "The abandoned threshold ({} days) must not be below the stale threshold ({} days)",
+                config.abandoned_after_days,
+                config.stale_after_days
+            ));
+        }
+
+        let min = if abandoned {
+            Staleness::Abandoned
+        } else if all {
+            Staleness::Active
+        } else {
+            Staleness::Stale
+        };
+        let mut projects = dprojc_utils::assess_projects(
+            &self.database.get_all_projects()?,
+            &self.database.get_last_accessed()?,
+            &config,
+            chrono::Utc::now(),
+        );
+        projects.retain(|project| project.staleness >= min);
+
+        OutputFormatter::new(format.clone()).format_stale(&projects)
+    }
+
+    /// Run the dups command
+    pub async fn run_dups(&self, format: &OutputFormat) -> anyhow::Result<()> {
+        let groups = dprojc_utils::find_duplicates(
+            &self.database.get_all_projects()?,
+            &self.database.get_frecency_scores()?,
+        );
+        OutputFormatter::new(format.clone()).format_duplicates(&groups)
+    }
+
+    /// Run the diff command
+    pub async fn run_diff(
+        &self,
+        scans: Option<(i64, i64)>,
+        root: Option<&PathBuf>,
+        list: bool,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let formatter = OutputFormatter::new(format.clone());
+        if list {
+            return formatter.format_scan_summaries(&self.database.get_recent_scan_results(20)?);
+        }
+
+        let diff = match scans {
+            Some((from, to)) => self.database.diff_scans(from, to)?.ok_or_else(|| {
+                anyhow::anyhow!("Scan {} or {} not found; see `dpc diff --list`", from, to)
+            })?,
+            None => {
+                // Scan results store the absolute, uncanonicalized root
+                let root = match root {
+                    Some(root) if root.is_relative() => Some(std::env::current_dir()?.join(root)),
+                    root => root.cloned(),
+                };
+                let (from, to) = self
+                    .database
+                    .get_latest_scan_pair(root.as_deref())?
+                    .ok_or_else(|| match &root {
+                        Some(root) => anyhow::anyhow!(
+                            "{} has not been scanned twice yet",
+                            dprojc_utils::format_path_display(root)
+                        ),
+                        None => {
+                            anyhow::anyhow!("The last scanned root has not been scanned twice yet")
+                        }
+                    })?;
+                self.database
+                    .diff_scans(from, to)?
+                    .ok_or_else(|| anyhow::anyhow!("Scan {} or {} not found", from, to))?
+            }
+        };
+        formatter.format_scan_diff(&diff)
+    }
+
+    /// Run the licenses command
+    pub async fn run_licenses(
+        &self,
+        unknown: bool,
+        license: Option<&str>,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let mut projects: Vec<_> = self
+            .database
+            .get_all_projects()?
+            .into_iter()
+            .filter(|project| match (project.license(), license) {
+                (Some(_), _) if unknown => false,
+                (Some(expression), Some(id)) => expression_names(&expression, id),
+                (None, Some(_)) => false,
+                _ => true,
+            })
+            .collect();
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+
+        OutputFormatter::new(format.clone()).format_licenses(&projects)
+    }
+
+    /// Run the clean-artifacts command
+    pub async fn run_clean_artifacts(
+        &self,
+        paths: &[PathBuf],
+        project_type: Option<&str>,
+        max_age_days: u32,
+        dry_run: bool,
+        interactive: bool,
+        to_trash: bool,
+    ) -> anyhow::Result<()> {
+        let mut projects = if let Some(name) = project_type {
+            let project_type = self.resolve_project_type(name)?;
+            self.database.get_projects_by_type(&project_type)?
+        } else {
+            self.database.get_all_projects()?
+        };
+        if !paths.is_empty() {
+            let roots = paths
+                .iter()
+                .map(|path| dprojc_utils::normalize_path(path))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            projects.retain(|project| roots.iter().any(|root| project.path.starts_with(root)));
+        }
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(max_age_days.into());
+        let mut seen = std::collections::HashSet::new();
+        let mut stale = Vec::new();
+        let mut recent = 0;
+        for project in &projects {
+            for artifact in find_artifacts(project) {
+                // Workspace members can point at their root's directories
+                if !seen.insert(artifact.path.clone()) {
+                    continue;
+                }
+                if artifact.is_stale(cutoff) {
+                    stale.push(artifact);
+                } else {
+                    recent += 1;
+                }
+            }
+        }
+        stale.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+
+        if stale.is_empty() {
+            println!(
+                "No build artifacts untouched for {} days ({} recently used kept).",
+                max_age_days, recent
+            );
+            return Ok(());
+        }
+
+        let mut table = comfy_table::Table::new();
+        table.set_header(vec!["Artifact", "Type", "Size", "Last Modified"]);
+        for artifact in &stale {
+            table.add_row(vec![
+                dprojc_utils::format_path_display(&artifact.path),
+                artifact.project_type.to_string(),
+                format_file_size(artifact.bytes),
+                artifact.last_modified.map_or("-".to_string(), |modified| {
+                    modified.format("%Y-%m-%d").to_string()
+                }),
+            ]);
+        }
+        println!("{}", table);
+        let total: u64 = stale.iter().map(|artifact| artifact.bytes).sum();
+
+        if dry_run {
+            println!(
+                "DRY RUN - would free {} from {} directories ({} recently used kept)",
+                format_file_size(total),
+                stale.len(),
+                recent
+            );
+            return Ok(());
+        }
+
+        let mut freed = 0;
+        let mut removed = 0;
+        let mut failed = 0;
+        for artifact in &stale {
+            if interactive
+                && !confirm(&format!(
+                    "Remove {} ({})?",
+                    artifact.path.display(),
+                    format_file_size(artifact.bytes)
+                ))?
+            {
+                continue;
+            }
+            match remove_artifact(&artifact.path, to_trash) {
+                Ok(()) => {
+                    freed += artifact.bytes;
+                    removed += 1;
+                    if self.verbose > 0 {
+                        println!("  ✓ {}", artifact.path.display());
+                    }
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("  ✗ {}", e);
+                }
+            }
+        }
+
+        println!(
+            "{} {} from {} directories ({} recently used kept{})",
+            if to_trash { "Moved to trash" } else { "Freed" },
+            format_file_size(freed),
+            removed,
+            recent,
+            if failed > 0 {
+                format!(", {} failed", failed)
+            } else {
+                String::new()
+            }
+        );
+        Ok(())
+    }
+
+    /// Run the audit command
+    pub async fn run_audit(
+        &self,
+        advisory_db: Option<&PathBuf>,
+        format: &OutputFormat,
+    ) -> anyhow::Result<()> {
+        let path = advisory_db
+            .or(self.config.advisory_db.as_ref())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No advisory database configured; set advisory_db in the config file, \
+                     DURABLE_ADVISORY_DB or pass --advisory-db"
+                )
+            })?;
+        let path = dprojc_utils::expand_tilde(&path.to_string_lossy())?;
+        let database = AdvisoryDatabase::load(&path)?;
+        if self.verbose > 0 {
+            eprintln!(
+                "Loaded {} advisories from {}",
+                database.len(),
+                path.display()
+            );
+        }
+
+        let mut findings = Vec::new();
+        for project in self.database.get_all_projects()? {
+            // Lockfiles are read again so the audit sees what is on disk now,
+            // falling back to the last scan for projects that are out of reach
+            let dependencies = if project.path.is_dir() {
+                read_lockfiles(&project.path)
+            } else {
+                project.dependencies.clone()
+            };
+            findings.extend(database.audit(&project.path, &dependencies));
+        }
+        findings.sort_by(|a, b| {
+            (&a.path, &a.dependency, &a.advisory_id).cmp(&(&b.path, &b.dependency, &b.advisory_id))
+        });
+
+        OutputFormatter::new(format.clone()).format_audit(&findings)
+    }
+
+    /// Resolve a `--project-type` argument against the configured or previously
+    /// stored custom types and the built-in types
+    ///
+    /// A custom type named like a built-in one shadows it, as during scans.
+    fn resolve_project_type(&self, name: &str) -> anyhow::Result<ProjectType> {
+        let stored = self.database.get_project_types()?;
+        self.config
+            .project_types
+            .iter()
+            .chain(stored.iter())
+            .find(|definition| definition.name.eq_ignore_ascii_case(name))
+            .map(|definition| ProjectType::Custom(definition.name.clone()))
+            .or_else(|| ProjectType::from_builtin_name(name))
+            .ok_or_else(|| anyhow::anyhow!("Unknown project type: {}", name))
+    }
+
     /// Run the config command
     pub async fn run_config(&self, format: &OutputFormat) -> anyhow::Result<()> {
         let output = match format {
             OutputFormat::Json => serde_json::to_string_pretty(&self.config)?,
             OutputFormat::Yaml => serde_yaml::to_string(&self.config)?,
             OutputFormat::Table => {
-                format!("Max Depth: {:?}\nExclude Patterns: {:?}\nProject Indicators: {:?}\nFollow Symlinks: {}",
+                format!("Max Depth: {:?}\nExclude Patterns: {:?}\nProject Indicators: {:?}\nFollow Symlinks: {}\nRespect .gitignore: {}\nRespect .dprojcignore: {}\nScan Threads: {}\nGit Metadata: {}\nDisk Usage: {}\nAdvisory Database: {}\nIndicator Rules: {:?}\nProject Types: {:?}",
                        self.config.max_depth,
                        self.config.exclude_patterns,
                        self.config.project_indicators,
-                       self.config.follow_symlinks)
+                       self.config.follow_symlinks,
+                       self.config.respect_gitignore,
+                       self.config.respect_dprojcignore,
+                       self.config.threads,
+                       self.config.git_metadata,
+                       self.config.disk_usage,
+                       self.config.advisory_db.as_ref().map_or("(none)".to_string(), |p| p.display().to_string()),
+                       self.config.indicator_rules.iter().map(|r| &r.name).collect::<Vec<_>>(),
+                       self.config.project_types.iter().map(|t| &t.name).collect::<Vec<_>>())
             }
         };
 
@@ -498,6 +1031,147 @@ impl CliRunner {
     }
 }
 
+/// Filters on what a project pins and has configured, shared by listing commands
+#[derive(Debug, Default)]
+pub struct ProjectFilter {
+    /// A `tool` or `tool@version` the project must pin
+    pub toolchain: Option<String>,
+    /// Automation the project must have, every entry
+    pub with_automation: Vec<AutomationFilter>,
+    /// Automation the project must not have, any entry
+    pub without_automation: Vec<AutomationFilter>,
+}
+
+impl ProjectFilter {
+    /// Build a filter from command-line values, rejecting unknown automation names
+    pub fn new(
+        toolchain: Option<&str>,
+        with_automation: &[String],
+        without_automation: &[String],
+    ) -> anyhow::Result<Self> {
+        let parse = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| {
+                    AutomationFilter::from_name(name).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown automation: {} (expected ci, container, tasks or one of: {})",
+                            name,
+                            AutomationKind::ALL.map(|kind| kind.id()).join(", ")
+                        )
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        Ok(Self {
+            toolchain: toolchain.map(str::to_string),
+            with_automation: parse(with_automation)?,
+            without_automation: parse(without_automation)?,
+        })
+    }
+
+    pub fn matches(&self, project: &Project) -> bool {
+        self.toolchain
+            .as_deref()
+            .is_none_or(|toolchain| project.toolchains.iter().any(|pin| pin.matches(toolchain)))
+            && self
+                .with_automation
+                .iter()
+                .all(|filter| filter.matches(project))
+            && !self
+                .without_automation
+                .iter()
+                .any(|filter| filter.matches(project))
+    }
+}
+
+/// Scan settings given on the command line, on top of the configuration
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScanOverrides {
+    pub max_depth: Option<usize>,
+    pub threads: Option<usize>,
+    /// Measure disk usage even when the configuration leaves it off
+    pub disk_usage: bool,
+    /// Count lines of code even when the configuration leaves it off
+    pub code_stats: bool,
+}
+
+impl ScanOverrides {
+    /// Apply the overrides to a copy of the configuration
+    pub fn apply(&self, config: &ScanConfig) -> ScanConfig {
+        let mut config = config.clone();
+        if let Some(depth) = self.max_depth {
+            config.max_depth = Some(depth);
+        }
+        if let Some(threads) = self.threads {
+            config.threads = threads;
+        }
+        config.disk_usage |= self.disk_usage;
+        config.code_stats |= self.code_stats;
+        config
+    }
+}
+
+/// Whether an SPDX expression is, or contains, the license identifier `id`
+fn expression_names(expression: &str, id: &str) -> bool {
+    expression.eq_ignore_ascii_case(id)
+        || expression
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .any(|part| part.eq_ignore_ascii_case(id))
+}
+
+/// Describe how far the scan of `root` has come, for the progress line
+fn format_scan_progress(root: &std::path::Path, progress: &ScanProgress) -> String {
+    let current = progress
+        .current_path
+        .strip_prefix(root)
+        .unwrap_or(&progress.current_path);
+    format!(
+        "{}: {} dirs, {} projects, {} errors  {}",
+        dprojc_utils::format_path_display(root),
+        progress.dirs_scanned,
+        progress.projects_found,
+        progress.errors,
+        current.display()
+    )
+}
+
+/// Print a change made by `watch`; refreshed projects are only shown when verbose
+fn print_catalog_change(change: &CatalogChange, verbose: bool) {
+    let line = match change {
+        CatalogChange::Added(path) => {
+            format!("Added {}", dprojc_utils::format_path_display(path))
+        }
+        CatalogChange::Updated(path) if verbose => {
+            format!("Updated {}", dprojc_utils::format_path_display(path))
+        }
+        CatalogChange::Updated(_) => return,
+        CatalogChange::Removed(path) => {
+            format!("Removed {}", dprojc_utils::format_path_display(path))
+        }
+        CatalogChange::Rescanned { root, projects } => format!(
+            "Rescanned {}: {} projects",
+            dprojc_utils::format_path_display(root),
+            projects
+        ),
+    };
+    println!("{} {}", chrono::Local::now().format("%H:%M:%S"), line);
+}
+
+/// Ask a yes/no question on the terminal; anything but `y` or `yes` is a no
+fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    use std::io::Write;
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -512,6 +1186,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -528,6 +1203,12 @@ mod tests {
                 (ProjectType::Rust, 3),
                 (ProjectType::NodeJs, 2),
             ]),
+            ecosystem_counts: std::collections::HashMap::from([
+                (ProjectType::Rust, 3),
+                (ProjectType::NodeJs, 3),
+            ]),
+            toolchain_counts: Vec::new(),
+            language_counts: Vec::new(),
         }
     }
 
@@ -574,6 +1255,7 @@ mod tests {
             database: Some(db_path),
             command: Commands::Stats {
                 format: OutputFormat::Table,
+                toolchain: None,
             },
         };
 
@@ -608,18 +1290,21 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: PathBuf::from("/path/to/node/project"),
                 project_type: ProjectType::NodeJs,
                 indicators: vec![ProjectIndicator::PackageJson],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: PathBuf::from("/other/path"),
                 project_type: ProjectType::Git,
                 indicators: vec![ProjectIndicator::GitDirectory],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
         ];
 
@@ -657,6 +1342,66 @@ mod tests {
         assert_eq!(filtered.len(), 0);
     }
 
+    #[test]
+    fn test_project_filter() {
+        use dprojc_types::{AutomationConfig, ToolchainPin};
+
+        let mut project = create_test_project();
+        project.toolchains = vec![ToolchainPin {
+            tool: "node".to_string(),
+            version: "16.20.1".to_string(),
+            source: ".nvmrc".to_string(),
+        }];
+        project.automation = vec![AutomationConfig {
+            kind: AutomationKind::Dockerfile,
+            path: "Dockerfile".to_string(),
+            targets: Vec::new(),
+        }];
+        let filter = |toolchain: Option<&str>, with: &[&str], without: &[&str]| {
+            let strings = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            ProjectFilter::new(toolchain, &strings(with), &strings(without)).unwrap()
+        };
+
+        assert!(ProjectFilter::default().matches(&project));
+        assert!(filter(Some("node@16"), &["docker"], &["ci"]).matches(&project));
+        assert!(!filter(Some("node@18"), &[], &[]).matches(&project));
+        assert!(!filter(None, &["container", "makefile"], &[]).matches(&project));
+        assert!(!filter(None, &[], &["container"]).matches(&project));
+        assert!(ProjectFilter::new(None, &["travis".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_scan_overrides() {
+        let config = ScanConfig {
+            disk_usage: true,
+            ..ScanConfig::default()
+        };
+        let applied = ScanOverrides {
+            max_depth: Some(3),
+            code_stats: true,
+            ..ScanOverrides::default()
+        }
+        .apply(&config);
+        assert_eq!(applied.max_depth, Some(3));
+        assert_eq!(applied.threads, config.threads);
+        // Flags only turn measurements on; configured ones stay on
+        assert!(applied.disk_usage);
+        assert!(applied.code_stats);
+        assert!(!ScanOverrides::default().apply(&config).code_stats);
+    }
+
+    #[test]
+    fn test_expression_names() {
+        assert!(expression_names("MIT", "mit"));
+        assert!(expression_names("MIT OR Apache-2.0", "Apache-2.0"));
+        assert!(expression_names(
+            "(MIT OR Apache-2.0) AND BSD-3-Clause",
+            "MIT"
+        ));
+        assert!(!expression_names("MIT-0", "MIT"));
+        assert!(expression_names("MIT AND ISC", "MIT AND ISC"));
+    }
+
     #[test]
     fn test_limit_application() {
         let projects = vec![
@@ -665,18 +1410,21 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: PathBuf::from("/path/2"),
                 project_type: ProjectType::NodeJs,
                 indicators: vec![ProjectIndicator::PackageJson],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: PathBuf::from("/path/3"),
                 project_type: ProjectType::Python,
                 indicators: vec![ProjectIndicator::PyprojectToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
         ];
 
@@ -14,6 +14,7 @@ use dprojc_types::ScanConfig;
 pub mod commands;
 pub mod output;
 
+pub use commands::{ProjectFilter, ScanOverrides};
 pub use output::OutputFormat;
 
 #[cfg(test)]
@@ -32,6 +33,7 @@ mod tests {
                 database: None,
                 command: Commands::Stats {
                     format: OutputFormat::Table,
+                    toolchain: None,
                 },
             }
         });
@@ -55,6 +57,236 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deps_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "deps", "serde", "--version", "1.0.200"]).unwrap();
+        match cli.command {
+            Commands::Deps {
+                package,
+                package_version,
+                spread,
+                ..
+            } => {
+                assert_eq!(package.as_deref(), Some("serde"));
+                assert_eq!(package_version.as_deref(), Some("1.0.200"));
+                assert!(!spread);
+            }
+            _ => panic!("expected the deps command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "deps", "--spread"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "deps", "--project", "."]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "deps"]).is_err());
+        assert!(Cli::try_parse_from(["dpc", "deps", "serde", "--project", "."]).is_err());
+    }
+
+    #[test]
+    fn test_toolchain_filter_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "list", "--toolchain", "node@16"]).unwrap();
+        match cli.command {
+            Commands::List { toolchain, .. } => assert_eq!(toolchain.as_deref(), Some("node@16")),
+            _ => panic!("expected the list command"),
+        }
+
+        let cli = Cli::try_parse_from(["dpc", "stats", "--toolchain", "python"]).unwrap();
+        match cli.command {
+            Commands::Stats { toolchain, .. } => assert_eq!(toolchain.as_deref(), Some("python")),
+            _ => panic!("expected the stats command"),
+        }
+    }
+
+    #[test]
+    fn test_automation_filter_arguments() {
+        let cli = Cli::try_parse_from([
+            "dpc",
+            "list",
+            "--with",
+            "dockerfile",
+            "--with",
+            "makefile",
+            "--without",
+            "ci",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::List {
+                with_automation,
+                without_automation,
+                ..
+            } => {
+                assert_eq!(with_automation, vec!["dockerfile", "makefile"]);
+                assert_eq!(without_automation, vec!["ci"]);
+            }
+            _ => panic!("expected the list command"),
+        }
+    }
+
+    #[test]
+    fn test_clean_artifacts_arguments() {
+        let cli = Cli::try_parse_from([
+            "dpc",
+            "clean-artifacts",
+            "/src",
+            "--max-age-days",
+            "30",
+            "--trash",
+            "-i",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::CleanArtifacts {
+                paths,
+                max_age_days,
+                interactive,
+                trash,
+                dry_run,
+                ..
+            } => {
+                assert_eq!(paths, vec![PathBuf::from("/src")]);
+                assert_eq!(max_age_days, 30);
+                assert!(interactive && trash && !dry_run);
+            }
+            _ => panic!("expected the clean-artifacts command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "clean-artifacts"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["dpc", "clean-artifacts", "--dry-run", "--interactive"]).is_err()
+        );
+        // A negative age would select every artifact
+        assert!(Cli::try_parse_from(["dpc", "clean-artifacts", "--max-age-days=-1"]).is_err());
+    }
+
+    #[test]
+    fn test_scan_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "scan", "~/src", "--code-stats"]).unwrap();
+        match cli.command {
+            Commands::Scan {
+                code_stats,
+                disk_usage,
+                ..
+            } => {
+                assert!(code_stats);
+                assert!(!disk_usage);
+            }
+            _ => panic!("expected the scan command"),
+        }
+
+        let cli = Cli::try_parse_from(["dpc", "scan", "~/src", "--incremental"]).unwrap();
+        match cli.command {
+            Commands::Scan { incremental, .. } => assert!(incremental),
+            _ => panic!("expected the scan command"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["dpc", "scan", "~/src", "--incremental", "--no-save"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_du_arguments() {
+        let cli =
+            Cli::try_parse_from(["dpc", "du", "--sort", "artifacts", "--limit", "5"]).unwrap();
+        match cli.command {
+            Commands::Du { sort, limit, .. } => {
+                assert_eq!(sort, DiskUsageSort::Artifacts);
+                assert_eq!(limit, Some(5));
+            }
+            _ => panic!("expected the du command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "du", "--sort", "name"]).is_err());
+    }
+
+    #[test]
+    fn test_audit_arguments() {
+        let cli =
+            Cli::try_parse_from(["dpc", "audit", "--advisory-db", "/srv/advisory-db"]).unwrap();
+        match cli.command {
+            Commands::Audit { advisory_db, .. } => {
+                assert_eq!(advisory_db, Some(PathBuf::from("/srv/advisory-db")));
+            }
+            _ => panic!("expected the audit command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "audit", "--format", "json"]).is_ok());
+    }
+
+    #[test]
+    fn test_licenses_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "licenses", "--license", "MIT"]).unwrap();
+        match cli.command {
+            Commands::Licenses {
+                unknown, license, ..
+            } => {
+                assert!(!unknown);
+                assert_eq!(license.as_deref(), Some("MIT"));
+            }
+            _ => panic!("expected the licenses command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "licenses", "--unknown"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "licenses", "--unknown", "--license", "MIT"]).is_err());
+    }
+
+    #[test]
+    fn test_stale_arguments() {
+        let cli =
+            Cli::try_parse_from(["dpc", "stale", "--abandoned", "--stale-after", "30"]).unwrap();
+        match cli.command {
+            Commands::Stale {
+                all,
+                abandoned,
+                stale_after,
+                abandoned_after,
+                ..
+            } => {
+                assert!(!all);
+                assert!(abandoned);
+                assert_eq!(stale_after, Some(30));
+                assert_eq!(abandoned_after, None);
+            }
+            _ => panic!("expected the stale command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "stale", "--all", "--format", "json"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "stale", "--all", "--abandoned"]).is_err());
+        assert!(Cli::try_parse_from(["dpc", "stale", "--stale-after", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_diff_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "diff", "3", "7"]).unwrap();
+        match cli.command {
+            Commands::Diff { from, to, root, .. } => {
+                assert_eq!(from.zip(to), Some((3, 7)));
+                assert_eq!(root, None);
+            }
+            _ => panic!("expected the diff command"),
+        }
+
+        assert!(Cli::try_parse_from(["dpc", "diff"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "diff", "--root", "/src", "--format", "json"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "diff", "--list"]).is_ok());
+        assert!(Cli::try_parse_from(["dpc", "diff", "3"]).is_err());
+        assert!(Cli::try_parse_from(["dpc", "diff", "3", "7", "--root", "/src"]).is_err());
+        assert!(Cli::try_parse_from(["dpc", "diff", "--list", "--root", "/src"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_arguments() {
+        let cli = Cli::try_parse_from(["dpc", "watch", "/src", "/work"]).unwrap();
+        match cli.command {
+            Commands::Watch { paths } => {
+                assert_eq!(paths, vec![PathBuf::from("/src"), PathBuf::from("/work")]);
+            }
+            _ => panic!("expected the watch command"),
+        }
+
+        let cli = Cli::try_parse_from(["dpc", "daemon"]).unwrap();
+        assert!(matches!(cli.command, Commands::Watch { paths } if paths.is_empty()));
+    }
+
     #[test]
     fn test_cli_command_factory() {
         // Test that CLI implements CommandFactory
@@ -97,6 +329,19 @@ pub enum Commands {
         #[arg(long)]
         max_depth: Option<usize>,
 
+        /// Number of walker threads per scan root (0 = one per CPU)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Measure total, source and build artifact size of every project
+        #[arg(long)]
+        disk_usage: bool,
+
+        /// Deep scan: count code, comment and blank lines per language in
+        /// every project
+        #[arg(long)]
+        code_stats: bool,
+
         /// Output format
         #[arg(long, default_value = "table")]
         format: OutputFormat,
@@ -104,6 +349,19 @@ pub enum Commands {
         /// Don't save results to database
         #[arg(long)]
         no_save: bool,
+
+        /// Only list the directories whose modification time changed since
+        /// the last incremental scan of each path
+        #[arg(long, conflicts_with = "no_save")]
+        incremental: bool,
+    },
+
+    /// Keep the catalog current by watching directories for changes until
+    /// interrupted
+    #[command(alias = "daemon")]
+    Watch {
+        /// Roots to watch (defaults to `watch_roots` from the configuration)
+        paths: Vec<PathBuf>,
     },
 
     /// List projects from the catalog
@@ -112,7 +370,7 @@ pub enum Commands {
         #[arg(long)]
         project_type: Option<String>,
 
-        /// Search query (fuzzy match on path)
+        /// Search query (matches path, type, or the package name, description, authors and license)
         #[arg(long)]
         search: Option<String>,
 
@@ -123,6 +381,23 @@ pub enum Commands {
         /// Limit number of results
         #[arg(long)]
         limit: Option<usize>,
+
+        /// Hide workspace members and show a member count on each workspace
+        #[arg(long)]
+        collapse_workspaces: bool,
+
+        /// Only projects pinning a tool, or a version of it (e.g. node, node@16)
+        #[arg(long)]
+        toolchain: Option<String>,
+
+        /// Only projects with this automation: ci, container, tasks, or a kind
+        /// such as github-actions, dockerfile or makefile (repeatable)
+        #[arg(long = "with", value_name = "AUTOMATION")]
+        with_automation: Vec<String>,
+
+        /// Only projects without this automation, e.g. `--without ci` (repeatable)
+        #[arg(long = "without", value_name = "AUTOMATION")]
+        without_automation: Vec<String>,
     },
 
     /// Search projects in the catalog
@@ -139,6 +414,133 @@ pub enum Commands {
         limit: Option<usize>,
     },
 
+    /// Find projects that use a package, or packages resolved to several versions
+    Deps {
+        /// Package name as it appears in lockfiles
+        #[arg(required_unless_present_any = ["spread", "project"])]
+        package: Option<String>,
+
+        /// Restrict to one ecosystem (cargo, npm, pypi, rubygems, go)
+        #[arg(long)]
+        ecosystem: Option<String>,
+
+        /// Only list projects that resolve this exact version
+        #[arg(long = "version", value_name = "VERSION")]
+        package_version: Option<String>,
+
+        /// Show every version in use with its projects; without a package, only
+        /// packages resolved to more than one version are listed
+        #[arg(long)]
+        spread: bool,
+
+        /// List the locked dependencies of one project instead
+        #[arg(long, conflicts_with_all = ["package", "spread"])]
+        project: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Check the locked dependencies of cataloged projects against a local
+    /// advisory database
+    Audit {
+        /// RustSec advisory-db clone or directory of OSV JSON files (overrides
+        /// the `advisory_db` setting)
+        #[arg(long)]
+        advisory_db: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show the disk usage measured for each project, largest first
+    Du {
+        /// Size to order by
+        #[arg(long, value_enum, default_value = "total")]
+        sort: DiskUsageSort,
+
+        /// Maximum number of projects to show
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show the license of each project, from manifests and license files
+    Licenses {
+        /// Only show projects without an identified license
+        #[arg(long)]
+        unknown: bool,
+
+        /// Only show projects whose license expression names this SPDX
+        /// identifier (e.g. MIT)
+        #[arg(long, conflicts_with = "unknown")]
+        license: Option<String>,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show projects nobody has worked on lately, judged by their last
+    /// commit, file modification and visit
+    Stale {
+        /// Include active projects
+        #[arg(long)]
+        all: bool,
+
+        /// Only show abandoned projects
+        #[arg(long, conflicts_with = "all")]
+        abandoned: bool,
+
+        /// Idle days before a project is stale (overrides `stale_after_days`)
+        #[arg(long, value_name = "DAYS")]
+        stale_after: Option<u32>,
+
+        /// Idle days before a project is abandoned (overrides
+        /// `abandoned_after_days`)
+        #[arg(long, value_name = "DAYS")]
+        abandoned_after: Option<u32>,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show repositories and projects that exist in more than one place
+    Dups {
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show projects that appeared, disappeared or changed between two
+    /// scans, by default the last two scans of the root scanned last
+    Diff {
+        /// Id of the earlier scan
+        #[arg(requires = "to")]
+        from: Option<i64>,
+
+        /// Id of the later scan
+        to: Option<i64>,
+
+        /// Compare the last two scans of this root
+        #[arg(long, conflicts_with = "from")]
+        root: Option<PathBuf>,
+
+        /// List recent scans and their ids instead
+        #[arg(long, conflicts_with_all = ["from", "root"])]
+        list: bool,
+
+        /// Output format
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
     /// Generate a report of the catalog
     Report {
         /// Output file path (prints to stdout if not specified)
@@ -159,6 +561,10 @@ pub enum Commands {
         /// Output format
         #[arg(long, default_value = "table")]
         format: OutputFormat,
+
+        /// Only count pinned versions of this tool (e.g. node)
+        #[arg(long)]
+        toolchain: Option<String>,
     },
 
     /// Show current configuration
@@ -232,11 +638,59 @@ pub enum Commands {
         dry_run: bool,
     },
 
+    /// Remove stale build artifacts and installed dependencies from cataloged projects
+    CleanArtifacts {
+        /// Only clean projects under these paths (defaults to the whole catalog)
+        paths: Vec<PathBuf>,
+
+        /// Only clean projects of this type
+        #[arg(long)]
+        project_type: Option<String>,
+
+        /// Keep artifact directories with files modified within this many days
+        #[arg(long, default_value = "14")]
+        max_age_days: u32,
+
+        /// List what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Ask before removing each directory
+        #[arg(short, long, conflicts_with = "dry_run")]
+        interactive: bool,
+
+        /// Move directories to the trash instead of deleting them
+        #[arg(long)]
+        trash: bool,
+    },
+
     /// Shell integration commands
     #[command(subcommand)]
     Shell(ShellCommands),
 }
 
+/// Size the `du` command orders projects by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiskUsageSort {
+    /// Everything under the project
+    Total,
+    /// Files outside build artifact directories
+    Source,
+    /// Build artifacts and installed dependencies (reclaimable space)
+    Artifacts,
+}
+
+impl DiskUsageSort {
+    /// The size this order compares
+    pub fn bytes(&self, usage: &dprojc_types::DiskUsage) -> u64 {
+        match self {
+            DiskUsageSort::Total => usage.total_bytes,
+            DiskUsageSort::Source => usage.source_bytes,
+            DiskUsageSort::Artifacts => usage.artifact_bytes,
+        }
+    }
+}
+
 /// Shell integration subcommands
 #[derive(Subcommand)]
 pub enum ShellCommands {
@@ -311,29 +765,114 @@ impl CliRunner {
             Commands::Scan {
                 paths,
                 max_depth,
+                threads,
+                disk_usage,
+                code_stats,
                 format,
                 no_save,
-            } => self.run_scan(paths, *max_depth, format, *no_save).await,
+                incremental,
+            } => {
+                let overrides = ScanOverrides {
+                    max_depth: *max_depth,
+                    threads: *threads,
+                    disk_usage: *disk_usage,
+                    code_stats: *code_stats,
+                };
+                self.run_scan(paths, &overrides, format, *no_save, *incremental)
+                    .await
+            }
+            Commands::Watch { paths } => self.run_watch(paths).await,
             Commands::List {
                 project_type,
                 search,
                 format,
                 limit,
+                collapse_workspaces,
+                toolchain,
+                with_automation,
+                without_automation,
             } => {
-                self.run_list(project_type.as_deref(), search.as_deref(), format, *limit)
-                    .await
+                let filter =
+                    ProjectFilter::new(toolchain.as_deref(), with_automation, without_automation)?;
+                self.run_list(
+                    project_type.as_deref(),
+                    search.as_deref(),
+                    format,
+                    *limit,
+                    *collapse_workspaces,
+                    &filter,
+                )
+                .await
             }
             Commands::Search {
                 query,
                 format,
                 limit,
             } => self.run_search(query, format, *limit).await,
+            Commands::Deps {
+                package,
+                ecosystem,
+                package_version,
+                spread,
+                project,
+                format,
+            } => {
+                self.run_deps(
+                    package.as_deref(),
+                    ecosystem.as_deref(),
+                    package_version.as_deref(),
+                    *spread,
+                    project.as_ref(),
+                    format,
+                )
+                .await
+            }
+            Commands::Audit {
+                advisory_db,
+                format,
+            } => self.run_audit(advisory_db.as_ref(), format).await,
+            Commands::Du {
+                sort,
+                limit,
+                format,
+            } => self.run_du(*sort, *limit, format).await,
+            Commands::Licenses {
+                unknown,
+                license,
+                format,
+            } => {
+                self.run_licenses(*unknown, license.as_deref(), format)
+                    .await
+            }
+            Commands::Stale {
+                all,
+                abandoned,
+                stale_after,
+                abandoned_after,
+                format,
+            } => {
+                self.run_stale(*all, *abandoned, *stale_after, *abandoned_after, format)
+                    .await
+            }
+            Commands::Dups { format } => self.run_dups(format).await,
+            Commands::Diff {
+                from,
+                to,
+                root,
+                list,
+                format,
+            } => {
+                let scans = from.zip(*to);
+                self.run_diff(scans, root.as_ref(), *list, format).await
+            }
             Commands::Report {
                 output,
                 format,
                 stats,
             } => self.run_report(output.as_ref(), format, *stats).await,
-            Commands::Stats { format } => self.run_stats(format).await,
+            Commands::Stats { format, toolchain } => {
+                self.run_stats(format, toolchain.as_deref()).await
+            }
             Commands::Config { format } => self.run_config(format).await,
             Commands::Clean {
                 max_age_days,
@@ -368,6 +907,24 @@ impl CliRunner {
                 self.run_clean_old_cargo(paths, *max_age_hours, *dry_run)
                     .await
             }
+            Commands::CleanArtifacts {
+                paths,
+                project_type,
+                max_age_days,
+                dry_run,
+                interactive,
+                trash,
+            } => {
+                self.run_clean_artifacts(
+                    paths,
+                    project_type.as_deref(),
+                    *max_age_days,
+                    *dry_run,
+                    *interactive,
+                    *trash,
+                )
+                .await
+            }
             Commands::Shell(shell_cmd) => self.run_shell(shell_cmd).await,
         }
     }
@@ -1,9 +1,15 @@
 //! Output formatting utilities
 
 use comfy_table::Table;
-use dprojc_types::{Project, ReportData, ScanResult, StatsData};
-use dprojc_utils::format_path_display;
+use dprojc_types::{
+    AuditFinding, Dependency, Dependent, DiskUsage, DuplicateGroup, LicenseDetection, Project,
+    ReportData, ScanDiff, ScanResult, ScanResultSummary, StaleProject, Staleness, StatsData,
+    VersionSpread,
+};
+use dprojc_utils::{format_file_size, format_path_display, workspace_depths};
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Output format enum
 #[derive(Clone, Debug, clap::ValueEnum)]
@@ -19,11 +25,22 @@ pub enum OutputFormat {
 /// Output formatter
 pub struct OutputFormatter {
     format: OutputFormat,
+    /// Number of hidden members per collapsed workspace, shown in tables
+    collapsed_members: HashMap<PathBuf, usize>,
 }
 
 impl OutputFormatter {
     pub fn new(format: OutputFormat) -> Self {
-        Self { format }
+        Self {
+            format,
+            collapsed_members: HashMap::new(),
+        }
+    }
+
+    /// Note how many members were folded into each workspace
+    pub fn with_collapsed_members(mut self, collapsed_members: HashMap<PathBuf, usize>) -> Self {
+        self.collapsed_members = collapsed_members;
+        self
     }
 
     pub fn format_projects(&self, projects: &[Project]) -> anyhow::Result<()> {
@@ -89,6 +106,200 @@ impl OutputFormatter {
             OutputFormat::Yaml => self.format_report_yaml(report, writer),
         }
     }
+
+    pub fn format_dependents(&self, dependents: &[Dependent]) -> anyhow::Result<()> {
+        self.format_dependents_to_writer(dependents, &mut io::stdout())
+    }
+
+    pub fn format_dependents_to_writer<W: Write>(
+        &self,
+        dependents: &[Dependent],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_dependents_table(dependents, writer),
+            OutputFormat::Json => Self::format_json(dependents, writer),
+            OutputFormat::Yaml => Self::format_yaml(dependents, writer),
+        }
+    }
+
+    pub fn format_version_spread(&self, spread: &[VersionSpread]) -> anyhow::Result<()> {
+        self.format_version_spread_to_writer(spread, &mut io::stdout())
+    }
+
+    pub fn format_version_spread_to_writer<W: Write>(
+        &self,
+        spread: &[VersionSpread],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_version_spread_table(spread, writer),
+            OutputFormat::Json => Self::format_json(spread, writer),
+            OutputFormat::Yaml => Self::format_yaml(spread, writer),
+        }
+    }
+
+    pub fn format_dependencies(&self, dependencies: &[Dependency]) -> anyhow::Result<()> {
+        self.format_dependencies_to_writer(dependencies, &mut io::stdout())
+    }
+
+    pub fn format_dependencies_to_writer<W: Write>(
+        &self,
+        dependencies: &[Dependency],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_dependencies_table(dependencies, writer),
+            OutputFormat::Json => Self::format_json(dependencies, writer),
+            OutputFormat::Yaml => Self::format_yaml(dependencies, writer),
+        }
+    }
+
+    /// Format the disk usage of projects that have been measured
+    pub fn format_disk_usage(&self, projects: &[Project]) -> anyhow::Result<()> {
+        self.format_disk_usage_to_writer(projects, &mut io::stdout())
+    }
+
+    pub fn format_disk_usage_to_writer<W: Write>(
+        &self,
+        projects: &[Project],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        /// A project's path next to its sizes, for JSON and YAML
+        #[derive(serde::Serialize)]
+        struct Row<'a> {
+            path: &'a Path,
+            #[serde(flatten)]
+            usage: &'a DiskUsage,
+        }
+
+        let rows: Vec<Row> = projects
+            .iter()
+            .filter_map(|project| {
+                Some(Row {
+                    path: &project.path,
+                    usage: project.disk_usage.as_ref()?,
+                })
+            })
+            .collect();
+        match self.format {
+            OutputFormat::Table => self.format_disk_usage_table(projects, writer),
+            OutputFormat::Json => Self::format_json(&rows, writer),
+            OutputFormat::Yaml => Self::format_yaml(&rows, writer),
+        }
+    }
+
+    pub fn format_audit(&self, findings: &[AuditFinding]) -> anyhow::Result<()> {
+        self.format_audit_to_writer(findings, &mut io::stdout())
+    }
+
+    pub fn format_audit_to_writer<W: Write>(
+        &self,
+        findings: &[AuditFinding],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_audit_table(findings, writer),
+            OutputFormat::Json => Self::format_json(findings, writer),
+            OutputFormat::Yaml => Self::format_yaml(findings, writer),
+        }
+    }
+
+    pub fn format_stale(&self, projects: &[StaleProject]) -> anyhow::Result<()> {
+        self.format_stale_to_writer(projects, &mut io::stdout())
+    }
+
+    pub fn format_stale_to_writer<W: Write>(
+        &self,
+        projects: &[StaleProject],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_stale_table(projects, writer),
+            OutputFormat::Json => Self::format_json(projects, writer),
+            OutputFormat::Yaml => Self::format_yaml(projects, writer),
+        }
+    }
+
+    pub fn format_duplicates(&self, groups: &[DuplicateGroup]) -> anyhow::Result<()> {
+        self.format_duplicates_to_writer(groups, &mut io::stdout())
+    }
+
+    pub fn format_duplicates_to_writer<W: Write>(
+        &self,
+        groups: &[DuplicateGroup],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_duplicates_table(groups, writer),
+            OutputFormat::Json => Self::format_json(groups, writer),
+            OutputFormat::Yaml => Self::format_yaml(groups, writer),
+        }
+    }
+
+    pub fn format_scan_diff(&self, diff: &ScanDiff) -> anyhow::Result<()> {
+        self.format_scan_diff_to_writer(diff, &mut io::stdout())
+    }
+
+    pub fn format_scan_diff_to_writer<W: Write>(
+        &self,
+        diff: &ScanDiff,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_scan_diff_table(diff, writer),
+            OutputFormat::Json => Self::format_json(diff, writer),
+            OutputFormat::Yaml => Self::format_yaml(diff, writer),
+        }
+    }
+
+    pub fn format_scan_summaries(&self, scans: &[ScanResultSummary]) -> anyhow::Result<()> {
+        self.format_scan_summaries_to_writer(scans, &mut io::stdout())
+    }
+
+    pub fn format_scan_summaries_to_writer<W: Write>(
+        &self,
+        scans: &[ScanResultSummary],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Table => self.format_scan_summaries_table(scans, writer),
+            OutputFormat::Json => Self::format_json(scans, writer),
+            OutputFormat::Yaml => Self::format_yaml(scans, writer),
+        }
+    }
+
+    pub fn format_licenses(&self, projects: &[Project]) -> anyhow::Result<()> {
+        self.format_licenses_to_writer(projects, &mut io::stdout())
+    }
+
+    pub fn format_licenses_to_writer<W: Write>(
+        &self,
+        projects: &[Project],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        /// A project's resolved license next to what it was resolved from
+        #[derive(serde::Serialize)]
+        struct Row<'a> {
+            path: &'a Path,
+            license: Option<String>,
+            detections: &'a [LicenseDetection],
+        }
+
+        let rows: Vec<Row> = projects
+            .iter()
+            .map(|project| Row {
+                path: &project.path,
+                license: project.license(),
+                detections: &project.licenses,
+            })
+            .collect();
+        match self.format {
+            OutputFormat::Table => self.format_licenses_table(projects, writer),
+            OutputFormat::Json => Self::format_json(&rows, writer),
+            OutputFormat::Yaml => Self::format_yaml(&rows, writer),
+        }
+    }
 }
 
 impl OutputFormatter {
@@ -103,11 +314,34 @@ impl OutputFormatter {
         }
 
         let mut table = Table::new();
-        table.set_header(vec!["Path", "Type", "Indicators", "Last Scanned"]);
+        table.set_header(vec![
+            "Path",
+            "Name",
+            "Type",
+            "Toolchains",
+            "Branch",
+            "Git Status",
+            "Indicators",
+            "Last Scanned",
+        ]);
 
-        for project in projects {
-            let path = format_path_display(&project.path);
-            let project_type = format!("{}", project.project_type);
+        // Members listed after their workspace are indented beneath it
+        let depths = workspace_depths(projects);
+        for (project, depth) in projects.iter().zip(depths) {
+            let mut path = format_path_display(&project.path);
+            if depth > 0 {
+                path = format!("{}└ {}", "  ".repeat(depth - 1), path);
+            }
+            if let Some(members) = self.collapsed_members.get(&project.path) {
+                path = format!("{} (+{} members)", path, members);
+            }
+            let project_type = if project.ecosystems.is_empty() {
+                project.project_type.to_string()
+            } else {
+                let ecosystems: Vec<String> =
+                    project.ecosystems.iter().map(|e| e.to_string()).collect();
+                format!("{} (+{})", project.project_type, ecosystems.join(", "))
+            };
             let indicators: Vec<String> = project
                 .indicators
                 .iter()
@@ -115,10 +349,33 @@ impl OutputFormatter {
                 .collect();
             let indicators_str = indicators.join(", ");
             let last_scanned = project.last_scanned.format("%Y-%m-%d %H:%M:%S");
+            let (branch, git_status) = match &project.git {
+                Some(git) => (git.head_label(), git.status_summary()),
+                None => (String::new(), String::new()),
+            };
+
+            let name = project
+                .manifest
+                .as_ref()
+                .and_then(|manifest| manifest.label())
+                .unwrap_or_default();
+
+            // The first pin of each tool is the one its version manager selects
+            let mut tools = std::collections::HashSet::new();
+            let toolchains: Vec<String> = project
+                .toolchains
+                .iter()
+                .filter(|pin| tools.insert(pin.tool.as_str()))
+                .map(|pin| format!("{} {}", pin.tool, pin.version))
+                .collect();
 
             table.add_row(vec![
                 path,
+                name,
                 project_type,
+                toolchains.join(", "),
+                branch,
+                git_status,
                 indicators_str,
                 last_scanned.to_string(),
             ]);
@@ -206,19 +463,72 @@ impl OutputFormatter {
         writeln!(writer, "{}", table)?;
         writeln!(writer)?;
 
-        // Project counts by type
-        if !stats.project_counts.is_empty() {
+        // Project counts by type; a project counts once as its primary type and
+        // once for every ecosystem it contains
+        if !stats.project_counts.is_empty() || !stats.ecosystem_counts.is_empty() {
             writeln!(writer, "Projects by Type:")?;
             let mut type_table = Table::new();
-            type_table.set_header(vec!["Type", "Count"]);
+            type_table.set_header(vec!["Type", "Primary", "Containing"]);
 
-            for (project_type, count) in &stats.project_counts {
-                type_table.add_row(vec![format!("{}", project_type), count.to_string()]);
+            let mut types: Vec<_> = stats
+                .project_counts
+                .keys()
+                .chain(stats.ecosystem_counts.keys())
+                .collect();
+            types.sort();
+            types.dedup();
+            for project_type in types {
+                let primary = stats.project_counts.get(project_type).copied().unwrap_or(0);
+                let containing = stats
+                    .ecosystem_counts
+                    .get(project_type)
+                    .copied()
+                    .unwrap_or(primary);
+                type_table.add_row(vec![
+                    project_type.to_string(),
+                    primary.to_string(),
+                    containing.to_string(),
+                ]);
             }
 
             writeln!(writer, "{}", type_table)?;
         }
 
+        if !stats.toolchain_counts.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "Pinned Toolchains:")?;
+            let mut toolchain_table = Table::new();
+            toolchain_table.set_header(vec!["Tool", "Version", "Projects"]);
+            for count in &stats.toolchain_counts {
+                toolchain_table.add_row(vec![
+                    count.tool.clone(),
+                    count.version.clone(),
+                    count.projects.to_string(),
+                ]);
+            }
+            writeln!(writer, "{}", toolchain_table)?;
+        }
+
+        if !stats.language_counts.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "Languages:")?;
+            let mut language_table = Table::new();
+            language_table.set_header(vec![
+                "Language", "Projects", "Files", "Code", "Comments", "Blanks",
+            ]);
+            for count in &stats.language_counts {
+                language_table.add_row(vec![
+                    count.stats.language.clone(),
+                    count.projects.to_string(),
+                    count.stats.files.to_string(),
+                    count.stats.code.to_string(),
+                    count.stats.comments.to_string(),
+                    count.stats.blanks.to_string(),
+                ]);
+            }
+            writeln!(writer, "{}", language_table)?;
+        }
+
         Ok(())
     }
 
@@ -320,6 +630,497 @@ impl OutputFormatter {
         Ok(())
     }
 
+    fn format_dependents_table<W: Write>(
+        &self,
+        dependents: &[Dependent],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if dependents.is_empty() {
+            writeln!(writer, "No projects use this package.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec!["Project", "Ecosystem", "Package", "Version"]);
+        for dependent in dependents {
+            table.add_row(vec![
+                format_path_display(&dependent.path),
+                dependent.dependency.ecosystem.to_string(),
+                dependent.dependency.name.clone(),
+                dependent.dependency.version.clone(),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+        Ok(())
+    }
+
+    fn format_version_spread_table<W: Write>(
+        &self,
+        spread: &[VersionSpread],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if spread.is_empty() {
+            writeln!(writer, "No packages found.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec!["Ecosystem", "Package", "Version", "Projects"]);
+        for package in spread {
+            for (version, paths) in &package.versions {
+                let paths: Vec<String> = paths.iter().map(|p| format_path_display(p)).collect();
+                table.add_row(vec![
+                    package.ecosystem.to_string(),
+                    package.name.clone(),
+                    version.clone(),
+                    paths.join("\n"),
+                ]);
+            }
+        }
+        writeln!(writer, "{}", table)?;
+        Ok(())
+    }
+
+    fn format_dependencies_table<W: Write>(
+        &self,
+        dependencies: &[Dependency],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if dependencies.is_empty() {
+            writeln!(writer, "No locked dependencies recorded.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec!["Ecosystem", "Package", "Version"]);
+        for dependency in dependencies {
+            table.add_row(vec![
+                dependency.ecosystem.to_string(),
+                dependency.name.clone(),
+                dependency.version.clone(),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+        Ok(())
+    }
+
+    fn format_disk_usage_table<W: Write>(
+        &self,
+        projects: &[Project],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        let measured: Vec<(&Project, &DiskUsage)> = projects
+            .iter()
+            .filter_map(|project| Some((project, project.disk_usage.as_ref()?)))
+            .collect();
+        if measured.is_empty() {
+            writeln!(
+                writer,
+                "No disk usage recorded. Scan with --disk-usage or set disk_usage: true."
+            )?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec![
+            "Path",
+            "Total",
+            "Source",
+            "Artifacts",
+            "Largest Artifact Dirs",
+            "Measured",
+        ]);
+        for (project, usage) in &measured {
+            let dirs: Vec<String> = usage
+                .artifact_dirs
+                .iter()
+                .take(3)
+                .map(|dir| format!("{} ({})", dir.path.display(), format_file_size(dir.bytes)))
+                .collect();
+            table.add_row(vec![
+                format_path_display(&project.path),
+                format_file_size(usage.total_bytes),
+                format_file_size(usage.source_bytes),
+                format_file_size(usage.artifact_bytes),
+                dirs.join(", "),
+                usage.measured_at.format("%Y-%m-%d %H:%M").to_string(),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+
+        // Nested projects are already counted in the project that contains them
+        let reclaimable: u64 = measured
+            .iter()
+            .filter(|(project, _)| {
+                !measured.iter().any(|(other, _)| {
+                    other.path != project.path && project.path.starts_with(&other.path)
+                })
+            })
+            .map(|(_, usage)| usage.artifact_bytes)
+            .sum();
+        writeln!(
+            writer,
+            "Reclaimable: {} in build artifacts",
+            format_file_size(reclaimable)
+        )?;
+        Ok(())
+    }
+
+    fn format_audit_table<W: Write>(
+        &self,
+        findings: &[AuditFinding],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if findings.is_empty() {
+            writeln!(writer, "No vulnerable dependencies found.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec![
+            "Project", "Package", "Version", "Advisory", "Title", "Fixed In",
+        ]);
+        for finding in findings {
+            let fixed = if finding.fixed_versions.is_empty() {
+                "-".to_string()
+            } else {
+                finding.fixed_versions.join(", ")
+            };
+            table.add_row(vec![
+                format_path_display(&finding.path),
+                format!(
+                    "{} ({})",
+                    finding.dependency.name, finding.dependency.ecosystem
+                ),
+                finding.dependency.version.clone(),
+                finding.advisory_id.clone(),
+                finding.title.clone(),
+                fixed,
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+
+        let projects: std::collections::HashSet<_> = findings.iter().map(|f| &f.path).collect();
+        writeln!(
+            writer,
+            "{} advisories affect {} projects",
+            findings.len(),
+            projects.len()
+        )?;
+        Ok(())
+    }
+
+    fn format_duplicates_table<W: Write>(
+        &self,
+        groups: &[DuplicateGroup],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if groups.is_empty() {
+            writeln!(writer, "No duplicate projects found.")?;
+            return Ok(());
+        }
+
+        for group in groups {
+            let matched_by: Vec<String> = group.matched_by.iter().map(|m| m.to_string()).collect();
+            writeln!(writer, "Matched by {}", matched_by.join(", "))?;
+
+            let mut table = Table::new();
+            table.set_header(vec![
+                "Path",
+                "Branch",
+                "Updated",
+                "Local Changes",
+                "Frecency",
+                "",
+            ]);
+            for copy in &group.copies {
+                let mut changes = Vec::new();
+                if copy.uncommitted_changes > 0 {
+                    changes.push(format!("{} uncommitted", copy.uncommitted_changes));
+                }
+                if copy.unpushed_commits > 0 {
+                    changes.push(format!("{} unpushed", copy.unpushed_commits));
+                }
+                if copy.stashes > 0 {
+                    changes.push(format!("{} stashed", copy.stashes));
+                }
+                let mut notes = Vec::new();
+                if copy.newest {
+                    notes.push("newest");
+                }
+                if copy.most_frecent {
+                    notes.push("most used");
+                }
+                table.add_row(vec![
+                    format_path_display(&copy.path),
+                    copy.branch.clone().unwrap_or_else(|| "-".to_string()),
+                    copy.updated
+                        .map_or("-".to_string(), |date| date.format("%Y-%m-%d").to_string()),
+                    if changes.is_empty() {
+                        "-".to_string()
+                    } else {
+                        changes.join(", ")
+                    },
+                    format!("{:.1}", copy.frecency),
+                    notes.join(", "),
+                ]);
+            }
+            writeln!(writer, "{}", table)?;
+            writeln!(writer)?;
+        }
+
+        let copies: usize = groups.iter().map(|group| group.copies.len()).sum();
+        let with_changes = groups
+            .iter()
+            .flat_map(|group| &group.copies)
+            .filter(|copy| copy.has_local_changes())
+            .count();
+        writeln!(
+            writer,
+            "{} projects exist in {} copies; {} copies have local changes",
+            groups.len(),
+            copies,
+            with_changes
+        )?;
+        Ok(())
+    }
+
+    fn format_scan_summaries_table<W: Write>(
+        &self,
+        scans: &[ScanResultSummary],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if scans.is_empty() {
+            writeln!(writer, "No scans recorded.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec!["Id", "Scanned", "Root", "Dirs", "Duration", "Errors"]);
+        for scan in scans {
+            table.add_row(vec![
+                scan.id.to_string(),
+                scan.scan_timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                format_path_display(&scan.root_path),
+                scan.dirs_scanned.to_string(),
+                format!("{}ms", scan.scan_duration_ms),
+                scan.error_count.to_string(),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+        Ok(())
+    }
+
+    fn format_scan_diff_table<W: Write>(
+        &self,
+        diff: &ScanDiff,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        let roots = if diff.from.root_path == diff.to.root_path {
+            format_path_display(&diff.to.root_path)
+        } else {
+            format!(
+                "{} and {}",
+                format_path_display(&diff.from.root_path),
+                format_path_display(&diff.to.root_path)
+            )
+        };
+        writeln!(
+            writer,
+            "Scan {} ({}) -> scan {} ({}) of {}",
+            diff.from.id,
+            diff.from.scan_timestamp.format("%Y-%m-%d %H:%M"),
+            diff.to.id,
+            diff.to.scan_timestamp.format("%Y-%m-%d %H:%M"),
+            roots
+        )?;
+        if diff.is_empty() {
+            writeln!(writer, "No projects appeared, disappeared or changed.")?;
+            return Ok(());
+        }
+
+        let indicators = |indicators: &[dprojc_types::ProjectIndicator], sign: &str| {
+            indicators
+                .iter()
+                .map(|indicator| format!("{}{}", sign, indicator))
+                .collect::<Vec<_>>()
+        };
+        let mut table = Table::new();
+        table.set_header(vec!["Change", "Path", "Type", "Indicators"]);
+        for (change, projects) in [("added", &diff.added), ("removed", &diff.removed)] {
+            for project in projects {
+                table.add_row(vec![
+                    change.to_string(),
+                    format_path_display(&project.path),
+                    project.project_type.to_string(),
+                    indicators(&project.indicators, "").join(", "),
+                ]);
+            }
+        }
+        for project in &diff.changed {
+            let project_type = if project.old_type == project.new_type {
+                project.new_type.to_string()
+            } else {
+                format!("{} -> {}", project.old_type, project.new_type)
+            };
+            let mut changed = indicators(&project.added_indicators, "+");
+            changed.extend(indicators(&project.removed_indicators, "-"));
+            table.add_row(vec![
+                "changed".to_string(),
+                format_path_display(&project.path),
+                project_type,
+                changed.join(", "),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+        writeln!(
+            writer,
+            "{} added, {} removed, {} changed",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        )?;
+        Ok(())
+    }
+
+    fn format_stale_table<W: Write>(
+        &self,
+        projects: &[StaleProject],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if projects.is_empty() {
+            writeln!(writer, "No stale projects found.")?;
+            return Ok(());
+        }
+
+        let date = |date: Option<chrono::DateTime<chrono::Utc>>| {
+            date.map_or("-".to_string(), |date| date.format("%Y-%m-%d").to_string())
+        };
+        let mut table = Table::new();
+        table.set_header(vec![
+            "Path",
+            "Status",
+            "Idle",
+            "Last Commit",
+            "Last Modified",
+            "Last Visit",
+            "Uncommitted",
+        ]);
+        for project in projects {
+            table.add_row(vec![
+                format_path_display(&project.path),
+                project.staleness.to_string(),
+                project
+                    .idle_days
+                    .map_or("never active".to_string(), |days| format!("{} days", days)),
+                date(project.signals.last_commit),
+                date(project.signals.last_modified),
+                date(project.signals.last_accessed),
+                if project.signals.uncommitted_changes {
+                    "yes".to_string()
+                } else {
+                    "-".to_string()
+                },
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+
+        let count = |staleness: Staleness| {
+            projects
+                .iter()
+                .filter(|project| project.staleness == staleness)
+                .count()
+        };
+        writeln!(
+            writer,
+            "{} active, {} stale, {} abandoned",
+            count(Staleness::Active),
+            count(Staleness::Stale),
+            count(Staleness::Abandoned)
+        )?;
+        let uncommitted = projects
+            .iter()
+            .filter(|project| {
+                project.staleness != Staleness::Active && project.signals.uncommitted_changes
+            })
+            .count();
+        if uncommitted > 0 {
+            writeln!(
+                writer,
+                "{} idle projects have uncommitted changes",
+                uncommitted
+            )?;
+        }
+        Ok(())
+    }
+
+    fn format_licenses_table<W: Write>(
+        &self,
+        projects: &[Project],
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if projects.is_empty() {
+            writeln!(writer, "No projects found.")?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_header(vec!["Path", "License", "Sources"]);
+        let mut counts: std::collections::BTreeMap<String, usize> = Default::default();
+        let mut unknown = 0;
+        for project in projects {
+            let license = project.license();
+            match &license {
+                Some(license) => *counts.entry(license.clone()).or_default() += 1,
+                None => unknown += 1,
+            }
+            let sources: Vec<String> = project
+                .licenses
+                .iter()
+                .map(|detection| match (&detection.declared, &detection.spdx) {
+                    (Some(declared), _) => format!("{}: {}", detection.source, declared),
+                    (None, Some(spdx)) => {
+                        format!("{}: {} ({}%)", detection.source, spdx, detection.confidence)
+                    }
+                    (None, None) => format!("{}: unrecognized", detection.source),
+                })
+                .collect();
+            table.add_row(vec![
+                format_path_display(&project.path),
+                license.unwrap_or_else(|| "unknown".to_string()),
+                sources.join(", "),
+            ]);
+        }
+        writeln!(writer, "{}", table)?;
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (license, count) in counts {
+            writeln!(writer, "{:>5}  {}", count, license)?;
+        }
+        if unknown > 0 {
+            writeln!(writer, "{:>5}  without an identified license", unknown)?;
+        }
+        Ok(())
+    }
+
+    fn format_json<T: serde::Serialize + ?Sized, W: Write>(
+        value: &T,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, value)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn format_yaml<T: serde::Serialize + ?Sized, W: Write>(
+        value: &T,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        serde_yaml::to_writer(writer, value)?;
+        Ok(())
+    }
+
     fn format_report_json<W: Write>(
         &self,
         report: &ReportData,
@@ -353,12 +1154,14 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: PathBuf::from("/test/node_project"),
                 project_type: ProjectType::NodeJs,
                 indicators: vec![ProjectIndicator::PackageJson],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
         ]
     }
@@ -376,6 +1179,25 @@ mod tests {
                 (ProjectType::Rust, 1),
                 (ProjectType::NodeJs, 1),
             ]),
+            ecosystem_counts: std::collections::HashMap::from([
+                (ProjectType::Rust, 1),
+                (ProjectType::NodeJs, 2),
+            ]),
+            toolchain_counts: vec![dprojc_types::ToolchainCount {
+                tool: "node".to_string(),
+                version: "16".to_string(),
+                projects: 2,
+            }],
+            language_counts: vec![dprojc_types::LanguageCount {
+                projects: 2,
+                stats: dprojc_types::LanguageStats {
+                    language: "Rust".to_string(),
+                    files: 12,
+                    code: 1_500,
+                    comments: 200,
+                    blanks: 100,
+                },
+            }],
         }
     }
 
@@ -394,13 +1216,20 @@ mod tests {
             }],
             dirs_scanned: 10,
             scan_duration_ms: 500,
+            ..Default::default()
         }]
     }
 
     #[test]
     fn test_output_formatter_table() {
         let formatter = OutputFormatter::new(OutputFormat::Table);
-        let projects = create_test_projects();
+        let mut projects = create_test_projects();
+        let pin = |version: &str, source: &str| dprojc_types::ToolchainPin {
+            tool: "node".to_string(),
+            version: version.to_string(),
+            source: source.to_string(),
+        };
+        projects[1].toolchains = vec![pin("16.20.1", ".nvmrc"), pin(">=14", "package.json")];
 
         let mut output = Vec::new();
         formatter
@@ -412,6 +1241,462 @@ mod tests {
         assert!(output_str.contains("node_project"));
         assert!(output_str.contains("Rust"));
         assert!(output_str.contains("Node.js"));
+        assert!(output_str.contains("node 16.20.1"));
+        assert!(!output_str.contains(">=14"));
+    }
+
+    #[test]
+    fn test_output_formatter_table_workspaces() {
+        let mut projects = create_test_projects();
+        let workspace = projects[0].path.clone();
+        projects[1].parent = Some(workspace.clone());
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_projects_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("└ "));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .with_collapsed_members(HashMap::from([(workspace, 4)]))
+            .format_projects_to_writer(&projects[..1], &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("(+4 members)"));
+        assert!(!output_str.contains("└ "));
+    }
+
+    #[test]
+    fn test_output_formatter_table_git() {
+        let mut projects = create_test_projects();
+        projects[0].git = Some(dprojc_types::GitMetadata {
+            branch: Some("main".to_string()),
+            head_commit: Some("0123456789abcdef".to_string()),
+            untracked_count: 1,
+            ..dprojc_types::GitMetadata::default()
+        });
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_projects_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("Git Status"));
+        assert!(output_str.contains("main@0123456"));
+        assert!(output_str.contains("1 untracked"));
+    }
+
+    #[test]
+    fn test_output_formatter_table_manifest() {
+        let mut projects = create_test_projects();
+        projects[0].manifest = Some(dprojc_types::ProjectManifest {
+            source: "Cargo.toml".to_string(),
+            name: Some("widget".to_string()),
+            version: Some("1.2.0".to_string()),
+            ..dprojc_types::ProjectManifest::default()
+        });
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_projects_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("widget 1.2.0"));
+    }
+
+    #[test]
+    fn test_output_formatter_dependencies() {
+        use dprojc_types::{Dependency, DependencyEcosystem, Dependent, VersionSpread};
+
+        let lodash = Dependency {
+            ecosystem: DependencyEcosystem::Npm,
+            name: "lodash".to_string(),
+            version: "4.17.21".to_string(),
+        };
+        let dependents = vec![Dependent {
+            path: PathBuf::from("/test/web"),
+            dependency: lodash.clone(),
+        }];
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_dependents_to_writer(&dependents, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("/test/web"));
+        assert!(output_str.contains("npm"));
+        assert!(output_str.contains("4.17.21"));
+
+        let spread = vec![VersionSpread {
+            ecosystem: DependencyEcosystem::Npm,
+            name: "lodash".to_string(),
+            versions: vec![
+                ("3.10.1".to_string(), vec![PathBuf::from("/test/admin")]),
+                ("4.17.21".to_string(), vec![PathBuf::from("/test/web")]),
+            ],
+        }];
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_version_spread_to_writer(&spread, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("3.10.1"));
+        assert!(output_str.contains("/test/admin"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_dependencies_to_writer(&[lodash], &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed[0]["ecosystem"], "npm");
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_dependents_to_writer(&[], &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("No projects"));
+    }
+
+    #[test]
+    fn test_output_formatter_disk_usage() {
+        use dprojc_types::{ArtifactDir, DiskUsage};
+
+        let mut projects = create_test_projects();
+        projects[0].path = PathBuf::from("/test/mono");
+        projects[0].disk_usage = Some(DiskUsage {
+            total_bytes: 3 * 1024 * 1024,
+            source_bytes: 1024 * 1024,
+            artifact_bytes: 2 * 1024 * 1024,
+            artifact_dirs: vec![ArtifactDir {
+                path: PathBuf::from("crates/core/target"),
+                bytes: 2 * 1024 * 1024,
+            }],
+            measured_at: chrono::Utc::now(),
+        });
+        projects[1].path = PathBuf::from("/test/mono/crates/core");
+        projects[1].disk_usage = Some(DiskUsage {
+            total_bytes: 2 * 1024 * 1024,
+            source_bytes: 0,
+            artifact_bytes: 2 * 1024 * 1024,
+            artifact_dirs: Vec::new(),
+            measured_at: chrono::Utc::now(),
+        });
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_disk_usage_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("3.0 MB"));
+        assert!(output_str.contains("crates/core/target (2.0 MB)"));
+        // The member's artifacts are part of the workspace's and counted once
+        assert!(output_str.contains("Reclaimable: 2.0 MB"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_disk_usage_to_writer(&projects, &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed[0]["path"], "/test/mono");
+        assert_eq!(parsed[0]["artifact_bytes"], 2 * 1024 * 1024);
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_disk_usage_to_writer(&create_test_projects(), &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("No disk usage recorded"));
+    }
+
+    #[test]
+    fn test_output_formatter_audit() {
+        use dprojc_types::{AuditFinding, Dependency, DependencyEcosystem};
+
+        let findings = vec![AuditFinding {
+            path: PathBuf::from("/test/api"),
+            dependency: Dependency {
+                ecosystem: DependencyEcosystem::Cargo,
+                name: "time".to_string(),
+                version: "0.1.45".to_string(),
+            },
+            advisory_id: "RUSTSEC-2020-0071".to_string(),
+            aliases: vec!["CVE-2020-26235".to_string()],
+            title: "Potential segfault in the time crate".to_string(),
+            url: None,
+            fixed_versions: vec![">=0.2.23".to_string()],
+        }];
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_audit_to_writer(&findings, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("RUSTSEC-2020-0071"));
+        assert!(output_str.contains("time (cargo)"));
+        assert!(output_str.contains(">=0.2.23"));
+        assert!(output_str.contains("1 advisories affect 1 projects"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Yaml)
+            .format_audit_to_writer(&findings, &mut output)
+            .unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_slice(&output).unwrap();
+        assert_eq!(parsed[0]["advisory_id"], "RUSTSEC-2020-0071");
+        assert_eq!(parsed[0]["dependency"]["version"], "0.1.45");
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_audit_to_writer(&[], &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("No vulnerable dependencies"));
+    }
+
+    #[test]
+    fn test_output_formatter_duplicates() {
+        use dprojc_types::{DuplicateCopy, DuplicateMatch};
+
+        let copy = |path: &str| DuplicateCopy {
+            path: PathBuf::from(path),
+            branch: Some("main".to_string()),
+            head_commit: None,
+            updated: None,
+            uncommitted_changes: 0,
+            unpushed_commits: 0,
+            stashes: 0,
+            frecency: 0.0,
+            newest: false,
+            most_frecent: false,
+        };
+        let groups = vec![DuplicateGroup {
+            matched_by: vec![
+                DuplicateMatch::RemoteUrl("github.com/acme/app".to_string()),
+                DuplicateMatch::RootCommit("0123456789abcdef".to_string()),
+            ],
+            copies: vec![
+                DuplicateCopy {
+                    uncommitted_changes: 3,
+                    unpushed_commits: 1,
+                    newest: true,
+                    ..copy("/test/a/app")
+                },
+                DuplicateCopy {
+                    frecency: 2.5,
+                    most_frecent: true,
+                    ..copy("/test/b/app")
+                },
+            ],
+        }];
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_duplicates_to_writer(&groups, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("Matched by remote github.com/acme/app, root commit 0123456"));
+        assert!(output_str.contains("3 uncommitted, 1 unpushed"));
+        assert!(output_str.contains("most used"));
+        assert!(output_str.contains("1 projects exist in 2 copies; 1 copies have local changes"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_duplicates_to_writer(&groups, &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(
+            parsed[0]["matched_by"][0]["remote_url"],
+            "github.com/acme/app"
+        );
+        assert_eq!(parsed[0]["copies"][0]["newest"], true);
+        assert_eq!(parsed[0]["copies"][1]["frecency"], 2.5);
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_duplicates_to_writer(&[], &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("No duplicate projects"));
+    }
+
+    #[test]
+    fn test_output_formatter_scan_diff() {
+        use dprojc_types::{ProjectChange, ScanResultSummary, ScannedProject};
+
+        let summary = |id, timestamp: &str| ScanResultSummary {
+            id,
+            scan_timestamp: chrono::DateTime::parse_from_rfc3339(timestamp)
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+            root_path: PathBuf::from("/test"),
+            dirs_scanned: 10,
+            scan_duration_ms: 20,
+            error_count: 0,
+            excluded_dirs_count: 0,
+        };
+        let mut diff = ScanDiff {
+            from: summary(3, "2024-03-01T09:00:00Z"),
+            to: summary(5, "2024-03-02T10:30:00Z"),
+            added: vec![ScannedProject {
+                path: PathBuf::from("/test/new"),
+                project_type: ProjectType::Go,
+                indicators: vec![ProjectIndicator::GoMod],
+            }],
+            removed: Vec::new(),
+            changed: vec![ProjectChange {
+                path: PathBuf::from("/test/app"),
+                old_type: ProjectType::NodeJs,
+                new_type: ProjectType::Rust,
+                added_indicators: vec![ProjectIndicator::CargoToml],
+                removed_indicators: vec![ProjectIndicator::PackageJson],
+            }],
+        };
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_scan_diff_to_writer(&diff, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("Scan 3 (2024-03-01 09:00) -> scan 5 (2024-03-02 10:30)"));
+        assert!(output_str.contains("Node.js -> Rust"));
+        assert!(output_str.contains("+Cargo.toml, -package.json"));
+        assert!(output_str.contains("1 added, 0 removed, 1 changed"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_scan_diff_to_writer(&diff, &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed["from"]["id"], 3);
+        assert_eq!(parsed["added"][0]["path"], "/test/new");
+        assert_eq!(parsed["changed"][0]["new_type"], "Rust");
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Yaml)
+            .format_scan_diff_to_writer(&diff, &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("removed: []"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_scan_summaries_to_writer(&[diff.to.clone(), diff.from.clone()], &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("2024-03-02 10:30"));
+        assert!(output_str.contains("20ms"));
+
+        diff.added.clear();
+        diff.changed.clear();
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_scan_diff_to_writer(&diff, &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("No projects appeared, disappeared or changed."));
+    }
+
+    #[test]
+    fn test_output_formatter_stale() {
+        use dprojc_types::ActivitySignals;
+
+        let accessed = chrono::DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let projects = vec![
+            StaleProject {
+                path: PathBuf::from("/test/old"),
+                staleness: Staleness::Abandoned,
+                idle_days: None,
+                signals: ActivitySignals::default(),
+            },
+            StaleProject {
+                path: PathBuf::from("/test/paused"),
+                staleness: Staleness::Stale,
+                idle_days: Some(120),
+                signals: ActivitySignals {
+                    last_accessed: Some(accessed),
+                    uncommitted_changes: true,
+                    ..ActivitySignals::default()
+                },
+            },
+        ];
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_stale_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("never active"));
+        assert!(output_str.contains("120 days"));
+        assert!(output_str.contains("2024-03-01"));
+        assert!(output_str.contains("0 active, 1 stale, 1 abandoned"));
+        assert!(output_str.contains("1 idle projects have uncommitted changes"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_stale_to_writer(&projects, &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed[0]["staleness"], "abandoned");
+        assert!(parsed[0]["idle_days"].is_null());
+        assert_eq!(parsed[1]["idle_days"], 120);
+        assert_eq!(parsed[1]["uncommitted_changes"], true);
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_stale_to_writer(&[], &mut output)
+            .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("No stale projects"));
+    }
+
+    #[test]
+    fn test_output_formatter_licenses() {
+        let mut projects = create_test_projects();
+        projects[0].licenses = vec![
+            LicenseDetection {
+                source: "Cargo.toml".to_string(),
+                declared: Some("MIT/Apache-2.0".to_string()),
+                spdx: Some("MIT OR Apache-2.0".to_string()),
+                confidence: 100,
+            },
+            LicenseDetection {
+                source: "LICENSE-MIT".to_string(),
+                declared: None,
+                spdx: Some("MIT".to_string()),
+                confidence: 98,
+            },
+        ];
+        projects[1].licenses = vec![LicenseDetection {
+            source: "COPYING".to_string(),
+            declared: None,
+            spdx: None,
+            confidence: 12,
+        }];
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Table)
+            .format_licenses_to_writer(&projects, &mut output)
+            .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("MIT OR Apache-2.0"));
+        assert!(output_str.contains("LICENSE-MIT: MIT (98%)"));
+        assert!(output_str.contains("COPYING: unrecognized"));
+        assert!(output_str.contains("1  without an identified license"));
+
+        let mut output = Vec::new();
+        OutputFormatter::new(OutputFormat::Json)
+            .format_licenses_to_writer(&projects, &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed[0]["license"], "MIT OR Apache-2.0");
+        assert_eq!(parsed[0]["detections"][1]["confidence"], 98);
+        assert!(parsed[1]["license"].is_null());
     }
 
     #[test]
@@ -475,6 +1760,11 @@ mod tests {
         assert!(output_str.contains("Total Projects"));
         assert!(output_str.contains("1"));
         assert!(output_str.contains("2"));
+        assert!(output_str.contains("Containing"));
+        assert!(output_str.contains("Pinned Toolchains"));
+        assert!(output_str.contains("node"));
+        assert!(output_str.contains("Languages"));
+        assert!(output_str.contains("1500"));
     }
 
     #[test]
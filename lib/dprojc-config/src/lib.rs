@@ -23,14 +23,101 @@
 //!   - .git
 //!   - package.json
 //! follow_symlinks: false
+//! respect_gitignore: true
+//! respect_dprojcignore: true
+//! threads: 0
+//! git_metadata: true
+//! disk_usage: false
+//! code_stats: false
+//! advisory_db: ~/src/advisory-db
+//! stale_after_days: 90
+//! abandoned_after_days: 365
+//! watch_roots:
+//!   - ~/src
+//! watch_debounce_ms: 500
+//! max_watches: 8192
+//! rescan_interval_minutes: 60
+//! indicator_rules:
+//!   - name: poetry
+//!     file_contains:
+//!       file: pyproject.toml
+//!       contains: "[tool.poetry]"
+//!   - name: terraform
+//!     glob: "*.tf"
+//!   - name: rails
+//!     directory_shape: [Gemfile, config/routes.rb, app/]
+//! project_types:
+//!   - name: terraform
+//!     priority: 20
+//!     indicators: ["*.tf"]
+//!   - name: helm
+//!     indicators: [Chart.yaml]
 //! ```
 //!
+//! Exclude patterns use gitignore syntax. When `respect_gitignore` is enabled the
+//! scanner also honors `.gitignore`, `.ignore`, `.git/info/exclude` and the global
+//! git excludes file; `respect_dprojcignore` controls catalog-specific
+//! `.dprojcignore` files, which use the same syntax. `threads` sets how many walker
+//! threads share the work inside a single scan root; `0` uses one per CPU.
+//! `git_metadata` reads the branch, HEAD commit, remotes and working tree state
+//! of every git repository found; turn it off to skip the status check on large
+//! repositories.
+//!
+//! `disk_usage` walks every project once more to add up its total, source and
+//! build artifact sizes. It is off by default because it reads the metadata of
+//! every file, dependency directories included; a scan without it keeps the
+//! last measured figures.
+//!
+//! `code_stats` is the deep scan: it reads every source file of each project to
+//! count code, comment and blank lines per language. It honors the exclude
+//! patterns and ignore files like the project walk does, and like `disk_usage`
+//! a scan without it keeps the last counts.
+//!
+//! `advisory_db` points `audit` at a local clone of the RustSec advisory
+//! database or a directory of OSV JSON files; nothing is downloaded, so keep
+//! the clone up to date yourself.
+//!
+//! `stale_after_days` and `abandoned_after_days` are the thresholds `stale`
+//! applies to the days since a project's last commit, file modification or
+//! visit. The abandoned threshold must not be below the stale one.
+//!
+//! `watch_roots` are the roots `watch` keeps current when it is started without
+//! paths. It applies changes once the file system has been quiet for
+//! `watch_debounce_ms`, registers at most `max_watches` directories, and
+//! rescans roots with more directories than that every
+//! `rescan_interval_minutes` instead. Linux limits watches per user through
+//! `fs.inotify.max_user_watches`; roots beyond that limit are rescanned too.
+//!
+//! `indicator_rules` mark a directory as a project when a glob matches one of its
+//! entries, when a file contains some text, or when a set of entries is present
+//! (a trailing `/` requires a directory). Matches are reported as custom
+//! indicators carrying the rule name; rules sharing a name are alternatives.
+//!
+//! `project_types` is the registry of user-defined project types. Each type lists
+//! the `indicators` and `rules` that identify it and a `priority`; a directory gets
+//! the highest-priority matching type, where built-in types rank from 10 (Rust)
+//! down to 1 (Git) and a custom type without a priority ranks 2. A type named
+//! like a built-in one replaces it.
+//!
 //! # Environment Variables
 //!
 //! - `DURABLE_MAX_DEPTH`: Maximum scan depth (integer)
 //! - `DURABLE_EXCLUDE_PATTERNS`: Comma-separated list of patterns to exclude
 //! - `DURABLE_PROJECT_INDICATORS`: Comma-separated list of project indicators
 //! - `DURABLE_FOLLOW_SYMLINKS`: Whether to follow symlinks (true/false)
+//! - `DURABLE_RESPECT_GITIGNORE`: Whether to honor git ignore files (true/false)
+//! - `DURABLE_RESPECT_DPROJCIGNORE`: Whether to honor `.dprojcignore` files (true/false)
+//! - `DURABLE_SCAN_THREADS`: Number of walker threads per scan root (integer, 0 = auto)
+//! - `DURABLE_GIT_METADATA`: Whether to read git repository state (true/false)
+//! - `DURABLE_DISK_USAGE`: Whether to measure project disk usage (true/false)
+//! - `DURABLE_CODE_STATS`: Whether to count lines of code per language (true/false)
+//! - `DURABLE_ADVISORY_DB`: Path of the local advisory database
+//! - `DURABLE_STALE_AFTER_DAYS`: Idle days before a project is stale (integer)
+//! - `DURABLE_ABANDONED_AFTER_DAYS`: Idle days before a project is abandoned (integer)
+//! - `DURABLE_WATCH_ROOTS`: Comma-separated list of roots `watch` follows
+//! - `DURABLE_WATCH_DEBOUNCE_MS`: Quiet time before `watch` applies changes (milliseconds)
+//! - `DURABLE_MAX_WATCHES`: Most directories `watch` registers (integer)
+//! - `DURABLE_RESCAN_INTERVAL_MINUTES`: Minutes between rescans of unwatched roots (integer)
 //!
 //! # Configuration File Locations
 //!
@@ -42,14 +129,14 @@
 //! 4. `~/.config/durable/.durable.yaml`
 //! 5. `~/.durable.yaml`
 
-use dprojc_types::ScanConfig;
+use dprojc_types::{IndicatorRule, ProjectTypeDefinition, ScanConfig};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Configuration file structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ConfigFile {
     /// Maximum depth to scan
     pub max_depth: Option<usize>,
@@ -59,6 +146,36 @@ pub struct ConfigFile {
     pub project_indicators: Option<Vec<String>>,
     /// Whether to follow symbolic links
     pub follow_symlinks: Option<bool>,
+    /// Whether to honor git ignore files
+    pub respect_gitignore: Option<bool>,
+    /// Whether to honor `.dprojcignore` files
+    pub respect_dprojcignore: Option<bool>,
+    /// Number of walker threads per scan root
+    pub threads: Option<usize>,
+    /// Whether to read git repository state
+    pub git_metadata: Option<bool>,
+    /// Whether to measure project disk usage
+    pub disk_usage: Option<bool>,
+    /// Whether to count lines of code per language
+    pub code_stats: Option<bool>,
+    /// Path of the local advisory database
+    pub advisory_db: Option<PathBuf>,
+    /// Idle days before a project is stale
+    pub stale_after_days: Option<u32>,
+    /// Idle days before a project is abandoned
+    pub abandoned_after_days: Option<u32>,
+    /// Roots `watch` follows by default
+    pub watch_roots: Option<Vec<PathBuf>>,
+    /// Quiet time before `watch` applies changes, in milliseconds
+    pub watch_debounce_ms: Option<u64>,
+    /// Most directories `watch` registers
+    pub max_watches: Option<usize>,
+    /// Minutes between rescans of roots too large to watch
+    pub rescan_interval_minutes: Option<u32>,
+    /// Glob, file-contents and directory-shape indicator rules
+    pub indicator_rules: Option<Vec<IndicatorRule>>,
+    /// User-defined project types
+    pub project_types: Option<Vec<ProjectTypeDefinition>>,
 }
 
 /// Configuration manager for loading and merging configurations
@@ -227,6 +344,51 @@ impl ConfigManager {
         if let Some(follow_symlinks) = file_config.follow_symlinks {
             config.follow_symlinks = follow_symlinks;
         }
+        if let Some(respect_gitignore) = file_config.respect_gitignore {
+            config.respect_gitignore = respect_gitignore;
+        }
+        if let Some(respect_dprojcignore) = file_config.respect_dprojcignore {
+            config.respect_dprojcignore = respect_dprojcignore;
+        }
+        if let Some(threads) = file_config.threads {
+            config.threads = threads;
+        }
+        if let Some(git_metadata) = file_config.git_metadata {
+            config.git_metadata = git_metadata;
+        }
+        if let Some(disk_usage) = file_config.disk_usage {
+            config.disk_usage = disk_usage;
+        }
+        if let Some(code_stats) = file_config.code_stats {
+            config.code_stats = code_stats;
+        }
+        if let Some(advisory_db) = file_config.advisory_db {
+            config.advisory_db = Some(advisory_db);
+        }
+        if let Some(stale_after_days) = file_config.stale_after_days {
+            config.stale_after_days = stale_after_days;
+        }
+        if let Some(abandoned_after_days) = file_config.abandoned_after_days {
+            config.abandoned_after_days = abandoned_after_days;
+        }
+        if let Some(watch_roots) = file_config.watch_roots {
+            config.watch_roots = watch_roots;
+        }
+        if let Some(watch_debounce_ms) = file_config.watch_debounce_ms {
+            config.watch_debounce_ms = watch_debounce_ms;
+        }
+        if let Some(max_watches) = file_config.max_watches {
+            config.max_watches = max_watches;
+        }
+        if let Some(rescan_interval_minutes) = file_config.rescan_interval_minutes {
+            config.rescan_interval_minutes = rescan_interval_minutes;
+        }
+        if let Some(indicator_rules) = file_config.indicator_rules {
+            config.indicator_rules = indicator_rules;
+        }
+        if let Some(project_types) = file_config.project_types {
+            config.project_types = project_types;
+        }
     }
 
     fn load_from_env(config: &mut ScanConfig) -> anyhow::Result<()> {
@@ -270,6 +432,90 @@ impl ConfigManager {
             }
         }
 
+        if let Ok(respect_gitignore_str) = env::var("DURABLE_RESPECT_GITIGNORE") {
+            if let Ok(respect_gitignore) = respect_gitignore_str.trim().parse::<bool>() {
+                config.respect_gitignore = respect_gitignore;
+            }
+        }
+
+        if let Ok(respect_dprojcignore_str) = env::var("DURABLE_RESPECT_DPROJCIGNORE") {
+            if let Ok(respect_dprojcignore) = respect_dprojcignore_str.trim().parse::<bool>() {
+                config.respect_dprojcignore = respect_dprojcignore;
+            }
+        }
+
+        if let Ok(threads_str) = env::var("DURABLE_SCAN_THREADS") {
+            if let Ok(threads) = threads_str.trim().parse::<usize>() {
+                config.threads = threads;
+            }
+        }
+
+        if let Ok(git_metadata_str) = env::var("DURABLE_GIT_METADATA") {
+            if let Ok(git_metadata) = git_metadata_str.trim().parse::<bool>() {
+                config.git_metadata = git_metadata;
+            }
+        }
+
+        if let Ok(disk_usage_str) = env::var("DURABLE_DISK_USAGE") {
+            if let Ok(disk_usage) = disk_usage_str.trim().parse::<bool>() {
+                config.disk_usage = disk_usage;
+            }
+        }
+
+        if let Ok(code_stats_str) = env::var("DURABLE_CODE_STATS") {
+            if let Ok(code_stats) = code_stats_str.trim().parse::<bool>() {
+                config.code_stats = code_stats;
+            }
+        }
+
+        if let Ok(advisory_db) = env::var("DURABLE_ADVISORY_DB") {
+            if !advisory_db.trim().is_empty() {
+                config.advisory_db = Some(PathBuf::from(advisory_db.trim()));
+            }
+        }
+
+        if let Ok(stale_after_days_str) = env::var("DURABLE_STALE_AFTER_DAYS") {
+            if let Ok(stale_after_days) = stale_after_days_str.trim().parse::<u32>() {
+                config.stale_after_days = stale_after_days;
+            }
+        }
+
+        if let Ok(abandoned_after_days_str) = env::var("DURABLE_ABANDONED_AFTER_DAYS") {
+            if let Ok(abandoned_after_days) = abandoned_after_days_str.trim().parse::<u32>() {
+                config.abandoned_after_days = abandoned_after_days;
+            }
+        }
+
+        if let Ok(watch_roots_str) = env::var("DURABLE_WATCH_ROOTS") {
+            let roots = watch_roots_str
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .collect::<Vec<_>>();
+            if !roots.is_empty() {
+                config.watch_roots = roots;
+            }
+        }
+
+        if let Ok(watch_debounce_ms_str) = env::var("DURABLE_WATCH_DEBOUNCE_MS") {
+            if let Ok(watch_debounce_ms) = watch_debounce_ms_str.trim().parse::<u64>() {
+                config.watch_debounce_ms = watch_debounce_ms;
+            }
+        }
+
+        if let Ok(max_watches_str) = env::var("DURABLE_MAX_WATCHES") {
+            if let Ok(max_watches) = max_watches_str.trim().parse::<usize>() {
+                config.max_watches = max_watches;
+            }
+        }
+
+        if let Ok(rescan_interval_minutes_str) = env::var("DURABLE_RESCAN_INTERVAL_MINUTES") {
+            if let Ok(rescan_interval_minutes) = rescan_interval_minutes_str.trim().parse::<u32>() {
+                config.rescan_interval_minutes = rescan_interval_minutes;
+            }
+        }
+
         Ok(())
     }
 
@@ -299,6 +545,19 @@ impl ConfigManager {
                 ));
             }
         }
+        if config.abandoned_after_days < config.stale_after_days {
+            return Err(anyhow::anyhow!(
+                "abandoned_after_days must be at least stale_after_days"
+            ));
+        }
+        if config.max_watches == 0 {
+            return Err(anyhow::anyhow!("max_watches must be greater than 0"));
+        }
+        if config.rescan_interval_minutes == 0 {
+            return Err(anyhow::anyhow!(
+                "rescan_interval_minutes must be greater than 0"
+            ));
+        }
         Ok(())
     }
 }
@@ -306,6 +565,7 @@ impl ConfigManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dprojc_types::IndicatorRuleKind;
     use std::io::Write;
     use std::sync::Mutex;
     use tempfile::NamedTempFile;
@@ -359,6 +619,102 @@ mod tests {
         assert!(!config.follow_symlinks);
     }
 
+    #[test]
+    fn test_load_ignore_toggles_from_env() {
+        let _guard = EnvGuard::new(&["DURABLE_RESPECT_GITIGNORE", "DURABLE_RESPECT_DPROJCIGNORE"]);
+
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert!(config.respect_gitignore);
+        assert!(config.respect_dprojcignore);
+
+        env::set_var("DURABLE_RESPECT_GITIGNORE", "false");
+        env::set_var("DURABLE_RESPECT_DPROJCIGNORE", " false ");
+
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert!(!config.respect_gitignore);
+        assert!(!config.respect_dprojcignore);
+    }
+
+    #[test]
+    fn test_load_git_metadata_toggle_from_env() {
+        let _guard = EnvGuard::new(&[
+            "DURABLE_GIT_METADATA",
+            "DURABLE_DISK_USAGE",
+            "DURABLE_CODE_STATS",
+        ]);
+
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert!(config.git_metadata);
+        assert!(!config.disk_usage);
+        assert!(!config.code_stats);
+
+        env::set_var("DURABLE_GIT_METADATA", "false");
+        env::set_var("DURABLE_DISK_USAGE", "true");
+        env::set_var("DURABLE_CODE_STATS", "true");
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert!(!config.git_metadata);
+        assert!(config.disk_usage);
+        assert!(config.code_stats);
+    }
+
+    #[test]
+    fn test_load_advisory_db_from_env() {
+        let _guard = EnvGuard::new(&["DURABLE_ADVISORY_DB"]);
+
+        assert_eq!(
+            ConfigManager::load_from_env_only().unwrap().advisory_db,
+            None
+        );
+
+        env::set_var("DURABLE_ADVISORY_DB", "/srv/advisory-db");
+        assert_eq!(
+            ConfigManager::load_from_env_only().unwrap().advisory_db,
+            Some(PathBuf::from("/srv/advisory-db"))
+        );
+    }
+
+    #[test]
+    fn test_load_staleness_thresholds_from_env() {
+        let _guard = EnvGuard::new(&["DURABLE_STALE_AFTER_DAYS", "DURABLE_ABANDONED_AFTER_DAYS"]);
+
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert_eq!(config.stale_after_days, 90);
+        assert_eq!(config.abandoned_after_days, 365);
+
+        env::set_var("DURABLE_STALE_AFTER_DAYS", "30");
+        env::set_var("DURABLE_ABANDONED_AFTER_DAYS", "not-a-number");
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert_eq!(config.stale_after_days, 30);
+        assert_eq!(config.abandoned_after_days, 365);
+    }
+
+    #[test]
+    fn test_load_watch_settings_from_env() {
+        let _guard = EnvGuard::new(&[
+            "DURABLE_WATCH_ROOTS",
+            "DURABLE_WATCH_DEBOUNCE_MS",
+            "DURABLE_MAX_WATCHES",
+            "DURABLE_RESCAN_INTERVAL_MINUTES",
+        ]);
+
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert!(config.watch_roots.is_empty());
+        assert_eq!(config.watch_debounce_ms, 500);
+
+        env::set_var("DURABLE_WATCH_ROOTS", "/src, /work ,");
+        env::set_var("DURABLE_WATCH_DEBOUNCE_MS", "250");
+        env::set_var("DURABLE_MAX_WATCHES", "100");
+        env::set_var("DURABLE_RESCAN_INTERVAL_MINUTES", "often");
+        let config = ConfigManager::load_from_env_only().unwrap();
+        assert_eq!(
+            config.watch_roots,
+            vec![PathBuf::from("/src"), PathBuf::from("/work")]
+        );
+        assert_eq!(config.watch_debounce_ms, 250);
+        assert_eq!(config.max_watches, 100);
+        assert_eq!(config.rescan_interval_minutes, 60);
+    }
+
     #[test]
     fn test_load_from_env_only() {
         let _guard = EnvGuard::new(&[
@@ -391,6 +747,23 @@ exclude_patterns:
 project_indicators:
   - test_indicator
 follow_symlinks: true
+respect_gitignore: false
+threads: 8
+indicator_rules:
+  - name: poetry
+    file_contains:
+      file: pyproject.toml
+      contains: "[tool.poetry]"
+  - name: rails
+    directory_shape: [Gemfile, app/]
+project_types:
+  - name: terraform
+    priority: 20
+    indicators: ["*.tf"]
+  - name: ansible-role
+    rules:
+      - name: ansible-role
+        directory_shape: [tasks/main.yml, meta/]
 "#;
 
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -410,6 +783,39 @@ follow_symlinks: true
             Some(vec!["test_indicator".to_string()])
         );
         assert_eq!(file_config.follow_symlinks, Some(true));
+        assert_eq!(file_config.respect_gitignore, Some(false));
+        assert_eq!(file_config.respect_dprojcignore, None);
+        assert_eq!(file_config.threads, Some(8));
+        assert_eq!(
+            file_config.indicator_rules,
+            Some(vec![
+                IndicatorRule {
+                    name: "poetry".to_string(),
+                    kind: IndicatorRuleKind::FileContains {
+                        file: "pyproject.toml".to_string(),
+                        contains: "[tool.poetry]".to_string(),
+                    },
+                },
+                IndicatorRule {
+                    name: "rails".to_string(),
+                    kind: IndicatorRuleKind::DirectoryShape(vec![
+                        "Gemfile".to_string(),
+                        "app/".to_string(),
+                    ]),
+                },
+            ])
+        );
+        let project_types = file_config.project_types.unwrap();
+        assert_eq!(project_types.len(), 2);
+        assert_eq!(project_types[0].name, "terraform");
+        assert_eq!(project_types[0].priority, 20);
+        assert_eq!(project_types[0].indicators, vec!["*.tf"]);
+        assert_eq!(project_types[1].name, "ansible-role");
+        assert_eq!(
+            project_types[1].priority,
+            dprojc_types::DEFAULT_CUSTOM_TYPE_PRIORITY
+        );
+        assert_eq!(project_types[1].rules.len(), 1);
     }
 
     #[test]
@@ -420,6 +826,29 @@ follow_symlinks: true
             exclude_patterns: Some(vec!["merged_exclude".to_string()]),
             project_indicators: Some(vec!["merged_indicator".to_string()]),
             follow_symlinks: Some(true),
+            respect_gitignore: Some(false),
+            respect_dprojcignore: None,
+            threads: Some(2),
+            git_metadata: Some(false),
+            disk_usage: Some(true),
+            code_stats: Some(true),
+            advisory_db: Some(PathBuf::from("/srv/advisory-db")),
+            stale_after_days: Some(60),
+            abandoned_after_days: None,
+            watch_roots: Some(vec![PathBuf::from("/src")]),
+            watch_debounce_ms: Some(1000),
+            max_watches: None,
+            rescan_interval_minutes: Some(5),
+            indicator_rules: Some(vec![IndicatorRule {
+                name: "terraform".to_string(),
+                kind: IndicatorRuleKind::Glob("*.tf".to_string()),
+            }]),
+            project_types: Some(vec![ProjectTypeDefinition {
+                name: "helm".to_string(),
+                indicators: vec!["Chart.yaml".to_string()],
+                rules: Vec::new(),
+                priority: 5,
+            }]),
         };
 
         ConfigManager::merge_config_file(&mut config, file_config);
@@ -428,6 +857,23 @@ follow_symlinks: true
         assert_eq!(config.exclude_patterns, vec!["merged_exclude"]);
         assert_eq!(config.project_indicators, vec!["merged_indicator"]);
         assert!(config.follow_symlinks);
+        assert!(!config.respect_gitignore);
+        assert!(config.respect_dprojcignore);
+        assert_eq!(config.threads, 2);
+        assert!(!config.git_metadata);
+        assert!(config.disk_usage);
+        assert!(config.code_stats);
+        assert_eq!(config.advisory_db, Some(PathBuf::from("/srv/advisory-db")));
+        assert_eq!(config.stale_after_days, 60);
+        assert_eq!(config.abandoned_after_days, 365);
+        assert_eq!(config.watch_roots, vec![PathBuf::from("/src")]);
+        assert_eq!(config.watch_debounce_ms, 1000);
+        assert_eq!(config.max_watches, 8192);
+        assert_eq!(config.rescan_interval_minutes, 5);
+        assert_eq!(config.indicator_rules.len(), 1);
+        assert_eq!(config.indicator_rules[0].name, "terraform");
+        assert_eq!(config.project_types.len(), 1);
+        assert_eq!(config.project_types[0].name, "helm");
     }
 
     #[test]
@@ -620,5 +1066,19 @@ project_indicators:
         config.exclude_patterns = vec!["valid".to_string()]; // reset
         config.project_indicators = vec!["valid".to_string(), "   ".to_string()]; // whitespace only
         assert!(ConfigManager::validate_config(&config).is_err());
+
+        // Abandoned threshold below the stale one
+        config.project_indicators = vec!["valid".to_string()]; // reset
+        config.stale_after_days = 400;
+        assert!(ConfigManager::validate_config(&config).is_err());
+        config.abandoned_after_days = 400;
+        assert!(ConfigManager::validate_config(&config).is_ok());
+
+        // Watch budget and rescan interval must be positive
+        config.max_watches = 0;
+        assert!(ConfigManager::validate_config(&config).is_err());
+        config.max_watches = 8192;
+        config.rescan_interval_minutes = 0;
+        assert!(ConfigManager::validate_config(&config).is_err());
     }
 }
@@ -1,11 +1,55 @@
 use dprojc_config::ConfigManager;
 use dprojc_db::ProjectDatabase;
-use dprojc_scanner::{scan_directory_with_config, SharedScanner};
-use dprojc_types::{Project, ProjectType, ScanConfig, ScanResult};
+use dprojc_scanner::{scan_directory_with_config, ProjectScanner, ScanControl, SharedScanner};
+use dprojc_types::{
+    DependencyEcosystem, Dependent, DuplicateGroup, IncrementalScanResult, LanguageCount, Project,
+    ProjectAlias, ProjectType, ScanConfig, ScanDiff, ScanResult, StaleProject, Staleness,
+    VersionSpread,
+};
 use dprojc_types::{ScanResultSummary, ScanStatistics};
 use dprojc_utils::default_db_path;
 use std::path::Path;
 
+mod watch;
+
+pub use watch::CatalogWatcher;
+
+/// Rescan a root incrementally and store the results
+///
+/// The root's directory cache from its previous incremental scan decides
+/// which directories are listed again; the first rescan of a root walks all
+/// of it and records the cache. A rescan cancelled through `control` only
+/// saves the projects it got to, leaving the cache and scan history as they
+/// were.
+pub async fn rescan_and_store(
+    scanner: &ProjectScanner,
+    db: &mut ProjectDatabase,
+    root: &Path,
+    control: &ScanControl,
+) -> anyhow::Result<IncrementalScanResult> {
+    // Directory caches are keyed by the absolute, uncanonicalized root, like scans
+    let root = if root.is_absolute() {
+        root.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(root)
+    };
+    let cache = db.get_directory_cache(&root)?;
+    let known = db.get_project_paths_under(&root)?;
+
+    let rescan = scanner
+        .rescan_with_control(&root, &cache, &known, control)
+        .await?;
+    db.replace_project_types(&scanner.config().project_types)?;
+    if rescan.scan.cancelled {
+        for project in &rescan.scan.projects {
+            db.upsert_project(project)?;
+        }
+    } else {
+        db.store_incremental_scan(&rescan)?;
+    }
+    Ok(rescan)
+}
+
 /// Main catalog struct that orchestrates scanning, database operations, and configuration
 pub struct ProjectCatalog {
     config: ScanConfig,
@@ -59,6 +103,7 @@ impl ProjectCatalog {
     /// Scan a single directory for projects and store results
     pub async fn scan_directory<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<ScanResult> {
         let scan_result = self.scanner.scan(path.as_ref()).await?;
+        self.db.replace_project_types(&self.config.project_types)?;
         self.db.store_scan_result(&scan_result)?;
         Ok(scan_result)
     }
@@ -69,6 +114,7 @@ impl ProjectCatalog {
         paths: &[std::path::PathBuf],
     ) -> anyhow::Result<Vec<ScanResult>> {
         let scan_results = self.scanner.scan_multiple(paths).await?;
+        self.db.replace_project_types(&self.config.project_types)?;
         for result in &scan_results {
             self.db.store_scan_result(result)?;
         }
@@ -88,9 +134,15 @@ impl ProjectCatalog {
         Ok(self.db.get_projects_by_type(project_type)?)
     }
 
-    /// Search projects by path pattern
+    /// Search projects by path or by declared package name, description, authors or license
     pub async fn search_projects(&self, pattern: &str) -> anyhow::Result<Vec<Project>> {
-        Ok(self.db.search_projects_by_path(pattern)?)
+        let mut projects = self.db.search_projects_by_path(pattern)?;
+        for project in self.db.search_projects_by_manifest(pattern)? {
+            if !projects.iter().any(|p| p.path == project.path) {
+                projects.push(project);
+            }
+        }
+        Ok(projects)
     }
 
     /// Get projects by indicator type
@@ -108,6 +160,77 @@ impl ProjectCatalog {
         Ok(self.db.get_project_counts_by_type()?)
     }
 
+    /// Get the number of projects containing each ecosystem
+    pub async fn get_ecosystem_counts(
+        &self,
+    ) -> anyhow::Result<std::collections::HashMap<ProjectType, usize>> {
+        Ok(self.db.get_project_counts_by_ecosystem()?)
+    }
+
+    /// Get the lines of code per language summed over projects counted in a deep scan
+    pub async fn get_language_counts(&self) -> anyhow::Result<Vec<LanguageCount>> {
+        Ok(self.db.get_language_counts()?)
+    }
+
+    /// Classify projects by how long they have been idle, longest idle first
+    ///
+    /// Only projects at least as idle as `min` are returned, so
+    /// `Staleness::Stale` leaves out active projects and `Staleness::Active`
+    /// keeps every project. Thresholds come from the configuration; see
+    /// [`dprojc_utils::assess_staleness`] for the signals used.
+    pub async fn get_stale_projects(&self, min: Staleness) -> anyhow::Result<Vec<StaleProject>> {
+        let projects = self.db.get_all_projects()?;
+        let last_accessed = self.db.get_last_accessed()?;
+        let mut assessed = dprojc_utils::assess_projects(
+            &projects,
+            &last_accessed,
+            &self.config,
+            chrono::Utc::now(),
+        );
+        assessed.retain(|project| project.staleness >= min);
+        Ok(assessed)
+    }
+
+    /// Get the former paths of projects that were moved, most recent move first
+    ///
+    /// A scan that finds a repository with the identity of exactly one stored
+    /// project whose directory is gone keeps that project, with its id and
+    /// access history, at the new path; see [`dprojc_utils::project_identity`].
+    pub async fn get_project_aliases(&self) -> anyhow::Result<Vec<ProjectAlias>> {
+        Ok(self.db.get_project_aliases()?)
+    }
+
+    /// Get the groups of projects that are copies of each other
+    ///
+    /// See [`dprojc_utils::find_duplicates`] for how copies are matched.
+    pub async fn get_duplicates(&self) -> anyhow::Result<Vec<DuplicateGroup>> {
+        let projects = self.db.get_all_projects()?;
+        let frecency = self.db.get_frecency_scores()?;
+        Ok(dprojc_utils::find_duplicates(&projects, &frecency))
+    }
+
+    /// Get the projects whose lockfiles resolve a package, optionally at one version
+    pub async fn get_dependents(
+        &self,
+        name: &str,
+        ecosystem: Option<DependencyEcosystem>,
+        version: Option<&str>,
+    ) -> anyhow::Result<Vec<Dependent>> {
+        Ok(self.db.get_dependents(name, ecosystem, version)?)
+    }
+
+    /// Get the versions packages resolve to across projects
+    ///
+    /// See [`ProjectDatabase::get_version_spread`] for how `min_versions` filters.
+    pub async fn get_version_spread(
+        &self,
+        ecosystem: Option<DependencyEcosystem>,
+        name: Option<&str>,
+        min_versions: usize,
+    ) -> anyhow::Result<Vec<VersionSpread>> {
+        Ok(self.db.get_version_spread(ecosystem, name, min_versions)?)
+    }
+
     /// Get recent scan results
     pub async fn get_recent_scans(&self, limit: usize) -> anyhow::Result<Vec<ScanResultSummary>> {
         Ok(self.db.get_recent_scan_results(limit)?)
@@ -118,6 +241,24 @@ impl ProjectCatalog {
         Ok(self.db.get_scan_statistics()?)
     }
 
+    /// Compare the projects found by two stored scans
+    pub async fn diff_scans(
+        &self,
+        from_scan_id: i64,
+        to_scan_id: i64,
+    ) -> anyhow::Result<Option<ScanDiff>> {
+        Ok(self.db.diff_scans(from_scan_id, to_scan_id)?)
+    }
+
+    /// Compare the two latest scans of a root, by default the root scanned
+    /// last
+    pub async fn diff_latest_scans(&self, root: Option<&Path>) -> anyhow::Result<Option<ScanDiff>> {
+        match self.db.get_latest_scan_pair(root)? {
+            Some((from, to)) => self.diff_scans(from, to).await,
+            None => Ok(None),
+        }
+    }
+
     /// Get a project by path
     pub async fn get_project_by_path<P: AsRef<Path>>(
         &self,
@@ -142,7 +283,9 @@ impl ProjectCatalog {
         self.scanner.set_config(config).await;
     }
 
-    /// Perform an incremental scan (only scan paths that haven't been scanned recently)
+    /// Perform an incremental scan: paths scanned within `max_age_hours` are
+    /// skipped, and the others only have the directories that changed since
+    /// their last incremental scan listed again
     pub async fn incremental_scan(
         &mut self,
         paths: &[std::path::PathBuf],
@@ -156,8 +299,10 @@ impl ProjectCatalog {
             let has_recent_scan = self.db.has_path_been_scanned_recently(path, cutoff_time)?;
 
             if !has_recent_scan {
-                let result = self.scan_directory(path).await?;
-                results.push(result);
+                let scanner = ProjectScanner::with_config(self.config.clone())?;
+                let rescan =
+                    rescan_and_store(&scanner, &mut self.db, path, &ScanControl::default()).await?;
+                results.push(rescan.scan);
             }
         }
 
@@ -331,6 +476,52 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_stale_projects() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+
+        // A file written just now counts as activity
+        assert!(catalog
+            .get_stale_projects(Staleness::Stale)
+            .await
+            .unwrap()
+            .is_empty());
+        let all = catalog.get_stale_projects(Staleness::Active).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].staleness, Staleness::Active);
+        assert_eq!(all[0].idle_days, Some(0));
+        assert!(all[0].signals.last_modified.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_diff_latest_scans() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("app/.git")).unwrap();
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+        assert!(catalog.diff_latest_scans(None).await.unwrap().is_none());
+
+        fs::create_dir(temp_dir.path().join("lib")).unwrap();
+        fs::write(temp_dir.path().join("lib/Cargo.toml"), "[package]").unwrap();
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+
+        let diff = catalog
+            .diff_latest_scans(Some(temp_dir.path()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, temp_dir.path().join("lib"));
+        assert_eq!(diff.added[0].project_type, ProjectType::Rust);
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
     #[tokio::test]
     async fn test_project_counts() {
         let (mut catalog, _temp_dir) = create_test_catalog().await;
@@ -391,6 +582,36 @@ mod tests {
         assert_eq!(results2.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_cancelled_rescan_saves_projects_only() {
+        let db_dir = tempdir().unwrap();
+        let mut db = ProjectDatabase::open(db_dir.path().join("test.db")).unwrap();
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        for name in ["api", "web", "tools"] {
+            fs::create_dir_all(temp_dir.path().join(name).join("src")).unwrap();
+        }
+        let scanner = ProjectScanner::new().unwrap();
+
+        // Cancelled once the root is reported, after it was examined
+        let token = dprojc_scanner::CancellationToken::new();
+        let control = ScanControl::new()
+            .with_cancellation(token.clone())
+            .with_progress(move |_: &dprojc_types::ScanProgress| token.cancel());
+        let rescan = rescan_and_store(&scanner, &mut db, temp_dir.path(), &control)
+            .await
+            .unwrap();
+        assert!(rescan.scan.cancelled);
+
+        assert!(db.get_project_by_path(temp_dir.path()).unwrap().is_some());
+        assert!(db.get_recent_scan_results(10).unwrap().is_empty());
+        assert!(db
+            .get_directory_cache(temp_dir.path())
+            .unwrap()
+            .directories
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_export_projects_json() {
         let (mut catalog, _temp_dir) = create_test_catalog().await;
@@ -848,6 +1069,63 @@ mod tests {
         assert_eq!(results.len(), 1); // Case insensitive
     }
 
+    #[tokio::test]
+    async fn test_dependency_queries() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        for (name, version) in [("api", "1.0.100"), ("cli", "1.0.200")] {
+            let project_path = temp_dir.path().join(name);
+            fs::create_dir(&project_path).unwrap();
+            fs::write(project_path.join("Cargo.toml"), "[package]").unwrap();
+            fs::write(
+                project_path.join("Cargo.lock"),
+                format!(
+                    "[[package]]\nname = \"serde\"\nversion = \"{}\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+                    version
+                ),
+            )
+            .unwrap();
+        }
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+
+        let dependents = catalog
+            .get_dependents("serde", Some(DependencyEcosystem::Cargo), None)
+            .await
+            .unwrap();
+        assert_eq!(dependents.len(), 2);
+        assert_eq!(dependents[0].path, temp_dir.path().join("api"));
+
+        let spread = catalog.get_version_spread(None, None, 2).await.unwrap();
+        assert_eq!(spread.len(), 1);
+        assert_eq!(spread[0].versions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_projects_by_manifest() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path().join("svc");
+        fs::create_dir(&project_path).unwrap();
+        fs::write(
+            project_path.join("Cargo.toml"),
+            "[package]\nname = \"ledger\"\ndescription = \"Double-entry bookkeeping\"\n",
+        )
+        .unwrap();
+
+        catalog.scan_directory(&project_path).await.unwrap();
+
+        let results = catalog.search_projects("ledger").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name(), "ledger");
+        let results = catalog.search_projects("bookkeeping").await.unwrap();
+        assert_eq!(results.len(), 1);
+        // A path match is not listed twice
+        let results = catalog.search_projects("svc").await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_project_counts_comprehensive() {
         let (mut catalog, _temp_dir) = create_test_catalog().await;
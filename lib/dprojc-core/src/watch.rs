@@ -0,0 +1,505 @@
+//! Keeping the catalog current while the watched roots change
+
+use dprojc_db::ProjectDatabase;
+use dprojc_scanner::{DirChange, DirectoryWatcher, ProjectScanner, ScanControl};
+use dprojc_types::{CatalogChange, Project, ScanConfig};
+use dprojc_utils::assign_workspace_parents;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Directories that changed since the catalog was last brought up to date
+#[derive(Default)]
+struct PendingChanges {
+    entries: BTreeSet<PathBuf>,
+    created: BTreeSet<PathBuf>,
+    removed: BTreeSet<PathBuf>,
+    overflow: bool,
+    last_seen: Option<Instant>,
+}
+
+/// Keeps the projects below a set of roots current in the database
+///
+/// Roots are watched directory by directory while the `max_watches` budget
+/// and the system limit last. Roots that don't fit are rescanned every
+/// `rescan_interval_minutes` instead.
+pub struct CatalogWatcher {
+    config: ScanConfig,
+    scanner: ProjectScanner,
+    watcher: DirectoryWatcher,
+    watched: Vec<PathBuf>,
+    /// Roots that are rescanned, with the time their next rescan is due
+    polled: HashMap<PathBuf, Instant>,
+    pending: PendingChanges,
+}
+
+impl CatalogWatcher {
+    /// Bring the roots up to date and start watching them
+    ///
+    /// Roots that have not been scanned within the rescan interval are
+    /// scanned first. Returns the changes made along the way.
+    pub async fn start(
+        config: ScanConfig,
+        roots: &[PathBuf],
+        db: &mut ProjectDatabase,
+    ) -> anyhow::Result<(Self, Vec<CatalogChange>)> {
+        let watcher = DirectoryWatcher::new(config.max_watches)?;
+        let mut catalog_watcher = Self {
+            scanner: ProjectScanner::with_config(config.clone())?,
+            config,
+            watcher,
+            watched: Vec::new(),
+            polled: HashMap::new(),
+            pending: PendingChanges::default(),
+        };
+
+        let cutoff = chrono::Utc::now()
+            - chrono::Duration::minutes(catalog_watcher.config.rescan_interval_minutes as i64);
+        let mut changes = Vec::new();
+        for root in roots {
+            // Scan results record the absolute, uncanonicalized root
+            let root = if root.is_absolute() {
+                root.clone()
+            } else {
+                std::env::current_dir()?.join(root)
+            };
+            if !root.is_dir() {
+                return Err(anyhow::anyhow!(
+                    "{} is not a directory",
+                    dprojc_utils::format_path_display(&root)
+                ));
+            }
+            if !db.has_path_been_scanned_recently(&root, cutoff)? {
+                changes.extend(catalog_watcher.rescan(&root, db).await?);
+            }
+            catalog_watcher.add_root(root);
+        }
+
+        Ok((catalog_watcher, changes))
+    }
+
+    /// Watch a root, or rescan it periodically when it can't be watched
+    fn add_root(&mut self, root: PathBuf) {
+        if self.watcher.watch_tree(&root, &root, &self.config).is_ok() {
+            self.watched.push(root);
+        } else {
+            self.polled
+                .insert(root, Instant::now() + self.rescan_interval());
+        }
+    }
+
+    fn rescan_interval(&self) -> Duration {
+        Duration::from_secs(self.config.rescan_interval_minutes as u64 * 60)
+    }
+
+    /// Number of directories being watched
+    pub fn watched_dirs(&self) -> usize {
+        self.watcher.len()
+    }
+
+    /// Roots kept current by watching their directories
+    pub fn watched_roots(&self) -> &[PathBuf] {
+        &self.watched
+    }
+
+    /// Roots kept current by periodic rescans, ordered by path
+    pub fn polled_roots(&self) -> Vec<&Path> {
+        let mut roots: Vec<&Path> = self.polled.keys().map(PathBuf::as_path).collect();
+        roots.sort();
+        roots
+    }
+
+    /// Apply the changes seen since the last call
+    ///
+    /// File system changes are applied once none arrived for
+    /// `watch_debounce_ms`, so a checkout or build touching many files causes
+    /// one update. Polled roots are rescanned when due.
+    pub async fn poll(&mut self, db: &mut ProjectDatabase) -> anyhow::Result<Vec<CatalogChange>> {
+        let changes = self.watcher.read_changes();
+        self.collect_changes(changes);
+
+        let mut changes = Vec::new();
+        let debounce = Duration::from_millis(self.config.watch_debounce_ms);
+        if self
+            .pending
+            .last_seen
+            .is_some_and(|seen| seen.elapsed() >= debounce)
+        {
+            let pending = std::mem::take(&mut self.pending);
+            changes.extend(self.apply(pending, db).await?);
+        }
+
+        let now = Instant::now();
+        let mut due: Vec<PathBuf> = self
+            .polled
+            .iter()
+            .filter(|(_, due)| **due <= now)
+            .map(|(root, _)| root.clone())
+            .collect();
+        due.sort();
+        for root in due {
+            changes.extend(self.rescan(&root, db).await?);
+            self.polled
+                .insert(root, Instant::now() + self.rescan_interval());
+        }
+
+        Ok(changes)
+    }
+
+    /// Wait until changes settle or a polled root is due, then apply them
+    /// like [`CatalogWatcher::poll`]
+    pub async fn next_changes(
+        &mut self,
+        db: &mut ProjectDatabase,
+    ) -> anyhow::Result<Vec<CatalogChange>> {
+        loop {
+            let deadline = self.next_deadline();
+            if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                return self.poll(db).await;
+            }
+            let sleep = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let changes = tokio::select! {
+                changes = self.watcher.changes() => changes?,
+                () = sleep => Vec::new(),
+            };
+            self.collect_changes(changes);
+        }
+    }
+
+    /// Keep the catalog current until the returned future is dropped,
+    /// passing each change to `on_change`
+    pub async fn run<F>(&mut self, db: &mut ProjectDatabase, mut on_change: F) -> anyhow::Result<()>
+    where
+        F: FnMut(&CatalogChange),
+    {
+        loop {
+            for change in self.next_changes(db).await? {
+                on_change(&change);
+            }
+        }
+    }
+
+    /// When pending changes have settled or the next polled root is due
+    fn next_deadline(&self) -> Option<Instant> {
+        let debounce = Duration::from_millis(self.config.watch_debounce_ms);
+        let settled = self.pending.last_seen.map(|seen| seen + debounce);
+        settled
+            .into_iter()
+            .chain(self.polled.values().copied())
+            .min()
+    }
+
+    /// Move the reported directory changes into the pending set
+    fn collect_changes(&mut self, changes: Vec<DirChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        let watcher = &mut self.watcher;
+
+        let mut over_budget = Vec::new();
+        for change in changes {
+            match change {
+                DirChange::Entries(dir) => {
+                    self.pending.entries.insert(dir);
+                }
+                DirChange::Created(dir) => {
+                    // Watch new directories right away so nothing created
+                    // inside them is missed
+                    if let Some(root) = root_of(&self.watched, &dir) {
+                        if watcher.watch_tree(&root, &dir, &self.config).is_err() {
+                            over_budget.push(root);
+                        }
+                    }
+                    self.pending.created.insert(dir);
+                }
+                DirChange::Removed(dir) => {
+                    watcher.unwatch_tree(&dir);
+                    self.pending.removed.insert(dir);
+                }
+                DirChange::Overflow => self.pending.overflow = true,
+            }
+        }
+
+        // A root that outgrew the budget is rescanned from now on, starting
+        // with the next poll
+        for root in over_budget {
+            self.watcher.unwatch_tree(&root);
+            self.watched.retain(|watched| watched != &root);
+            self.polled.insert(root, Instant::now());
+        }
+
+        self.pending.last_seen = Some(Instant::now());
+    }
+
+    /// Update the catalog for the directories that changed
+    async fn apply(
+        &mut self,
+        pending: PendingChanges,
+        db: &mut ProjectDatabase,
+    ) -> anyhow::Result<Vec<CatalogChange>> {
+        let mut changes = Vec::new();
+
+        // Events were dropped, so only a rescan can tell what changed
+        if pending.overflow {
+            for root in self.watched.clone() {
+                changes.extend(self.rescan(&root, db).await?);
+            }
+            return Ok(changes);
+        }
+
+        // New directories go first so a project moved within a root is
+        // recorded at its new path before its old path is cleaned up
+        for dir in outermost(&pending.created) {
+            let Some(root) = root_of(&self.watched, dir) else {
+                continue;
+            };
+            let projects = self.scanner.detect_projects_under(&root, dir)?;
+            changes.extend(store_projects(&root, projects, db)?);
+        }
+
+        for dir in &pending.entries {
+            if pending
+                .created
+                .iter()
+                .any(|created| dir.starts_with(created))
+            {
+                continue;
+            }
+            let Some(root) = root_of(&self.watched, dir) else {
+                continue;
+            };
+            match self.scanner.detect_project(&root, dir)? {
+                Some(project) => changes.extend(store_projects(&root, vec![project], db)?),
+                None => {
+                    if db.delete_project_by_path(dir)? {
+                        changes.push(CatalogChange::Removed(dir.clone()));
+                    }
+                }
+            }
+        }
+
+        for dir in outermost(&pending.removed) {
+            // Moved away and back again before the changes settled
+            if dir.exists() {
+                continue;
+            }
+            changes.extend(
+                db.delete_projects_under(dir)?
+                    .into_iter()
+                    .map(CatalogChange::Removed),
+            );
+        }
+
+        Ok(changes)
+    }
+
+    /// Rescan a root, listing again only the directories that changed since
+    /// its last rescan, and remove the projects below it that are gone
+    async fn rescan(
+        &self,
+        root: &Path,
+        db: &mut ProjectDatabase,
+    ) -> anyhow::Result<Vec<CatalogChange>> {
+        let known: HashSet<PathBuf> = db.get_project_paths_under(root)?.into_iter().collect();
+        let rescan =
+            crate::rescan_and_store(&self.scanner, db, root, &ScanControl::default()).await?;
+
+        let mut changes: Vec<CatalogChange> = rescan
+            .scan
+            .projects
+            .iter()
+            .filter(|project| !known.contains(&project.path))
+            .map(|project| CatalogChange::Added(project.path.clone()))
+            .collect();
+        changes.extend(rescan.removed.into_iter().map(CatalogChange::Removed));
+        changes.push(CatalogChange::Rescanned {
+            root: root.to_path_buf(),
+            projects: rescan.scan.projects.len(),
+        });
+        Ok(changes)
+    }
+}
+
+/// The innermost watched root containing a directory
+fn root_of(roots: &[PathBuf], dir: &Path) -> Option<PathBuf> {
+    roots
+        .iter()
+        .filter(|root| dir.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .cloned()
+}
+
+/// The directories that are not below another one in the set
+fn outermost(dirs: &BTreeSet<PathBuf>) -> impl Iterator<Item = &PathBuf> {
+    dirs.iter().filter(move |dir| {
+        !dirs
+            .iter()
+            .any(|other| other != *dir && dir.starts_with(other))
+    })
+}
+
+/// Store detected projects, linking them to workspaces already in the catalog
+fn store_projects(
+    root: &Path,
+    mut projects: Vec<Project>,
+    db: &mut ProjectDatabase,
+) -> anyhow::Result<Vec<CatalogChange>> {
+    let detected = projects.len();
+
+    // Workspace roots above the detected projects are only known from the
+    // catalog, so they join the list while parents are assigned
+    let mut ancestors = BTreeSet::new();
+    for project in &projects {
+        for ancestor in project.path.ancestors().skip(1) {
+            if !ancestor.starts_with(root) {
+                break;
+            }
+            ancestors.insert(ancestor.to_path_buf());
+        }
+    }
+    for ancestor in ancestors {
+        if projects.iter().any(|project| project.path == ancestor) {
+            continue;
+        }
+        if let Some(project) = db.get_project_by_path(&ancestor)? {
+            projects.push(project);
+        }
+    }
+    assign_workspace_parents(&mut projects);
+    projects.truncate(detected);
+
+    let mut changes = Vec::new();
+    for project in projects {
+        let known = db.get_project_by_path(&project.path)?.is_some();
+        db.upsert_project(&project)?;
+        changes.push(if known {
+            CatalogChange::Updated(project.path)
+        } else {
+            CatalogChange::Added(project.path)
+        });
+    }
+    Ok(changes)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn watch_config() -> ScanConfig {
+        ScanConfig {
+            watch_debounce_ms: 0,
+            ..ScanConfig::default()
+        }
+    }
+
+    /// Apply changes until all of `expected` were made
+    async fn wait_for(
+        watcher: &mut CatalogWatcher,
+        db: &mut ProjectDatabase,
+        expected: &[CatalogChange],
+    ) {
+        let mut changes = Vec::new();
+        while !expected.iter().all(|change| changes.contains(change)) {
+            let more = tokio::time::timeout(Duration::from_secs(10), watcher.next_changes(db))
+                .await
+                .expect("no change made in time");
+            changes.extend(more.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_catalog_watcher_follows_changes() {
+        let db_dir = tempdir().unwrap();
+        let mut db = ProjectDatabase::open(db_dir.path().join("test.db")).unwrap();
+        let temp = tempdir().unwrap();
+        let root = temp.path().to_path_buf();
+        fs::create_dir(root.join("app")).unwrap();
+        fs::write(root.join("app/Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let (mut watcher, changes) =
+            CatalogWatcher::start(watch_config(), std::slice::from_ref(&root), &mut db)
+                .await
+                .unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                CatalogChange::Added(root.join("app")),
+                CatalogChange::Rescanned {
+                    root: root.clone(),
+                    projects: 1
+                },
+            ]
+        );
+        assert_eq!(watcher.watched_roots(), std::slice::from_ref(&root));
+        assert!(watcher.polled_roots().is_empty());
+
+        // A second start finds the root freshly scanned
+        let (_, changes) =
+            CatalogWatcher::start(watch_config(), std::slice::from_ref(&root), &mut db)
+                .await
+                .unwrap();
+        assert!(changes.is_empty());
+
+        fs::create_dir(root.join("lib")).unwrap();
+        fs::write(root.join("lib/package.json"), "{}").unwrap();
+        fs::write(root.join("app/README.md"), "# app\n").unwrap();
+        wait_for(
+            &mut watcher,
+            &mut db,
+            &[
+                CatalogChange::Added(root.join("lib")),
+                CatalogChange::Updated(root.join("app")),
+            ],
+        )
+        .await;
+
+        fs::rename(root.join("lib"), root.join("web")).unwrap();
+        fs::remove_file(root.join("app/Cargo.toml")).unwrap();
+        wait_for(
+            &mut watcher,
+            &mut db,
+            &[
+                CatalogChange::Added(root.join("web")),
+                CatalogChange::Removed(root.join("lib")),
+                CatalogChange::Removed(root.join("app")),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            db.get_project_paths_under(&root).unwrap(),
+            vec![root.join("web")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_catalog_watcher_polls_roots_over_budget() {
+        let db_dir = tempdir().unwrap();
+        let mut db = ProjectDatabase::open(db_dir.path().join("test.db")).unwrap();
+        let temp = tempdir().unwrap();
+        let root = temp.path().to_path_buf();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+
+        let config = ScanConfig {
+            max_watches: 2,
+            ..watch_config()
+        };
+        let (mut watcher, _) = CatalogWatcher::start(config, std::slice::from_ref(&root), &mut db)
+            .await
+            .unwrap();
+        assert!(watcher.watched_roots().is_empty());
+        assert_eq!(watcher.polled_roots(), vec![root.as_path()]);
+        assert_eq!(watcher.watched_dirs(), 0);
+
+        // The next rescan is not due for an hour
+        fs::write(root.join("a/Cargo.toml"), "[package]\n").unwrap();
+        assert!(watcher.poll(&mut db).await.unwrap().is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 use dprojc_types::{
-    Project, ProjectIndicator, ProjectType, ScanError, ScanResult, ScanResultSummary,
-    ScanStatistics,
+    AutomationConfig, AutomationKind, CodeStats, Dependency, DependencyEcosystem, Dependent,
+    DirectoryCache, DirectoryState, DiskUsage, GitMetadata, GitRemote, IncrementalScanResult,
+    LanguageCount, LanguageStats, LicenseDetection, Project, ProjectAlias, ProjectIndicator,
+    ProjectManifest, ProjectType, ProjectTypeDefinition, ScanDiff, ScanError, ScanResult,
+    ScanResultSummary, ScanStatistics, ScannedProject, ToolchainCount, ToolchainPin, VersionSpread,
 };
 use dprojc_utils::default_db_path;
 use rusqlite::{params, Connection, OptionalExtension, Transaction};
@@ -30,8 +33,11 @@ pub enum DatabaseError {
 /// Result type for database operations
 pub type Result<T> = std::result::Result<T, DatabaseError>;
 
+/// A project row: id, path, primary type and last scan time
+type ProjectRow = (i64, String, ProjectType, chrono::DateTime<chrono::Utc>);
+
 /// Database schema version
-const CURRENT_SCHEMA_VERSION: i32 = 2;
+const CURRENT_SCHEMA_VERSION: i32 = 5;
 
 /// Database schema definitions
 mod schema {
@@ -56,6 +62,145 @@ mod schema {
         )
     "#;
 
+    pub const CREATE_ECOSYSTEMS_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_ecosystems (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            project_type TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_PARENTS_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_parents (
+            project_id INTEGER PRIMARY KEY,
+            parent_path TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_GIT_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_git (
+            project_id INTEGER PRIMARY KEY,
+            branch TEXT,
+            head_commit TEXT,
+            head_date TEXT,
+            upstream TEXT,
+            ahead INTEGER NOT NULL DEFAULT 0,
+            behind INTEGER NOT NULL DEFAULT 0,
+            dirty_count INTEGER NOT NULL DEFAULT 0,
+            untracked_count INTEGER NOT NULL DEFAULT 0,
+            stash_count INTEGER NOT NULL DEFAULT 0,
+            is_worktree BOOLEAN NOT NULL DEFAULT 0,
+            is_submodule BOOLEAN NOT NULL DEFAULT 0,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_GIT_REMOTES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_git_remotes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_MANIFESTS_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_manifests (
+            project_id INTEGER PRIMARY KEY,
+            source TEXT NOT NULL,
+            name TEXT,
+            version TEXT,
+            description TEXT,
+            authors TEXT NOT NULL DEFAULT '[]',
+            license TEXT,
+            homepage TEXT,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_DEPENDENCIES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_dependencies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            ecosystem TEXT NOT NULL,
+            name TEXT NOT NULL,
+            version TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_TOOLCHAINS_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_toolchains (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            tool TEXT NOT NULL,
+            version TEXT NOT NULL,
+            source TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_AUTOMATION_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_automation (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            path TEXT NOT NULL,
+            targets TEXT NOT NULL DEFAULT '[]',
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_LICENSES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_licenses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            source TEXT NOT NULL,
+            declared TEXT,
+            spdx TEXT,
+            confidence INTEGER NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_DISK_USAGE_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_disk_usage (
+            project_id INTEGER PRIMARY KEY,
+            total_bytes INTEGER NOT NULL,
+            source_bytes INTEGER NOT NULL,
+            artifact_bytes INTEGER NOT NULL,
+            artifact_dirs TEXT NOT NULL DEFAULT '[]',
+            measured_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_CODE_STATS_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_code_stats (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            language TEXT NOT NULL,
+            files INTEGER NOT NULL,
+            code INTEGER NOT NULL,
+            comments INTEGER NOT NULL,
+            blanks INTEGER NOT NULL,
+            measured_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
+    pub const CREATE_PROJECT_ALIASES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_aliases (
+            path TEXT PRIMARY KEY,
+            project_id INTEGER NOT NULL,
+            moved_at TEXT NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )
+    "#;
+
     pub const CREATE_SCAN_RESULTS_TABLE: &str = r#"
         CREATE TABLE IF NOT EXISTS scan_results (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -91,9 +236,40 @@ mod schema {
         CREATE TABLE IF NOT EXISTS scan_projects (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             scan_result_id INTEGER NOT NULL,
-            project_id INTEGER NOT NULL,
+            project_id INTEGER,
+            path TEXT NOT NULL,
+            project_type TEXT NOT NULL,
+            indicators TEXT NOT NULL,
             FOREIGN KEY (scan_result_id) REFERENCES scan_results (id) ON DELETE CASCADE,
-            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE SET NULL
+        )
+    "#;
+
+    pub const CREATE_PROJECT_TYPES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS project_types (
+            name TEXT PRIMARY KEY,
+            priority INTEGER NOT NULL,
+            definition TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+    "#;
+
+    pub const CREATE_DIRECTORY_CACHES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS directory_caches (
+            root_path TEXT PRIMARY KEY,
+            fingerprint TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+    "#;
+
+    pub const CREATE_CACHED_DIRECTORIES_TABLE: &str = r#"
+        CREATE TABLE IF NOT EXISTS cached_directories (
+            root_path TEXT NOT NULL,
+            path TEXT NOT NULL,
+            mtime_ns INTEGER,
+            excluded INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (root_path, path),
+            FOREIGN KEY (root_path) REFERENCES directory_caches (root_path) ON DELETE CASCADE
         )
     "#;
 
@@ -124,6 +300,64 @@ mod schema {
         CREATE INDEX IF NOT EXISTS idx_indicators_project ON project_indicators (project_id)
     "#;
 
+    pub const CREATE_ECOSYSTEMS_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_ecosystems_project ON project_ecosystems (project_id)
+    "#;
+
+    pub const CREATE_ECOSYSTEMS_TYPE_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_ecosystems_type ON project_ecosystems (project_type)
+    "#;
+
+    pub const CREATE_PROJECT_PARENTS_PARENT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_parents_parent ON project_parents (parent_path)
+    "#;
+
+    pub const CREATE_PROJECT_GIT_REMOTES_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_git_remotes_project ON project_git_remotes (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_MANIFESTS_NAME_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_manifests_name ON project_manifests (name)
+    "#;
+
+    pub const CREATE_PROJECT_DEPENDENCIES_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_dependencies_project ON project_dependencies (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_DEPENDENCIES_PACKAGE_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_dependencies_package
+        ON project_dependencies (name COLLATE NOCASE, ecosystem)
+    "#;
+
+    pub const CREATE_PROJECT_TOOLCHAINS_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_toolchains_project ON project_toolchains (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_TOOLCHAINS_TOOL_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_toolchains_tool ON project_toolchains (tool, version)
+    "#;
+
+    pub const CREATE_PROJECT_AUTOMATION_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_automation_project ON project_automation (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_LICENSES_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_licenses_project ON project_licenses (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_DISK_USAGE_ARTIFACT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_disk_usage_artifact
+        ON project_disk_usage (artifact_bytes)
+    "#;
+
+    pub const CREATE_PROJECT_CODE_STATS_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_code_stats_project ON project_code_stats (project_id)
+    "#;
+
+    pub const CREATE_PROJECT_ALIASES_PROJECT_INDEX: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_project_aliases_project ON project_aliases (project_id)
+    "#;
+
     pub const CREATE_SCAN_RESULTS_TIMESTAMP_INDEX: &str = r#"
         CREATE INDEX IF NOT EXISTS idx_scan_results_timestamp ON scan_results (scan_timestamp)
     "#;
@@ -180,10 +414,37 @@ impl ProjectDatabase {
         self.conn.execute(schema::CREATE_MIGRATIONS_TABLE, [])?;
         self.conn.execute(schema::CREATE_PROJECTS_TABLE, [])?;
         self.conn.execute(schema::CREATE_INDICATORS_TABLE, [])?;
+        self.conn.execute(schema::CREATE_ECOSYSTEMS_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_PARENTS_TABLE, [])?;
+        self.conn.execute(schema::CREATE_PROJECT_GIT_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_GIT_REMOTES_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_MANIFESTS_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_DEPENDENCIES_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_TOOLCHAINS_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_AUTOMATION_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_LICENSES_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_DISK_USAGE_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_CODE_STATS_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_ALIASES_TABLE, [])?;
         self.conn.execute(schema::CREATE_SCAN_RESULTS_TABLE, [])?;
         self.conn.execute(schema::CREATE_SCAN_ERRORS_TABLE, [])?;
         self.conn.execute(schema::CREATE_EXCLUDED_DIRS_TABLE, [])?;
         self.conn.execute(schema::CREATE_SCAN_PROJECTS_TABLE, [])?;
+        self.conn.execute(schema::CREATE_PROJECT_TYPES_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_DIRECTORY_CACHES_TABLE, [])?;
+        self.conn
+            .execute(schema::CREATE_CACHED_DIRECTORIES_TABLE, [])?;
 
         // Create indexes
         self.conn.execute(schema::CREATE_PROJECTS_PATH_INDEX, [])?;
@@ -191,6 +452,34 @@ impl ProjectDatabase {
 
         self.conn
             .execute(schema::CREATE_INDICATORS_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_ECOSYSTEMS_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_ECOSYSTEMS_TYPE_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_PARENTS_PARENT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_GIT_REMOTES_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_MANIFESTS_NAME_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_DEPENDENCIES_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_DEPENDENCIES_PACKAGE_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_TOOLCHAINS_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_TOOLCHAINS_TOOL_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_AUTOMATION_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_LICENSES_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_DISK_USAGE_ARTIFACT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_CODE_STATS_PROJECT_INDEX, [])?;
+        self.conn
+            .execute(schema::CREATE_PROJECT_ALIASES_PROJECT_INDEX, [])?;
         self.conn
             .execute(schema::CREATE_SCAN_RESULTS_TIMESTAMP_INDEX, [])?;
         self.conn
@@ -250,6 +539,81 @@ impl ProjectDatabase {
             })?;
         }
 
+        if current_version < 3 {
+            self.run_migration("add_git_root_commit", || {
+                self.conn
+                    .execute("ALTER TABLE project_git ADD COLUMN root_commit TEXT", [])?;
+                Ok(())
+            })?;
+        }
+
+        if current_version < 4 {
+            self.run_migration("add_project_identity", || {
+                // Location-independent key used to follow moved projects
+                self.conn
+                    .execute("ALTER TABLE projects ADD COLUMN identity TEXT", [])?;
+                self.conn.execute(
+                    "CREATE INDEX IF NOT EXISTS idx_projects_identity ON projects(identity)",
+                    [],
+                )?;
+                Ok(())
+            })?;
+        }
+
+        if current_version < 5 {
+            self.run_migration("snapshot_scan_projects", || {
+                // Projects are updated in place and deleted once gone, so each
+                // scan keeps its own copy of what it found for comparing scans
+                // later. SQLite cannot change a foreign key in place, so the
+                // table is rebuilt, filling in the copies of earlier scans.
+                self.conn.execute(
+                    "ALTER TABLE scan_projects RENAME TO scan_projects_old",
+                    [],
+                )?;
+                self.conn.execute(schema::CREATE_SCAN_PROJECTS_TABLE, [])?;
+                let mut stmt = self.conn.prepare(
+                    r#"
+                    SELECT sp.id, sp.scan_result_id, p.id, p.path, p.project_type
+                    FROM scan_projects_old sp
+                    INNER JOIN projects p ON p.id = sp.project_id
+                    "#,
+                )?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, i64>(1)?,
+                            row.get::<_, i64>(2)?,
+                            row.get::<_, String>(3)?,
+                            row.get::<_, String>(4)?,
+                        ))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                for (id, scan_result_id, project_id, path, project_type) in rows {
+                    self.conn.execute(
+                        r#"
+                        INSERT INTO scan_projects (id, scan_result_id, project_id, path, project_type, indicators)
+                        VALUES (?, ?, ?, ?, ?, ?)
+                        "#,
+                        params![
+                            id,
+                            scan_result_id,
+                            project_id,
+                            path,
+                            project_type,
+                            serde_json::to_string(&self.get_indicators(project_id)?)?
+                        ],
+                    )?;
+                }
+                self.conn.execute("DROP TABLE scan_projects_old", [])?;
+                self.conn
+                    .execute(schema::CREATE_SCAN_PROJECTS_SCAN_RESULT_INDEX, [])?;
+                self.conn
+                    .execute(schema::CREATE_SCAN_PROJECTS_PROJECT_INDEX, [])?;
+                Ok(())
+            })?;
+        }
+
         Ok(())
     }
 
@@ -309,21 +673,41 @@ impl ProjectDatabase {
     pub fn upsert_project(&mut self, project: &Project) -> Result<i64> {
         let tx = self.conn.transaction()?;
 
-        // Insert or replace project
-        tx.execute(
+        // Disk usage and code stats are only measured on request, so a scan
+        // without them keeps the last figures
+        let disk_usage = match &project.disk_usage {
+            Some(usage) => Some(usage.clone()),
+            None => Self::stored_disk_usage(&tx, &project.path)?,
+        };
+        let code_stats = match &project.code_stats {
+            Some(stats) => Some(stats.clone()),
+            None => Self::stored_code_stats(&tx, &project.path)?,
+        };
+
+        // Insert the project or update it in place, keeping its id and the
+        // access history recorded for frecency
+        let identity = dprojc_utils::project_identity(project);
+        Self::follow_move(&tx, &project.path, identity.as_deref())?;
+        let project_id: i64 = tx.query_row(
             r#"
-            INSERT OR REPLACE INTO projects (path, project_type, last_scanned, updated_at)
-            VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+            INSERT INTO projects (path, project_type, last_scanned, identity, updated_at)
+            VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT (path) DO UPDATE SET
+                project_type = excluded.project_type,
+                last_scanned = excluded.last_scanned,
+                identity = excluded.identity,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING id
             "#,
             params![
                 project.path.to_string_lossy(),
                 serde_json::to_string(&project.project_type)?,
-                project.last_scanned.to_rfc3339()
+                project.last_scanned.to_rfc3339(),
+                identity
             ],
+            |row| row.get(0),
         )?;
 
-        let project_id = tx.last_insert_rowid();
-
         // Delete existing indicators for this project
         tx.execute(
             "DELETE FROM project_indicators WHERE project_id = ?",
@@ -346,272 +730,1240 @@ impl ProjectDatabase {
             )?;
         }
 
+        Self::write_ecosystems(&tx, project_id, &project.ecosystems)?;
+        Self::write_parent(&tx, project_id, project.parent.as_deref())?;
+        Self::write_git(&tx, project_id, project.git.as_ref())?;
+        Self::write_manifest(&tx, project_id, project.manifest.as_ref())?;
+        Self::write_dependencies(&tx, project_id, &project.dependencies)?;
+        Self::write_toolchains(&tx, project_id, &project.toolchains)?;
+        Self::write_automation(&tx, project_id, &project.automation)?;
+        Self::write_licenses(&tx, project_id, &project.licenses)?;
+        Self::write_disk_usage(&tx, project_id, disk_usage.as_ref())?;
+        Self::write_code_stats(&tx, project_id, code_stats.as_ref())?;
+
         tx.commit()?;
         Ok(project_id)
     }
 
-    /// Get a project by path
-    pub fn get_project_by_path<P: AsRef<Path>>(&self, path: P) -> Result<Option<Project>> {
-        let path_str = path.as_ref().to_string_lossy();
+    /// Move the row of a project found at a new path, keeping its id and history
+    ///
+    /// When nothing is stored at `path` and exactly one row with the same
+    /// identity points at a directory that no longer exists, that row is taken
+    /// over and its old path kept as an alias. Several such rows leave it
+    /// unclear which one moved, so none is. A path that holds a project again
+    /// stops being an alias.
+    fn follow_move(conn: &Connection, path: &Path, identity: Option<&str>) -> Result<()> {
+        let path_str = path.to_string_lossy();
+        conn.execute(
+            "DELETE FROM project_aliases WHERE path = ?",
+            params![path_str],
+        )?;
+        let Some(identity) = identity else {
+            return Ok(());
+        };
+        let stored: bool = conn.query_row(
+            "SELECT COUNT(*) FROM projects WHERE path = ?",
+            params![path_str],
+            |row| Ok(row.get::<_, i64>(0)? > 0),
+        )?;
+        if stored {
+            return Ok(());
+        }
+
+        let mut stmt = conn.prepare("SELECT id, path FROM projects WHERE identity = ?")?;
+        let mut missing = stmt
+            .query_map(params![identity], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .filter(|candidate| {
+                candidate
+                    .as_ref()
+                    .map_or(true, |(_, old_path)| !Path::new(old_path).exists())
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if missing.len() != 1 {
+            return Ok(());
+        }
+        let (project_id, old_path) = missing.remove(0);
+
+        conn.execute(
+            "UPDATE projects SET path = ? WHERE id = ?",
+            params![path_str, project_id],
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO project_aliases (path, project_id, moved_at) VALUES (?, ?, ?)",
+            params![old_path, project_id, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
 
+    /// Get every former path of a cataloged project, most recent move first
+    pub fn get_project_aliases(&self) -> Result<Vec<ProjectAlias>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT p.id, p.path, p.project_type, p.last_scanned
-            FROM projects p
-            WHERE p.path = ?
+            SELECT a.path, p.path, a.moved_at
+            FROM project_aliases a
+            INNER JOIN projects p ON p.id = a.project_id
+            ORDER BY a.moved_at DESC, a.path
             "#,
         )?;
-
-        let mut rows = stmt.query_map(params![path_str], |row| {
-            let project_type_json: String = row.get(2)?;
-            let last_scanned_str: String = row.get(3)?;
-
-            Ok((
-                row.get::<_, i64>(0)?,    // id
-                row.get::<_, String>(1)?, // path
-                serde_json::from_str(&project_type_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?,
-                chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            0,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .with_timezone(&chrono::Utc),
-            ))
-        })?;
-
-        if let Some(row_result) = rows.next() {
-            let (id, path_str, project_type, last_scanned) = row_result?;
-
-            // Get indicators
-            let mut indicators = Vec::new();
-            let mut indicator_stmt = self
-                .conn
-                .prepare("SELECT indicator_type FROM project_indicators WHERE project_id = ?")?;
-            let indicator_rows = indicator_stmt.query_map(params![id], |row| {
-                let indicator_json: String = row.get(0)?;
-                serde_json::from_str(&indicator_json).map_err(|e| {
+        let aliases = stmt
+            .query_map([], |row| {
+                let moved_at: String = row.get(2)?;
+                let moved_at = chrono::DateTime::parse_from_rfc3339(&moved_at).map_err(|e| {
                     rusqlite::Error::FromSqlConversionFailure(
-                        0,
+                        2,
                         rusqlite::types::Type::Text,
                         Box::new(e),
                     )
+                })?;
+                Ok(ProjectAlias {
+                    path: std::path::PathBuf::from(row.get::<_, String>(0)?),
+                    project_path: std::path::PathBuf::from(row.get::<_, String>(1)?),
+                    moved_at: moved_at.with_timezone(&chrono::Utc),
                 })
-            })?;
-
-            for indicator_result in indicator_rows {
-                indicators.push(indicator_result?);
-            }
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(aliases)
+    }
 
-            let project = Project {
-                path: std::path::PathBuf::from(path_str),
-                project_type,
-                indicators,
-                last_scanned,
-            };
+    /// Get the current path of a project by one of its former paths
+    pub fn resolve_alias<P: AsRef<Path>>(&self, path: P) -> Result<Option<std::path::PathBuf>> {
+        let path = self
+            .conn
+            .query_row(
+                r#"
+                SELECT p.path
+                FROM project_aliases a
+                INNER JOIN projects p ON p.id = a.project_id
+                WHERE a.path = ?
+                "#,
+                params![path.as_ref().to_string_lossy()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        Ok(path.map(std::path::PathBuf::from))
+    }
 
-            Ok(Some(project))
-        } else {
-            Ok(None)
+    /// Replace the secondary ecosystems stored for a project
+    fn write_ecosystems(
+        conn: &Connection,
+        project_id: i64,
+        ecosystems: &[ProjectType],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_ecosystems WHERE project_id = ?",
+            params![project_id],
+        )?;
+        for ecosystem in ecosystems {
+            conn.execute(
+                "INSERT INTO project_ecosystems (project_id, project_type) VALUES (?, ?)",
+                params![project_id, serde_json::to_string(ecosystem)?],
+            )?;
         }
+        Ok(())
     }
 
-    /// Get all projects
-    pub fn get_all_projects(&self) -> Result<Vec<Project>> {
-        // Use a JOIN to fetch indicators in a single query (avoid N+1)
+    /// Get the secondary ecosystems of a project in the order they were stored
+    fn get_ecosystems(&self, project_id: i64) -> Result<Vec<ProjectType>> {
         let mut stmt = self.conn.prepare(
-            r#"
-            SELECT p.id, p.path, p.project_type, p.last_scanned, pi.indicator_type
-            FROM projects p
-            LEFT JOIN project_indicators pi ON p.id = pi.project_id
-            ORDER BY p.path, pi.indicator_type
-            "#,
+            "SELECT project_type FROM project_ecosystems WHERE project_id = ? ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![project_id], |row| row.get::<_, String>(0))?;
+
+        let mut ecosystems = Vec::new();
+        for row_result in rows {
+            ecosystems.push(serde_json::from_str(&row_result?)?);
+        }
+        Ok(ecosystems)
+    }
+
+    /// Get the indicators of a project in the order they were stored
+    fn get_indicators(&self, project_id: i64) -> Result<Vec<ProjectIndicator>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT indicator_type FROM project_indicators WHERE project_id = ? ORDER BY id",
         )?;
+        let rows = stmt.query_map(params![project_id], |row| row.get::<_, String>(0))?;
 
+        let mut indicators = Vec::new();
+        for row_result in rows {
+            indicators.push(serde_json::from_str(&row_result?)?);
+        }
+        Ok(indicators)
+    }
+
+    /// Get the indicators of the given projects in the order they were stored, keyed by project id
+    fn get_indicators_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<ProjectIndicator>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, indicator_type FROM project_indicators WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
         let rows = stmt.query_map([], |row| {
-            let project_type_json: String = row.get(2)?;
-            let last_scanned_str: String = row.get(3)?;
-            let indicator_json: Option<String> = row.get(4)?;
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
 
-            Ok((
-                row.get::<_, i64>(0)?,    // id
-                row.get::<_, String>(1)?, // path
-                serde_json::from_str(&project_type_json).map_err(|e| {
+        let mut indicators: std::collections::HashMap<i64, Vec<ProjectIndicator>> =
+            std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, indicator_json) = row_result?;
+            indicators
+                .entry(project_id)
+                .or_default()
+                .push(serde_json::from_str(&indicator_json)?);
+        }
+        Ok(indicators)
+    }
+
+    /// Get the secondary ecosystems of the given projects, keyed by project id
+    fn get_ecosystems_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<ProjectType>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, project_type FROM project_ecosystems WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut ecosystems: std::collections::HashMap<i64, Vec<ProjectType>> =
+            std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, project_type_json) = row_result?;
+            ecosystems
+                .entry(project_id)
+                .or_default()
+                .push(serde_json::from_str(&project_type_json)?);
+        }
+        Ok(ecosystems)
+    }
+
+    /// Replace the workspace a project is recorded as a member of
+    fn write_parent(conn: &Connection, project_id: i64, parent: Option<&Path>) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_parents WHERE project_id = ?",
+            params![project_id],
+        )?;
+        if let Some(parent) = parent {
+            conn.execute(
+                "INSERT INTO project_parents (project_id, parent_path) VALUES (?, ?)",
+                params![project_id, parent.to_string_lossy()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get the workspace a project is a member of
+    fn get_parent(&self, project_id: i64) -> Result<Option<std::path::PathBuf>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT parent_path FROM project_parents WHERE project_id = ?",
+                params![project_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .map(std::path::PathBuf::from))
+    }
+
+    /// Get the workspace of the given projects that are a member of one, keyed by project id
+    fn get_parents_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, std::path::PathBuf>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, parent_path FROM project_parents WHERE project_id IN ({})",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut parents = std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, parent_path) = row_result?;
+            parents.insert(project_id, std::path::PathBuf::from(parent_path));
+        }
+        Ok(parents)
+    }
+
+    /// Replace the git repository state stored for a project
+    fn write_git(conn: &Connection, project_id: i64, git: Option<&GitMetadata>) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_git WHERE project_id = ?",
+            params![project_id],
+        )?;
+        conn.execute(
+            "DELETE FROM project_git_remotes WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let Some(git) = git else {
+            return Ok(());
+        };
+
+        conn.execute(
+            r#"
+            INSERT INTO project_git (
+                project_id, branch, head_commit, head_date, upstream, ahead, behind,
+                dirty_count, untracked_count, stash_count, is_worktree, is_submodule,
+                root_commit
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                project_id,
+                git.branch,
+                git.head_commit,
+                git.head_date.map(|date| date.to_rfc3339()),
+                git.upstream,
+                git.ahead as i64,
+                git.behind as i64,
+                git.dirty_count as i64,
+                git.untracked_count as i64,
+                git.stash_count as i64,
+                git.is_worktree,
+                git.is_submodule,
+                git.root_commit,
+            ],
+        )?;
+        for remote in &git.remotes {
+            conn.execute(
+                "INSERT INTO project_git_remotes (project_id, name, url) VALUES (?, ?, ?)",
+                params![project_id, remote.name, remote.url],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Columns read by [`git_from_row`](Self::git_from_row), in order
+    const GIT_COLUMNS: &'static str = "project_id, branch, head_commit, head_date, upstream, \
+        ahead, behind, dirty_count, untracked_count, stash_count, is_worktree, is_submodule, \
+        root_commit";
+
+    /// Read one `project_git` row selected by [`GIT_COLUMNS`](Self::GIT_COLUMNS)
+    fn git_from_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, GitMetadata)> {
+        let head_date = row
+            .get::<_, Option<String>>(3)?
+            .map(|date| {
+                chrono::DateTime::parse_from_rfc3339(&date).map_err(|e| {
                     rusqlite::Error::FromSqlConversionFailure(
-                        0,
+                        3,
                         rusqlite::types::Type::Text,
                         Box::new(e),
                     )
-                })?,
-                chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            0,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .with_timezone(&chrono::Utc),
-                indicator_json,
+                })
+            })
+            .transpose()?
+            .map(|date| date.with_timezone(&chrono::Utc));
+        Ok((
+            row.get(0)?,
+            GitMetadata {
+                branch: row.get(1)?,
+                head_commit: row.get(2)?,
+                head_date,
+                root_commit: row.get(12)?,
+                remotes: Vec::new(),
+                upstream: row.get(4)?,
+                ahead: row.get::<_, i64>(5)? as usize,
+                behind: row.get::<_, i64>(6)? as usize,
+                dirty_count: row.get::<_, i64>(7)? as usize,
+                untracked_count: row.get::<_, i64>(8)? as usize,
+                stash_count: row.get::<_, i64>(9)? as usize,
+                is_worktree: row.get(10)?,
+                is_submodule: row.get(11)?,
+            },
+        ))
+    }
+
+    /// Get the git repository state of a project, if it is a repository
+    fn get_git(&self, project_id: i64) -> Result<Option<GitMetadata>> {
+        let Some((_, mut git)) = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_git WHERE project_id = ?",
+                    Self::GIT_COLUMNS
+                ),
+                params![project_id],
+                Self::git_from_row,
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT name, url FROM project_git_remotes WHERE project_id = ? ORDER BY id",
+        )?;
+        git.remotes = stmt
+            .query_map(params![project_id], |row| {
+                Ok(GitRemote {
+                    name: row.get(0)?,
+                    url: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Some(git))
+    }
+
+    /// Get the git repository state of the given projects that have one, keyed by project id
+    fn get_git_of(&self, ids: &[i64]) -> Result<std::collections::HashMap<i64, GitMetadata>> {
+        let id_list = Self::id_list(ids);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_git WHERE project_id IN ({})",
+            Self::GIT_COLUMNS,
+            id_list
+        ))?;
+        let mut git = stmt
+            .query_map([], Self::git_from_row)?
+            .collect::<std::result::Result<std::collections::HashMap<_, _>, _>>()?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, name, url FROM project_git_remotes WHERE project_id IN ({}) ORDER BY id",
+            id_list
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                GitRemote {
+                    name: row.get(1)?,
+                    url: row.get(2)?,
+                },
             ))
         })?;
+        for row_result in rows {
+            let (project_id, remote) = row_result?;
+            if let Some(metadata) = git.get_mut(&project_id) {
+                metadata.remotes.push(remote);
+            }
+        }
+        Ok(git)
+    }
+
+    /// Replace the manifest metadata stored for a project
+    fn write_manifest(
+        conn: &Connection,
+        project_id: i64,
+        manifest: Option<&ProjectManifest>,
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_manifests WHERE project_id = ?",
+            params![project_id],
+        )?;
+        if let Some(manifest) = manifest {
+            conn.execute(
+                r#"
+                INSERT INTO project_manifests (
+                    project_id, source, name, version, description, authors, license, homepage
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    project_id,
+                    manifest.source,
+                    manifest.name,
+                    manifest.version,
+                    manifest.description,
+                    serde_json::to_string(&manifest.authors)?,
+                    manifest.license,
+                    manifest.homepage,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Columns read by [`manifest_from_row`](Self::manifest_from_row), in order
+    const MANIFEST_COLUMNS: &'static str =
+        "project_id, source, name, version, description, authors, license, homepage";
+
+    /// Read one `project_manifests` row selected by [`MANIFEST_COLUMNS`](Self::MANIFEST_COLUMNS)
+    fn manifest_from_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, ProjectManifest)> {
+        let authors_json: String = row.get(5)?;
+        let authors = serde_json::from_str(&authors_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        Ok((
+            row.get(0)?,
+            ProjectManifest {
+                source: row.get(1)?,
+                name: row.get(2)?,
+                version: row.get(3)?,
+                description: row.get(4)?,
+                authors,
+                license: row.get(6)?,
+                homepage: row.get(7)?,
+            },
+        ))
+    }
+
+    /// Get the manifest metadata of a project
+    fn get_manifest(&self, project_id: i64) -> Result<Option<ProjectManifest>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_manifests WHERE project_id = ?",
+                    Self::MANIFEST_COLUMNS
+                ),
+                params![project_id],
+                Self::manifest_from_row,
+            )
+            .optional()?
+            .map(|(_, manifest)| manifest))
+    }
+
+    /// Get the manifest metadata of the given projects that have one, keyed by project id
+    fn get_manifests_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, ProjectManifest>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_manifests WHERE project_id IN ({})",
+            Self::MANIFEST_COLUMNS,
+            Self::id_list(ids)
+        ))?;
+        let manifests = stmt
+            .query_map([], Self::manifest_from_row)?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(manifests)
+    }
+
+    /// Search projects by declared package name, description, authors or license
+    ///
+    /// Matching is a case-insensitive substring match, ordered by path.
+    pub fn search_projects_by_manifest(&self, pattern: &str) -> Result<Vec<Project>> {
+        let like_pattern = format!("%{}%", pattern);
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.id, p.path, p.project_type, p.last_scanned
+            FROM projects p
+            JOIN project_manifests pm ON pm.project_id = p.id
+            WHERE pm.name LIKE ?1
+               OR pm.description LIKE ?1
+               OR pm.authors LIKE ?1
+               OR pm.license LIKE ?1
+            ORDER BY p.path
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(params![like_pattern], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
+    }
+
+    /// Replace the resolved dependencies stored for a project
+    fn write_dependencies(
+        conn: &Connection,
+        project_id: i64,
+        dependencies: &[Dependency],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_dependencies WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO project_dependencies (project_id, ecosystem, name, version) VALUES (?, ?, ?, ?)",
+        )?;
+        for dependency in dependencies {
+            stmt.execute(params![
+                project_id,
+                dependency.ecosystem.id(),
+                dependency.name,
+                dependency.version
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Read an ecosystem, name and version starting at column `first`
+    fn dependency_from_row(row: &rusqlite::Row, first: usize) -> rusqlite::Result<Dependency> {
+        let ecosystem: String = row.get(first)?;
+        Ok(Dependency {
+            ecosystem: DependencyEcosystem::from_name(&ecosystem).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    first,
+                    rusqlite::types::Type::Text,
+                    format!("unknown dependency ecosystem: {}", ecosystem).into(),
+                )
+            })?,
+            name: row.get(first + 1)?,
+            version: row.get(first + 2)?,
+        })
+    }
+
+    /// Get the resolved dependencies of a project in the order they were stored
+    fn get_dependencies(&self, project_id: i64) -> Result<Vec<Dependency>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ecosystem, name, version FROM project_dependencies WHERE project_id = ? ORDER BY id",
+        )?;
+        let dependencies = stmt
+            .query_map(params![project_id], |row| Self::dependency_from_row(row, 0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(dependencies)
+    }
+
+    /// Get the resolved dependencies of the given projects, keyed by project id
+    fn get_dependencies_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<Dependency>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, ecosystem, name, version FROM project_dependencies WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, Self::dependency_from_row(row, 1)?))
+        })?;
+
+        let mut dependencies: std::collections::HashMap<i64, Vec<Dependency>> =
+            std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, dependency) = row_result?;
+            dependencies.entry(project_id).or_default().push(dependency);
+        }
+        Ok(dependencies)
+    }
+
+    /// Replace the toolchain pins stored for a project
+    fn write_toolchains(
+        conn: &Connection,
+        project_id: i64,
+        toolchains: &[ToolchainPin],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_toolchains WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO project_toolchains (project_id, tool, version, source) VALUES (?, ?, ?, ?)",
+        )?;
+        for pin in toolchains {
+            stmt.execute(params![project_id, pin.tool, pin.version, pin.source])?;
+        }
+        Ok(())
+    }
+
+    /// Read a tool, version and source starting at column `first`
+    fn toolchain_from_row(row: &rusqlite::Row, first: usize) -> rusqlite::Result<ToolchainPin> {
+        Ok(ToolchainPin {
+            tool: row.get(first)?,
+            version: row.get(first + 1)?,
+            source: row.get(first + 2)?,
+        })
+    }
+
+    /// Get the toolchain pins of a project in the order they were stored
+    fn get_toolchains(&self, project_id: i64) -> Result<Vec<ToolchainPin>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tool, version, source FROM project_toolchains WHERE project_id = ? ORDER BY id",
+        )?;
+        let toolchains = stmt
+            .query_map(params![project_id], |row| Self::toolchain_from_row(row, 0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(toolchains)
+    }
 
-        // Group results by project (since JOIN creates one row per indicator)
-        let mut projects_map: std::collections::HashMap<
-            String,
-            (
-                i64,
-                ProjectType,
-                chrono::DateTime<chrono::Utc>,
-                Vec<ProjectIndicator>,
-            ),
-        > = std::collections::HashMap::new();
+    /// Get the toolchain pins of the given projects, keyed by project id
+    fn get_toolchains_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<ToolchainPin>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, tool, version, source FROM project_toolchains WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, Self::toolchain_from_row(row, 1)?))
+        })?;
 
+        let mut toolchains: std::collections::HashMap<i64, Vec<ToolchainPin>> =
+            std::collections::HashMap::new();
         for row_result in rows {
-            let (id, path_str, project_type, last_scanned, indicator_json) = row_result?;
+            let (project_id, pin) = row_result?;
+            toolchains.entry(project_id).or_default().push(pin);
+        }
+        Ok(toolchains)
+    }
 
-            let entry = projects_map.entry(path_str.clone()).or_insert((
-                id,
-                project_type,
-                last_scanned,
-                Vec::new(),
-            ));
+    /// Replace the automation configuration stored for a project
+    fn write_automation(
+        conn: &Connection,
+        project_id: i64,
+        automation: &[AutomationConfig],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_automation WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO project_automation (project_id, kind, path, targets) VALUES (?, ?, ?, ?)",
+        )?;
+        for config in automation {
+            stmt.execute(params![
+                project_id,
+                config.kind.id(),
+                config.path,
+                serde_json::to_string(&config.targets)?
+            ])?;
+        }
+        Ok(())
+    }
 
-            if let Some(ind_json) = indicator_json {
-                let indicator: ProjectIndicator = serde_json::from_str(&ind_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?;
-                entry.3.push(indicator);
+    /// Read a kind, path and targets starting at column `first`
+    fn automation_from_row(
+        row: &rusqlite::Row,
+        first: usize,
+    ) -> rusqlite::Result<AutomationConfig> {
+        let kind: String = row.get(first)?;
+        let targets: String = row.get(first + 2)?;
+        Ok(AutomationConfig {
+            kind: AutomationKind::from_name(&kind).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    first,
+                    rusqlite::types::Type::Text,
+                    format!("unknown automation kind: {}", kind).into(),
+                )
+            })?,
+            path: row.get(first + 1)?,
+            targets: serde_json::from_str(&targets).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    first + 2,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?,
+        })
+    }
+
+    /// Get the automation configuration of a project in the order it was stored
+    fn get_automation(&self, project_id: i64) -> Result<Vec<AutomationConfig>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, path, targets FROM project_automation WHERE project_id = ? ORDER BY id",
+        )?;
+        let automation = stmt
+            .query_map(params![project_id], |row| Self::automation_from_row(row, 0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(automation)
+    }
+
+    /// Get the automation configuration of the given projects, keyed by project id
+    fn get_automation_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<AutomationConfig>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, kind, path, targets FROM project_automation WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, Self::automation_from_row(row, 1)?))
+        })?;
+
+        let mut automation: std::collections::HashMap<i64, Vec<AutomationConfig>> =
+            std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, config) = row_result?;
+            automation.entry(project_id).or_default().push(config);
+        }
+        Ok(automation)
+    }
+
+    /// Replace the licenses stored for a project
+    fn write_licenses(
+        conn: &Connection,
+        project_id: i64,
+        licenses: &[LicenseDetection],
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_licenses WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO project_licenses (project_id, source, declared, spdx, confidence) VALUES (?, ?, ?, ?, ?)",
+        )?;
+        for license in licenses {
+            stmt.execute(params![
+                project_id,
+                license.source,
+                license.declared,
+                license.spdx,
+                license.confidence
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Read a source, declared value, SPDX expression and confidence starting at column `first`
+    fn license_from_row(row: &rusqlite::Row, first: usize) -> rusqlite::Result<LicenseDetection> {
+        Ok(LicenseDetection {
+            source: row.get(first)?,
+            declared: row.get(first + 1)?,
+            spdx: row.get(first + 2)?,
+            confidence: row.get(first + 3)?,
+        })
+    }
+
+    /// Get the licenses of a project in the order they were stored
+    fn get_licenses(&self, project_id: i64) -> Result<Vec<LicenseDetection>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, declared, spdx, confidence FROM project_licenses WHERE project_id = ? ORDER BY id",
+        )?;
+        let licenses = stmt
+            .query_map(params![project_id], |row| Self::license_from_row(row, 0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(licenses)
+    }
+
+    /// Get the licenses of the given projects, keyed by project id
+    fn get_licenses_of(
+        &self,
+        ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<LicenseDetection>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT project_id, source, declared, spdx, confidence FROM project_licenses WHERE project_id IN ({}) ORDER BY id",
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, Self::license_from_row(row, 1)?))
+        })?;
+
+        let mut licenses: std::collections::HashMap<i64, Vec<LicenseDetection>> =
+            std::collections::HashMap::new();
+        for row_result in rows {
+            let (project_id, license) = row_result?;
+            licenses.entry(project_id).or_default().push(license);
+        }
+        Ok(licenses)
+    }
+
+    /// Count the projects pinning each tool version, ordered by tool and version
+    ///
+    /// A project pinning the same version in several files counts once. The
+    /// counts can be narrowed to one tool.
+    pub fn get_toolchain_counts(&self, tool: Option<&str>) -> Result<Vec<ToolchainCount>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT tool, version, COUNT(DISTINCT project_id)
+            FROM project_toolchains
+            WHERE ?1 IS NULL OR tool = ?1
+            GROUP BY tool, version
+            ORDER BY tool, version
+            "#,
+        )?;
+        let counts = stmt
+            .query_map(params![tool], |row| {
+                Ok(ToolchainCount {
+                    tool: row.get(0)?,
+                    version: row.get(1)?,
+                    projects: row.get::<_, i64>(2)? as usize,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
+    /// Get the projects that resolve a package, ordered by path and version
+    ///
+    /// Package names match without regard to case. Without an ecosystem every
+    /// registry is searched, and without a version every version matches.
+    pub fn get_dependents(
+        &self,
+        name: &str,
+        ecosystem: Option<DependencyEcosystem>,
+        version: Option<&str>,
+    ) -> Result<Vec<Dependent>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.path, d.ecosystem, d.name, d.version
+            FROM project_dependencies d
+            JOIN projects p ON p.id = d.project_id
+            WHERE d.name = ?1 COLLATE NOCASE
+              AND (?2 IS NULL OR d.ecosystem = ?2)
+              AND (?3 IS NULL OR d.version = ?3)
+            ORDER BY p.path, d.version
+            "#,
+        )?;
+        let dependents = stmt
+            .query_map(params![name, ecosystem.map(|e| e.id()), version], |row| {
+                Ok(Dependent {
+                    path: std::path::PathBuf::from(row.get::<_, String>(0)?),
+                    dependency: Self::dependency_from_row(row, 1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(dependents)
+    }
+
+    /// Get the versions each package resolves to across the catalog
+    ///
+    /// Only packages resolved to at least `min_versions` distinct versions are
+    /// returned, so `2` lists packages whose versions have drifted apart. The
+    /// result can be narrowed to one ecosystem or one package name.
+    pub fn get_version_spread(
+        &self,
+        ecosystem: Option<DependencyEcosystem>,
+        name: Option<&str>,
+        min_versions: usize,
+    ) -> Result<Vec<VersionSpread>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT d.ecosystem, d.name, d.version, p.path
+            FROM project_dependencies d
+            JOIN projects p ON p.id = d.project_id
+            WHERE (?1 IS NULL OR d.ecosystem = ?1)
+              AND (?2 IS NULL OR d.name = ?2 COLLATE NOCASE)
+            ORDER BY d.ecosystem, d.name, d.version, p.path
+            "#,
+        )?;
+        let rows = stmt.query_map(params![ecosystem.map(|e| e.id()), name], |row| {
+            Ok((
+                Self::dependency_from_row(row, 0)?,
+                std::path::PathBuf::from(row.get::<_, String>(3)?),
+            ))
+        })?;
+
+        let mut spreads: Vec<VersionSpread> = Vec::new();
+        for row_result in rows {
+            let (dependency, path) = row_result?;
+            let same_package = spreads.last().is_some_and(|spread| {
+                spread.ecosystem == dependency.ecosystem && spread.name == dependency.name
+            });
+            if !same_package {
+                spreads.push(VersionSpread {
+                    ecosystem: dependency.ecosystem,
+                    name: dependency.name.clone(),
+                    versions: Vec::new(),
+                });
+            }
+            let versions = &mut spreads.last_mut().unwrap().versions;
+            match versions.last_mut() {
+                Some((version, paths)) if *version == dependency.version => {
+                    if paths.last() != Some(&path) {
+                        paths.push(path);
+                    }
+                }
+                _ => versions.push((dependency.version, vec![path])),
             }
         }
+        spreads.retain(|spread| spread.versions.len() >= min_versions);
+        Ok(spreads)
+    }
 
-        // Convert to Vec and sort by path
-        let mut projects: Vec<Project> = projects_map
-            .into_iter()
-            .map(
-                |(path_str, (_, project_type, last_scanned, indicators))| Project {
-                    path: std::path::PathBuf::from(path_str),
-                    project_type,
-                    indicators,
-                    last_scanned,
-                },
+    /// Replace the disk usage stored for a project
+    fn write_disk_usage(
+        conn: &Connection,
+        project_id: i64,
+        disk_usage: Option<&DiskUsage>,
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_disk_usage WHERE project_id = ?",
+            params![project_id],
+        )?;
+        if let Some(usage) = disk_usage {
+            conn.execute(
+                r#"
+                INSERT INTO project_disk_usage (
+                    project_id, total_bytes, source_bytes, artifact_bytes, artifact_dirs,
+                    measured_at
+                ) VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    project_id,
+                    usage.total_bytes as i64,
+                    usage.source_bytes as i64,
+                    usage.artifact_bytes as i64,
+                    serde_json::to_string(&usage.artifact_dirs)?,
+                    usage.measured_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Columns read by [`disk_usage_from_row`](Self::disk_usage_from_row), in order
+    const DISK_USAGE_COLUMNS: &'static str =
+        "project_id, total_bytes, source_bytes, artifact_bytes, artifact_dirs, measured_at";
+
+    /// Read one `project_disk_usage` row selected by [`DISK_USAGE_COLUMNS`](Self::DISK_USAGE_COLUMNS)
+    fn disk_usage_from_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, DiskUsage)> {
+        let artifact_dirs_json: String = row.get(4)?;
+        let artifact_dirs = serde_json::from_str(&artifact_dirs_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        let measured_at: String = row.get(5)?;
+        let measured_at = chrono::DateTime::parse_from_rfc3339(&measured_at)
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    5,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?
+            .with_timezone(&chrono::Utc);
+        Ok((
+            row.get(0)?,
+            DiskUsage {
+                total_bytes: row.get::<_, i64>(1)? as u64,
+                source_bytes: row.get::<_, i64>(2)? as u64,
+                artifact_bytes: row.get::<_, i64>(3)? as u64,
+                artifact_dirs,
+                measured_at,
+            },
+        ))
+    }
+
+    /// Get the disk usage stored for the project at `path` before it is replaced
+    fn stored_disk_usage(conn: &Connection, path: &Path) -> Result<Option<DiskUsage>> {
+        Ok(conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_disk_usage
+                     WHERE project_id = (SELECT id FROM projects WHERE path = ?)",
+                    Self::DISK_USAGE_COLUMNS
+                ),
+                params![path.to_string_lossy()],
+                Self::disk_usage_from_row,
             )
-            .collect();
+            .optional()?
+            .map(|(_, usage)| usage))
+    }
+
+    /// Get the disk usage measured for a project
+    fn get_disk_usage(&self, project_id: i64) -> Result<Option<DiskUsage>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_disk_usage WHERE project_id = ?",
+                    Self::DISK_USAGE_COLUMNS
+                ),
+                params![project_id],
+                Self::disk_usage_from_row,
+            )
+            .optional()?
+            .map(|(_, usage)| usage))
+    }
+
+    /// Get the disk usage of the given projects that were measured, keyed by project id
+    fn get_disk_usage_of(&self, ids: &[i64]) -> Result<std::collections::HashMap<i64, DiskUsage>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_disk_usage WHERE project_id IN ({})",
+            Self::DISK_USAGE_COLUMNS,
+            Self::id_list(ids)
+        ))?;
+        let usage = stmt
+            .query_map([], Self::disk_usage_from_row)?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(usage)
+    }
+
+    /// Replace the lines of code stored for a project
+    fn write_code_stats(
+        conn: &Connection,
+        project_id: i64,
+        code_stats: Option<&CodeStats>,
+    ) -> Result<()> {
+        conn.execute(
+            "DELETE FROM project_code_stats WHERE project_id = ?",
+            params![project_id],
+        )?;
+        let Some(stats) = code_stats else {
+            return Ok(());
+        };
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO project_code_stats (
+                project_id, language, files, code, comments, blanks, measured_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )?;
+        for language in &stats.languages {
+            stmt.execute(params![
+                project_id,
+                language.language,
+                language.files as i64,
+                language.code as i64,
+                language.comments as i64,
+                language.blanks as i64,
+                stats.measured_at.to_rfc3339(),
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Columns read by [`code_stats_from_row`](Self::code_stats_from_row), in order
+    const CODE_STATS_COLUMNS: &'static str =
+        "project_id, language, files, code, comments, blanks, measured_at";
+
+    /// Read one `project_code_stats` row selected by [`CODE_STATS_COLUMNS`](Self::CODE_STATS_COLUMNS)
+    fn code_stats_from_row(
+        row: &rusqlite::Row,
+    ) -> rusqlite::Result<(i64, LanguageStats, chrono::DateTime<chrono::Utc>)> {
+        let measured_at: String = row.get(6)?;
+        let measured_at = chrono::DateTime::parse_from_rfc3339(&measured_at)
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    6,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?
+            .with_timezone(&chrono::Utc);
+        Ok((
+            row.get(0)?,
+            LanguageStats {
+                language: row.get(1)?,
+                files: row.get::<_, i64>(2)? as u64,
+                code: row.get::<_, i64>(3)? as u64,
+                comments: row.get::<_, i64>(4)? as u64,
+                blanks: row.get::<_, i64>(5)? as u64,
+            },
+            measured_at,
+        ))
+    }
+
+    /// Group language rows by project, keeping the order they were read in
+    fn collect_code_stats(
+        rows: impl Iterator<
+            Item = rusqlite::Result<(i64, LanguageStats, chrono::DateTime<chrono::Utc>)>,
+        >,
+    ) -> Result<std::collections::HashMap<i64, CodeStats>> {
+        let mut stats: std::collections::HashMap<i64, CodeStats> = std::collections::HashMap::new();
+        for row in rows {
+            let (project_id, language, measured_at) = row?;
+            let entry = stats.entry(project_id).or_insert_with(|| CodeStats {
+                languages: Vec::new(),
+                measured_at,
+            });
+            entry.languages.push(language);
+        }
+        Ok(stats)
+    }
 
-        projects.sort_by(|a, b| a.path.cmp(&b.path));
+    /// Get the lines of code stored for the project at `path` before it is replaced
+    fn stored_code_stats(conn: &Connection, path: &Path) -> Result<Option<CodeStats>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM project_code_stats
+             WHERE project_id = (SELECT id FROM projects WHERE path = ?)
+             ORDER BY id",
+            Self::CODE_STATS_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![path.to_string_lossy()], Self::code_stats_from_row)?;
+        Ok(Self::collect_code_stats(rows)?.into_values().next())
+    }
 
-        Ok(projects)
+    /// Get the lines of code counted for a project
+    fn get_code_stats(&self, project_id: i64) -> Result<Option<CodeStats>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_code_stats WHERE project_id = ? ORDER BY id",
+            Self::CODE_STATS_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![project_id], Self::code_stats_from_row)?;
+        Ok(Self::collect_code_stats(rows)?.remove(&project_id))
     }
 
-    /// Delete a project by path
-    pub fn delete_project_by_path<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
-        let path_str = path.as_ref().to_string_lossy();
-        let rows_affected = self
-            .conn
-            .execute("DELETE FROM projects WHERE path = ?", params![path_str])?;
-        Ok(rows_affected > 0)
+    /// Get the lines of code of the given projects that were counted, keyed by project id
+    fn get_code_stats_of(&self, ids: &[i64]) -> Result<std::collections::HashMap<i64, CodeStats>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_code_stats WHERE project_id IN ({}) ORDER BY id",
+            Self::CODE_STATS_COLUMNS,
+            Self::id_list(ids)
+        ))?;
+        let rows = stmt.query_map([], Self::code_stats_from_row)?;
+        Self::collect_code_stats(rows)
     }
 
-    /// Get projects by type
-    pub fn get_projects_by_type(&self, project_type: &ProjectType) -> Result<Vec<Project>> {
+    /// Sum the lines of code per language over the catalog, most code first
+    ///
+    /// A project nested inside another counted project, such as a workspace
+    /// member, is already part of the outer project's count and is skipped.
+    pub fn get_language_counts(&self) -> Result<Vec<LanguageCount>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT p.id, p.path, p.project_type, p.last_scanned
-            FROM projects p
-            WHERE p.project_type = ?
-            ORDER BY p.path
+            SELECT p.path, c.language, c.files, c.code, c.comments, c.blanks
+            FROM project_code_stats c
+            JOIN projects p ON p.id = c.project_id
             "#,
         )?;
-
-        let project_iter =
-            stmt.query_map(params![serde_json::to_string(project_type)?], |row| {
-                let project_type_json: String = row.get(2)?;
-                let last_scanned_str: String = row.get(3)?;
-
+        let rows = stmt
+            .query_map([], |row| {
                 Ok((
-                    row.get::<_, i64>(0)?,    // id
-                    row.get::<_, String>(1)?, // path
-                    serde_json::from_str(&project_type_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            0,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?,
-                    chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                        .map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(
-                                0,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })?
-                        .with_timezone(&chrono::Utc),
+                    std::path::PathBuf::from(row.get::<_, String>(0)?),
+                    LanguageStats {
+                        language: row.get(1)?,
+                        files: row.get::<_, i64>(2)? as u64,
+                        code: row.get::<_, i64>(3)? as u64,
+                        comments: row.get::<_, i64>(4)? as u64,
+                        blanks: row.get::<_, i64>(5)? as u64,
+                    },
                 ))
-            })?;
-
-        let mut projects = Vec::new();
-        for row_result in project_iter {
-            let (id, path_str, project_type, last_scanned) = row_result?;
-
-            // Get indicators
-            let mut indicators = Vec::new();
-            let mut indicator_stmt = self
-                .conn
-                .prepare("SELECT indicator_type FROM project_indicators WHERE project_id = ?")?;
-            let indicator_rows = indicator_stmt.query_map(params![id], |row| {
-                let indicator_json: String = row.get(0)?;
-                serde_json::from_str(&indicator_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })
-            })?;
-
-            for indicator_result in indicator_rows {
-                indicators.push(indicator_result?);
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let counted: std::collections::HashSet<&Path> =
+            rows.iter().map(|(path, _)| path.as_path()).collect();
+        let mut counts: std::collections::BTreeMap<&str, LanguageCount> =
+            std::collections::BTreeMap::new();
+        for (path, stats) in &rows {
+            if path
+                .ancestors()
+                .skip(1)
+                .any(|ancestor| counted.contains(ancestor))
+            {
+                continue;
             }
-
-            let project = Project {
-                path: std::path::PathBuf::from(path_str),
-                project_type,
-                indicators,
-                last_scanned,
-            };
-
-            projects.push(project);
+            let count = counts
+                .entry(stats.language.as_str())
+                .or_insert_with(|| LanguageCount {
+                    projects: 0,
+                    stats: LanguageStats {
+                        language: stats.language.clone(),
+                        ..LanguageStats::default()
+                    },
+                });
+            count.projects += 1;
+            count.stats.add(stats);
         }
 
-        Ok(projects)
+        let mut counts: Vec<LanguageCount> = counts.into_values().collect();
+        counts.sort_by_key(|count| std::cmp::Reverse(count.stats.code));
+        Ok(counts)
     }
 
-    /// Get projects by indicator type
-    pub fn get_projects_by_indicator(&self, indicator: &ProjectIndicator) -> Result<Vec<Project>> {
+    /// Get the projects recorded as members of a workspace, ordered by path
+    pub fn get_workspace_members<P: AsRef<Path>>(&self, workspace: P) -> Result<Vec<Project>> {
+        let workspace_str = workspace.as_ref().to_string_lossy();
         let mut stmt = self.conn.prepare(
             r#"
             SELECT p.id, p.path, p.project_type, p.last_scanned
             FROM projects p
-            INNER JOIN project_indicators pi ON p.id = pi.project_id
-            WHERE pi.indicator_type = ?
+            JOIN project_parents pp ON pp.project_id = p.id
+            WHERE pp.parent_path = ?
             ORDER BY p.path
             "#,
         )?;
+        let rows = stmt
+            .query_map(params![workspace_str], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
+    }
+
+    /// Get a project by path
+    pub fn get_project_by_path<P: AsRef<Path>>(&self, path: P) -> Result<Option<Project>> {
+        let path_str = path.as_ref().to_string_lossy();
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.id, p.path, p.project_type, p.last_scanned
+            FROM projects p
+            WHERE p.path = ?
+            "#,
+        )?;
 
-        let project_iter = stmt.query_map(params![serde_json::to_string(indicator)?], |row| {
+        let mut rows = stmt.query_map(params![path_str], |row| {
             let project_type_json: String = row.get(2)?;
             let last_scanned_str: String = row.get(3)?;
 
@@ -620,7 +1972,7 @@ impl ProjectDatabase {
                 row.get::<_, String>(1)?, // path
                 serde_json::from_str(&project_type_json).map_err(|e| {
                     rusqlite::Error::FromSqlConversionFailure(
-                        2,
+                        0,
                         rusqlite::types::Type::Text,
                         Box::new(e),
                     )
@@ -628,7 +1980,7 @@ impl ProjectDatabase {
                 chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
                     .map_err(|e| {
                         rusqlite::Error::FromSqlConversionFailure(
-                            3,
+                            0,
                             rusqlite::types::Type::Text,
                             Box::new(e),
                         )
@@ -637,8 +1989,7 @@ impl ProjectDatabase {
             ))
         })?;
 
-        let mut projects = Vec::new();
-        for row_result in project_iter {
+        if let Some(row_result) = rows.next() {
             let (id, path_str, project_type, last_scanned) = row_result?;
 
             // Get indicators
@@ -664,27 +2015,240 @@ impl ProjectDatabase {
             let project = Project {
                 path: std::path::PathBuf::from(path_str),
                 project_type,
+                ecosystems: self.get_ecosystems(id)?,
+                parent: self.get_parent(id)?,
+                git: self.get_git(id)?,
+                manifest: self.get_manifest(id)?,
+                dependencies: self.get_dependencies(id)?,
+                toolchains: self.get_toolchains(id)?,
+                automation: self.get_automation(id)?,
+                licenses: self.get_licenses(id)?,
+                disk_usage: self.get_disk_usage(id)?,
+                code_stats: self.get_code_stats(id)?,
                 indicators,
                 last_scanned,
             };
 
-            projects.push(project);
+            Ok(Some(project))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read one project row selected as `id, path, project_type, last_scanned`
+    fn project_row(row: &rusqlite::Row) -> rusqlite::Result<ProjectRow> {
+        let project_type_json: String = row.get(2)?;
+        let last_scanned_str: String = row.get(3)?;
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            serde_json::from_str(&project_type_json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    2,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?,
+            chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        3,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })?
+                .with_timezone(&chrono::Utc),
+        ))
+    }
+
+    /// Comma-separated project ids for an `IN (…)` list
+    fn id_list(ids: &[i64]) -> String {
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Build projects from their rows, loading each child table of just these
+    /// projects in one query
+    fn attach_details(&self, rows: Vec<ProjectRow>) -> Result<Vec<Project>> {
+        if rows.is_empty() {
+            return Ok(Vec::new());
         }
+        let ids: Vec<i64> = rows.iter().map(|row| row.0).collect();
+        let mut indicators = self.get_indicators_of(&ids)?;
+        let mut ecosystems = self.get_ecosystems_of(&ids)?;
+        let mut parents = self.get_parents_of(&ids)?;
+        let mut git = self.get_git_of(&ids)?;
+        let mut manifests = self.get_manifests_of(&ids)?;
+        let mut dependencies = self.get_dependencies_of(&ids)?;
+        let mut toolchains = self.get_toolchains_of(&ids)?;
+        let mut automation = self.get_automation_of(&ids)?;
+        let mut licenses = self.get_licenses_of(&ids)?;
+        let mut disk_usage = self.get_disk_usage_of(&ids)?;
+        let mut code_stats = self.get_code_stats_of(&ids)?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, path_str, project_type, last_scanned)| Project {
+                path: std::path::PathBuf::from(path_str),
+                project_type,
+                ecosystems: ecosystems.remove(&id).unwrap_or_default(),
+                parent: parents.remove(&id),
+                git: git.remove(&id),
+                manifest: manifests.remove(&id),
+                dependencies: dependencies.remove(&id).unwrap_or_default(),
+                toolchains: toolchains.remove(&id).unwrap_or_default(),
+                automation: automation.remove(&id).unwrap_or_default(),
+                licenses: licenses.remove(&id).unwrap_or_default(),
+                disk_usage: disk_usage.remove(&id),
+                code_stats: code_stats.remove(&id),
+                indicators: indicators.remove(&id).unwrap_or_default(),
+                last_scanned,
+            })
+            .collect())
+    }
+
+    /// Get all projects
+    pub fn get_all_projects(&self) -> Result<Vec<Project>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, path, project_type, last_scanned FROM projects")?;
+        let rows = stmt
+            .query_map([], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut projects = self.attach_details(rows)?;
+
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
 
         Ok(projects)
     }
 
-    /// Get project counts by type
+    /// Delete a project by path
+    pub fn delete_project_by_path<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
+        let path_str = path.as_ref().to_string_lossy();
+        let rows_affected = self
+            .conn
+            .execute("DELETE FROM projects WHERE path = ?", params![path_str])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Get the paths of the projects in a directory or below it, ordered by path
+    pub fn get_project_paths_under<P: AsRef<Path>>(
+        &self,
+        dir: P,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        let (dir_str, prefix) = Self::subtree_pattern(dir.as_ref());
+        let mut stmt = self.conn.prepare(
+            "SELECT path FROM projects WHERE path = ?1 OR substr(path, 1, length(?2)) = ?2 ORDER BY path",
+        )?;
+        let paths = stmt
+            .query_map(params![dir_str, prefix], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(std::path::PathBuf::from))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(paths)
+    }
+
+    /// Delete the projects in a directory or below it, returning their paths
+    pub fn delete_projects_under<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        let paths = self.get_project_paths_under(dir.as_ref())?;
+        let (dir_str, prefix) = Self::subtree_pattern(dir.as_ref());
+        self.conn.execute(
+            "DELETE FROM projects WHERE path = ?1 OR substr(path, 1, length(?2)) = ?2",
+            params![dir_str, prefix],
+        )?;
+        Ok(paths)
+    }
+
+    /// The path of a directory and the prefix shared by every path below it
+    fn subtree_pattern(dir: &Path) -> (String, String) {
+        let dir_str = dir.to_string_lossy().into_owned();
+        let prefix = format!(
+            "{}{}",
+            dir_str.trim_end_matches(std::path::MAIN_SEPARATOR),
+            std::path::MAIN_SEPARATOR
+        );
+        (dir_str, prefix)
+    }
+
+    /// Get projects that contain an ecosystem, as their primary type or a secondary one
+    pub fn get_projects_by_type(&self, project_type: &ProjectType) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.id, p.path, p.project_type, p.last_scanned
+            FROM projects p
+            WHERE p.project_type = ?1
+               OR p.id IN (SELECT project_id FROM project_ecosystems WHERE project_type = ?1)
+            ORDER BY p.path
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(
+                params![serde_json::to_string(project_type)?],
+                Self::project_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
+    }
+
+    /// Get projects by indicator type
+    pub fn get_projects_by_indicator(&self, indicator: &ProjectIndicator) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.id, p.path, p.project_type, p.last_scanned
+            FROM projects p
+            INNER JOIN project_indicators pi ON p.id = pi.project_id
+            WHERE pi.indicator_type = ?
+            ORDER BY p.path
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(
+                params![serde_json::to_string(indicator)?],
+                Self::project_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
+    }
+
+    /// Get project counts by primary type
     pub fn get_project_counts_by_type(
         &self,
     ) -> Result<std::collections::HashMap<ProjectType, usize>> {
-        let mut stmt = self.conn.prepare(
+        self.query_type_counts(
             r#"
             SELECT project_type, COUNT(*)
             FROM projects
             GROUP BY project_type
             "#,
-        )?;
+        )
+    }
+
+    /// Get the number of projects containing each ecosystem, as primary type or secondary
+    pub fn get_project_counts_by_ecosystem(
+        &self,
+    ) -> Result<std::collections::HashMap<ProjectType, usize>> {
+        self.query_type_counts(
+            r#"
+            SELECT project_type, COUNT(DISTINCT project_id)
+            FROM (
+                SELECT id AS project_id, project_type FROM projects
+                UNION ALL
+                SELECT project_id, project_type FROM project_ecosystems
+            )
+            GROUP BY project_type
+            "#,
+        )
+    }
+
+    /// Run a `(project_type, count)` query and collect it into a map
+    fn query_type_counts(
+        &self,
+        sql: &str,
+    ) -> Result<std::collections::HashMap<ProjectType, usize>> {
+        let mut stmt = self.conn.prepare(sql)?;
 
         let rows = stmt.query_map([], |row| {
             let project_type_json: String = row.get(0)?;
@@ -711,6 +2275,51 @@ impl ProjectDatabase {
         Ok(counts)
     }
 
+    /// Replace the stored project type registry
+    ///
+    /// The registry is kept alongside the projects so readers that do not load
+    /// the scan configuration, like the TUI, still know custom type priorities.
+    pub fn replace_project_types(&mut self, project_types: &[ProjectTypeDefinition]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM project_types", [])?;
+        for definition in project_types {
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO project_types (name, priority, definition, updated_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+                params![
+                    definition.name,
+                    definition.priority,
+                    serde_json::to_string(definition)?,
+                    chrono::Utc::now().to_rfc3339()
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get the stored project type registry, highest priority first
+    pub fn get_project_types(&self) -> Result<Vec<ProjectTypeDefinition>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT definition
+            FROM project_types
+            ORDER BY priority DESC, name
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut project_types = Vec::new();
+        for row_result in rows {
+            project_types.push(serde_json::from_str(&row_result?)?);
+        }
+
+        Ok(project_types)
+    }
+
     /// Search projects by path pattern
     pub fn search_projects_by_path(&self, pattern: &str) -> Result<Vec<Project>> {
         self.search_projects_by_path_limit(pattern, None)
@@ -724,125 +2333,136 @@ impl ProjectDatabase {
     ) -> Result<Vec<Project>> {
         let like_pattern = format!("%{}%", pattern);
 
-        let query = if let Some(lim) = limit {
-            format!(
-                r#"
-                SELECT p.id, p.path, p.project_type, p.last_scanned, pi.indicator_type
-                FROM projects p
-                LEFT JOIN project_indicators pi ON p.id = pi.project_id
-                WHERE p.path LIKE ?
-                ORDER BY LENGTH(p.path), p.path, pi.indicator_type
-                LIMIT {}
-                "#,
-                lim
-            )
-        } else {
-            r#"
-            SELECT p.id, p.path, p.project_type, p.last_scanned, pi.indicator_type
-            FROM projects p
-            LEFT JOIN project_indicators pi ON p.id = pi.project_id
-            WHERE p.path LIKE ?
-            ORDER BY LENGTH(p.path), p.path, pi.indicator_type
+        // Shortest paths first, then alphabetical
+        let mut query = r#"
+            SELECT id, path, project_type, last_scanned
+            FROM projects
+            WHERE path LIKE ?
+            ORDER BY LENGTH(path), path
             "#
-            .to_string()
-        };
+        .to_string();
+        if let Some(lim) = limit {
+            query.push_str(&format!("LIMIT {}", lim));
+        }
 
         let mut stmt = self.conn.prepare(&query)?;
+        let rows = stmt
+            .query_map(params![like_pattern], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
+    }
 
-        let rows = stmt.query_map(params![like_pattern], |row| {
-            let project_type_json: String = row.get(2)?;
-            let last_scanned_str: String = row.get(3)?;
-            let indicator_json: Option<String> = row.get(4)?;
+    /// Store a scan result
+    pub fn store_scan_result(&mut self, scan_result: &ScanResult) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let scan_result_id = Self::insert_scan_result_with_tx(&tx, scan_result)?;
 
-            Ok((
-                row.get::<_, i64>(0)?,    // id
-                row.get::<_, String>(1)?, // path
-                serde_json::from_str(&project_type_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?,
-                chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            0,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .with_timezone(&chrono::Utc),
-                indicator_json,
-            ))
-        })?;
+        // Store projects (this will also update existing ones)
+        for project in &scan_result.projects {
+            let project_id = ProjectDatabase::upsert_project_with_tx(&tx, project)?;
+            Self::insert_scan_project_with_tx(&tx, scan_result_id, project_id, project)?;
+        }
 
-        // Group results by project
-        let mut projects_map: std::collections::HashMap<
-            String,
-            (
-                i64,
-                ProjectType,
-                chrono::DateTime<chrono::Utc>,
-                Vec<ProjectIndicator>,
-            ),
-        > = std::collections::HashMap::new();
-        let mut project_order: Vec<String> = Vec::new();
+        tx.commit()?;
+        Ok(scan_result_id)
+    }
 
-        for row_result in rows {
-            let (id, path_str, project_type, last_scanned, indicator_json) = row_result?;
-
-            if !projects_map.contains_key(&path_str) {
-                project_order.push(path_str.clone());
-                projects_map.insert(
-                    path_str.clone(),
-                    (id, project_type, last_scanned, Vec::new()),
-                );
-            }
+    /// Store an incremental rescan
+    ///
+    /// Projects are stored like a full scan's. Projects that are gone are
+    /// deleted, and the root's directory cache is replaced for the next
+    /// rescan.
+    pub fn store_incremental_scan(&mut self, rescan: &IncrementalScanResult) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let scan_result_id = Self::insert_scan_result_with_tx(&tx, &rescan.scan)?;
 
-            if let Some(ind_json) = indicator_json {
-                let indicator: ProjectIndicator = serde_json::from_str(&ind_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?;
-                if let Some(entry) = projects_map.get_mut(&path_str) {
-                    entry.3.push(indicator);
-                }
-            }
+        for project in &rescan.scan.projects {
+            let project_id = ProjectDatabase::upsert_project_with_tx(&tx, project)?;
+            Self::insert_scan_project_with_tx(&tx, scan_result_id, project_id, project)?;
+        }
 
-            // Respect the limit on number of projects
-            if let Some(lim) = limit {
-                if projects_map.len() >= lim {
-                    break;
-                }
+        // Deleted after storing, so projects that moved within the root were
+        // carried to their new path first
+        for path in &rescan.removed {
+            tx.execute(
+                "DELETE FROM projects WHERE path = ?",
+                params![path.to_string_lossy()],
+            )?;
+        }
+
+        let root = rescan.scan.root_path.to_string_lossy();
+        tx.execute(
+            r#"
+            INSERT INTO directory_caches (root_path, fingerprint, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT (root_path) DO UPDATE SET fingerprint = ?2, updated_at = ?3
+            "#,
+            params![
+                root,
+                rescan.cache.fingerprint,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM cached_directories WHERE root_path = ?",
+            params![root],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO cached_directories (root_path, path, mtime_ns, excluded) VALUES (?, ?, ?, ?)",
+            )?;
+            for directory in &rescan.cache.directories {
+                stmt.execute(params![
+                    root,
+                    directory.path.to_string_lossy(),
+                    directory.mtime_ns,
+                    directory.excluded
+                ])?;
             }
         }
 
-        // Maintain order (shortest paths first, then alphabetical)
-        let projects: Vec<Project> = project_order
-            .into_iter()
-            .filter_map(|path_str| {
-                projects_map
-                    .remove(&path_str)
-                    .map(|(_, project_type, last_scanned, indicators)| Project {
-                        path: std::path::PathBuf::from(path_str),
-                        project_type,
-                        indicators,
-                        last_scanned,
-                    })
-            })
-            .collect();
-
-        Ok(projects)
+        tx.commit()?;
+        Ok(scan_result_id)
     }
 
-    /// Store a scan result
-    pub fn store_scan_result(&mut self, scan_result: &ScanResult) -> Result<i64> {
-        let tx = self.conn.transaction()?;
+    /// Get the directories the last incremental scan of a root recorded
+    ///
+    /// A root that was never scanned incrementally has an empty cache.
+    pub fn get_directory_cache<P: AsRef<Path>>(&self, root: P) -> Result<DirectoryCache> {
+        let root = root.as_ref().to_string_lossy();
+        let Some(fingerprint) = self
+            .conn
+            .query_row(
+                "SELECT fingerprint FROM directory_caches WHERE root_path = ?",
+                params![root],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+        else {
+            return Ok(DirectoryCache::default());
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT path, mtime_ns, excluded FROM cached_directories WHERE root_path = ? ORDER BY path",
+        )?;
+        let directories = stmt
+            .query_map(params![root], |row| {
+                Ok(DirectoryState {
+                    path: std::path::PathBuf::from(row.get::<_, String>(0)?),
+                    mtime_ns: row.get(1)?,
+                    excluded: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(DirectoryCache {
+            fingerprint,
+            directories,
+        })
+    }
 
+    /// Record a scan with its errors and excluded directories
+    fn insert_scan_result_with_tx(tx: &Transaction, scan_result: &ScanResult) -> Result<i64> {
         // Insert scan result
         tx.execute(
             r#"
@@ -880,38 +2500,69 @@ impl ProjectDatabase {
             )?;
         }
 
-        // Store projects (this will also update existing ones)
-        for project in &scan_result.projects {
-            let project_id = ProjectDatabase::upsert_project_with_tx(&tx, project)?;
+        Ok(scan_result_id)
+    }
 
-            // Link project to this scan result
-            tx.execute(
-                "INSERT INTO scan_projects (scan_result_id, project_id) VALUES (?, ?)",
-                params![scan_result_id, project_id],
+    /// Link a project to a scan, recording what the scan found
+    fn insert_scan_project_with_tx(
+        tx: &Transaction,
+        scan_result_id: i64,
+        project_id: i64,
+        project: &Project,
+    ) -> Result<()> {
+        tx.execute(
+                r#"
+                INSERT INTO scan_projects (scan_result_id, project_id, path, project_type, indicators)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+                params![
+                    scan_result_id,
+                    project_id,
+                    project.path.to_string_lossy(),
+                    serde_json::to_string(&project.project_type)?,
+                    serde_json::to_string(&project.indicators)?
+                ],
             )?;
-        }
-
-        tx.commit()?;
-        Ok(scan_result_id)
+        Ok(())
     }
 
     /// Helper method to upsert project within a transaction
     fn upsert_project_with_tx(tx: &Transaction, project: &Project) -> Result<i64> {
-        // Insert or replace project
-        tx.execute(
+        // Disk usage and code stats are only measured on request, so a scan
+        // without them keeps the last figures
+        let disk_usage = match &project.disk_usage {
+            Some(usage) => Some(usage.clone()),
+            None => Self::stored_disk_usage(tx, &project.path)?,
+        };
+        let code_stats = match &project.code_stats {
+            Some(stats) => Some(stats.clone()),
+            None => Self::stored_code_stats(tx, &project.path)?,
+        };
+
+        // Insert the project or update it in place, keeping its id and the
+        // access history recorded for frecency
+        let identity = dprojc_utils::project_identity(project);
+        Self::follow_move(tx, &project.path, identity.as_deref())?;
+        let project_id: i64 = tx.query_row(
             r#"
-            INSERT OR REPLACE INTO projects (path, project_type, last_scanned, updated_at)
-            VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+            INSERT INTO projects (path, project_type, last_scanned, identity, updated_at)
+            VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT (path) DO UPDATE SET
+                project_type = excluded.project_type,
+                last_scanned = excluded.last_scanned,
+                identity = excluded.identity,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING id
             "#,
             params![
                 project.path.to_string_lossy(),
                 serde_json::to_string(&project.project_type)?,
-                project.last_scanned.to_rfc3339()
+                project.last_scanned.to_rfc3339(),
+                identity
             ],
+            |row| row.get(0),
         )?;
 
-        let project_id = tx.last_insert_rowid();
-
         // Delete existing indicators for this project
         tx.execute(
             "DELETE FROM project_indicators WHERE project_id = ?",
@@ -934,21 +2585,54 @@ impl ProjectDatabase {
             )?;
         }
 
+        Self::write_ecosystems(tx, project_id, &project.ecosystems)?;
+        Self::write_parent(tx, project_id, project.parent.as_deref())?;
+        Self::write_git(tx, project_id, project.git.as_ref())?;
+        Self::write_manifest(tx, project_id, project.manifest.as_ref())?;
+        Self::write_dependencies(tx, project_id, &project.dependencies)?;
+        Self::write_toolchains(tx, project_id, &project.toolchains)?;
+        Self::write_automation(tx, project_id, &project.automation)?;
+        Self::write_licenses(tx, project_id, &project.licenses)?;
+        Self::write_disk_usage(tx, project_id, disk_usage.as_ref())?;
+        Self::write_code_stats(tx, project_id, code_stats.as_ref())?;
+
         Ok(project_id)
     }
 
     /// Get recent scan results
     pub fn get_recent_scan_results(&self, limit: usize) -> Result<Vec<ScanResultSummary>> {
-        let mut stmt = self.conn.prepare(
+        self.query_scan_summaries(
+            "ORDER BY scan_timestamp DESC, id DESC LIMIT ?",
+            params![limit as i64],
+        )
+    }
+
+    /// Get the summary of a single scan result
+    pub fn get_scan_result_summary(
+        &self,
+        scan_result_id: i64,
+    ) -> Result<Option<ScanResultSummary>> {
+        Ok(self
+            .query_scan_summaries("WHERE id = ?", params![scan_result_id])?
+            .pop())
+    }
+
+    /// Load scan result summaries selected by a `WHERE`/`ORDER BY` clause
+    fn query_scan_summaries<P: rusqlite::Params>(
+        &self,
+        clause: &str,
+        params: P,
+    ) -> Result<Vec<ScanResultSummary>> {
+        let mut stmt = self.conn.prepare(&format!(
             r#"
             SELECT id, scan_timestamp, root_path, dirs_scanned, scan_duration_ms
             FROM scan_results
-            ORDER BY scan_timestamp DESC
-            LIMIT ?
+            {}
             "#,
-        )?;
+            clause
+        ))?;
 
-        let rows = stmt.query_map(params![limit as i64], |row| {
+        let rows = stmt.query_map(params, |row| {
             Ok(ScanResultSummary {
                 id: row.get(0)?,
                 scan_timestamp: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(1)?)
@@ -1054,7 +2738,6 @@ impl ProjectDatabase {
         }
 
         // Get projects for this scan
-        let mut projects = Vec::new();
         let mut project_stmt = self.conn.prepare(
             r#"
             SELECT p.id, p.path, p.project_type, p.last_scanned
@@ -1063,64 +2746,10 @@ impl ProjectDatabase {
             WHERE sp.scan_result_id = ?
             "#,
         )?;
-        let project_rows = project_stmt.query_map(params![scan_result_id], |row| {
-            let project_type_json: String = row.get(2)?;
-            let last_scanned_str: String = row.get(3)?;
-
-            Ok((
-                row.get::<_, i64>(0)?,    // id
-                row.get::<_, String>(1)?, // path
-                serde_json::from_str(&project_type_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        2,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?,
-                chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            3,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .with_timezone(&chrono::Utc),
-            ))
-        })?;
-
-        for row_result in project_rows {
-            let (id, path_str, project_type, last_scanned) = row_result?;
-
-            // Get indicators for this project
-            let mut indicators = Vec::new();
-            let mut indicator_stmt = self
-                .conn
-                .prepare("SELECT indicator_type FROM project_indicators WHERE project_id = ?")?;
-            let indicator_rows = indicator_stmt.query_map(params![id], |row| {
-                let indicator_json: String = row.get(0)?;
-                serde_json::from_str(&indicator_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })
-            })?;
-
-            for indicator_result in indicator_rows {
-                indicators.push(indicator_result?);
-            }
-
-            let project = Project {
-                path: std::path::PathBuf::from(path_str),
-                project_type,
-                indicators,
-                last_scanned,
-            };
-
-            projects.push(project);
-        }
+        let rows = project_stmt
+            .query_map(params![scan_result_id], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let projects = self.attach_details(rows)?;
 
         let scan_result = ScanResult {
             root_path: std::path::PathBuf::from(root_path),
@@ -1129,11 +2758,96 @@ impl ProjectDatabase {
             errors,
             dirs_scanned: dirs_scanned as usize,
             scan_duration_ms: scan_duration_ms as u64,
+            cancelled: false,
         };
 
         Ok(Some(scan_result))
     }
 
+    /// Ids of the two latest scans of a root, older first
+    ///
+    /// Without a root, the root of the latest scan is used. Returns `None`
+    /// until the root has been scanned twice.
+    pub fn get_latest_scan_pair(&self, root: Option<&Path>) -> Result<Option<(i64, i64)>> {
+        let root = match root {
+            Some(root) => root.to_string_lossy().into_owned(),
+            None => match self
+                .conn
+                .query_row(
+                    "SELECT root_path FROM scan_results ORDER BY scan_timestamp DESC, id DESC LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?
+            {
+                Some(root) => root,
+                None => return Ok(None),
+            },
+        };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM scan_results WHERE root_path = ? ORDER BY scan_timestamp DESC, id DESC LIMIT 2",
+        )?;
+        let ids = stmt
+            .query_map(params![root], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(match ids[..] {
+            [latest, previous] => Some((previous, latest)),
+            _ => None,
+        })
+    }
+
+    /// Get the projects a scan found, as it found them, ordered by path
+    ///
+    /// Projects deleted from the catalog since are still reported.
+    pub fn get_scanned_projects(&self, scan_result_id: i64) -> Result<Vec<ScannedProject>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT sp.path, sp.project_type, sp.indicators
+            FROM scan_projects sp
+            WHERE sp.scan_result_id = ?
+            ORDER BY sp.path
+            "#,
+        )?;
+        let rows = stmt
+            .query_map(params![scan_result_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut projects = Vec::new();
+        for (path, project_type, indicators) in rows {
+            projects.push(ScannedProject {
+                path: std::path::PathBuf::from(path),
+                project_type: serde_json::from_str(&project_type)?,
+                indicators: serde_json::from_str(&indicators)?,
+            });
+        }
+        Ok(projects)
+    }
+
+    /// Compare the projects found by two scans
+    ///
+    /// Returns `None` when either scan result does not exist.
+    pub fn diff_scans(&self, from_scan_id: i64, to_scan_id: i64) -> Result<Option<ScanDiff>> {
+        let (Some(from), Some(to)) = (
+            self.get_scan_result_summary(from_scan_id)?,
+            self.get_scan_result_summary(to_scan_id)?,
+        ) else {
+            return Ok(None);
+        };
+        Ok(Some(ScanDiff::between(
+            from,
+            to,
+            self.get_scanned_projects(from_scan_id)?,
+            self.get_scanned_projects(to_scan_id)?,
+        )))
+    }
+
     /// Get scan statistics
     pub fn get_scan_statistics(&self) -> Result<ScanStatistics> {
         let total_scans: i64 =
@@ -1215,7 +2929,7 @@ impl ProjectDatabase {
                 SELECT DISTINCT sp.project_id
                 FROM scan_projects sp
                 INNER JOIN scan_results sr ON sp.scan_result_id = sr.id
-                WHERE sr.scan_timestamp >= ?
+                WHERE sr.scan_timestamp >= ? AND sp.project_id IS NOT NULL
             )
             "#,
             params![cutoff_date.to_rfc3339()],
@@ -1241,10 +2955,25 @@ impl ProjectDatabase {
             "migrations",
             "projects",
             "project_indicators",
+            "project_ecosystems",
+            "project_parents",
+            "project_git",
+            "project_git_remotes",
+            "project_manifests",
+            "project_dependencies",
+            "project_toolchains",
+            "project_automation",
+            "project_licenses",
+            "project_disk_usage",
+            "project_code_stats",
+            "project_aliases",
             "scan_results",
             "scan_errors",
             "excluded_dirs",
             "scan_projects",
+            "project_types",
+            "directory_caches",
+            "cached_directories",
         ];
 
         for table in &tables {
@@ -1316,10 +3045,25 @@ impl ProjectDatabase {
     /// Drop all tables (for restore operations)
     fn drop_all_tables(&self) -> Result<()> {
         let tables = vec![
+            "cached_directories",
+            "directory_caches",
+            "project_types",
             "scan_projects",
             "excluded_dirs",
             "scan_errors",
             "scan_results",
+            "project_aliases",
+            "project_code_stats",
+            "project_disk_usage",
+            "project_licenses",
+            "project_automation",
+            "project_toolchains",
+            "project_dependencies",
+            "project_manifests",
+            "project_git_remotes",
+            "project_git",
+            "project_parents",
+            "project_ecosystems",
             "project_indicators",
             "projects",
             "migrations",
@@ -1355,8 +3099,23 @@ impl ProjectDatabase {
         self.conn.execute("DELETE FROM excluded_dirs", [])?;
         self.conn.execute("DELETE FROM scan_projects", [])?;
         self.conn.execute("DELETE FROM project_indicators", [])?;
+        self.conn.execute("DELETE FROM project_ecosystems", [])?;
+        self.conn.execute("DELETE FROM project_parents", [])?;
+        self.conn.execute("DELETE FROM project_git_remotes", [])?;
+        self.conn.execute("DELETE FROM project_git", [])?;
+        self.conn.execute("DELETE FROM project_manifests", [])?;
+        self.conn.execute("DELETE FROM project_dependencies", [])?;
+        self.conn.execute("DELETE FROM project_toolchains", [])?;
+        self.conn.execute("DELETE FROM project_automation", [])?;
+        self.conn.execute("DELETE FROM project_licenses", [])?;
+        self.conn.execute("DELETE FROM project_disk_usage", [])?;
+        self.conn.execute("DELETE FROM project_code_stats", [])?;
+        self.conn.execute("DELETE FROM project_aliases", [])?;
         self.conn.execute("DELETE FROM projects", [])?;
         self.conn.execute("DELETE FROM scan_results", [])?;
+        self.conn.execute("DELETE FROM project_types", [])?;
+        self.conn.execute("DELETE FROM cached_directories", [])?;
+        self.conn.execute("DELETE FROM directory_caches", [])?;
         self.conn.execute("DELETE FROM migrations", [])?;
         Ok(())
     }
@@ -1403,102 +3162,17 @@ impl ProjectDatabase {
 
     /// Get projects sorted by frecency score
     pub fn get_projects_by_frecency(&self, limit: usize) -> Result<Vec<Project>> {
-        // Use a JOIN to fetch indicators in a single query (avoid N+1)
         let mut stmt = self.conn.prepare(
-            "SELECT p.id, p.path, p.project_type, p.last_scanned, pi.indicator_type, p.frecency_score
-             FROM projects p
-             LEFT JOIN project_indicators pi ON p.id = pi.project_id
-             WHERE p.frecency_score > 0
-             ORDER BY p.frecency_score DESC, p.path, pi.indicator_type
-             LIMIT ?1"
+            "SELECT id, path, project_type, last_scanned
+             FROM projects
+             WHERE frecency_score > 0
+             ORDER BY frecency_score DESC, path
+             LIMIT ?1",
         )?;
-
-        let rows = stmt.query_map(params![limit], |row| {
-            let project_type_json: String = row.get(2)?;
-            let last_scanned_str: String = row.get(3)?;
-            let indicator_json: Option<String> = row.get(4)?;
-
-            Ok((
-                row.get::<_, i64>(0)?,    // id
-                row.get::<_, String>(1)?, // path
-                serde_json::from_str(&project_type_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?,
-                chrono::DateTime::parse_from_rfc3339(&last_scanned_str)
-                    .map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            0,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?
-                    .with_timezone(&chrono::Utc),
-                indicator_json,
-            ))
-        })?;
-
-        // Group results by project (since JOIN creates multiple rows per project)
-        let mut projects_map: std::collections::HashMap<
-            String,
-            (
-                i64,
-                ProjectType,
-                chrono::DateTime<chrono::Utc>,
-                Vec<ProjectIndicator>,
-            ),
-        > = std::collections::HashMap::new();
-        let mut project_order: Vec<String> = Vec::new();
-
-        for row_result in rows {
-            let (id, path_str, project_type, last_scanned, indicator_json) = row_result?;
-
-            if !projects_map.contains_key(&path_str) {
-                project_order.push(path_str.clone());
-                projects_map.insert(
-                    path_str.clone(),
-                    (id, project_type, last_scanned, Vec::new()),
-                );
-            }
-
-            if let Some(ind_json) = indicator_json {
-                let indicator: ProjectIndicator = serde_json::from_str(&ind_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?;
-                if let Some(entry) = projects_map.get_mut(&path_str) {
-                    entry.3.push(indicator);
-                }
-            }
-
-            // Respect the limit on number of projects (not rows)
-            if projects_map.len() >= limit {
-                break;
-            }
-        }
-
-        // Maintain frecency order
-        let projects: Vec<Project> = project_order
-            .into_iter()
-            .filter_map(|path_str| {
-                projects_map
-                    .remove(&path_str)
-                    .map(|(_, project_type, last_scanned, indicators)| Project {
-                        path: std::path::PathBuf::from(path_str),
-                        project_type,
-                        indicators,
-                        last_scanned,
-                    })
-            })
-            .collect();
-
-        Ok(projects)
+        let rows = stmt
+            .query_map(params![limit], Self::project_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        self.attach_details(rows)
     }
 
     /// Get frecency score for a specific path
@@ -1514,25 +3188,63 @@ impl ProjectDatabase {
                 |row| {
                     let current_score: f64 = row.get(0)?;
                     let last_accessed: Option<i64> = row.get(1)?;
-
-                    // Apply time decay
-                    let decayed_score = if let Some(last_access) = last_accessed {
-                        let time_diff = (now - last_access) as f64;
-                        let days_diff = time_diff / 86400.0;
-                        let half_life_days = 30.0;
-                        let decay_factor = 0.5_f64.powf(days_diff / half_life_days);
-                        current_score * decay_factor
-                    } else {
-                        current_score
-                    };
-
-                    Ok(decayed_score)
+                    Ok(Self::decayed_frecency(current_score, last_accessed, now))
                 },
             )
             .optional()?;
 
         Ok(score)
     }
+
+    /// Get the frecency score of every visited project, keyed by path
+    pub fn get_frecency_scores(
+        &self,
+    ) -> Result<std::collections::HashMap<std::path::PathBuf, f64>> {
+        let now = chrono::Utc::now().timestamp();
+        let mut stmt = self.conn.prepare(
+            "SELECT path, frecency_score, last_accessed FROM projects WHERE frecency_score > 0",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                std::path::PathBuf::from(row.get::<_, String>(0)?),
+                Self::decayed_frecency(row.get(1)?, row.get(2)?, now),
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Apply the 30-day half-life to a stored frecency score
+    fn decayed_frecency(score: f64, last_accessed: Option<i64>, now: i64) -> f64 {
+        match last_accessed {
+            Some(last_access) => {
+                let days_diff = (now - last_access) as f64 / 86400.0;
+                let half_life_days = 30.0;
+                score * 0.5_f64.powf(days_diff / half_life_days)
+            }
+            None => score,
+        }
+    }
+
+    /// Get when each visited project was last accessed, keyed by path
+    pub fn get_last_accessed(
+        &self,
+    ) -> Result<std::collections::HashMap<std::path::PathBuf, chrono::DateTime<chrono::Utc>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, last_accessed FROM projects WHERE last_accessed IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut last_accessed = std::collections::HashMap::new();
+        for row in rows {
+            let (path, timestamp) = row?;
+            if let Some(accessed) = chrono::DateTime::from_timestamp(timestamp, 0) {
+                last_accessed.insert(std::path::PathBuf::from(path), accessed);
+            }
+        }
+        Ok(last_accessed)
+    }
 }
 
 #[cfg(test)]
@@ -1595,19 +3307,168 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+
+        // Insert project
+        let project_id = db.upsert_project(&project)?;
+        assert!(project_id > 0);
+
+        // Get project by path
+        let retrieved = db.get_project_by_path("/test/project")?;
+        assert!(retrieved.is_some());
+        let retrieved = retrieved.unwrap();
+        assert_eq!(retrieved.path, project.path);
+        assert_eq!(retrieved.project_type, project.project_type);
+        assert_eq!(retrieved.indicators, project.indicators);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rescan_keeps_access_history() -> Result<()> {
+        let mut db = create_test_db()?;
+        let project = Project {
+            path: std::path::PathBuf::from("/test/project"),
+            project_type: ProjectType::Rust,
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let project_id = db.upsert_project(&project)?;
+        assert!(db.get_last_accessed()?.is_empty());
+        assert!(db.record_access("/test/project")?);
+
+        // Rescanning updates the project in place
+        let rescanned = Project {
+            project_type: ProjectType::NodeJs,
+            indicators: vec![ProjectIndicator::PackageJson],
+            ..project
+        };
+        assert_eq!(db.upsert_project(&rescanned)?, project_id);
+        let retrieved = db.get_project_by_path("/test/project")?.unwrap();
+        assert_eq!(retrieved.project_type, ProjectType::NodeJs);
+        assert_eq!(retrieved.indicators, vec![ProjectIndicator::PackageJson]);
+
+        assert!(db.get_frecency_score("/test/project")?.unwrap() > 0.0);
+        let last_accessed = db.get_last_accessed()?;
+        assert_eq!(last_accessed.len(), 1);
+        assert!(last_accessed.contains_key(Path::new("/test/project")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_moved_project_keeps_its_row() -> Result<()> {
+        let mut db = create_test_db()?;
+        let temp_dir = tempfile::tempdir()?;
+        let old_path = temp_dir.path().join("src/app");
+        let copy_path = temp_dir.path().join("backup/app");
+        let new_path = temp_dir.path().join("work/app");
+        for dir in [&old_path, &copy_path, &new_path] {
+            std::fs::create_dir_all(dir)?;
+        }
+        let project = |path: &Path| Project {
+            path: path.to_path_buf(),
+            project_type: ProjectType::Git,
+            git: Some(GitMetadata {
+                root_commit: Some("0123456789abcdef".to_string()),
+                remotes: vec![GitRemote {
+                    name: "origin".to_string(),
+                    url: "git@example.com:acme/app.git".to_string(),
+                }],
+                ..GitMetadata::default()
+            }),
+            indicators: vec![ProjectIndicator::GitDirectory],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let project_id = db.upsert_project(&project(&old_path))?;
+        db.record_access(&old_path)?;
+
+        // A second copy next to the original is a project of its own
+        assert_ne!(db.upsert_project(&project(&copy_path))?, project_id);
+
+        // Once the original is gone, the project found elsewhere takes its row
+        std::fs::remove_dir_all(&old_path)?;
+        assert_eq!(db.upsert_project(&project(&new_path))?, project_id);
+        assert!(db.get_project_by_path(&old_path)?.is_none());
+        assert!(db.get_frecency_score(&new_path)?.unwrap() > 0.0);
+        assert_eq!(db.get_all_projects()?.len(), 2);
+
+        let aliases = db.get_project_aliases()?;
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].path, old_path);
+        assert_eq!(aliases[0].project_path, new_path);
+        assert_eq!(db.resolve_alias(&old_path)?, Some(new_path.clone()));
+
+        // A new project at the old path replaces the alias
+        let mut unrelated = project(&old_path);
+        unrelated.git = None;
+        db.upsert_project(&unrelated)?;
+        assert!(db.get_project_aliases()?.is_empty());
+        assert_eq!(db.resolve_alias(&old_path)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_needs_an_unambiguous_identity() -> Result<()> {
+        let mut db = create_test_db()?;
+        let temp_dir = tempfile::tempdir()?;
+        let project = |path: std::path::PathBuf, remote: Option<&str>| Project {
+            path,
+            project_type: ProjectType::Rust,
+            manifest: Some(ProjectManifest {
+                source: "Cargo.toml".to_string(),
+                name: Some("app".to_string()),
+                ..ProjectManifest::default()
+            }),
+            git: Some(GitMetadata {
+                root_commit: Some("0123456789abcdef".to_string()),
+                remotes: remote
+                    .map(|url| GitRemote {
+                        name: "origin".to_string(),
+                        url: url.to_string(),
+                    })
+                    .into_iter()
+                    .collect(),
+                ..GitMetadata::default()
+            }),
+            ..Default::default()
         };
 
-        // Insert project
-        let project_id = db.upsert_project(&project)?;
-        assert!(project_id > 0);
-
-        // Get project by path
-        let retrieved = db.get_project_by_path("/test/project")?;
-        assert!(retrieved.is_some());
-        let retrieved = retrieved.unwrap();
-        assert_eq!(retrieved.path, project.path);
-        assert_eq!(retrieved.project_type, project.project_type);
-        assert_eq!(retrieved.indicators, project.indicators);
+        // Neither a manifest name nor a root commit without a remote follows
+        // a project to its new path
+        let gone = temp_dir.path().join("gone/app");
+        let local_id = db.upsert_project(&project(gone.clone(), None))?;
+        let found = temp_dir.path().join("found/app");
+        assert_ne!(db.upsert_project(&project(found, None))?, local_id);
+
+        // Two missing copies leave it unclear which one moved
+        let copies = [temp_dir.path().join("a/app"), temp_dir.path().join("b/app")];
+        for copy in &copies {
+            std::fs::create_dir_all(copy)?;
+        }
+        let first = db.upsert_project(&project(
+            copies[0].clone(),
+            Some("git@example.com:acme/app.git"),
+        ))?;
+        let second = db.upsert_project(&project(
+            copies[1].clone(),
+            Some("git@example.com:acme/app.git"),
+        ))?;
+        assert_ne!(first, second);
+        for copy in &copies {
+            std::fs::remove_dir_all(copy)?;
+        }
+        let moved = db.upsert_project(&project(
+            temp_dir.path().join("c/app"),
+            Some("git@example.com:acme/app.git"),
+        ))?;
+        assert_ne!(moved, first);
+        assert_ne!(moved, second);
+        assert!(db.get_project_aliases()?.is_empty());
 
         Ok(())
     }
@@ -1622,12 +3483,14 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
             Project {
                 path: std::path::PathBuf::from("/test/project2"),
                 project_type: ProjectType::NodeJs,
                 indicators: vec![ProjectIndicator::PackageJson],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             },
         ];
 
@@ -1650,6 +3513,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         let node_project = Project {
@@ -1657,6 +3521,7 @@ mod tests {
             project_type: ProjectType::NodeJs,
             indicators: vec![ProjectIndicator::PackageJson],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         db.upsert_project(&rust_project)?;
@@ -1682,6 +3547,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         db.upsert_project(&project)?;
@@ -1695,6 +3561,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_limited_getters_count_projects_and_load_their_details() -> Result<()> {
+        let mut db = create_test_db()?;
+        for name in ["app", "app-web", "other"] {
+            let path = std::path::PathBuf::from(format!("/src/{}", name));
+            db.upsert_project(&Project {
+                path: path.clone(),
+                project_type: ProjectType::Rust,
+                ecosystems: vec![ProjectType::NodeJs],
+                indicators: vec![ProjectIndicator::CargoToml, ProjectIndicator::PackageJson],
+                last_scanned: chrono::Utc::now(),
+                ..Default::default()
+            })?;
+            db.record_access(&path)?;
+        }
+
+        // The limit counts projects, not one row per indicator
+        let results = db.search_projects_by_path_limit("app", Some(2))?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].path, std::path::PathBuf::from("/src/app"));
+        for project in db.get_projects_by_frecency(2)?.iter().chain(&results) {
+            assert_eq!(project.indicators.len(), 2);
+            assert_eq!(project.ecosystems, vec![ProjectType::NodeJs]);
+        }
+        assert_eq!(db.get_projects_by_frecency(2)?.len(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_store_and_get_scan_result() -> Result<()> {
         let mut db = create_test_db()?;
@@ -1706,6 +3601,7 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             }],
             excluded_dirs: vec![std::path::PathBuf::from("/test/excluded")],
             errors: vec![ScanError {
@@ -1715,6 +3611,7 @@ mod tests {
             }],
             dirs_scanned: 100,
             scan_duration_ms: 5000,
+            ..Default::default()
         };
 
         // Store scan result
@@ -1745,6 +3642,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 50,
             scan_duration_ms: 2000,
+            ..Default::default()
         };
 
         db.store_scan_result(&scan_result)?;
@@ -1756,6 +3654,187 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_store_incremental_scan() -> Result<()> {
+        let mut db = create_test_db()?;
+        let project = |path: &str| Project {
+            path: std::path::PathBuf::from(path),
+            project_type: ProjectType::Rust,
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let directory = |path: &str, mtime_ns| DirectoryState {
+            path: std::path::PathBuf::from(path),
+            mtime_ns,
+            excluded: false,
+        };
+
+        db.upsert_project(&project("/src/kept"))?;
+        db.upsert_project(&project("/src/gone"))?;
+        assert_eq!(db.get_directory_cache("/src")?, DirectoryCache::default());
+
+        let rescan = IncrementalScanResult {
+            scan: ScanResult {
+                root_path: std::path::PathBuf::from("/src"),
+                projects: vec![project("/src/kept"), project("/src/new")],
+                excluded_dirs: vec![],
+                errors: vec![],
+                dirs_scanned: 3,
+                scan_duration_ms: 5,
+                ..Default::default()
+            },
+            removed: vec![std::path::PathBuf::from("/src/gone")],
+            unchanged_dirs: 1,
+            cache: DirectoryCache {
+                fingerprint: "walk".to_string(),
+                directories: vec![
+                    directory("/src", Some(10)),
+                    directory("/src/kept", Some(20)),
+                    directory("/src/new", None),
+                ],
+            },
+        };
+        let scan_id = db.store_incremental_scan(&rescan)?;
+
+        assert_eq!(
+            db.get_project_paths_under("/src")?,
+            vec![
+                std::path::PathBuf::from("/src/kept"),
+                std::path::PathBuf::from("/src/new"),
+            ]
+        );
+        assert_eq!(db.get_scanned_projects(scan_id)?.len(), 2);
+        assert_eq!(db.get_directory_cache("/src")?, rescan.cache);
+
+        // The next rescan replaces the cache
+        let mut next = rescan.clone();
+        next.cache.directories.truncate(1);
+        db.store_incremental_scan(&next)?;
+        assert_eq!(db.get_directory_cache("/src")?.directories.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_scans() -> Result<()> {
+        let mut db = create_test_db()?;
+        let project = |path: &str, project_type, indicators: Vec<ProjectIndicator>| Project {
+            path: std::path::PathBuf::from(path),
+            project_type,
+            indicators,
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let scan = |root: &str, projects| ScanResult {
+            root_path: std::path::PathBuf::from(root),
+            projects,
+            excluded_dirs: vec![],
+            errors: vec![],
+            dirs_scanned: 10,
+            scan_duration_ms: 100,
+            ..Default::default()
+        };
+
+        assert_eq!(db.get_latest_scan_pair(None)?, None);
+        let first = db.store_scan_result(&scan(
+            "/src",
+            vec![
+                project(
+                    "/src/app",
+                    ProjectType::NodeJs,
+                    vec![ProjectIndicator::PackageJson],
+                ),
+                project("/src/old", ProjectType::Go, vec![ProjectIndicator::GoMod]),
+            ],
+        ))?;
+        assert_eq!(db.get_latest_scan_pair(None)?, None);
+        let second = db.store_scan_result(&scan(
+            "/src",
+            vec![
+                project(
+                    "/src/app",
+                    ProjectType::Rust,
+                    vec![ProjectIndicator::PackageJson, ProjectIndicator::CargoToml],
+                ),
+                project(
+                    "/src/new",
+                    ProjectType::Python,
+                    vec![ProjectIndicator::SetupPy],
+                ),
+            ],
+        ))?;
+        db.store_scan_result(&scan("/elsewhere", vec![]))?;
+
+        assert_eq!(
+            db.get_latest_scan_pair(Some(Path::new("/src")))?,
+            Some((first, second))
+        );
+        assert_eq!(db.get_latest_scan_pair(None)?, None);
+
+        // The first scan still reports the type it saw before the update
+        let diff = db.diff_scans(first, second)?.unwrap();
+        assert_eq!(diff.from.id, first);
+        assert_eq!(diff.to.id, second);
+        assert_eq!(diff.added[0].path, std::path::PathBuf::from("/src/new"));
+        assert_eq!(diff.removed[0].path, std::path::PathBuf::from("/src/old"));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].old_type, ProjectType::NodeJs);
+        assert_eq!(diff.changed[0].new_type, ProjectType::Rust);
+        assert_eq!(
+            diff.changed[0].added_indicators,
+            vec![ProjectIndicator::CargoToml]
+        );
+        assert!(diff.changed[0].removed_indicators.is_empty());
+
+        assert!(db.diff_scans(second, second)?.unwrap().is_empty());
+        assert!(db.diff_scans(first, 999)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_reports_deleted_projects() -> Result<()> {
+        let mut db = create_test_db()?;
+        let scan = |paths: &[&str]| ScanResult {
+            root_path: std::path::PathBuf::from("/src"),
+            projects: paths
+                .iter()
+                .map(|path| Project {
+                    path: std::path::PathBuf::from(path),
+                    project_type: ProjectType::Go,
+                    indicators: vec![ProjectIndicator::GoMod],
+                    last_scanned: chrono::Utc::now(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let first = db.store_scan_result(&scan(&["/src/app", "/src/old"]))?;
+        // A project that disappeared is deleted before the next scan is stored
+        assert!(db.delete_project_by_path("/src/old")?);
+        let second = db.store_scan_result(&scan(&["/src/app"]))?;
+
+        let diff = db.diff_scans(first, second)?.unwrap();
+        assert!(diff.added.is_empty());
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, std::path::PathBuf::from("/src/old"));
+        assert_eq!(diff.removed[0].project_type, ProjectType::Go);
+        assert_eq!(diff.removed[0].indicators, vec![ProjectIndicator::GoMod]);
+
+        // Scans that outlived their projects do not keep others from cleanup
+        db.upsert_project(&Project {
+            path: std::path::PathBuf::from("/elsewhere/lost"),
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        })?;
+        assert_eq!(db.cleanup_orphaned_projects(30)?, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_scan_statistics() -> Result<()> {
         let mut db = create_test_db()?;
@@ -1770,6 +3849,7 @@ mod tests {
                 project_type: ProjectType::Rust,
                 indicators: vec![ProjectIndicator::CargoToml],
                 last_scanned: chrono::Utc::now(),
+                ..Default::default()
             }],
             excluded_dirs: vec![],
             errors: vec![ScanError {
@@ -1779,6 +3859,7 @@ mod tests {
             }],
             dirs_scanned: 25,
             scan_duration_ms: 1000,
+            ..Default::default()
         };
 
         db.store_scan_result(&scan_result)?;
@@ -1802,6 +3883,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         db.upsert_project(&project)?;
@@ -1821,6 +3903,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_delete_projects_under() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        for path in [
+            "/src/app",
+            "/src/app/crates/core",
+            "/src/application",
+            "/work",
+        ] {
+            db.upsert_project(&Project {
+                path: std::path::PathBuf::from(path),
+                project_type: ProjectType::Rust,
+                indicators: vec![ProjectIndicator::CargoToml],
+                last_scanned: chrono::Utc::now(),
+                ..Default::default()
+            })?;
+        }
+
+        assert_eq!(db.get_project_paths_under("/src")?.len(), 3);
+
+        let deleted = db.delete_projects_under("/src/app")?;
+        assert_eq!(
+            deleted,
+            vec![
+                std::path::PathBuf::from("/src/app"),
+                std::path::PathBuf::from("/src/app/crates/core"),
+            ]
+        );
+        assert_eq!(
+            db.get_project_paths_under("/")?,
+            vec![
+                std::path::PathBuf::from("/src/application"),
+                std::path::PathBuf::from("/work"),
+            ]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_projects_by_indicator() -> Result<()> {
         let mut db = create_test_db()?;
@@ -1830,6 +3952,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         let node_project = Project {
@@ -1837,6 +3960,7 @@ mod tests {
             project_type: ProjectType::NodeJs,
             indicators: vec![ProjectIndicator::PackageJson],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         db.upsert_project(&rust_project)?;
@@ -1862,6 +3986,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         let rust_project2 = Project {
@@ -1869,6 +3994,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         let node_project = Project {
@@ -1876,6 +4002,7 @@ mod tests {
             project_type: ProjectType::NodeJs,
             indicators: vec![ProjectIndicator::PackageJson],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         db.upsert_project(&rust_project1)?;
@@ -1889,6 +4016,570 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_secondary_ecosystems() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let polyglot = Project {
+            path: std::path::PathBuf::from("/test/polyglot"),
+            project_type: ProjectType::Rust,
+            ecosystems: vec![ProjectType::NodeJs, ProjectType::Python],
+            indicators: vec![
+                ProjectIndicator::CargoToml,
+                ProjectIndicator::PackageJson,
+                ProjectIndicator::RequirementsTxt,
+            ],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let node_project = Project {
+            path: std::path::PathBuf::from("/test/node"),
+            project_type: ProjectType::NodeJs,
+            indicators: vec![ProjectIndicator::PackageJson],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&polyglot)?;
+        db.upsert_project(&node_project)?;
+
+        let retrieved = db.get_project_by_path("/test/polyglot")?.unwrap();
+        assert_eq!(retrieved.ecosystems, polyglot.ecosystems);
+        let all = db.get_all_projects()?;
+        assert_eq!(all[1].ecosystems, polyglot.ecosystems);
+        assert!(all[0].ecosystems.is_empty());
+
+        // Secondary ecosystems are visible to type queries
+        let node_projects = db.get_projects_by_type(&ProjectType::NodeJs)?;
+        assert_eq!(node_projects.len(), 2);
+        let python_projects = db.get_projects_by_type(&ProjectType::Python)?;
+        assert_eq!(python_projects.len(), 1);
+        assert_eq!(python_projects[0].project_type, ProjectType::Rust);
+
+        let primary = db.get_project_counts_by_type()?;
+        assert_eq!(primary.get(&ProjectType::NodeJs), Some(&1));
+        assert_eq!(primary.get(&ProjectType::Python), None);
+        let containing = db.get_project_counts_by_ecosystem()?;
+        assert_eq!(containing.get(&ProjectType::NodeJs), Some(&2));
+        assert_eq!(containing.get(&ProjectType::Python), Some(&1));
+        assert_eq!(containing.get(&ProjectType::Rust), Some(&1));
+
+        // Rescanning replaces the stored ecosystems
+        let rescanned = Project {
+            ecosystems: vec![ProjectType::Go],
+            ..polyglot
+        };
+        db.upsert_project(&rescanned)?;
+        assert_eq!(
+            db.get_project_by_path("/test/polyglot")?
+                .unwrap()
+                .ecosystems,
+            vec![ProjectType::Go]
+        );
+        assert!(db.get_projects_by_type(&ProjectType::Python)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workspace_parents() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let workspace = Project {
+            path: std::path::PathBuf::from("/test/mono"),
+            project_type: ProjectType::Rust,
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let member = Project {
+            path: std::path::PathBuf::from("/test/mono/crates/core"),
+            parent: Some(workspace.path.clone()),
+            ..workspace.clone()
+        };
+        db.upsert_project(&member)?;
+        db.upsert_project(&workspace)?;
+
+        let retrieved = db.get_project_by_path("/test/mono/crates/core")?.unwrap();
+        assert_eq!(retrieved.parent, Some(workspace.path.clone()));
+        let all = db.get_all_projects()?;
+        assert_eq!(all[0].parent, None);
+        assert_eq!(all[1].parent, Some(workspace.path.clone()));
+
+        // Rewriting the workspace row keeps its members linked by path
+        db.upsert_project(&workspace)?;
+        let members = db.get_workspace_members("/test/mono")?;
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].path, member.path);
+
+        // A member that leaves the workspace loses its parent
+        db.upsert_project(&Project {
+            parent: None,
+            ..member.clone()
+        })?;
+        assert!(db.get_workspace_members("/test/mono")?.is_empty());
+        assert_eq!(
+            db.get_project_by_path("/test/mono/crates/core")?
+                .unwrap()
+                .parent,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_manifests() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let manifest = ProjectManifest {
+            source: "package.json".to_string(),
+            name: Some("@acme/ui".to_string()),
+            version: Some("2.1.0".to_string()),
+            description: Some("Design system components".to_string()),
+            authors: vec!["Grace <grace@example.com>".to_string()],
+            license: Some("MIT".to_string()),
+            homepage: None,
+        };
+        let project = Project {
+            path: std::path::PathBuf::from("/test/ui"),
+            project_type: ProjectType::NodeJs,
+            manifest: Some(manifest.clone()),
+            indicators: vec![ProjectIndicator::PackageJson],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project)?;
+        db.upsert_project(&Project {
+            path: std::path::PathBuf::from("/test/other"),
+            manifest: None,
+            ..project.clone()
+        })?;
+
+        let retrieved = db.get_project_by_path("/test/ui")?.unwrap();
+        assert_eq!(retrieved.manifest.as_ref(), Some(&manifest));
+        assert_eq!(retrieved.name(), "@acme/ui");
+        let all = db.get_all_projects()?;
+        assert_eq!(all[0].manifest, None);
+        assert_eq!(all[1].manifest.as_ref(), Some(&manifest));
+
+        for query in ["acme", "DESIGN", "grace@", "mit"] {
+            let found = db.search_projects_by_manifest(query)?;
+            assert_eq!(found.len(), 1, "query {}", query);
+            assert_eq!(found[0].path, project.path);
+        }
+        assert!(db.search_projects_by_manifest("2.1.0")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_usage() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let usage = DiskUsage {
+            total_bytes: 5_000_000_000,
+            source_bytes: 1_000,
+            artifact_bytes: 4_999_000_000,
+            artifact_dirs: vec![dprojc_types::ArtifactDir {
+                path: std::path::PathBuf::from("target"),
+                bytes: 4_999_000_000,
+            }],
+            measured_at: chrono::Utc::now(),
+        };
+        let project = Project {
+            path: std::path::PathBuf::from("/test/api"),
+            project_type: ProjectType::Rust,
+            disk_usage: Some(usage.clone()),
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project)?;
+
+        let retrieved = db.get_project_by_path("/test/api")?.unwrap();
+        assert_eq!(retrieved.disk_usage.as_ref(), Some(&usage));
+        assert_eq!(db.get_all_projects()?[0].disk_usage.as_ref(), Some(&usage));
+
+        // A later scan without measuring keeps the last figures, also when
+        // saved as part of a batch
+        db.upsert_project(&Project {
+            disk_usage: None,
+            ..project.clone()
+        })?;
+        assert_eq!(
+            db.get_project_by_path("/test/api")?.unwrap().disk_usage,
+            Some(usage.clone())
+        );
+        db.store_scan_result(&ScanResult {
+            root_path: std::path::PathBuf::from("/test"),
+            projects: vec![Project {
+                disk_usage: None,
+                ..project
+            }],
+            excluded_dirs: Vec::new(),
+            errors: Vec::new(),
+            dirs_scanned: 1,
+            scan_duration_ms: 1,
+            ..Default::default()
+        })?;
+        assert_eq!(
+            db.get_project_by_path("/test/api")?.unwrap().disk_usage,
+            Some(usage)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_code_stats() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let language = |language: &str, files, code, comments, blanks| LanguageStats {
+            language: language.to_string(),
+            files,
+            code,
+            comments,
+            blanks,
+        };
+        let stats = CodeStats {
+            languages: vec![
+                language("Rust", 10, 1_200, 300, 150),
+                language("TOML", 3, 40, 2, 5),
+            ],
+            measured_at: chrono::Utc::now(),
+        };
+        let project = |path: &str, code_stats: Option<CodeStats>| Project {
+            path: std::path::PathBuf::from(path),
+            project_type: ProjectType::Rust,
+            code_stats,
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project("/work/app", Some(stats.clone())))?;
+
+        let retrieved = db.get_project_by_path("/work/app")?.unwrap();
+        assert_eq!(retrieved.code_stats.as_ref(), Some(&stats));
+        assert_eq!(db.get_all_projects()?[0].code_stats.as_ref(), Some(&stats));
+
+        // A scan without counting keeps the last counts
+        db.upsert_project(&project("/work/app", None))?;
+        assert_eq!(
+            db.get_project_by_path("/work/app")?.unwrap().code_stats,
+            Some(stats.clone())
+        );
+
+        // A member inside the counted workspace is not added twice
+        db.upsert_project(&project(
+            "/work/app/crates/core",
+            Some(CodeStats {
+                languages: vec![language("Rust", 4, 500, 100, 50)],
+                measured_at: chrono::Utc::now(),
+            }),
+        ))?;
+        db.upsert_project(&project(
+            "/work/site",
+            Some(CodeStats {
+                languages: vec![language("TypeScript", 20, 800, 40, 90)],
+                measured_at: chrono::Utc::now(),
+            }),
+        ))?;
+        let counts: Vec<_> = db
+            .get_language_counts()?
+            .into_iter()
+            .map(|count| (count.stats.language, count.projects, count.stats.code))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Rust".to_string(), 1, 1_200),
+                ("TypeScript".to_string(), 1, 800),
+                ("TOML".to_string(), 1, 40),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dependencies() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let dependency = |ecosystem, name: &str, version: &str| Dependency {
+            ecosystem,
+            name: name.to_string(),
+            version: version.to_string(),
+        };
+        let project = |path: &str, dependencies| Project {
+            path: std::path::PathBuf::from(path),
+            project_type: ProjectType::NodeJs,
+            dependencies,
+            indicators: vec![ProjectIndicator::PackageJson],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let web = project(
+            "/test/web",
+            vec![
+                dependency(DependencyEcosystem::Npm, "lodash", "4.17.21"),
+                dependency(DependencyEcosystem::Npm, "react", "18.2.0"),
+            ],
+        );
+        let admin = project(
+            "/test/admin",
+            vec![
+                dependency(DependencyEcosystem::Npm, "lodash", "3.10.1"),
+                dependency(DependencyEcosystem::Npm, "react", "18.2.0"),
+                dependency(DependencyEcosystem::Cargo, "lodash", "0.1.0"),
+            ],
+        );
+        db.upsert_project(&web)?;
+        db.upsert_project(&admin)?;
+
+        assert_eq!(
+            db.get_project_by_path("/test/web")?.unwrap().dependencies,
+            web.dependencies
+        );
+        assert_eq!(db.get_all_projects()?[0].dependencies, admin.dependencies);
+
+        let dependents = db.get_dependents("LODASH", Some(DependencyEcosystem::Npm), None)?;
+        assert_eq!(dependents.len(), 2);
+        assert_eq!(dependents[0].path, admin.path);
+        assert_eq!(dependents[0].dependency.version, "3.10.1");
+        assert_eq!(db.get_dependents("lodash", None, None)?.len(), 3);
+        assert_eq!(
+            db.get_dependents("lodash", None, Some("4.17.21"))?[0].path,
+            web.path
+        );
+
+        // Only lodash has drifted apart; react is on one version everywhere
+        let spread = db.get_version_spread(Some(DependencyEcosystem::Npm), None, 2)?;
+        assert_eq!(spread.len(), 1);
+        assert_eq!(spread[0].name, "lodash");
+        assert_eq!(
+            spread[0].versions,
+            vec![
+                ("3.10.1".to_string(), vec![admin.path.clone()]),
+                ("4.17.21".to_string(), vec![web.path.clone()]),
+            ]
+        );
+        let react = db.get_version_spread(None, Some("react"), 1)?;
+        assert_eq!(
+            react[0].versions,
+            vec![(
+                "18.2.0".to_string(),
+                vec![admin.path.clone(), web.path.clone()]
+            )]
+        );
+
+        // Rescanning without a lockfile drops the stored dependencies
+        db.upsert_project(&project("/test/web", Vec::new()))?;
+        assert_eq!(db.get_dependents("react", None, None)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_toolchains() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let pin = |tool: &str, version: &str, source: &str| ToolchainPin {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            source: source.to_string(),
+        };
+        let project = |path: &str, toolchains| Project {
+            path: std::path::PathBuf::from(path),
+            project_type: ProjectType::NodeJs,
+            toolchains,
+            indicators: vec![ProjectIndicator::PackageJson],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let web = project(
+            "/test/web",
+            vec![
+                pin("node", "16", ".nvmrc"),
+                pin("node", "16", ".tool-versions"),
+                pin("python", "3.11", ".tool-versions"),
+            ],
+        );
+        let admin = project("/test/admin", vec![pin("node", "18.17.0", ".nvmrc")]);
+        db.upsert_project(&web)?;
+        db.upsert_project(&admin)?;
+
+        assert_eq!(
+            db.get_project_by_path("/test/web")?.unwrap().toolchains,
+            web.toolchains
+        );
+        assert_eq!(db.get_all_projects()?[0].toolchains, admin.toolchains);
+
+        let count = |tool: &str, version: &str, projects| ToolchainCount {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            projects,
+        };
+        assert_eq!(
+            db.get_toolchain_counts(None)?,
+            vec![
+                count("node", "16", 1),
+                count("node", "18.17.0", 1),
+                count("python", "3.11", 1),
+            ]
+        );
+        assert_eq!(db.get_toolchain_counts(Some("python"))?.len(), 1);
+
+        // Rescanning without pin files drops the stored pins
+        db.upsert_project(&project("/test/web", Vec::new()))?;
+        assert_eq!(db.get_toolchain_counts(Some("python"))?, Vec::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_automation() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let project = Project {
+            path: std::path::PathBuf::from("/test/api"),
+            project_type: ProjectType::Rust,
+            automation: vec![
+                AutomationConfig {
+                    kind: AutomationKind::GithubActions,
+                    path: ".github/workflows/ci.yml".to_string(),
+                    targets: vec!["test".to_string(), "lint".to_string()],
+                },
+                AutomationConfig {
+                    kind: AutomationKind::Dockerfile,
+                    path: "Dockerfile".to_string(),
+                    targets: Vec::new(),
+                },
+            ],
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project)?;
+
+        assert_eq!(
+            db.get_project_by_path("/test/api")?.unwrap().automation,
+            project.automation
+        );
+        assert_eq!(db.get_all_projects()?[0].automation, project.automation);
+
+        db.upsert_project(&Project {
+            automation: Vec::new(),
+            ..project
+        })?;
+        assert!(db.get_all_projects()?[0].automation.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_metadata() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let git = GitMetadata {
+            branch: Some("main".to_string()),
+            head_commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            head_date: chrono::DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+                .ok()
+                .map(|date| date.with_timezone(&chrono::Utc)),
+            remotes: vec![
+                GitRemote {
+                    name: "origin".to_string(),
+                    url: "git@example.com:app.git".to_string(),
+                },
+                GitRemote {
+                    name: "upstream".to_string(),
+                    url: "https://example.com/app.git".to_string(),
+                },
+            ],
+            upstream: Some("origin/main".to_string()),
+            ahead: 2,
+            behind: 1,
+            dirty_count: 3,
+            untracked_count: 4,
+            stash_count: 1,
+            is_worktree: false,
+            is_submodule: true,
+            root_commit: Some("fedcba9876543210fedcba9876543210fedcba98".to_string()),
+        };
+        let project = Project {
+            path: std::path::PathBuf::from("/test/app"),
+            project_type: ProjectType::Git,
+            git: Some(git.clone()),
+            indicators: vec![ProjectIndicator::GitDirectory],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project)?;
+
+        let retrieved = db.get_project_by_path("/test/app")?.unwrap();
+        assert_eq!(retrieved.git.as_ref(), Some(&git));
+        assert_eq!(db.get_all_projects()?[0].git.as_ref(), Some(&git));
+
+        // A rescan that no longer finds a repository clears the stored state
+        db.upsert_project(&Project {
+            git: None,
+            ..project.clone()
+        })?;
+        assert_eq!(db.get_project_by_path("/test/app")?.unwrap().git, None);
+        let remotes: i64 =
+            db.conn
+                .query_row("SELECT COUNT(*) FROM project_git_remotes", [], |row| {
+                    row.get(0)
+                })?;
+        assert_eq!(remotes, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_project_types() -> Result<()> {
+        let mut db = create_test_db()?;
+
+        let terraform = ProjectType::Custom("terraform".to_string());
+        let project = Project {
+            path: std::path::PathBuf::from("/test/infra"),
+            project_type: terraform.clone(),
+            indicators: vec![ProjectIndicator::Custom("*.tf".to_string())],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        db.upsert_project(&project)?;
+
+        let by_type = db.get_projects_by_type(&terraform)?;
+        assert_eq!(by_type.len(), 1);
+        assert_eq!(by_type[0].project_type, terraform);
+        assert_eq!(db.get_project_counts_by_type()?.get(&terraform), Some(&1));
+
+        let definitions = vec![
+            ProjectTypeDefinition {
+                name: "helm".to_string(),
+                indicators: vec!["Chart.yaml".to_string()],
+                rules: Vec::new(),
+                priority: 3,
+            },
+            ProjectTypeDefinition {
+                name: "terraform".to_string(),
+                indicators: vec!["*.tf".to_string()],
+                rules: Vec::new(),
+                priority: 20,
+            },
+        ];
+        db.replace_project_types(&definitions)?;
+        let stored = db.get_project_types()?;
+        assert_eq!(stored, vec![definitions[1].clone(), definitions[0].clone()]);
+
+        // Replacing drops types that are no longer configured
+        db.replace_project_types(&definitions[..1])?;
+        assert_eq!(db.get_project_types()?, vec![definitions[0].clone()]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_backup_and_restore() -> Result<()> {
         let mut db = create_test_db()?;
@@ -1899,6 +4590,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
         db.upsert_project(&project)?;
 
@@ -1909,6 +4601,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 10,
             scan_duration_ms: 100,
+            ..Default::default()
         };
         db.store_scan_result(&scan_result)?;
 
@@ -1948,6 +4641,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 5,
             scan_duration_ms: 50,
+            ..Default::default()
         };
 
         // Store scan result
@@ -1989,6 +4683,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 1,
             scan_duration_ms: 10,
+            ..Default::default()
         };
 
         let scan2 = ScanResult {
@@ -1998,6 +4693,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 1,
             scan_duration_ms: 10,
+            ..Default::default()
         };
 
         // Store both
@@ -2029,6 +4725,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
         db.upsert_project(&project)?;
 
@@ -2040,6 +4737,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 1,
             scan_duration_ms: 10,
+            ..Default::default()
         };
         db.store_scan_result(&scan_result)?;
 
@@ -2076,6 +4774,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
         db.upsert_project(&project)?;
 
@@ -2086,6 +4785,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 1,
             scan_duration_ms: 10,
+            ..Default::default()
         };
         db.store_scan_result(&scan_result)?;
 
@@ -2133,6 +4833,7 @@ mod tests {
             project_type: ProjectType::Unknown,
             indicators: vec![],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
         db.upsert_project(&project_no_indicators)?;
         let retrieved = db.get_project_by_path("/test/no_indicators")?;
@@ -2145,6 +4846,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
         db.upsert_project(&project_special)?;
         let retrieved = db.get_project_by_path("/test/path with spaces & symbols!@#")?;
@@ -2172,6 +4874,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 10,
             scan_duration_ms: 500,
+            ..Default::default()
         };
 
         let scan_id = db.store_scan_result(&scan_result)?;
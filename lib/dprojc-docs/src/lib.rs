@@ -1,10 +1,12 @@
 use anyhow::Result;
 use dprojc_core::ProjectCatalog;
-use dprojc_types::Project;
+use dprojc_types::{Project, ProjectIndicator, ProjectType};
+use dprojc_utils::{group_workspace_members, IndicatorMatcher};
 use handlebars::Handlebars;
 use pulldown_cmark::{html, Parser};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use syntect::highlighting::ThemeSet;
 use syntect::html::highlighted_html_for_string;
@@ -197,9 +199,11 @@ impl<'a> DocumentationGenerator<'a> {
     /// Generate projects list page
     async fn generate_projects_list(&self) -> Result<()> {
         std::fs::create_dir_all(&self.config.output_dir)?;
-        let projects = self.catalog.get_all_projects().await?;
+        let mut projects = self.catalog.get_all_projects().await?;
         let counts = self.catalog.get_project_counts().await?;
 
+        // List workspace members right after their workspace
+        group_workspace_members(&mut projects);
         let projects_data: Vec<serde_json::Value> = projects
             .iter()
             .map(|p| self.create_project_data(p))
@@ -223,11 +227,15 @@ impl<'a> DocumentationGenerator<'a> {
         let stats = self.catalog.get_scan_statistics().await?;
         let counts = self.catalog.get_project_counts().await?;
         let recent_scans = self.catalog.get_recent_scans(10).await?;
+        let projects = self.catalog.get_all_projects().await?;
+        let language_counts = self.catalog.get_language_counts().await?;
 
         let data = serde_json::json!({
             "statistics": stats,
             "project_counts": counts,
             "recent_scans": recent_scans,
+            "language_counts": language_counts,
+            "licenses": Self::license_groups(&projects),
             "generated_at": chrono::Utc::now().to_rfc3339(),
         });
 
@@ -325,18 +333,89 @@ impl<'a> DocumentationGenerator<'a> {
 
     /// Create project data for templates
     fn create_project_data(&self, project: &Project) -> serde_json::Value {
+        let parent = project.parent.as_ref().map(|parent| {
+            serde_json::json!({
+                "id": Self::derive_project_id(parent),
+                "name": Self::project_name(parent),
+                "path": parent.display().to_string(),
+            })
+        });
+        let automation: Vec<_> = project
+            .automation
+            .iter()
+            .map(|config| {
+                serde_json::json!({
+                    "kind": config.kind.label(),
+                    "path": config.path,
+                    "targets": config.targets,
+                })
+            })
+            .collect();
         serde_json::json!({
             "id": Self::derive_project_id(&project.path),
-            "name": project.path.file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string()),
+            "name": project.name(),
             "path": project.path.display().to_string(),
+            "parent": parent,
+            "manifest": project.manifest,
+            "automation": automation,
             "project_type": project.project_type.to_string(),
-            "language": Self::detect_language(&project.path).unwrap_or_else(|| "Unknown".to_string()),
+            "ecosystems": project.ecosystems.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            "language": Self::project_language(project),
             "last_modified": project.last_scanned.to_rfc3339(),
         })
     }
 
+    /// Projects grouped by license, most common first
+    ///
+    /// Projects without an identified license form a last group flagged as
+    /// unknown, listing the files or manifest values that were not recognized.
+    fn license_groups(projects: &[Project]) -> Vec<serde_json::Value> {
+        let mut groups: BTreeMap<Option<String>, Vec<&Project>> = BTreeMap::new();
+        for project in projects {
+            groups.entry(project.license()).or_default().push(project);
+        }
+        let unknown = groups.remove(&None);
+
+        let mut groups: Vec<(Option<String>, Vec<&Project>)> = groups.into_iter().collect();
+        groups.sort_by_key(|(_, projects)| std::cmp::Reverse(projects.len()));
+        groups.extend(unknown.map(|projects| (None, projects)));
+
+        groups
+            .into_iter()
+            .map(|(license, projects)| {
+                let projects: Vec<_> = projects
+                    .into_iter()
+                    .map(|project| {
+                        let unrecognized: Vec<&str> = project
+                            .licenses
+                            .iter()
+                            .filter(|detection| detection.spdx.is_none())
+                            .map(|detection| detection.source.as_str())
+                            .collect();
+                        serde_json::json!({
+                            "id": Self::derive_project_id(&project.path),
+                            "name": project.name(),
+                            "unrecognized": unrecognized,
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "license": license.as_deref().unwrap_or("Unknown"),
+                    "unknown": license.is_none(),
+                    "count": projects.len(),
+                    "projects": projects,
+                })
+            })
+            .collect()
+    }
+
+    /// Display name of a workspace: the last component of its path
+    fn project_name(path: &Path) -> String {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
     /// Derive a project ID from its path
     fn derive_project_id(path: &Path) -> u64 {
         use std::collections::hash_map::DefaultHasher;
@@ -347,41 +426,42 @@ impl<'a> DocumentationGenerator<'a> {
         hasher.finish()
     }
 
+    /// Main language of a project: the one with the most code when lines were
+    /// counted, otherwise the one of its stored type or ecosystems
+    ///
+    /// The directory is only looked at when the catalog knows of no language.
+    fn project_language(project: &Project) -> String {
+        project
+            .code_stats
+            .as_ref()
+            .and_then(|stats| stats.primary_language())
+            .or_else(|| {
+                std::iter::once(&project.project_type)
+                    .chain(&project.ecosystems)
+                    .find_map(ProjectType::language)
+            })
+            .map(str::to_string)
+            .or_else(|| Self::detect_language(&project.path))
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
     /// Detect programming language from project path and indicators
+    ///
+    /// Uses the shared built-in detection table, so the label follows the
+    /// highest-precedence ecosystem the scanner would find in the directory.
     fn detect_language(path: &Path) -> Option<String> {
-        // Language detection based on common project files
-        if path.join("Cargo.toml").exists() {
-            Some("Rust".to_string())
-        } else if path.join("package.json").exists() {
-            Some("JavaScript/TypeScript".to_string())
-        } else if path.join("pyproject.toml").exists()
-            || path.join("setup.py").exists()
-            || path.join("requirements.txt").exists()
-        {
-            Some("Python".to_string())
-        } else if path.join("go.mod").exists() || path.join("go.sum").exists() {
-            Some("Go".to_string())
-        } else if path.join("pom.xml").exists()
-            || path.join("build.gradle").exists()
-            || path.join("build.gradle.kts").exists()
-        {
-            Some("Java".to_string())
-        } else if path.join("Gemfile").exists() || path.join(".gemspec").exists() {
-            Some("Ruby".to_string())
-        } else if path.join("composer.json").exists() {
-            Some("PHP".to_string())
-        } else if path.join("CMakeLists.txt").exists() || path.join("Makefile").exists() {
-            Some("C/C++".to_string())
-        } else if path.join("devenv.nix").exists() || path.join("flake.nix").exists() {
-            Some("Nix".to_string())
-        } else {
-            None
-        }
+        let matcher = IndicatorMatcher::new(&ProjectIndicator::builtin_patterns()).ok()?;
+        let indicators = matcher.find_in_dir(path).ok()?;
+        ProjectType::ecosystems_from_indicators(&indicators)
+            .iter()
+            .find_map(ProjectType::language)
+            .map(str::to_string)
     }
 
     /// Generate markdown report
     pub async fn generate_markdown_report(&self, output_path: &Path) -> Result<()> {
-        let projects = self.catalog.get_all_projects().await?;
+        let mut projects = self.catalog.get_all_projects().await?;
+        group_workspace_members(&mut projects);
         let stats = self.catalog.get_scan_statistics().await?;
         let counts = self.catalog.get_project_counts().await?;
 
@@ -410,17 +490,31 @@ impl<'a> DocumentationGenerator<'a> {
 
         content.push_str("## Projects\n\n");
         for project in projects {
-            let name = project
-                .path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-            let language =
-                Self::detect_language(&project.path).unwrap_or_else(|| "Unknown".to_string());
+            let name = project.name();
+            let language = Self::project_language(&project);
 
             content.push_str(&format!("### {}\n\n", name));
             content.push_str(&format!("- **Path:** `{}`\n", project.path.display()));
             content.push_str(&format!("- **Type:** {}\n", project.project_type));
+            if let Some(manifest) = &project.manifest {
+                if let Some(version) = &manifest.version {
+                    content.push_str(&format!("- **Version:** {}\n", version));
+                }
+                if let Some(description) = &manifest.description {
+                    content.push_str(&format!("- **Description:** {}\n", description));
+                }
+                if let Some(license) = &manifest.license {
+                    content.push_str(&format!("- **License:** {}\n", license));
+                }
+            }
+            if !project.ecosystems.is_empty() {
+                let ecosystems: Vec<String> =
+                    project.ecosystems.iter().map(|e| e.to_string()).collect();
+                content.push_str(&format!("- **Also:** {}\n", ecosystems.join(", ")));
+            }
+            if let Some(parent) = &project.parent {
+                content.push_str(&format!("- **Workspace:** `{}`\n", parent.display()));
+            }
             content.push_str(&format!("- **Language:** {}\n", language));
             content.push_str(&format!(
                 "- **Last Scanned:** {}\n\n",
@@ -626,6 +720,76 @@ mod tests {
         assert!(content.contains("Git"));
     }
 
+    #[tokio::test]
+    async fn test_generate_markdown_report_manifest() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        let project_dir = temp_dir.path().join("checkout");
+        fs::create_dir(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("package.json"),
+            r#"{"name": "storefront", "version": "3.0.1", "license": "MIT"}"#,
+        )
+        .unwrap();
+
+        catalog.scan_directory(&project_dir).await.unwrap();
+
+        let generator = DocumentationGenerator::new(&catalog, DocsConfig::default()).unwrap();
+        let output_path = temp_dir.path().join("report.md");
+        generator
+            .generate_markdown_report(&output_path)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(output_path).unwrap();
+        assert!(content.contains("### storefront"));
+        assert!(content.contains("- **Version:** 3.0.1"));
+        assert!(content.contains("- **License:** MIT"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_markdown_report_workspaces() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{"private": true, "workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("packages/ui")).unwrap();
+        fs::write(root.join("packages/ui/package.json"), "{}").unwrap();
+
+        catalog.scan_directory(root).await.unwrap();
+
+        let config = DocsConfig::default();
+        let generator = DocumentationGenerator::new(&catalog, config).unwrap();
+        let output_path = root.join("report.md");
+        generator
+            .generate_markdown_report(&output_path)
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(output_path).unwrap();
+        assert!(content.contains(&format!("- **Workspace:** `{}`", root.display())));
+
+        let member = catalog
+            .get_all_projects()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.parent.is_some())
+            .unwrap();
+        let data = generator.create_project_data(&member);
+        assert_eq!(data["parent"]["path"], root.display().to_string());
+        assert_eq!(
+            data["parent"]["id"],
+            DocumentationGenerator::derive_project_id(root)
+        );
+    }
+
     #[tokio::test]
     async fn test_generate_json_export() {
         let (mut catalog, _temp_dir) = create_test_catalog().await;
@@ -780,6 +944,74 @@ mod tests {
         let content = fs::read_to_string(stats_path).unwrap();
         assert!(content.contains("Catalog Statistics"));
         assert!(content.contains("Total Scans"));
+        assert!(content.contains("Licenses"));
+        // The scanned directory has no license, so it is flagged
+        assert!(content.contains("⚠ Unknown"));
+        assert!(content.contains("No lines of code counted"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_statistics_page_languages() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+        let mut config = catalog.config().clone();
+        config.code_stats = true;
+        catalog.set_config(config).await;
+
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        fs::write(
+            temp_dir.path().join("src/main.rs"),
+            "// entry point\nfn main() {}\n",
+        )
+        .unwrap();
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+
+        let output_dir = temp_dir.path().join("docs").to_string_lossy().to_string();
+        let config = DocsConfig {
+            output_dir,
+            ..Default::default()
+        };
+        let generator = DocumentationGenerator::new(&catalog, config).unwrap();
+        generator.generate_statistics().await.unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("docs/statistics.html")).unwrap();
+        assert!(content.contains("<td>Rust</td>"));
+        assert!(!content.contains("No lines of code counted"));
+    }
+
+    #[test]
+    fn test_license_groups() {
+        use dprojc_types::LicenseDetection;
+
+        let project = |path: &str, spdx: Option<&str>| Project {
+            path: std::path::PathBuf::from(path),
+            project_type: ProjectType::Rust,
+            licenses: vec![LicenseDetection {
+                source: "LICENSE".to_string(),
+                declared: None,
+                spdx: spdx.map(str::to_string),
+                confidence: 90,
+            }],
+            indicators: vec![ProjectIndicator::CargoToml],
+            last_scanned: chrono::Utc::now(),
+            ..Default::default()
+        };
+        let projects = vec![
+            project("/work/closed", None),
+            project("/work/a", Some("MIT")),
+            project("/work/b", Some("Apache-2.0")),
+            project("/work/c", Some("MIT")),
+        ];
+
+        let groups = DocumentationGenerator::license_groups(&projects);
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g["license"].as_str().unwrap(), g["count"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(summary, vec![("MIT", 2), ("Apache-2.0", 1), ("Unknown", 1)]);
+        assert_eq!(groups[2]["unknown"], true);
+        assert_eq!(groups[2]["projects"][0]["unrecognized"][0], "LICENSE");
     }
 
     #[tokio::test]
@@ -818,6 +1050,42 @@ mod tests {
         assert!(content.contains("Test Project"));
     }
 
+    #[tokio::test]
+    async fn test_generate_project_detail_automation() {
+        let (mut catalog, _temp_dir) = create_test_catalog().await;
+
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        fs::create_dir_all(temp_dir.path().join(".github/workflows")).unwrap();
+        fs::write(
+            temp_dir.path().join(".github/workflows/ci.yml"),
+            "on: [push]\njobs:\n  test:\n    runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Dockerfile"), "FROM alpine\n").unwrap();
+
+        catalog.scan_directory(temp_dir.path()).await.unwrap();
+
+        let output_dir = temp_dir.path().join("docs");
+        let config = DocsConfig {
+            output_dir: output_dir.to_string_lossy().to_string(),
+            include_readmes: false,
+            ..Default::default()
+        };
+        let mut generator = DocumentationGenerator::new(&catalog, config).unwrap();
+        generator.generate_project_details().await.unwrap();
+
+        let detail = fs::read_dir(&output_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .find(|e| e.file_name().to_string_lossy().starts_with("project_"))
+            .unwrap();
+        let content = fs::read_to_string(detail.path()).unwrap();
+        assert!(content.contains("GitHub Actions"));
+        assert!(content.contains(".github/workflows/ci.yml"));
+        assert!(content.contains("Dockerfile"));
+    }
+
     #[tokio::test]
     async fn test_generate_readme_pages() {
         let (mut catalog, _temp_dir) = create_test_catalog().await;
@@ -1012,6 +1280,33 @@ mod tests {
             Some("Nix".to_string())
         );
 
+        // Test ecosystems from the extended detection table
+        for (file, language) in [
+            ("App.csproj", "C#/F#"),
+            ("build.gradle.kts", "Java/Kotlin"),
+            ("mix.exs", "Elixir"),
+            ("Package.swift", "Swift"),
+            ("pubspec.yaml", "Dart"),
+            ("build.zig", "Zig"),
+            ("main.tf", "HCL"),
+        ] {
+            let dir = temp_dir.path().join(format!("project_{}", file));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join(file), "").unwrap();
+            assert_eq!(
+                DocumentationGenerator::detect_language(&dir),
+                Some(language.to_string()),
+                "{}",
+                file
+            );
+        }
+
+        // A compose file alone does not name a language
+        let compose_dir = temp_dir.path().join("compose_project");
+        fs::create_dir(&compose_dir).unwrap();
+        fs::write(compose_dir.join("docker-compose.yml"), "services: {}").unwrap();
+        assert_eq!(DocumentationGenerator::detect_language(&compose_dir), None);
+
         // Test unknown language
         let unknown_dir = temp_dir.path().join("unknown_project");
         fs::create_dir(&unknown_dir).unwrap();
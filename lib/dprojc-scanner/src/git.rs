@@ -0,0 +1,275 @@
+//! Reading git repository state with libgit2
+//!
+//! Repositories are opened in place, so no `git` executable is needed and a
+//! scan never changes a repository: nothing is fetched and the index is not
+//! refreshed on disk.
+
+use chrono::TimeZone;
+use dprojc_types::{GitMetadata, GitRemote};
+use git2::{BranchType, Repository, Status, StatusOptions};
+use std::path::Path;
+
+/// Index and working tree changes to tracked files
+const CHANGED: Status = Status::INDEX_NEW
+    .union(Status::INDEX_MODIFIED)
+    .union(Status::INDEX_DELETED)
+    .union(Status::INDEX_RENAMED)
+    .union(Status::INDEX_TYPECHANGE)
+    .union(Status::WT_MODIFIED)
+    .union(Status::WT_DELETED)
+    .union(Status::WT_RENAMED)
+    .union(Status::WT_TYPECHANGE)
+    .union(Status::CONFLICTED);
+
+/// Read the state of the git repository rooted at `path`
+///
+/// Returns `None` when `path` is not the top of a repository or the repository
+/// cannot be opened. Parts that cannot be read, such as the status of a bare
+/// repository, are left at their defaults.
+pub fn read_git_metadata(path: &Path) -> Option<GitMetadata> {
+    let mut repo = Repository::open(path).ok()?;
+
+    let mut metadata = GitMetadata {
+        is_worktree: repo.is_worktree(),
+        ..GitMetadata::default()
+    };
+    // A submodule checkout has a `.git` file pointing into its superproject
+    metadata.is_submodule = !metadata.is_worktree && path.join(".git").is_file();
+
+    read_head(&repo, &mut metadata);
+    metadata.remotes = read_remotes(&repo);
+    read_status(&repo, &mut metadata);
+
+    let mut stash_count = 0;
+    let _ = repo.stash_foreach(|_, _, _| {
+        stash_count += 1;
+        true
+    });
+    metadata.stash_count = stash_count;
+
+    Some(metadata)
+}
+
+/// Fill in the branch, HEAD commit and upstream tracking state
+fn read_head(repo: &Repository, metadata: &mut GitMetadata) {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => {
+            // An unborn branch still names the branch HEAD points at
+            metadata.branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(str::to_string))
+                .map(|target| {
+                    target
+                        .strip_prefix("refs/heads/")
+                        .unwrap_or(&target)
+                        .to_string()
+                });
+            return;
+        }
+    };
+
+    if let Ok(commit) = head.peel_to_commit() {
+        metadata.head_commit = Some(commit.id().to_string());
+        metadata.head_date = chrono::Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single();
+        metadata.root_commit = root_commit(repo, commit.id());
+    }
+
+    if !head.is_branch() {
+        return;
+    }
+    let Some(branch_name) = head.shorthand() else {
+        return;
+    };
+    metadata.branch = Some(branch_name.to_string());
+
+    let Ok(upstream) = repo
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+    else {
+        return;
+    };
+    metadata.upstream = upstream.name().ok().flatten().map(str::to_string);
+    if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+        if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, remote) {
+            metadata.ahead = ahead;
+            metadata.behind = behind;
+        }
+    }
+}
+
+/// Follow first parents from `head` back to the commit that has none
+fn root_commit(repo: &Repository, head: git2::Oid) -> Option<String> {
+    let mut walk = repo.revwalk().ok()?;
+    walk.push(head).ok()?;
+    walk.simplify_first_parent().ok()?;
+    walk.flatten().last().map(|oid| oid.to_string())
+}
+
+fn read_remotes(repo: &Repository) -> Vec<GitRemote> {
+    let Ok(names) = repo.remotes() else {
+        return Vec::new();
+    };
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let remote = repo.find_remote(name).ok()?;
+            Some(GitRemote {
+                name: name.to_string(),
+                url: remote.url()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Count changed and untracked files, ignoring submodules and ignored files
+fn read_status(repo: &Repository, metadata: &mut GitMetadata) {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let Ok(statuses) = repo.statuses(Some(&mut options)) else {
+        return;
+    };
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.intersects(CHANGED) {
+            metadata.dirty_count += 1;
+        } else if status.contains(Status::WT_NEW) {
+            metadata.untracked_count += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn commit_all(repo: &Repository, message: &str) -> git2::Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<_> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_read_git_metadata() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        repo.remote("origin", "https://example.com/app.git")
+            .unwrap();
+
+        // Before the first commit only the branch name is known
+        let unborn = read_git_metadata(temp_dir.path()).unwrap();
+        assert!(unborn.branch.is_some());
+        assert_eq!(unborn.head_commit, None);
+
+        fs::write(temp_dir.path().join("a.txt"), "one").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "one").unwrap();
+        let first = commit_all(&repo, "first");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        // Track a fake upstream one commit behind the branch
+        repo.reference(
+            &format!("refs/remotes/origin/{}", branch),
+            first,
+            true,
+            "test",
+        )
+        .unwrap();
+        let mut config = repo.config().unwrap();
+        config
+            .set_str(&format!("branch.{}.remote", branch), "origin")
+            .unwrap();
+        config
+            .set_str(
+                &format!("branch.{}.merge", branch),
+                &format!("refs/heads/{}", branch),
+            )
+            .unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "two").unwrap();
+        let second = commit_all(&repo, "second");
+
+        fs::write(temp_dir.path().join("a.txt"), "three").unwrap();
+        fs::remove_file(temp_dir.path().join("b.txt")).unwrap();
+        fs::create_dir(temp_dir.path().join("notes")).unwrap();
+        fs::write(temp_dir.path().join("notes/1.txt"), "").unwrap();
+        fs::write(temp_dir.path().join("notes/2.txt"), "").unwrap();
+
+        let metadata = read_git_metadata(temp_dir.path()).unwrap();
+        assert_eq!(metadata.branch, Some(branch.clone()));
+        assert_eq!(metadata.head_commit, Some(second.to_string()));
+        assert!(metadata.head_date.is_some());
+        assert_eq!(metadata.root_commit, Some(first.to_string()));
+        assert_eq!(
+            metadata.remotes,
+            vec![GitRemote {
+                name: "origin".to_string(),
+                url: "https://example.com/app.git".to_string(),
+            }]
+        );
+        assert_eq!(metadata.upstream, Some(format!("origin/{}", branch)));
+        assert_eq!((metadata.ahead, metadata.behind), (1, 0));
+        assert_eq!(metadata.dirty_count, 2);
+        assert_eq!(metadata.untracked_count, 1);
+        assert_eq!(metadata.stash_count, 0);
+        assert!(!metadata.is_worktree);
+        assert!(!metadata.is_submodule);
+        assert!(metadata.is_dirty());
+        assert_eq!(metadata.short_commit(), Some(&second.to_string()[..7]));
+    }
+
+    #[test]
+    fn test_read_git_metadata_stash_and_detached_head() {
+        let temp_dir = tempdir().unwrap();
+        let mut repo = Repository::init(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "one").unwrap();
+        let first = commit_all(&repo, "first");
+
+        fs::write(temp_dir.path().join("a.txt"), "two").unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        repo.stash_save(&signature, "wip", None).unwrap();
+        repo.set_head_detached(first).unwrap();
+
+        let metadata = read_git_metadata(temp_dir.path()).unwrap();
+        assert_eq!(metadata.stash_count, 1);
+        assert_eq!(metadata.branch, None);
+        assert_eq!(metadata.head_commit, Some(first.to_string()));
+        assert!(!metadata.is_dirty());
+    }
+
+    #[test]
+    fn test_read_git_metadata_not_a_repository() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(read_git_metadata(temp_dir.path()), None);
+    }
+}
@@ -1,25 +1,450 @@
-use dprojc_types::{Project, ProjectType, ScanConfig, ScanError, ScanErrorType, ScanResult};
+use dprojc_types::{
+    DirectoryCache, DirectoryState, IncrementalScanResult, Project, ProjectIndicator, ScanConfig,
+    ScanError, ScanErrorType, ScanProgress, ScanResult,
+};
 use dprojc_utils::{
-    create_walker, has_project_indicator, should_exclude_dir, should_skip_entry,
-    validate_scan_config, validate_scan_path,
+    assign_workspace_parents, classify_project, count_code, create_walker, detect_ecosystems,
+    is_hidden_path, measure_disk_usage, read_automation, read_licenses, read_lockfiles,
+    read_manifest, read_toolchains, should_skip_entry, validate_scan_config, validate_scan_path,
+    ExcludeMatcher, IndicatorMatcher,
 };
-use std::path::{Path, PathBuf};
+use ignore::WalkState;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
-/// Check if a project path should be skipped based on exclude patterns
-fn should_skip_project(path: &Path, exclude_patterns: &[String]) -> bool {
-    path.components().any(|comp| {
-        if let std::path::Component::Normal(name) = comp {
-            if let Some(name_str) = name.to_str() {
-                should_exclude_dir(name_str, exclude_patterns)
-            } else {
-                false
+mod git;
+mod progress;
+mod watch;
+
+pub use git::read_git_metadata;
+pub use progress::{CancellationToken, ProgressSink, ScanControl};
+pub use watch::{DirChange, DirectoryWatcher, WatchError};
+
+/// How close to the start of a scan a directory's modification time can be
+/// before a rescan no longer trusts it, covering coarse file system clocks
+const RACY_WINDOW_NS: i64 = 2_000_000_000;
+
+/// Shortest time between two progress reports from the same scan
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Find the path an error from the directory walker refers to
+fn walk_error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error_path(err)
+        }
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::Partial(errs) => errs.iter().find_map(walk_error_path),
+        _ => None,
+    }
+}
+
+/// Convert an error from the directory walker into a scan error
+fn walk_error_to_scan_error(err: &ignore::Error, error_type: ScanErrorType) -> ScanError {
+    ScanError {
+        path: walk_error_path(err)
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("unknown")),
+        error_type,
+        message: err.to_string(),
+    }
+}
+
+/// Build a project from the indicators found in its directory
+fn detected_project(
+    path: PathBuf,
+    indicators: Vec<ProjectIndicator>,
+    config: &ScanConfig,
+    excludes: &ExcludeMatcher,
+) -> Project {
+    let project_type = classify_project(&indicators, &config.project_types);
+    let ecosystems = detect_ecosystems(&indicators, &config.project_types, &project_type);
+    let git = if config.git_metadata && indicators.contains(&ProjectIndicator::GitDirectory) {
+        read_git_metadata(&path)
+    } else {
+        None
+    };
+    let manifest = read_manifest(&path, &project_type, &indicators);
+    let dependencies = read_lockfiles(&path);
+    let toolchains = read_toolchains(&path);
+    let automation = read_automation(&path);
+    let licenses = read_licenses(&path, manifest.as_ref());
+    let disk_usage = config.disk_usage.then(|| measure_disk_usage(&path));
+    let code_stats = config
+        .code_stats
+        .then(|| count_code(&path, config, excludes));
+    Project {
+        path,
+        project_type,
+        ecosystems,
+        parent: None,
+        git,
+        manifest,
+        dependencies,
+        toolchains,
+        automation,
+        licenses,
+        disk_usage,
+        code_stats,
+        indicators,
+        last_scanned: chrono::Utc::now(),
+    }
+}
+
+/// Whether an excluded directory lies between `root` and `dir`, inclusive
+///
+/// With `skip_hidden`, a hidden directory on the way counts as well.
+fn is_pruned(root: &Path, dir: &Path, excludes: &ExcludeMatcher, skip_hidden: bool) -> bool {
+    let Ok(relative) = dir.strip_prefix(root) else {
+        return true;
+    };
+    let mut path = root.to_path_buf();
+    for component in relative.components() {
+        path.push(component);
+        if excludes.is_excluded(&path, true) || (skip_hidden && is_hidden_path(&path)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// How far below `root` a directory lies, or `None` when it is outside it
+fn depth_below(root: &Path, dir: &Path) -> Option<usize> {
+    dir.strip_prefix(root)
+        .ok()
+        .map(|relative| relative.components().count())
+}
+
+/// Whether a scan of `root` would check `dir` for project indicators
+fn is_candidate_dir(
+    root: &Path,
+    dir: &Path,
+    config: &ScanConfig,
+    excludes: &ExcludeMatcher,
+) -> bool {
+    let Some(depth) = depth_below(root, dir) else {
+        return false;
+    };
+    if depth > 0 {
+        if config.max_depth.is_some_and(|max| depth > max) || is_pruned(root, dir, excludes, false)
+        {
+            return false;
+        }
+        // Hidden directories are still walked, but only indicator directories
+        // such as `.git` are checked themselves
+        let hidden_name = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .filter(|name| name.starts_with('.'));
+        if hidden_name.is_some_and(|name| !config.project_indicators.iter().any(|i| i == name)) {
+            return false;
+        }
+    }
+    dir.is_dir()
+}
+
+/// List `dir` and the directories below it that a walk of `root` reaches
+///
+/// With `skip_hidden`, hidden directories are left out along with everything
+/// below them.
+pub(crate) fn subtree_dirs(
+    root: &Path,
+    dir: &Path,
+    config: &ScanConfig,
+    excludes: &Arc<ExcludeMatcher>,
+    skip_hidden: bool,
+) -> Vec<PathBuf> {
+    let Some(depth) = depth_below(root, dir) else {
+        return Vec::new();
+    };
+    if config.max_depth.is_some_and(|max| depth > max)
+        || (depth > 0 && is_pruned(root, dir, excludes, skip_hidden))
+    {
+        return Vec::new();
+    }
+
+    let mut walker = create_walker(dir, config);
+    walker.max_depth(config.max_depth.map(|max| max - depth));
+    let excludes = Arc::clone(excludes);
+    walker.filter_entry(move |entry| {
+        let is_dir = match entry.file_type() {
+            Some(ft) if ft.is_symlink() => entry.path().is_dir(),
+            Some(ft) => ft.is_dir(),
+            None => false,
+        };
+        is_dir
+            && !excludes.is_excluded(entry.path(), true)
+            && !(skip_hidden && entry.depth() > 0 && is_hidden_path(entry.path()))
+    });
+    walker
+        .build()
+        .filter_map(Result::ok)
+        .map(ignore::DirEntry::into_path)
+        .collect()
+}
+
+/// Make a scan root absolute without resolving symlinks
+fn absolute_root(root_path: &Path) -> anyhow::Result<PathBuf> {
+    Ok(if root_path.is_absolute() {
+        root_path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(root_path)
+    })
+}
+
+/// Nanoseconds since the Unix epoch
+fn system_time_ns(time: std::time::SystemTime) -> Option<i64> {
+    let since_epoch = time.duration_since(std::time::UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_nanos()).ok()
+}
+
+fn is_symlink(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Whether the scan root lies inside a directory excluded by name, such as a
+/// root below `node_modules`
+///
+/// The walk only checks what is below the root, so the root's own path is
+/// checked against the patterns that match a name at any depth.
+fn is_excluded_root(root: &Path, config: &ScanConfig) -> anyhow::Result<bool> {
+    let by_name: Vec<String> = config
+        .exclude_patterns
+        .iter()
+        .filter(|pattern| {
+            !pattern
+                .trim_start_matches('!')
+                .trim_end_matches('/')
+                .contains('/')
+        })
+        .cloned()
+        .collect();
+    let matcher = ExcludeMatcher::new(Path::new(""), &by_name)?;
+    Ok(root.components().any(|component| {
+        matches!(component, Component::Normal(name) if matcher.is_excluded(Path::new(name), true))
+    }))
+}
+
+/// Matchers compiled once per scan and shared by every walk within it
+struct WalkContext {
+    excludes: Arc<ExcludeMatcher>,
+    indicators: Arc<IndicatorMatcher>,
+    root_excluded: bool,
+}
+
+impl WalkContext {
+    fn new(root: &Path, config: &ScanConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            excludes: Arc::new(ExcludeMatcher::new(root, &config.exclude_patterns)?),
+            indicators: Arc::new(IndicatorMatcher::from_config(config)?),
+            root_excluded: is_excluded_root(root, config)?,
+        })
+    }
+}
+
+/// Results gathered by the walker threads during a single scan
+#[derive(Default)]
+struct WalkCollector {
+    projects: std::sync::Mutex<Vec<Project>>,
+    excluded_dirs: std::sync::Mutex<Vec<PathBuf>>,
+    errors: std::sync::Mutex<Vec<ScanError>>,
+    dirs_scanned: AtomicUsize,
+    /// Whether to remember every directory for the next incremental rescan
+    record_directories: bool,
+    directories: std::sync::Mutex<Vec<DirectoryState>>,
+    control: ScanControl,
+    last_report: std::sync::Mutex<Option<Instant>>,
+    /// Set once the walk stopped early because the scan was cancelled
+    interrupted: AtomicBool,
+    /// Directories to check for indicators once their listings are complete
+    candidates: std::sync::Mutex<Vec<PathBuf>>,
+    /// Entries the walk listed that indicators can match, by directory
+    listings: std::sync::Mutex<HashMap<PathBuf, HashMap<OsString, bool>>>,
+}
+
+impl WalkCollector {
+    fn push_project(&self, project: Project) {
+        lock(&self.projects).push(project);
+    }
+
+    fn push_error(&self, error: ScanError) {
+        lock(&self.errors).push(error);
+    }
+
+    fn push_excluded_dir(&self, path: PathBuf) {
+        if self.record_directories {
+            lock(&self.directories).push(DirectoryState {
+                path: path.clone(),
+                mtime_ns: None,
+                excluded: true,
+            });
+        }
+        lock(&self.excluded_dirs).push(path);
+    }
+
+    /// Count a walked directory, remembering its modification time when recording
+    fn push_directory(&self, path: PathBuf, mtime_ns: Option<i64>) {
+        self.dirs_scanned.fetch_add(1, Ordering::Relaxed);
+        self.report_progress(&path);
+        if self.record_directories {
+            lock(&self.directories).push(DirectoryState {
+                path,
+                mtime_ns,
+                excluded: false,
+            });
+        }
+    }
+
+    /// Whether to stop walking, noting that the results are now partial
+    fn should_stop(&self) -> bool {
+        let cancelled = self.control.is_cancelled();
+        if cancelled {
+            self.interrupted.store(true, Ordering::Relaxed);
+        }
+        cancelled
+    }
+
+    fn progress(&self, current: &Path) -> ScanProgress {
+        ScanProgress {
+            current_path: current.to_path_buf(),
+            dirs_scanned: self.dirs_scanned.load(Ordering::Relaxed),
+            projects_found: lock(&self.projects).len(),
+            errors: lock(&self.errors).len(),
+        }
+    }
+
+    /// Report progress at `current` unless a report went out very recently
+    fn report_progress(&self, current: &Path) {
+        if !self.control.has_progress() {
+            return;
+        }
+        // A thread that is reporting right now speaks for this one too
+        let Ok(mut last) = self.last_report.try_lock() else {
+            return;
+        };
+        if last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+        self.control.report(&self.progress(current));
+    }
+
+    /// Send the final counts once the walk is over
+    fn finish_progress(&self, root: &Path) {
+        if self.control.has_progress() {
+            self.control.report(&self.progress(root));
+        }
+    }
+
+    fn push_candidate(&self, dir: PathBuf) {
+        lock(&self.candidates).push(dir);
+    }
+
+    /// Remember an entry of a directory listing if indicators can match it
+    fn push_listed(&self, path: &Path, is_dir: bool, indicators: &IndicatorMatcher) {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return;
+        };
+        if indicators.is_relevant_name(name) {
+            lock(&self.listings)
+                .entry(parent.to_path_buf())
+                .or_default()
+                .insert(name.to_os_string(), is_dir);
+        }
+    }
+
+    /// Take the candidate directories along with what was listed in them,
+    /// leaving out those where no indicator can match
+    fn take_listed_candidates(&self) -> Vec<(PathBuf, HashMap<OsString, bool>)> {
+        let candidates = std::mem::take(&mut *lock(&self.candidates));
+        let mut listings = lock(&self.listings);
+        candidates
+            .into_iter()
+            .filter_map(|dir| listings.remove(&dir).map(|listing| (dir, listing)))
+            .collect()
+    }
+
+    /// Take the recorded directories, ordered by path
+    fn take_directories(&self) -> Vec<DirectoryState> {
+        let mut directories = std::mem::take(&mut *lock(&self.directories));
+        directories.sort_by(|a, b| a.path.cmp(&b.path));
+        directories
+    }
+
+    /// Handle one entry produced by the walker
+    fn visit(
+        &self,
+        entry: Result<ignore::DirEntry, ignore::Error>,
+        config: &ScanConfig,
+        excludes: &ExcludeMatcher,
+    ) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                self.push_error(walk_error_to_scan_error(&err, ScanErrorType::IoError));
+                return;
             }
+        };
+
+        let mtime_ns = if self.record_directories {
+            std::fs::metadata(entry.path())
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(system_time_ns)
         } else {
-            false
+            None
+        };
+        self.push_directory(entry.path().to_path_buf(), mtime_ns);
+
+        // Malformed ignore files are reported but don't stop the walk
+        if let Some(err) = entry.error() {
+            self.push_error(walk_error_to_scan_error(err, ScanErrorType::Other));
         }
-    })
+
+        if entry.depth() == 0 || should_skip_entry(&entry, config, excludes) {
+            if entry.depth() != 0 && entry.path().is_dir() {
+                self.push_excluded_dir(entry.path().to_path_buf());
+            }
+            return;
+        }
+
+        // The walker lists the directory after visiting it, so indicators are
+        // checked once the walk is over; unreadable directories are reported
+        // by the walker and have no listing
+        self.push_candidate(entry.into_path());
+    }
+
+    /// Take the collected results, ordering everything by path so they don't
+    /// depend on how the work was scheduled across threads
+    fn take_sorted_parts(&self) -> (Vec<Project>, Vec<PathBuf>, Vec<ScanError>, usize) {
+        let mut projects = std::mem::take(&mut *lock(&self.projects));
+        let mut excluded_dirs = std::mem::take(&mut *lock(&self.excluded_dirs));
+        let mut errors = std::mem::take(&mut *lock(&self.errors));
+
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+        excluded_dirs.sort();
+        errors.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.message.cmp(&b.message)));
+
+        (
+            projects,
+            excluded_dirs,
+            errors,
+            self.dirs_scanned.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Lock a mutex, recovering the data if a walker thread panicked while holding it
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 /// The main scanner struct
@@ -43,90 +468,404 @@ impl ProjectScanner {
 
     /// Scan a directory for projects
     pub async fn scan(&self, root_path: &Path) -> anyhow::Result<ScanResult> {
+        self.scan_with_control(root_path, &ScanControl::default())
+            .await
+    }
+
+    /// Scan a directory for projects, reporting progress and stopping early
+    /// as `control` asks
+    ///
+    /// A cancelled scan returns what it found up to that point, with
+    /// `cancelled` set on the result.
+    pub async fn scan_with_control(
+        &self,
+        root_path: &Path,
+        control: &ScanControl,
+    ) -> anyhow::Result<ScanResult> {
         validate_scan_path(root_path)?;
+        let root_path_abs = absolute_root(root_path)?;
 
-        // Convert root_path to absolute path
-        let root_path_abs = if root_path.is_absolute() {
-            root_path.to_path_buf()
+        let start_time = std::time::Instant::now();
+        let collector = Arc::new(WalkCollector {
+            control: control.clone(),
+            ..WalkCollector::default()
+        });
+        let context = WalkContext::new(&root_path_abs, &self.config)?;
+
+        if context.root_excluded {
+            collector.push_excluded_dir(root_path_abs.clone());
         } else {
-            std::env::current_dir()?.join(root_path)
-        };
+            self.walk_subtree(&root_path_abs, &root_path_abs, &context, &collector);
+            self.detect_candidates(&context, &collector);
+        }
+        collector.finish_progress(&root_path_abs);
+
+        let cancelled = collector.interrupted.load(Ordering::Relaxed);
+        let (mut projects, excluded_dirs, errors, dirs_scanned) = collector.take_sorted_parts();
+
+        // Link workspace members to their roots now that every project is known
+        assign_workspace_parents(&mut projects);
+
+        let scan_duration_ms = start_time.elapsed().as_millis() as u64;
+
+        Ok(ScanResult {
+            root_path: root_path_abs,
+            projects,
+            excluded_dirs,
+            errors,
+            dirs_scanned,
+            scan_duration_ms,
+            cancelled,
+        })
+    }
+
+    /// Rescan a root, listing again only the directories whose entries
+    /// changed since `previous` was recorded
+    ///
+    /// Creating, removing or renaming an entry updates a directory's
+    /// modification time, and entries are what project detection looks at.
+    /// Every directory is still checked, but unchanged ones are only listed
+    /// when they are one of `known`, the paths of the cataloged projects below
+    /// the root, whose manifests, git state and other details are read again.
+    /// Edits to existing files that leave the modification time of a
+    /// directory without a project alone are only seen by a full scan.
+    pub async fn rescan(
+        &self,
+        root_path: &Path,
+        previous: &DirectoryCache,
+        known: &[PathBuf],
+    ) -> anyhow::Result<IncrementalScanResult> {
+        self.rescan_with_control(root_path, previous, known, &ScanControl::default())
+            .await
+    }
+
+    /// Rescan a root like [`ProjectScanner::rescan`], reporting progress and
+    /// stopping early as `control` asks
+    ///
+    /// A cancelled rescan can't tell which projects are gone, so it removes
+    /// none and hands back `previous` as the cache.
+    pub async fn rescan_with_control(
+        &self,
+        root_path: &Path,
+        previous: &DirectoryCache,
+        known: &[PathBuf],
+        control: &ScanControl,
+    ) -> anyhow::Result<IncrementalScanResult> {
+        validate_scan_path(root_path)?;
+        let root = absolute_root(root_path)?;
 
         let start_time = std::time::Instant::now();
-        let mut projects = Vec::new();
-        let mut excluded_dirs = Vec::new();
-        let mut errors = Vec::new();
-        let mut dirs_scanned = 0;
-
-        // Check the root directory for project indicators
-        let root_indicators =
-            has_project_indicator(&root_path_abs, &self.config.project_indicators);
-        if !root_indicators.is_empty()
-            && !should_skip_project(&root_path_abs, &self.config.exclude_patterns)
-        {
-            let project = Project {
-                path: root_path_abs.clone(),
-                project_type: ProjectType::from_indicators(&root_indicators),
-                indicators: root_indicators,
-                last_scanned: chrono::Utc::now(),
+        let started_ns = system_time_ns(std::time::SystemTime::now()).unwrap_or(i64::MAX);
+        let fingerprint = self.config.walk_fingerprint();
+        let context = WalkContext::new(&root, &self.config)?;
+        let collector = Arc::new(WalkCollector {
+            record_directories: true,
+            control: control.clone(),
+            ..WalkCollector::default()
+        });
+
+        // A cache recorded with other settings would skip the wrong directories
+        let cached: HashMap<&Path, &DirectoryState> = if previous.fingerprint == fingerprint {
+            previous
+                .directories
+                .iter()
+                .map(|state| (state.path.as_path(), state))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let mut cached_children: HashMap<&Path, Vec<&DirectoryState>> = HashMap::new();
+        for state in cached.values() {
+            if let Some(parent) = state.path.parent() {
+                cached_children.entry(parent).or_default().push(state);
+            }
+        }
+        let known_dirs: HashSet<&Path> = known.iter().map(PathBuf::as_path).collect();
+
+        let mut unchanged_dirs = 0;
+        let mut pending = Vec::new();
+        if context.root_excluded {
+            collector.push_excluded_dir(root.clone());
+        } else {
+            pending.push(root.clone());
+        }
+        while let Some(dir) = pending.pop() {
+            if collector.should_stop() {
+                break;
+            }
+            // Linked directories are only entered when following symlinks
+            let descend = dir == root || self.config.follow_symlinks || !is_symlink(&dir);
+            let previous_mtime = cached.get(dir.as_path()).and_then(|state| state.mtime_ns);
+            if previous_mtime.is_none() && descend {
+                // Nothing usable is known about it, so walk everything below it
+                self.walk_subtree(&root, &dir, &context, &collector);
+                continue;
+            }
+
+            let mtime = match std::fs::metadata(&dir) {
+                Ok(metadata) => metadata.modified().ok().and_then(system_time_ns),
+                // Removed since the last scan, along with its projects
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    collector.push_error(ScanError {
+                        path: dir.clone(),
+                        error_type: if err.kind() == std::io::ErrorKind::PermissionDenied {
+                            ScanErrorType::PermissionDenied
+                        } else {
+                            ScanErrorType::IoError
+                        },
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
             };
-            projects.push(project);
+            collector.push_directory(dir.clone(), mtime);
+
+            if mtime.is_some() && mtime == previous_mtime {
+                unchanged_dirs += 1;
+                // The project's entries are the same, but their contents and
+                // its repository may not be
+                if known_dirs.contains(dir.as_path()) {
+                    self.list_dir(&root, &dir, &context, &collector, false);
+                }
+                for child in cached_children.get(dir.as_path()).into_iter().flatten() {
+                    if child.excluded {
+                        collector.push_excluded_dir(child.path.clone());
+                    } else {
+                        pending.push(child.path.clone());
+                    }
+                }
+            } else {
+                pending.extend(self.list_dir(&root, &dir, &context, &collector, descend));
+            }
         }
+        self.detect_candidates(&context, &collector);
 
-        let walker = create_walker(&root_path_abs, &self.config);
+        collector.finish_progress(&root);
 
-        for entry in walker {
-            match entry {
-                Ok(entry) => {
-                    dirs_scanned += 1;
+        let cancelled = collector.interrupted.load(Ordering::Relaxed);
+        let (mut projects, excluded_dirs, errors, dirs_scanned) = collector.take_sorted_parts();
+        assign_workspace_parents(&mut projects);
 
-                    if entry.depth() == 0 || should_skip_entry(&entry, &self.config) {
-                        if entry.depth() != 0 && entry.path().is_dir() {
-                            excluded_dirs.push(entry.path().to_path_buf());
-                        }
-                        continue;
+        // Projects below directories that couldn't be read may still exist
+        let found: HashSet<&Path> = projects.iter().map(|p| p.path.as_path()).collect();
+        let mut removed: Vec<PathBuf> = known
+            .iter()
+            .filter(|path| {
+                !cancelled
+                    && path.starts_with(&root)
+                    && !found.contains(path.as_path())
+                    && !errors.iter().any(|error| path.starts_with(&error.path))
+            })
+            .cloned()
+            .collect();
+        removed.sort();
+
+        // A directory changed within the clock's resolution of being recorded
+        // could change again without its time moving, so it is listed again
+        // next time
+        let trusted_before = started_ns.saturating_sub(RACY_WINDOW_NS);
+        let directories = if cancelled {
+            previous.directories.clone()
+        } else {
+            collector
+                .take_directories()
+                .into_iter()
+                .map(|mut state| {
+                    if state.mtime_ns.is_some_and(|mtime| mtime >= trusted_before) {
+                        state.mtime_ns = None;
                     }
+                    state
+                })
+                .collect()
+        };
 
-                    // Check for project indicators
-                    let indicators =
-                        has_project_indicator(entry.path(), &self.config.project_indicators);
-                    if !indicators.is_empty()
-                        && !should_skip_project(entry.path(), &self.config.exclude_patterns)
-                    {
-                        // entry.path() from walkdir is already absolute since we use root_path_abs
-                        let project = Project {
-                            path: entry.path().to_path_buf(),
-                            project_type: ProjectType::from_indicators(&indicators),
-                            indicators,
-                            last_scanned: chrono::Utc::now(),
-                        };
-                        projects.push(project);
-                    }
+        let scan_duration_ms = start_time.elapsed().as_millis() as u64;
+
+        Ok(IncrementalScanResult {
+            scan: ScanResult {
+                root_path: root,
+                projects,
+                excluded_dirs,
+                errors,
+                dirs_scanned,
+                scan_duration_ms,
+                cancelled,
+            },
+            removed,
+            unchanged_dirs,
+            cache: DirectoryCache {
+                fingerprint: if cancelled {
+                    previous.fingerprint.clone()
+                } else {
+                    fingerprint
+                },
+                directories,
+            },
+        })
+    }
+
+    /// Queue `dir` for indicator checks if a scan of `root` examines it; the
+    /// root is examined whatever its name
+    fn push_candidate_dir(
+        &self,
+        root: &Path,
+        dir: &Path,
+        context: &WalkContext,
+        collector: &WalkCollector,
+    ) {
+        if dir == root || is_candidate_dir(root, dir, &self.config, &context.excludes) {
+            collector.push_candidate(dir.to_path_buf());
+        }
+    }
+
+    /// Walk `dir` and everything below it the way a scan of `root` would
+    ///
+    /// Indicators are matched against the listings the walker makes anyway,
+    /// so call [`ProjectScanner::detect_candidates`] once walking is done.
+    fn walk_subtree(
+        &self,
+        root: &Path,
+        dir: &Path,
+        context: &WalkContext,
+        collector: &Arc<WalkCollector>,
+    ) {
+        // The walk's starting point is reported by the walker but not checked
+        self.push_candidate_dir(root, dir, context, collector);
+
+        // Directories at the depth limit still need their entries listed, so
+        // the walker goes one level further and only records the names there
+        let depth = depth_below(root, dir).unwrap_or(0);
+        let limit = self.config.max_depth.map(|max| max.saturating_sub(depth));
+        let mut walker = create_walker(dir, &self.config);
+        walker.threads(self.config.threads);
+        walker.max_depth(limit.map(|limit| limit + 1));
+        {
+            let excludes = Arc::clone(&context.excludes);
+            let indicators = Arc::clone(&context.indicators);
+            let collector = Arc::clone(collector);
+            walker.filter_entry(move |entry| {
+                // Symlinks are resolved so linked project directories are
+                // still considered
+                let is_dir = match entry.file_type() {
+                    Some(ft) if ft.is_symlink() => entry.path().is_dir(),
+                    Some(ft) => ft.is_dir(),
+                    None => false,
+                };
+                // Every entry of a listed directory passes through here once
+                collector.push_listed(entry.path(), is_dir, &indicators);
+
+                // Only directories can be projects
+                if !is_dir || limit.is_some_and(|limit| entry.depth() > limit) {
+                    return false;
+                }
+                // Excluded directories are pruned from the walk entirely, so
+                // record them as they are filtered out
+                if excludes.is_excluded(entry.path(), true) {
+                    collector.push_excluded_dir(entry.path().to_path_buf());
+                    return false;
+                }
+                true
+            });
+        }
+
+        // Walk the tree on a work-stealing thread pool; each worker reports
+        // into the shared collector
+        walker.build_parallel().run(|| {
+            let config = &self.config;
+            let excludes = &context.excludes;
+            let collector = collector.as_ref();
+            Box::new(move |entry| {
+                if collector.should_stop() {
+                    return WalkState::Quit;
                 }
+                collector.visit(entry, config, excludes);
+                WalkState::Continue
+            })
+        });
+    }
+
+    /// List `dir` once, keeping the entries indicators can match, and return
+    /// the directories a scan of `root` walks into from it when `descend` is
+    /// set, reporting the excluded ones
+    fn list_dir(
+        &self,
+        root: &Path,
+        dir: &Path,
+        context: &WalkContext,
+        collector: &WalkCollector,
+        descend: bool,
+    ) -> Vec<PathBuf> {
+        self.push_candidate_dir(root, dir, context, collector);
+        let depth = depth_below(root, dir).unwrap_or(0);
+        let descend = descend && self.config.max_depth.is_none_or(|max| depth < max);
+
+        let mut walker = create_walker(dir, &self.config);
+        walker.max_depth(Some(1));
+        let mut children = Vec::new();
+        for entry in walker.build() {
+            let entry = match entry {
+                Ok(entry) => entry,
                 Err(err) => {
-                    let error = ScanError {
-                        path: err
-                            .path()
-                            .map(|p| p.to_path_buf())
-                            .unwrap_or_else(|| PathBuf::from("unknown")),
-                        error_type: ScanErrorType::IoError,
-                        message: err.to_string(),
-                    };
-                    errors.push(error);
+                    collector.push_error(walk_error_to_scan_error(&err, ScanErrorType::IoError));
+                    continue;
                 }
+            };
+            if entry.depth() == 0 {
+                continue;
+            }
+            let is_dir = entry.path().is_dir();
+            collector.push_listed(entry.path(), is_dir, &context.indicators);
+            if !is_dir || !descend {
+                continue;
+            }
+            if context.excludes.is_excluded(entry.path(), true) {
+                collector.push_excluded_dir(entry.into_path());
+            } else {
+                children.push(entry.into_path());
             }
         }
+        children
+    }
 
-        let scan_duration_ms = start_time.elapsed().as_millis() as u64;
-
-        Ok(ScanResult {
-            root_path: root_path_abs,
-            projects,
-            excluded_dirs,
-            errors,
-            dirs_scanned,
-            scan_duration_ms,
-        })
+    /// Match the candidate directories of a finished walk against their
+    /// listings, reading the metadata of the projects found in parallel
+    ///
+    /// A walk cut short by cancellation still has the directories it listed
+    /// examined, so their projects are part of the partial results.
+    fn detect_candidates(&self, context: &WalkContext, collector: &WalkCollector) {
+        let candidates = collector.take_listed_candidates();
+        let walk_interrupted = collector.interrupted.load(Ordering::Relaxed);
+        let threads = match self.config.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            threads => threads,
+        }
+        .min(candidates.len());
+
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    while let Some((dir, listing)) =
+                        candidates.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        if !walk_interrupted && collector.should_stop() {
+                            break;
+                        }
+                        let found = context.indicators.find_in_listing(dir, listing);
+                        if !found.is_empty() {
+                            collector.push_project(detected_project(
+                                dir.clone(),
+                                found,
+                                &self.config,
+                                &context.excludes,
+                            ));
+                        }
+                        collector.report_progress(dir);
+                    }
+                });
+            }
+        });
     }
 
     /// Scan multiple directories concurrently
@@ -163,6 +902,43 @@ impl ProjectScanner {
     pub fn set_config(&mut self, config: ScanConfig) {
         self.config = config;
     }
+
+    /// Detect the project in a single directory below `root`
+    ///
+    /// The directory is judged the way a scan of `root` would judge it, so
+    /// excluded, hidden and too deep directories are never projects.
+    pub fn detect_project(&self, root: &Path, dir: &Path) -> anyhow::Result<Option<Project>> {
+        let excludes = ExcludeMatcher::new(root, &self.config.exclude_patterns)?;
+        if is_excluded_root(root, &self.config)?
+            || !is_candidate_dir(root, dir, &self.config, &excludes)
+        {
+            return Ok(None);
+        }
+        let indicators = IndicatorMatcher::from_config(&self.config)?;
+        let found = indicators.find_in_dir(dir).unwrap_or_default();
+        Ok((!found.is_empty())
+            .then(|| detected_project(dir.to_path_buf(), found, &self.config, &excludes)))
+    }
+
+    /// Detect the projects in `dir` and every directory below it that a scan
+    /// of `root` would visit, ordered by path
+    pub fn detect_projects_under(&self, root: &Path, dir: &Path) -> anyhow::Result<Vec<Project>> {
+        let context = WalkContext::new(root, &self.config)?;
+        let Some(depth) = depth_below(root, dir) else {
+            return Ok(Vec::new());
+        };
+        if context.root_excluded
+            || self.config.max_depth.is_some_and(|max| depth > max)
+            || (depth > 0 && is_pruned(root, dir, &context.excludes, false))
+        {
+            return Ok(Vec::new());
+        }
+
+        let collector = Arc::new(WalkCollector::default());
+        self.walk_subtree(root, dir, &context, &collector);
+        self.detect_candidates(&context, &collector);
+        Ok(collector.take_sorted_parts().0)
+    }
 }
 
 impl Default for ProjectScanner {
@@ -202,6 +978,17 @@ impl SharedScanner {
         scanner.scan(root_path).await
     }
 
+    /// Scan a directory for projects, reporting progress and stopping early
+    /// as `control` asks
+    pub async fn scan_with_control(
+        &self,
+        root_path: &Path,
+        control: &ScanControl,
+    ) -> anyhow::Result<ScanResult> {
+        let scanner = self.0.lock().await;
+        scanner.scan_with_control(root_path, control).await
+    }
+
     /// Scan multiple directories concurrently
     pub async fn scan_multiple(&self, paths: &[PathBuf]) -> anyhow::Result<Vec<ScanResult>> {
         let scanner = self.0.lock().await;
@@ -239,7 +1026,8 @@ pub async fn scan_directory_with_config(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dprojc_types::ProjectIndicator;
+    use dprojc_types::ProjectType;
+    use dprojc_utils::DPROJC_IGNORE_FILENAME;
     use std::fs;
     use tempfile::tempdir;
 
@@ -474,6 +1262,171 @@ mod tests {
         assert_eq!(result2.projects.len(), 1);
     }
 
+    /// Move a directory's modification time out of the window rescans distrust
+    fn age(path: &Path) {
+        fs::File::open(path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(3600))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rescan_lists_only_changed_directories() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("app")).unwrap();
+        fs::write(root.join("app/Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir_all(root.join("lib/core")).unwrap();
+        fs::write(root.join("lib/core/package.json"), "{}").unwrap();
+        fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+        for dir in ["", "app", "lib", "lib/core"] {
+            age(&root.join(dir));
+        }
+
+        let paths = |projects: &[Project]| -> Vec<PathBuf> {
+            projects.iter().map(|p| p.path.clone()).collect()
+        };
+
+        let scanner = ProjectScanner::new().unwrap();
+        let first = scanner
+            .rescan(root, &DirectoryCache::default(), &[])
+            .await
+            .unwrap();
+        assert_eq!(
+            paths(&first.scan.projects),
+            vec![root.join("app"), root.join("lib/core")]
+        );
+        assert_eq!(first.unchanged_dirs, 0);
+        assert!(first.cache.directories.iter().any(|d| d.excluded));
+        assert!(first
+            .cache
+            .directories
+            .iter()
+            .all(|d| d.excluded || d.mtime_ns.is_some()));
+
+        // Editing a manifest leaves its directory's time alone, but known
+        // projects are read again
+        fs::write(root.join("app/Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let second = scanner
+            .rescan(root, &first.cache, &paths(&first.scan.projects))
+            .await
+            .unwrap();
+        assert!(second.removed.is_empty());
+        assert_eq!(second.unchanged_dirs, 4);
+        assert_eq!(second.scan.projects.len(), 2);
+        let manifest = second.scan.projects[0].manifest.as_ref().unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("app"));
+        assert_eq!(second.scan.excluded_dirs, vec![root.join("node_modules")]);
+
+        fs::remove_dir_all(root.join("app")).unwrap();
+        fs::create_dir(root.join("lib/web")).unwrap();
+        fs::write(root.join("lib/web/go.mod"), "module web\n").unwrap();
+
+        let third = scanner
+            .rescan(root, &second.cache, &paths(&second.scan.projects))
+            .await
+            .unwrap();
+        assert_eq!(third.removed, vec![root.join("app")]);
+        assert_eq!(third.unchanged_dirs, 1);
+        assert_eq!(
+            paths(&third.scan.projects),
+            vec![root.join("lib/core"), root.join("lib/web")]
+        );
+
+        // A cache recorded with other settings is not trusted
+        let deeper = ProjectScanner::with_config(ScanConfig {
+            max_depth: Some(5),
+            ..ScanConfig::default()
+        })
+        .unwrap();
+        let rescanned = deeper
+            .rescan(root, &third.cache, &paths(&third.scan.projects))
+            .await
+            .unwrap();
+        assert_eq!(rescanned.unchanged_dirs, 0);
+        assert_eq!(rescanned.scan.projects.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_scan_reports_progress() {
+        let temp_dir = tempdir().unwrap();
+        for name in ["api", "web", "tools"] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("package.json"), "{}").unwrap();
+        }
+
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = {
+            let reports = Arc::clone(&reports);
+            move |progress: &ScanProgress| reports.lock().unwrap().push(progress.clone())
+        };
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner
+            .scan_with_control(temp_dir.path(), &ScanControl::new().with_progress(sink))
+            .await
+            .unwrap();
+        assert!(!result.cancelled);
+
+        // The final report carries the totals of the finished scan
+        let reports = reports.lock().unwrap();
+        let last = reports.last().unwrap();
+        assert_eq!(last.dirs_scanned, result.dirs_scanned);
+        assert_eq!(last.projects_found, 3);
+        assert_eq!(last.errors, 0);
+        assert_eq!(last.current_path, result.root_path);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_scan_returns_partial_results() {
+        let temp_dir = tempdir().unwrap();
+        for i in 0..20 {
+            let dir = temp_dir.path().join(format!("project{i}"));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+        }
+        let scanner = ProjectScanner::new().unwrap();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let control = ScanControl::new().with_cancellation(token);
+        let result = scanner
+            .scan_with_control(temp_dir.path(), &control)
+            .await
+            .unwrap();
+        assert!(result.cancelled);
+        assert!(result.projects.is_empty());
+
+        // Cancelling from the sink stops the walk on the first report
+        let token = CancellationToken::new();
+        let control = ScanControl::new()
+            .with_cancellation(token.clone())
+            .with_progress(move |_: &ScanProgress| token.cancel());
+        let result = scanner
+            .scan_with_control(temp_dir.path(), &control)
+            .await
+            .unwrap();
+        assert!(result.cancelled);
+        assert!(result.projects.len() < 20);
+
+        // A cancelled rescan keeps the previous cache and removes nothing
+        let full = scanner
+            .rescan(temp_dir.path(), &DirectoryCache::default(), &[])
+            .await
+            .unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let control = ScanControl::new().with_cancellation(token);
+        let known: Vec<PathBuf> = full.scan.projects.iter().map(|p| p.path.clone()).collect();
+        let rescan = scanner
+            .rescan_with_control(temp_dir.path(), &full.cache, &known, &control)
+            .await
+            .unwrap();
+        assert!(rescan.scan.cancelled);
+        assert!(rescan.removed.is_empty());
+        assert_eq!(rescan.cache, full.cache);
+    }
+
     #[test]
     fn test_invalid_config_validation() {
         let invalid_config = ScanConfig {
@@ -664,6 +1617,392 @@ mod tests {
             .any(|p| p.ends_with("custom_exclude")));
     }
 
+    #[tokio::test]
+    async fn test_scan_respects_ignore_files() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+
+        // .gitignore rules only apply inside a git repository
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(root.join(".gitignore"), "/fixtures/\n").unwrap();
+        fs::write(
+            root.join(DPROJC_IGNORE_FILENAME),
+            "archive/*\n!archive/keep\n",
+        )
+        .unwrap();
+
+        for dir in ["fixtures/app", "archive/old", "archive/keep", "app"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("package.json"), "{}").unwrap();
+        }
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(root).await.unwrap();
+        let found: Vec<_> = result.projects.iter().map(|p| p.path.clone()).collect();
+
+        assert!(found.contains(&root.join("app")));
+        assert!(found.contains(&root.join("archive/keep")));
+        assert!(!found.contains(&root.join("archive/old")));
+        assert!(!found.contains(&root.join("fixtures/app")));
+
+        // Both kinds of ignore file can be switched off
+        let config = ScanConfig {
+            respect_gitignore: false,
+            respect_dprojcignore: false,
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(root).await.unwrap();
+        let found: Vec<_> = result.projects.iter().map(|p| p.path.clone()).collect();
+
+        assert!(found.contains(&root.join("archive/old")));
+        assert!(found.contains(&root.join("fixtures/app")));
+    }
+
+    #[tokio::test]
+    async fn test_scan_prunes_excluded_directories() {
+        let temp_dir = tempdir().unwrap();
+        let nested = temp_dir.path().join("node_modules").join("pkg");
+        fs::create_dir_all(nested.join("node_modules").join("dep")).unwrap();
+        fs::write(nested.join("package.json"), "{}").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+
+        // Only the top-level excluded directory is visited and recorded
+        assert!(result.projects.is_empty());
+        assert_eq!(
+            result.excluded_dirs,
+            vec![temp_dir.path().join("node_modules")]
+        );
+
+        // A root inside an excluded directory is excluded as a whole
+        let result = scanner.scan(&nested).await.unwrap();
+        assert!(result.projects.is_empty());
+        assert_eq!(result.excluded_dirs, vec![nested.clone()]);
+        assert!(scanner.detect_project(&nested, &nested).unwrap().is_none());
+
+        // Anchored patterns only apply below the root
+        let scanner = ProjectScanner::with_config(ScanConfig {
+            exclude_patterns: vec!["/pkg".to_string()],
+            ..ScanConfig::default()
+        })
+        .unwrap();
+        let result = scanner.scan(&nested).await.unwrap();
+        assert_eq!(result.projects.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_scan_is_deterministic() {
+        let temp_dir = tempdir().unwrap();
+        for group in 0..5 {
+            for i in 0..8 {
+                let dir = temp_dir
+                    .path()
+                    .join(format!("group_{}", group))
+                    .join(format!("project_{}", i));
+                fs::create_dir_all(dir.join("node_modules")).unwrap();
+                fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+            }
+        }
+
+        let scan_with_threads = |threads| {
+            let config = ScanConfig {
+                threads,
+                ..Default::default()
+            };
+            ProjectScanner::with_config(config).unwrap()
+        };
+
+        let single = scan_with_threads(1).scan(temp_dir.path()).await.unwrap();
+        let parallel = scan_with_threads(4).scan(temp_dir.path()).await.unwrap();
+
+        assert_eq!(single.projects.len(), 40);
+        let paths = |result: &ScanResult| -> Vec<PathBuf> {
+            result.projects.iter().map(|p| p.path.clone()).collect()
+        };
+        let mut sorted = paths(&single);
+        sorted.sort();
+        assert_eq!(paths(&single), sorted);
+        assert_eq!(paths(&single), paths(&parallel));
+        assert_eq!(single.excluded_dirs, parallel.excluded_dirs);
+        assert_eq!(single.dirs_scanned, parallel.dirs_scanned);
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_globbed_indicators() {
+        let temp_dir = tempdir().unwrap();
+        let gem_dir = temp_dir.path().join("my_gem");
+        fs::create_dir(&gem_dir).unwrap();
+        fs::write(gem_dir.join("my_gem.gemspec"), "").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+
+        assert_eq!(result.projects.len(), 1);
+        assert_eq!(result.projects[0].path, gem_dir);
+        assert_eq!(result.projects[0].project_type, ProjectType::Ruby);
+        assert_eq!(
+            result.projects[0].indicators,
+            vec![ProjectIndicator::Gemspec]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_indicator_rules() {
+        let temp_dir = tempdir().unwrap();
+        let poetry_dir = temp_dir.path().join("poetry_app");
+        let plain_dir = temp_dir.path().join("plain_app");
+        fs::create_dir(&poetry_dir).unwrap();
+        fs::create_dir(&plain_dir).unwrap();
+        fs::write(poetry_dir.join("pyproject.toml"), "[tool.poetry]\n").unwrap();
+        fs::write(plain_dir.join("pyproject.toml"), "[project]\n").unwrap();
+
+        let config = ScanConfig {
+            project_indicators: Vec::new(),
+            indicator_rules: vec![dprojc_types::IndicatorRule {
+                name: "poetry".to_string(),
+                kind: dprojc_types::IndicatorRuleKind::FileContains {
+                    file: "pyproject.toml".to_string(),
+                    contains: "[tool.poetry]".to_string(),
+                },
+            }],
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+
+        assert_eq!(result.projects.len(), 1);
+        assert_eq!(result.projects[0].path, poetry_dir);
+        assert_eq!(
+            result.projects[0].indicators,
+            vec![ProjectIndicator::Custom("poetry".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_assigns_custom_project_types() {
+        let temp_dir = tempdir().unwrap();
+        let infra_dir = temp_dir.path().join("infra");
+        fs::create_dir_all(infra_dir.join(".git")).unwrap();
+        fs::write(infra_dir.join("main.tf"), "").unwrap();
+
+        let config = ScanConfig {
+            project_types: vec![dprojc_types::ProjectTypeDefinition {
+                name: "terraform".to_string(),
+                indicators: vec!["*.tf".to_string()],
+                rules: Vec::new(),
+                priority: dprojc_types::DEFAULT_CUSTOM_TYPE_PRIORITY,
+            }],
+            ..Default::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+
+        assert_eq!(result.projects.len(), 1);
+        // The definition shadows the built-in Terraform type, whose indicator `*.tf` is
+        assert_eq!(
+            result.projects[0].project_type,
+            ProjectType::Custom("terraform".to_string())
+        );
+        assert!(result.projects[0].ecosystems.is_empty());
+        assert_eq!(
+            result.projects[0].indicators,
+            vec![
+                ProjectIndicator::GitDirectory,
+                ProjectIndicator::TerraformConfig
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_records_secondary_ecosystems() {
+        let temp_dir = tempdir().unwrap();
+        let app_dir = temp_dir.path().join("app");
+        fs::create_dir(&app_dir).unwrap();
+        fs::write(app_dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(app_dir.join("package.json"), "{}").unwrap();
+        fs::write(app_dir.join("requirements.txt"), "").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+
+        assert_eq!(result.projects.len(), 1);
+        let project = &result.projects[0];
+        assert_eq!(project.project_type, ProjectType::Rust);
+        assert_eq!(
+            project.ecosystems,
+            vec![ProjectType::NodeJs, ProjectType::Python]
+        );
+        assert!(project.has_ecosystem(&ProjectType::NodeJs));
+        assert!(!project.has_ecosystem(&ProjectType::Go));
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_extended_ecosystems() {
+        let temp_dir = tempdir().unwrap();
+        let service_dir = temp_dir.path().join("service");
+        fs::create_dir(&service_dir).unwrap();
+        fs::write(service_dir.join("Service.csproj"), "<Project />").unwrap();
+        fs::write(service_dir.join("docker-compose.yml"), "services: {}").unwrap();
+        let infra_dir = temp_dir.path().join("infra");
+        fs::create_dir(&infra_dir).unwrap();
+        fs::write(infra_dir.join("main.tf"), "").unwrap();
+        // A Makefile is only C/C++ evidence next to C/C++ sources
+        for (dir, file) in [
+            ("cli", "Cargo.toml"),
+            ("native", "main.c"),
+            ("scripts", "deploy.sh"),
+        ] {
+            let dir = temp_dir.path().join(dir);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join(file), "").unwrap();
+            fs::write(dir.join("Makefile"), "all:\n").unwrap();
+        }
+
+        let scanner = ProjectScanner::new().unwrap();
+        let mut result = scanner.scan(temp_dir.path()).await.unwrap();
+        result.projects.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let types: Vec<_> = result
+            .projects
+            .iter()
+            .map(|p| {
+                (
+                    p.path.file_name().unwrap().to_owned(),
+                    p.project_type.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("cli".into(), ProjectType::Rust),
+                ("infra".into(), ProjectType::Terraform),
+                ("native".into(), ProjectType::Cpp),
+                ("service".into(), ProjectType::DotNet),
+            ]
+        );
+        assert!(result.projects[0].ecosystems.is_empty());
+        assert_eq!(
+            result.projects[3].ecosystems,
+            vec![ProjectType::DockerCompose]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_links_workspace_members() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("crates/core")).unwrap();
+        fs::write(root.join("crates/core/Cargo.toml"), "[package]").unwrap();
+        fs::create_dir_all(root.join("web/packages/ui")).unwrap();
+        fs::write(
+            root.join("web/package.json"),
+            r#"{"private": true, "workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        fs::write(root.join("web/packages/ui/package.json"), "{}").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(root).await.unwrap();
+
+        let parent_of = |relative: &str| {
+            result
+                .projects
+                .iter()
+                .find(|p| p.path == root.join(relative))
+                .unwrap()
+                .parent
+                .clone()
+        };
+        assert_eq!(result.projects.len(), 4);
+        assert_eq!(parent_of(""), None);
+        assert_eq!(parent_of("crates/core"), Some(root.to_path_buf()));
+        assert_eq!(parent_of("web"), None);
+        assert_eq!(parent_of("web/packages/ui"), Some(root.join("web")));
+    }
+
+    #[tokio::test]
+    async fn test_scan_reads_git_metadata() {
+        let temp_dir = tempdir().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        repo.remote("origin", "https://example.com/app.git")
+            .unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        assert_eq!(result.projects.len(), 1);
+        let git = result.projects[0].git.as_ref().unwrap();
+        assert_eq!(git.remotes.len(), 1);
+        assert_eq!(git.untracked_count, 1);
+
+        let config = ScanConfig {
+            git_metadata: false,
+            ..ScanConfig::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        assert_eq!(result.projects[0].git, None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_measures_disk_usage_on_request() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        fs::create_dir(temp_dir.path().join("target")).unwrap();
+        fs::write(temp_dir.path().join("target/app"), vec![0u8; 64]).unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        assert_eq!(result.projects[0].disk_usage, None);
+
+        let config = ScanConfig {
+            disk_usage: true,
+            ..ScanConfig::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        let usage = result.projects[0].disk_usage.as_ref().unwrap();
+        assert_eq!(usage.artifact_bytes, 64);
+        assert_eq!(usage.source_bytes, 9);
+    }
+
+    #[tokio::test]
+    async fn test_scan_counts_code_on_request() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::create_dir(temp_dir.path().join("vendor")).unwrap();
+        fs::write(temp_dir.path().join("vendor/dep.rs"), "fn dep() {}\n").unwrap();
+
+        let scanner = ProjectScanner::new().unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        assert_eq!(result.projects[0].code_stats, None);
+
+        let config = ScanConfig {
+            code_stats: true,
+            ..ScanConfig::default()
+        };
+        let scanner = ProjectScanner::with_config(config).unwrap();
+        let result = scanner.scan(temp_dir.path()).await.unwrap();
+        let stats = result.projects[0].code_stats.as_ref().unwrap();
+        // vendor/ is excluded by default, so only src/main.rs is Rust code
+        let rust = &stats.languages[0];
+        assert_eq!(
+            (rust.language.as_str(), rust.files, rust.code),
+            ("Rust", 1, 1)
+        );
+        assert_eq!(stats.languages[1].language, "TOML");
+    }
+
     #[tokio::test]
     async fn test_scan_root_with_multiple_indicators_precedence() {
         let temp_dir = tempdir().unwrap();
@@ -0,0 +1,105 @@
+//! Progress reporting and cancellation for scans in flight
+
+use dprojc_types::ScanProgress;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Receives progress reports from a running scan
+///
+/// Reports come from the scanning threads, at most a few times a second, and
+/// once more when the scan finishes. Any `Fn(&ScanProgress)` closure that can
+/// be shared across threads is a sink.
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, progress: &ScanProgress);
+}
+
+impl<F> ProgressSink for F
+where
+    F: Fn(&ScanProgress) + Send + Sync,
+{
+    fn report(&self, progress: &ScanProgress) {
+        self(progress)
+    }
+}
+
+/// A flag that asks running scans to stop
+///
+/// Clones share the flag, so one can be handed to a signal handler while the
+/// scan holds another. Once cancelled it stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every scan holding this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How a caller follows and steers a scan
+///
+/// The default control reports nowhere and is never cancelled, which is what
+/// `ProjectScanner::scan` uses.
+#[derive(Clone, Default)]
+pub struct ScanControl {
+    progress: Option<Arc<dyn ProgressSink>>,
+    cancellation: CancellationToken,
+}
+
+impl ScanControl {
+    /// Create a control without a progress sink or cancellation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send progress reports to `sink`
+    pub fn with_progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.progress = Some(Arc::new(sink));
+        self
+    }
+
+    /// Stop the scan when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// The token that cancels the scan
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Whether the scan should stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    pub(crate) fn report(&self, progress: &ScanProgress) {
+        if let Some(sink) = &self.progress {
+            sink.report(progress);
+        }
+    }
+
+    pub(crate) fn has_progress(&self) -> bool {
+        self.progress.is_some()
+    }
+}
+
+impl std::fmt::Debug for ScanControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanControl")
+            .field("progress", &self.progress.is_some())
+            .field("cancellation", &self.cancellation)
+            .finish()
+    }
+}
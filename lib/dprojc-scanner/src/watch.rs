@@ -0,0 +1,338 @@
+//! Change notifications for the directories below a scan root
+//!
+//! A [`DirectoryWatcher`] registers one watch per directory with the
+//! platform's notification API, so it keeps count and refuses to grow past its
+//! budget. Callers fall back to rescanning roots it cannot cover.
+
+use crate::subtree_dirs;
+use dprojc_types::ScanConfig;
+use dprojc_utils::ExcludeMatcher;
+use notify::event::{AccessKind, AccessMode, ModifyKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// A change reported for a watched directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirChange {
+    /// Files in the directory were created, removed, renamed or written
+    Entries(PathBuf),
+    /// A directory appeared, either created or moved in
+    Created(PathBuf),
+    /// A directory disappeared, either removed or moved away
+    Removed(PathBuf),
+    /// Events were dropped or could not be read, so any watched directory
+    /// may have changed
+    Overflow,
+}
+
+/// Errors from registering or reading watches
+#[derive(Debug, thiserror::Error)]
+pub enum WatchError {
+    #[error("watching would exceed the budget of {0} directories")]
+    LimitReached(usize),
+    #[error("the system limit on watches was reached (see fs.inotify.max_user_watches)")]
+    SystemLimit,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Notify(notify::Error),
+    #[error("the watcher stopped delivering events")]
+    Stopped,
+}
+
+impl From<notify::Error> for WatchError {
+    fn from(err: notify::Error) -> Self {
+        match err.kind {
+            notify::ErrorKind::MaxFilesWatch => Self::SystemLimit,
+            notify::ErrorKind::Io(err) => Self::Io(err),
+            _ => Self::Notify(err),
+        }
+    }
+}
+
+impl WatchError {
+    /// Whether the error means there are not enough watches to go around
+    pub fn is_limit(&self) -> bool {
+        matches!(self, Self::LimitReached(_) | Self::SystemLimit)
+    }
+}
+
+/// Watches directories for changes that can affect project detection
+pub struct DirectoryWatcher {
+    watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<notify::Result<Event>>,
+    watches: HashSet<PathBuf>,
+    max_watches: usize,
+}
+
+impl DirectoryWatcher {
+    /// Create a watcher that registers at most `max_watches` directories
+    pub fn new(max_watches: usize) -> Result<Self, WatchError> {
+        let (sender, events) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            // The receiver only goes away together with the watcher
+            let _ = sender.send(event);
+        })?;
+        Ok(Self {
+            watcher,
+            events,
+            watches: HashSet::new(),
+            max_watches,
+        })
+    }
+
+    /// Number of directories being watched
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    /// Whether no directory is being watched
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Whether `dir` is being watched
+    pub fn is_watching(&self, dir: &Path) -> bool {
+        self.watches.contains(dir)
+    }
+
+    /// Watch `dir` and the directories below it that a scan of `root` visits
+    ///
+    /// Hidden directories are left out. When the budget or the system limit
+    /// would be exceeded, the watches added by this call are removed again.
+    /// Returns the number of directories added.
+    pub fn watch_tree(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        config: &ScanConfig,
+    ) -> Result<usize, WatchError> {
+        let excludes = Arc::new(
+            ExcludeMatcher::new(root, &config.exclude_patterns)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+        );
+
+        let mut added = Vec::new();
+        for path in subtree_dirs(root, dir, config, &excludes, true) {
+            if self.watches.contains(&path) {
+                continue;
+            }
+            if self.watches.len() >= self.max_watches {
+                self.remove_watches(added);
+                return Err(WatchError::LimitReached(self.max_watches));
+            }
+            match self.watcher.watch(&path, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    self.watches.insert(path.clone());
+                    added.push(path);
+                }
+                Err(err) => {
+                    let err = WatchError::from(err);
+                    if err.is_limit() {
+                        self.remove_watches(added);
+                        return Err(err);
+                    }
+                    // The directory vanished or can't be read; a later event
+                    // or rescan catches up with it
+                }
+            }
+        }
+        Ok(added.len())
+    }
+
+    /// Stop watching `dir` and every directory below it
+    pub fn unwatch_tree(&mut self, dir: &Path) {
+        let paths = self
+            .watches
+            .iter()
+            .filter(|path| path.starts_with(dir))
+            .cloned()
+            .collect();
+        self.remove_watches(paths);
+    }
+
+    fn remove_watches(&mut self, paths: Vec<PathBuf>) {
+        for path in paths {
+            if self.watches.remove(&path) {
+                // Fails harmlessly when the directory and its watch are gone
+                let _ = self.watcher.unwatch(&path);
+            }
+        }
+    }
+
+    /// Wait for the next changes and collect every change reported so far
+    ///
+    /// Fails only when the backend stopped, since nothing is reported after
+    /// that.
+    pub async fn changes(&mut self) -> Result<Vec<DirChange>, WatchError> {
+        let Some(event) = self.events.recv().await else {
+            // The backend dropped its end of the channel
+            return Err(WatchError::Stopped);
+        };
+        let mut changes = Vec::new();
+        self.translate(event, &mut changes);
+        changes.extend(self.read_changes());
+        Ok(changes)
+    }
+
+    /// Collect the changes reported since the last call without blocking
+    pub fn read_changes(&mut self) -> Vec<DirChange> {
+        let mut changes = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            self.translate(event, &mut changes);
+        }
+        changes
+    }
+
+    /// Turn an event into changes of the watched directories it concerns
+    ///
+    /// An error reading events may have lost some, which is reported like an
+    /// overflow.
+    fn translate(&self, event: notify::Result<Event>, changes: &mut Vec<DirChange>) {
+        let event = match event {
+            Ok(event) if !event.need_rescan() => event,
+            _ => {
+                changes.push(DirChange::Overflow);
+                return;
+            }
+        };
+        for path in event.paths {
+            match event.kind {
+                // Backends differ in telling directories from files and in
+                // pairing the two ends of a rename, so what the path is now
+                // decides
+                EventKind::Create(_)
+                | EventKind::Remove(_)
+                | EventKind::Modify(ModifyKind::Name(_)) => {
+                    let watched = self.watches.contains(&path);
+                    if !watched && path.is_dir() {
+                        changes.push(DirChange::Created(path.clone()));
+                    } else if watched && !path.exists() {
+                        changes.push(DirChange::Removed(path.clone()));
+                    }
+                }
+                EventKind::Modify(ModifyKind::Data(_))
+                | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {}
+                // Opening, reading and metadata changes don't affect detection
+                _ => continue,
+            }
+            // Any entry, directories included, can be an indicator of the
+            // directory holding it
+            if let Some(dir) = path.parent().filter(|dir| self.watches.contains(*dir)) {
+                changes.push(DirChange::Entries(dir.to_path_buf()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    /// Collect changes until all of `expected` were reported
+    async fn wait_for(watcher: &mut DirectoryWatcher, expected: &[DirChange]) -> Vec<DirChange> {
+        let mut changes = Vec::new();
+        while !expected.iter().all(|change| changes.contains(change)) {
+            let more = tokio::time::timeout(Duration::from_secs(10), watcher.changes())
+                .await
+                .expect("no change reported in time");
+            changes.extend(more.unwrap());
+        }
+        changes
+    }
+
+    #[tokio::test]
+    async fn test_watch_tree_and_changes() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("app/src")).unwrap();
+        fs::create_dir_all(root.join("app/.git/objects")).unwrap();
+        fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+
+        let config = ScanConfig::default();
+        let mut watcher = DirectoryWatcher::new(16).unwrap();
+        assert_eq!(watcher.watch_tree(root, root, &config).unwrap(), 3);
+        assert!(watcher.is_watching(&root.join("app/src")));
+        assert!(!watcher.is_watching(&root.join("app/.git")));
+        assert!(!watcher.is_watching(&root.join("node_modules")));
+
+        fs::write(root.join("app/Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir(root.join("lib")).unwrap();
+        fs::remove_dir(root.join("app/src")).unwrap();
+
+        let changes = wait_for(
+            &mut watcher,
+            &[
+                DirChange::Entries(root.join("app")),
+                DirChange::Created(root.join("lib")),
+                DirChange::Removed(root.join("app/src")),
+            ],
+        )
+        .await;
+        assert!(!changes.contains(&DirChange::Created(root.join("app/Cargo.toml"))));
+
+        // Directories moved in and out are reported like created and removed ones
+        assert_eq!(
+            watcher
+                .watch_tree(root, &root.join("lib"), &config)
+                .unwrap(),
+            1
+        );
+        let outside = tempdir().unwrap();
+        fs::rename(root.join("lib"), outside.path().join("lib")).unwrap();
+        fs::create_dir(outside.path().join("web")).unwrap();
+        fs::rename(outside.path().join("web"), root.join("web")).unwrap();
+        wait_for(
+            &mut watcher,
+            &[
+                DirChange::Removed(root.join("lib")),
+                DirChange::Created(root.join("web")),
+            ],
+        )
+        .await;
+        watcher.unwatch_tree(&root.join("lib"));
+
+        watcher.unwatch_tree(&root.join("app"));
+        assert_eq!(watcher.len(), 1);
+    }
+
+    #[test]
+    fn test_watch_tree_respects_budget() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::create_dir_all(root.join("c")).unwrap();
+
+        let mut watcher = DirectoryWatcher::new(3).unwrap();
+        let err = watcher
+            .watch_tree(root, root, &ScanConfig::default())
+            .unwrap_err();
+        assert!(err.is_limit());
+        assert!(watcher.is_empty());
+
+        assert_eq!(
+            watcher
+                .watch_tree(root, &root.join("a"), &ScanConfig::default())
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_backend_errors_read_as_overflow() {
+        let watcher = DirectoryWatcher::new(1).unwrap();
+        let mut changes = Vec::new();
+        watcher.translate(Err(notify::Error::generic("read failed")), &mut changes);
+        watcher.translate(
+            Ok(Event::new(EventKind::Other).set_flag(notify::event::Flag::Rescan)),
+            &mut changes,
+        );
+        assert_eq!(changes, vec![DirChange::Overflow, DirChange::Overflow]);
+    }
+}
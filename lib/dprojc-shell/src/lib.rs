@@ -162,6 +162,7 @@ mod tests {
             project_type,
             indicators: vec![ProjectIndicator::GitDirectory], // Simple indicator for testing
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::path::PathBuf;
 use tokio::sync::mpsc;
@@ -16,10 +17,13 @@ use ratatui::{
     Frame, Terminal,
 };
 
-use dprojc_scanner::SharedScanner;
-use dprojc_types::{Project, ScanResult};
+use dprojc_scanner::{ScanControl, SharedScanner};
+use dprojc_types::{Project, ProjectTypeDefinition, ScanProgress, ScanResult};
 
-use dprojc_utils::get_project_type_priority;
+use dprojc_utils::{
+    collapse_workspace_members, format_file_size, group_workspace_members, project_type_priority,
+    workspace_depths,
+};
 use fuzzy_matcher::FuzzyMatcher;
 
 /// TUI-specific configuration
@@ -151,6 +155,8 @@ impl TuiConfig {
 enum ScanResultMessage {
     /// Scan progress update (current, total)
     Progress(usize, usize),
+    /// How far the walk of the current root has come
+    Walking(ScanProgress),
     /// Scan completed successfully
     Success(Vec<ScanResult>),
     /// Scan failed with error
@@ -190,10 +196,20 @@ pub struct App {
     config: TuiConfig,
     /// Scanning progress (current/total paths)
     scan_progress: Option<(usize, usize)>,
+    /// Latest progress report from the root being scanned
+    scan_walk: Option<ScanProgress>,
     /// Current sort mode
     sort_mode: SortMode,
     /// Selected path to output (when user presses Enter to select)
     selected_path: Option<PathBuf>,
+    /// User-defined project types stored with the catalog, for type sorting
+    project_types: Vec<ProjectTypeDefinition>,
+    /// Whether workspace members are folded into their workspace
+    collapse_workspaces: bool,
+    /// Number of hidden members per collapsed workspace
+    collapsed_members: HashMap<PathBuf, usize>,
+    /// Projects the latest scan of their root found and the scan before missed
+    new_projects: HashSet<PathBuf>,
 }
 
 /// Application state
@@ -233,6 +249,8 @@ pub enum SortMode {
     Type,
     /// Sort by last scanned date
     Date,
+    /// Sort by measured disk usage, largest first
+    Size,
 }
 
 impl Default for App {
@@ -266,8 +284,13 @@ impl Default for App {
             scan_errors: Vec::new(),
             config,
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         }
     }
 }
@@ -376,6 +399,7 @@ impl App {
                 self.projects.clear();
                 self.scan_errors.clear();
                 self.scan_progress = None;
+                self.scan_walk = None;
 
                 for result in results {
                     self.projects.extend(result.projects);
@@ -393,17 +417,22 @@ impl App {
                 self.sort_projects();
 
                 // Initialize filtered projects
-                self.filtered_projects = self.projects.clone();
+                self.update_filtered_projects();
                 self.selected_index = 0;
                 self.state = AppState::Browsing;
             }
             ScanResultMessage::Error(err) => {
                 self.scan_errors.push(err);
                 self.scan_progress = None;
+                self.scan_walk = None;
                 self.state = AppState::Browsing; // Still allow browsing even with errors
             }
             ScanResultMessage::Progress(current, total) => {
                 self.scan_progress = Some((current + 1, total));
+                self.scan_walk = None;
+            }
+            ScanResultMessage::Walking(progress) => {
+                self.scan_walk = Some(progress);
             }
         }
     }
@@ -414,11 +443,13 @@ impl App {
         let db_path = dprojc_utils::default_db_path()?;
 
         if let Ok(db) = dprojc_db::ProjectDatabase::open(&db_path) {
+            self.project_types = db.get_project_types().unwrap_or_default();
+            self.new_projects = projects_new_since_previous_scan(&db).unwrap_or_default();
             if let Ok(projects) = db.get_all_projects() {
                 if !projects.is_empty() {
                     self.projects = projects;
                     self.sort_projects();
-                    self.filtered_projects = self.projects.clone();
+                    self.update_filtered_projects();
                     self.state = AppState::Browsing;
                     return Ok(());
                 }
@@ -516,7 +547,12 @@ impl App {
             KeyCode::Char('s') => {
                 self.cycle_sort_mode();
                 self.sort_projects();
-                self.filtered_projects = self.projects.clone();
+                self.update_filtered_projects();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('w') => {
+                self.collapse_workspaces = !self.collapse_workspaces;
+                self.update_filtered_projects();
                 self.selected_index = 0;
             }
             _ => {}
@@ -560,9 +596,10 @@ impl App {
                 self.projects.sort_by(|a, b| a.path.cmp(&b.path));
             }
             SortMode::Type => {
+                let project_types = &self.project_types;
                 self.projects.sort_by(|a, b| {
-                    let priority_a = get_project_type_priority(&a.project_type);
-                    let priority_b = get_project_type_priority(&b.project_type);
+                    let priority_a = project_type_priority(&a.project_type, project_types);
+                    let priority_b = project_type_priority(&b.project_type, project_types);
                     let priority_cmp = priority_b.cmp(&priority_a); // Higher priority first
                     if priority_cmp == std::cmp::Ordering::Equal {
                         let type_cmp = a.project_type.cmp(&b.project_type);
@@ -586,6 +623,13 @@ impl App {
                     }
                 });
             }
+            SortMode::Size => {
+                // Unmeasured projects sort last
+                let size =
+                    |project: &Project| project.disk_usage.as_ref().map(|usage| usage.total_bytes);
+                self.projects
+                    .sort_by(|a, b| size(b).cmp(&size(a)).then_with(|| a.path.cmp(&b.path)));
+            }
         }
     }
 
@@ -600,17 +644,29 @@ impl App {
                 .iter()
                 .filter(|project| {
                     let path_str = project.path.to_string_lossy();
-                    let type_str = format!("{:?}", project.project_type);
+                    let type_str = project.project_type.to_string();
                     matcher.fuzzy_match(&path_str, &self.search_query).is_some()
                         || matcher.fuzzy_match(&type_str, &self.search_query).is_some()
+                        || project
+                            .manifest
+                            .as_ref()
+                            .is_some_and(|manifest| manifest.matches(&self.search_query))
                 })
                 .cloned()
                 .collect();
+        }
 
-            // Reset selection if out of bounds
-            if self.selected_index >= self.filtered_projects.len() {
-                self.selected_index = self.filtered_projects.len().saturating_sub(1);
-            }
+        // Keep workspace members beneath their workspace, or fold them into it
+        if self.collapse_workspaces {
+            self.collapsed_members = collapse_workspace_members(&mut self.filtered_projects);
+        } else {
+            group_workspace_members(&mut self.filtered_projects);
+            self.collapsed_members.clear();
+        }
+
+        // Reset selection if out of bounds
+        if self.selected_index >= self.filtered_projects.len() {
+            self.selected_index = self.filtered_projects.len().saturating_sub(1);
         }
     }
 
@@ -658,31 +714,66 @@ impl App {
             .block(Block::default().borders(Borders::ALL).title("Header"));
         f.render_widget(header, chunks[0]);
 
+        let depths = workspace_depths(display_projects);
         let items: Vec<ListItem> = display_projects
             .iter()
+            .zip(depths)
             .enumerate()
-            .map(|(i, project)| {
+            .map(|(i, (project, depth))| {
+                let is_new = self.new_projects.contains(&project.path);
                 let style = if i == self.selected_index {
                     Style::default().bg(Color::Blue).fg(Color::White)
+                } else if is_new {
+                    Style::default().fg(Color::Green)
                 } else {
                     Style::default()
                 };
 
-                let path_display = dprojc_utils::format_path_display(&project.path);
-                let type_display = format!("{:?}", project.project_type);
+                let mut path_display = dprojc_utils::format_path_display(&project.path);
+                if depth > 0 {
+                    path_display = format!("{}└ {}", "  ".repeat(depth - 1), path_display);
+                }
+                if let Some(members) = self.collapsed_members.get(&project.path) {
+                    path_display = format!("{} (+{} members)", path_display, members);
+                }
+                let mut type_display = project.project_type.to_string();
+                if is_new {
+                    type_display.push_str(" (new)");
+                }
+                let package = project
+                    .manifest
+                    .as_ref()
+                    .and_then(|manifest| manifest.label())
+                    .map(|label| format!("{} | ", label))
+                    .unwrap_or_default();
+                let size = project
+                    .disk_usage
+                    .as_ref()
+                    .map(|usage| format!(" | Size: {}", format_file_size(usage.total_bytes)))
+                    .unwrap_or_default();
                 let content = format!(
-                    "{} {}\nLast scanned: {}",
+                    "{} {}\n{}Last scanned: {}{}",
                     path_display,
                     type_display,
-                    project.last_scanned.format("%Y-%m-%d %H:%M")
+                    package,
+                    project.last_scanned.format("%Y-%m-%d %H:%M"),
+                    size
                 );
 
                 ListItem::new(content).style(style)
             })
             .collect();
 
+        let list_title = if self.new_projects.is_empty() {
+            "Projects".to_string()
+        } else {
+            format!(
+                "Projects ({} new since the previous scan)",
+                self.new_projects.len()
+            )
+        };
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Projects"))
+            .block(Block::default().borders(Borders::ALL).title(list_title))
             .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
         f.render_widget(list, chunks[1]);
@@ -695,6 +786,7 @@ impl App {
                     SortMode::Path => "Sort: Path",
                     SortMode::Type => "Sort: Type",
                     SortMode::Date => "Sort: Date",
+                    SortMode::Size => "Sort: Size",
                 };
                 if self.scan_errors.is_empty() {
                     &format!("Browsing projects | {} | Press / to search, s to change sort, ? for help, q to quit", sort_indicator)
@@ -705,7 +797,17 @@ impl App {
             AppState::Searching => &format!("Search: {}", self.search_query),
             AppState::Details => "Viewing project details | Press Esc to go back",
             AppState::Scanning => {
-                if let Some((current, total)) = self.scan_progress {
+                if let (Some((current, total)), Some(walk)) = (self.scan_progress, &self.scan_walk)
+                {
+                    &format!(
+                        "Scanning directories... ({}/{}) | {} dirs, {} projects | {}",
+                        current,
+                        total,
+                        walk.dirs_scanned,
+                        walk.projects_found,
+                        walk.current_path.display()
+                    )
+                } else if let Some((current, total)) = self.scan_progress {
                     &format!("Scanning directories... ({}/{})", current, total)
                 } else {
                     "Scanning directories..."
@@ -756,13 +858,122 @@ impl App {
         f.render_widget(title, chunks[0]);
 
         // Details
-        let details_text = format!(
-            "Type: {:?}\nPath: {}\nLast Scanned: {}\nIndicators: {:?}",
+        let mut details_text = format!(
+            "Type: {}\nPath: {}\nLast Scanned: {}\nIndicators: {:?}",
             project.project_type,
             project.path.display(),
             project.last_scanned,
             project.indicators
         );
+        if let Some(parent) = &project.parent {
+            details_text.push_str(&format!("\nWorkspace: {}", parent.display()));
+        }
+        if let Some(manifest) = &project.manifest {
+            details_text.push_str(&format!("\n\nManifest: {}", manifest.source));
+            let fields = [
+                ("Name", manifest.name.clone()),
+                ("Version", manifest.version.clone()),
+                ("Description", manifest.description.clone()),
+                (
+                    "Authors",
+                    (!manifest.authors.is_empty()).then(|| manifest.authors.join(", ")),
+                ),
+                ("License", manifest.license.clone()),
+                ("Homepage", manifest.homepage.clone()),
+            ];
+            for (label, value) in fields {
+                if let Some(value) = value {
+                    details_text.push_str(&format!("\n{}: {}", label, value));
+                }
+            }
+        }
+        if !project.toolchains.is_empty() {
+            details_text.push_str("\n\nToolchains:");
+            for pin in &project.toolchains {
+                details_text.push_str(&format!(
+                    "\n  {} {} ({})",
+                    pin.tool, pin.version, pin.source
+                ));
+            }
+        }
+        if !project.automation.is_empty() {
+            details_text.push_str("\n\nAutomation:");
+            for config in &project.automation {
+                details_text.push_str(&format!("\n  {}: {}", config.kind.label(), config.path));
+                if !config.targets.is_empty() {
+                    details_text.push_str(&format!(" ({})", config.targets.join(", ")));
+                }
+            }
+        }
+        if !project.licenses.is_empty() {
+            details_text.push_str(&format!(
+                "\n\nLicense: {}",
+                project.license().as_deref().unwrap_or("unknown")
+            ));
+            for detection in &project.licenses {
+                let license = detection.spdx.as_deref().unwrap_or("unrecognized");
+                details_text.push_str(&format!("\n  {}: {}", detection.source, license));
+            }
+        }
+        if let Some(usage) = &project.disk_usage {
+            details_text.push_str(&format!(
+                "\n\nDisk Usage: {} total, {} source, {} build artifacts (measured {})",
+                format_file_size(usage.total_bytes),
+                format_file_size(usage.source_bytes),
+                format_file_size(usage.artifact_bytes),
+                usage.measured_at.format("%Y-%m-%d %H:%M")
+            ));
+            for dir in &usage.artifact_dirs {
+                details_text.push_str(&format!(
+                    "\n  {}: {}",
+                    dir.path.display(),
+                    format_file_size(dir.bytes)
+                ));
+            }
+        }
+        if let Some(stats) = &project.code_stats {
+            let total = stats.total();
+            details_text.push_str(&format!(
+                "\n\nLines of Code: {} code, {} comments, {} blank in {} files (counted {})",
+                total.code,
+                total.comments,
+                total.blanks,
+                total.files,
+                stats.measured_at.format("%Y-%m-%d %H:%M")
+            ));
+            for language in &stats.languages {
+                details_text.push_str(&format!(
+                    "\n  {}: {} code, {} comments, {} blank ({} files)",
+                    language.language,
+                    language.code,
+                    language.comments,
+                    language.blanks,
+                    language.files
+                ));
+            }
+        }
+        if let Some(git) = &project.git {
+            details_text.push_str(&format!(
+                "\n\nGit: {}\nStatus: {}",
+                git.head_label(),
+                git.status_summary()
+            ));
+            if let Some(date) = git.head_date {
+                details_text.push_str(&format!("\nLast Commit: {}", date));
+            }
+            if let Some(upstream) = &git.upstream {
+                details_text.push_str(&format!("\nUpstream: {}", upstream));
+            }
+            for remote in &git.remotes {
+                details_text.push_str(&format!("\nRemote {}: {}", remote.name, remote.url));
+            }
+            if git.is_worktree {
+                details_text.push_str("\nLinked worktree");
+            }
+            if git.is_submodule {
+                details_text.push_str("\nSubmodule");
+            }
+        }
 
         let details_paragraph = Paragraph::new(details_text)
             .block(Block::default().borders(Borders::ALL).title("Details"));
@@ -777,7 +988,7 @@ impl App {
 
     /// Draw the help view
     fn draw_help<B: Backend>(&self, f: &mut Frame<B>, size: Rect) {
-        let help_text = "Keyboard Shortcuts:\n\nNavigation:\n  ↑/k - Move up\n  ↓/j - Move down\n  Enter - View project details\n\nActions:\n  / - Search projects\n  s - Cycle sort mode (Path/Type/Date)\n  r - Refresh/scan again\n  o - Open in editor\n  t - Open in terminal\n  e - Show scan errors (if any)\n  ? - Show this help\n  q/Esc - Quit or go back\n\nSearch:\n  Type to search by path or project type\n  Fuzzy matching is supported\n  Press Enter or Esc to exit search";
+        let help_text = "Keyboard Shortcuts:\n\nNavigation:\n  ↑/k - Move up\n  ↓/j - Move down\n  Enter - View project details\n\nActions:\n  / - Search projects\n  s - Cycle sort mode (Path/Type/Date/Size)\n  w - Collapse/expand workspace members\n  r - Refresh/scan again\n  o - Open in editor\n  t - Open in terminal\n  e - Show scan errors (if any)\n  ? - Show this help\n  q/Esc - Quit or go back\n\nSearch:\n  Type to search by path, project type or package name\n  Fuzzy matching is supported\n  Press Enter or Esc to exit search\n\nProjects marked (new) were not found by the previous scan of their root";
 
         let help =
             Paragraph::new(help_text).block(Block::default().borders(Borders::ALL).title("Help"));
@@ -828,7 +1039,8 @@ impl App {
         self.sort_mode = match self.sort_mode {
             SortMode::Path => SortMode::Type,
             SortMode::Type => SortMode::Date,
-            SortMode::Date => SortMode::Path,
+            SortMode::Date => SortMode::Size,
+            SortMode::Size => SortMode::Path,
         };
     }
 
@@ -849,6 +1061,25 @@ impl App {
     }
 }
 
+/// Projects found by the latest scan of each root but not by the scan before
+fn projects_new_since_previous_scan(
+    db: &dprojc_db::ProjectDatabase,
+) -> dprojc_db::Result<HashSet<PathBuf>> {
+    let mut roots = HashSet::new();
+    let mut new_projects = HashSet::new();
+    for scan in db.get_recent_scan_results(100)? {
+        if !roots.insert(scan.root_path.clone()) {
+            continue;
+        }
+        if let Some((from, to)) = db.get_latest_scan_pair(Some(&scan.root_path))? {
+            if let Some(diff) = db.diff_scans(from, to)? {
+                new_projects.extend(diff.added.into_iter().map(|project| project.path));
+            }
+        }
+    }
+    Ok(new_projects)
+}
+
 /// Background task that handles scanning operations
 async fn scan_task(
     mut command_rx: mpsc::UnboundedReceiver<ScanCommand>,
@@ -870,7 +1101,13 @@ async fn scan_task(
                 for (i, path) in scan_paths.iter().enumerate() {
                     let _ = result_tx.send(ScanResultMessage::Progress(i, total_paths));
 
-                    match scanner.scan(path).await {
+                    let control = ScanControl::new().with_progress({
+                        let result_tx = result_tx.clone();
+                        move |progress: &ScanProgress| {
+                            let _ = result_tx.send(ScanResultMessage::Walking(progress.clone()));
+                        }
+                    });
+                    match scanner.scan_with_control(path, &control).await {
                         Ok(result) => results.push(result),
                         Err(err) => {
                             let _ = result_tx.send(ScanResultMessage::Error(format!(
@@ -953,6 +1190,7 @@ mod tests {
             project_type,
             indicators: vec![ProjectIndicator::GitDirectory],
             last_scanned: Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -988,8 +1226,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
         app.update_filtered_projects();
 
@@ -1018,8 +1261,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
         app.update_filtered_projects();
 
@@ -1047,8 +1295,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
         app.update_filtered_projects();
 
@@ -1180,6 +1433,7 @@ mod tests {
             errors: vec![],
             dirs_scanned: 10,
             scan_duration_ms: 100,
+            ..Default::default()
         };
 
         app.handle_scan_result(ScanResultMessage::Success(vec![scan_result]));
@@ -1218,6 +1472,7 @@ mod tests {
             errors: vec![scan_error],
             dirs_scanned: 10,
             scan_duration_ms: 100,
+            ..Default::default()
         };
 
         app.handle_scan_result(ScanResultMessage::Success(vec![scan_result]));
@@ -1324,6 +1579,28 @@ mod tests {
         assert_eq!(app.projects[1].project_type, ProjectType::NodeJs);
     }
 
+    #[test]
+    fn test_sort_projects_by_custom_type_priority() {
+        let mut app = App::new();
+        app.project_types = vec![dprojc_types::ProjectTypeDefinition {
+            name: "terraform".to_string(),
+            indicators: vec!["*.tf".to_string()],
+            rules: Vec::new(),
+            priority: 20,
+        }];
+        let terraform = ProjectType::Custom("terraform".to_string());
+        app.projects = vec![
+            create_test_project("/path/rust", ProjectType::Rust),
+            create_test_project("/path/infra", terraform.clone()),
+        ];
+
+        app.sort_mode = SortMode::Type;
+        app.sort_projects();
+
+        assert_eq!(app.projects[0].project_type, terraform);
+        assert_eq!(app.projects[1].project_type, ProjectType::Rust);
+    }
+
     #[test]
     fn test_sort_projects_by_date() {
         let mut app = App::new();
@@ -1346,6 +1623,77 @@ mod tests {
         assert_eq!(app.projects[1].project_type, ProjectType::Rust);
     }
 
+    #[test]
+    fn test_sort_projects_by_size() {
+        let mut app = App::new();
+        let measured = |path, total_bytes| {
+            let mut project = create_test_project(path, ProjectType::Rust);
+            project.disk_usage = Some(dprojc_types::DiskUsage {
+                total_bytes,
+                ..Default::default()
+            });
+            project
+        };
+        app.projects = vec![
+            create_test_project("/path/unmeasured", ProjectType::Rust),
+            measured("/path/small", 10),
+            measured("/path/large", 1_000),
+        ];
+
+        app.sort_mode = SortMode::Size;
+        app.sort_projects();
+
+        let paths: Vec<_> = app.projects.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/path/large"),
+                PathBuf::from("/path/small"),
+                PathBuf::from("/path/unmeasured"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_workspace_collapse() {
+        let mut app = App::new();
+        let mut member = create_test_project("/test/mono/crates/core", ProjectType::Rust);
+        member.parent = Some(PathBuf::from("/test/mono"));
+        app.projects = vec![
+            create_test_project("/test/mono", ProjectType::Rust),
+            create_test_project("/test/mono-docs", ProjectType::NodeJs),
+            member,
+        ];
+        app.state = AppState::Browsing;
+        app.update_filtered_projects();
+
+        // Members follow their workspace
+        let paths: Vec<_> = app
+            .filtered_projects
+            .iter()
+            .map(|p| p.path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/test/mono"),
+                PathBuf::from("/test/mono/crates/core"),
+                PathBuf::from("/test/mono-docs"),
+            ]
+        );
+
+        app.handle_project_list_key(crossterm::event::KeyCode::Char('w'));
+        assert_eq!(app.filtered_projects.len(), 2);
+        assert_eq!(
+            app.collapsed_members.get(&PathBuf::from("/test/mono")),
+            Some(&1)
+        );
+
+        app.handle_project_list_key(crossterm::event::KeyCode::Char('w'));
+        assert_eq!(app.filtered_projects.len(), 3);
+        assert!(app.collapsed_members.is_empty());
+    }
+
     #[test]
     fn test_cycle_sort_mode() {
         let mut app = App::new();
@@ -1356,6 +1704,8 @@ mod tests {
         app.cycle_sort_mode();
         assert_eq!(app.sort_mode, SortMode::Date);
         app.cycle_sort_mode();
+        assert_eq!(app.sort_mode, SortMode::Size);
+        app.cycle_sort_mode();
         assert_eq!(app.sort_mode, SortMode::Path);
     }
 
@@ -1379,8 +1729,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
         app.update_filtered_projects();
 
@@ -1408,8 +1763,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
         app.update_filtered_projects();
 
@@ -1473,6 +1833,19 @@ mod tests {
 
         app.handle_scan_result(ScanResultMessage::Progress(2, 10));
         assert_eq!(app.scan_progress, Some((3, 10))); // Note: +1 in implementation
+
+        let walk = ScanProgress {
+            current_path: PathBuf::from("/home/user/src/api"),
+            dirs_scanned: 120,
+            projects_found: 4,
+            errors: 0,
+        };
+        app.handle_scan_result(ScanResultMessage::Walking(walk.clone()));
+        assert_eq!(app.scan_walk, Some(walk));
+
+        // The next root starts without the previous root's counts
+        app.handle_scan_result(ScanResultMessage::Progress(3, 10));
+        assert_eq!(app.scan_walk, None);
     }
 
     #[test]
@@ -1501,6 +1874,33 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_draw_project_list_marks_new_projects() {
+        let mut app = App::new();
+        app.projects = vec![
+            create_test_project("/test/old", ProjectType::Rust),
+            create_test_project("/test/fresh", ProjectType::Go),
+        ];
+        app.new_projects.insert(PathBuf::from("/test/fresh"));
+        app.update_filtered_projects();
+
+        let backend = ratatui::backend::TestBackend::new(100, 24);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| app.draw_project_list(f, f.size()))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect();
+        assert!(screen.contains("1 new since the previous scan"));
+        assert!(screen.contains("Go (new)"));
+        assert!(!screen.contains("Rust (new)"));
+    }
+
     #[test]
     fn test_draw_errors_empty() {
         let app = App::new();
@@ -1664,8 +2064,13 @@ mod tests {
             scan_errors: Vec::new(),
             config: TuiConfig::default(),
             scan_progress: None,
+            scan_walk: None,
             sort_mode: SortMode::Path,
             selected_path: None,
+            project_types: Vec::new(),
+            collapse_workspaces: false,
+            collapsed_members: HashMap::new(),
+            new_projects: HashSet::new(),
         };
 
         app.filtered_projects = app.projects.clone();
@@ -1707,6 +2112,8 @@ mod tests {
         app.handle_project_list_key(crossterm::event::KeyCode::Char('s'));
         assert_eq!(app.sort_mode, SortMode::Date);
         app.handle_project_list_key(crossterm::event::KeyCode::Char('s'));
+        assert_eq!(app.sort_mode, SortMode::Size);
+        app.handle_project_list_key(crossterm::event::KeyCode::Char('s'));
         assert_eq!(app.sort_mode, SortMode::Path);
 
         // Test quit
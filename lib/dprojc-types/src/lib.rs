@@ -2,20 +2,863 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Represents a discovered software project
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Project {
     /// Absolute path to the project directory
     pub path: PathBuf,
     /// Type of project based on detected indicators
     pub project_type: ProjectType,
+    /// Other ecosystems detected in the project, highest priority first
+    #[serde(default)]
+    pub ecosystems: Vec<ProjectType>,
+    /// Workspace or monorepo root that declares this project as a member
+    #[serde(default)]
+    pub parent: Option<PathBuf>,
+    /// State of the git repository, when the project is one
+    #[serde(default)]
+    pub git: Option<GitMetadata>,
+    /// Package metadata declared in the project's manifest
+    #[serde(default)]
+    pub manifest: Option<ProjectManifest>,
+    /// Packages resolved in the project's lockfiles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<Dependency>,
+    /// Runtime and tool versions the project pins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toolchains: Vec<ToolchainPin>,
+    /// CI pipelines, container definitions and task runners configured in the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub automation: Vec<AutomationConfig>,
+    /// Licenses declared in the manifest or found in license files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<LicenseDetection>,
+    /// Space taken on disk, when measured during the scan
+    #[serde(default)]
+    pub disk_usage: Option<DiskUsage>,
+    /// Lines of code per language, when counted during a deep scan
+    #[serde(default)]
+    pub code_stats: Option<CodeStats>,
     /// List of indicators found in this project
     pub indicators: Vec<ProjectIndicator>,
     /// Timestamp when the project was last scanned
     pub last_scanned: chrono::DateTime<chrono::Utc>,
 }
 
-/// Types of projects that can be detected
+impl Project {
+    /// Whether the project belongs to an ecosystem, as its primary type or a secondary one
+    pub fn has_ecosystem(&self, project_type: &ProjectType) -> bool {
+        self.project_type == *project_type || self.ecosystems.contains(project_type)
+    }
+
+    /// SPDX expression the project is licensed under, when it could be identified
+    ///
+    /// A valid expression declared in the manifest wins. Otherwise the licenses
+    /// identified in license files are combined with `AND`, since every file
+    /// found applies unless the project says otherwise.
+    pub fn license(&self) -> Option<String> {
+        if let Some(declared) = self
+            .licenses
+            .iter()
+            .find(|license| license.declared.is_some() && license.spdx.is_some())
+        {
+            return declared.spdx.clone();
+        }
+        let mut identified: Vec<&str> = self
+            .licenses
+            .iter()
+            .filter_map(|license| license.spdx.as_deref())
+            .collect();
+        identified.sort();
+        identified.dedup();
+        (!identified.is_empty()).then(|| identified.join(" AND "))
+    }
+
+    /// Declared package name, falling back to the directory name
+    pub fn name(&self) -> String {
+        self.manifest
+            .as_ref()
+            .and_then(|manifest| manifest.name.clone())
+            .or_else(|| {
+                self.path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Package metadata read from a project manifest such as `Cargo.toml` or `package.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectManifest {
+    /// File the metadata was read from, relative to the project directory
+    pub source: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// Author names, with any email address kept as written
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// License name or SPDX expression
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+}
+
+impl ProjectManifest {
+    /// Whether any field besides the source was found
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.version.is_none()
+            && self.description.is_none()
+            && self.authors.is_empty()
+            && self.license.is_none()
+            && self.homepage.is_none()
+    }
+
+    /// Name and version for display, e.g. `widget 1.2.0`
+    pub fn label(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        Some(match &self.version {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        })
+    }
+
+    /// Whether the name, description, authors or license contain `query`, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [&self.name, &self.description, &self.license]
+            .into_iter()
+            .flatten()
+            .chain(&self.authors)
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// A runtime or tool version pinned by a file in the project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ToolchainPin {
+    /// Canonical tool name, e.g. `node`, `python` or `rust`
+    pub tool: String,
+    /// Version, channel or range as written, e.g. `18.17.0`, `stable` or `>=16`
+    pub version: String,
+    /// File the pin was read from, relative to the project directory
+    pub source: String,
+}
+
+impl ToolchainPin {
+    /// Canonical name of a tool, folding the aliases used by version managers
+    ///
+    /// # Examples
+    /// ```
+    /// use dprojc_types::ToolchainPin;
+    ///
+    /// assert_eq!(ToolchainPin::canonical_tool("nodejs"), "node");
+    /// assert_eq!(ToolchainPin::canonical_tool("Golang"), "go");
+    /// assert_eq!(ToolchainPin::canonical_tool("deno"), "deno");
+    /// ```
+    pub fn canonical_tool(name: &str) -> String {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "nodejs" | "javascript" => "node".to_string(),
+            "golang" => "go".to_string(),
+            "python3" => "python".to_string(),
+            "rustc" => "rust".to_string(),
+            _ => name,
+        }
+    }
+
+    /// Whether the pin matches a `tool` or `tool@version` filter
+    ///
+    /// A version matches whole leading components, so `node@16` matches pins of
+    /// `16`, `16.20.1`, `v16` and `>=16` but not `160`.
+    ///
+    /// # Examples
+    /// ```
+    /// use dprojc_types::ToolchainPin;
+    ///
+    /// let pin = ToolchainPin {
+    ///     tool: "node".to_string(),
+    ///     version: "v16.20.1".to_string(),
+    ///     source: ".nvmrc".to_string(),
+    /// };
+    /// assert!(pin.matches("nodejs"));
+    /// assert!(pin.matches("node@16"));
+    /// assert!(pin.matches("node@16.20"));
+    /// assert!(!pin.matches("node@18"));
+    /// ```
+    pub fn matches(&self, filter: &str) -> bool {
+        let (tool, version) = match filter.split_once('@') {
+            Some((tool, version)) => (tool, Some(version.trim())),
+            None => (filter, None),
+        };
+        if self.tool != Self::canonical_tool(tool) {
+            return false;
+        }
+        let Some(version) = version.filter(|version| !version.is_empty()) else {
+            return true;
+        };
+        let pinned = self
+            .version
+            .trim_start_matches(|c: char| "<>=^~ v".contains(c));
+        pinned
+            .strip_prefix(version)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '-', '+', ' ', ',']))
+    }
+}
+
+/// A license found in a project's manifest or license files
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LicenseDetection {
+    /// Manifest or license file the license was read from, relative to the project
+    pub source: String,
+    /// Value of the manifest's license field as written; `None` for license files
+    #[serde(default)]
+    pub declared: Option<String>,
+    /// SPDX identifier or expression; `None` when the license was not recognized
+    pub spdx: Option<String>,
+    /// How closely a license file matches the best known license text, in
+    /// percent; 100 for recognized manifest values and SPDX tags
+    pub confidence: u8,
+}
+
+/// Kind of automation a configuration file sets up
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutomationKind {
+    /// A workflow in `.github/workflows`
+    GithubActions,
+    /// `.gitlab-ci.yml`
+    GitlabCi,
+    /// `.circleci/config.yml`
+    #[serde(rename = "circleci")]
+    CircleCi,
+    /// `Jenkinsfile`
+    Jenkins,
+    /// `Dockerfile`, `Containerfile` or a variant such as `Dockerfile.dev`
+    Dockerfile,
+    /// `compose.yaml` or `docker-compose.yml`
+    DockerCompose,
+    /// A development container definition, `.devcontainer/devcontainer.json`
+    Devcontainer,
+    /// `Makefile`
+    Makefile,
+    /// `justfile`
+    Justfile,
+}
+
+/// Broad purpose of an [`AutomationKind`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AutomationCategory {
+    /// Continuous integration and delivery pipelines
+    Ci,
+    /// Container images and environments
+    Container,
+    /// Task runners for local commands
+    Tasks,
+}
+
+impl AutomationKind {
+    pub const ALL: [AutomationKind; 9] = [
+        AutomationKind::GithubActions,
+        AutomationKind::GitlabCi,
+        AutomationKind::CircleCi,
+        AutomationKind::Jenkins,
+        AutomationKind::Dockerfile,
+        AutomationKind::DockerCompose,
+        AutomationKind::Devcontainer,
+        AutomationKind::Makefile,
+        AutomationKind::Justfile,
+    ];
+
+    /// Stable lowercase identifier, also used for storage
+    pub fn id(&self) -> &'static str {
+        match self {
+            AutomationKind::GithubActions => "github-actions",
+            AutomationKind::GitlabCi => "gitlab-ci",
+            AutomationKind::CircleCi => "circleci",
+            AutomationKind::Jenkins => "jenkins",
+            AutomationKind::Dockerfile => "dockerfile",
+            AutomationKind::DockerCompose => "docker-compose",
+            AutomationKind::Devcontainer => "devcontainer",
+            AutomationKind::Makefile => "makefile",
+            AutomationKind::Justfile => "justfile",
+        }
+    }
+
+    /// Name for display, e.g. `GitHub Actions`
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutomationKind::GithubActions => "GitHub Actions",
+            AutomationKind::GitlabCi => "GitLab CI",
+            AutomationKind::CircleCi => "CircleCI",
+            AutomationKind::Jenkins => "Jenkins",
+            AutomationKind::Dockerfile => "Dockerfile",
+            AutomationKind::DockerCompose => "Docker Compose",
+            AutomationKind::Devcontainer => "Dev Container",
+            AutomationKind::Makefile => "Makefile",
+            AutomationKind::Justfile => "justfile",
+        }
+    }
+
+    /// Parse an identifier or a common alias such as `github`, `docker` or `make`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "github-actions" | "github" | "actions" | "gha" => Some(AutomationKind::GithubActions),
+            "gitlab-ci" | "gitlab" => Some(AutomationKind::GitlabCi),
+            "circleci" | "circle" => Some(AutomationKind::CircleCi),
+            "jenkins" | "jenkinsfile" => Some(AutomationKind::Jenkins),
+            "dockerfile" | "docker" | "containerfile" => Some(AutomationKind::Dockerfile),
+            "docker-compose" | "compose" => Some(AutomationKind::DockerCompose),
+            "devcontainer" => Some(AutomationKind::Devcontainer),
+            "makefile" | "make" => Some(AutomationKind::Makefile),
+            "justfile" | "just" => Some(AutomationKind::Justfile),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> AutomationCategory {
+        match self {
+            AutomationKind::GithubActions
+            | AutomationKind::GitlabCi
+            | AutomationKind::CircleCi
+            | AutomationKind::Jenkins => AutomationCategory::Ci,
+            AutomationKind::Dockerfile
+            | AutomationKind::DockerCompose
+            | AutomationKind::Devcontainer => AutomationCategory::Container,
+            AutomationKind::Makefile | AutomationKind::Justfile => AutomationCategory::Tasks,
+        }
+    }
+}
+
+impl std::fmt::Display for AutomationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A CI, container or task runner configuration file found in a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutomationConfig {
+    pub kind: AutomationKind,
+    /// The configuration file, relative to the project directory
+    pub path: String,
+    /// Named units the file defines: CI jobs or stages, Dockerfile build
+    /// stages, compose services, or make and just targets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+}
+
+/// A kind or category of automation to filter projects by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomationFilter {
+    Kind(AutomationKind),
+    Category(AutomationCategory),
+}
+
+impl AutomationFilter {
+    /// Parse a category (`ci`, `container`, `tasks`) or any [`AutomationKind`] name
+    ///
+    /// # Examples
+    /// ```
+    /// use dprojc_types::{AutomationCategory, AutomationFilter, AutomationKind};
+    ///
+    /// assert_eq!(
+    ///     AutomationFilter::from_name("ci"),
+    ///     Some(AutomationFilter::Category(AutomationCategory::Ci))
+    /// );
+    /// assert_eq!(
+    ///     AutomationFilter::from_name("docker"),
+    ///     Some(AutomationFilter::Kind(AutomationKind::Dockerfile))
+    /// );
+    /// assert_eq!(AutomationFilter::from_name("travis"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ci" | "cd" | "ci-cd" => Some(AutomationFilter::Category(AutomationCategory::Ci)),
+            "container" | "containers" => {
+                Some(AutomationFilter::Category(AutomationCategory::Container))
+            }
+            "tasks" | "task-runner" => Some(AutomationFilter::Category(AutomationCategory::Tasks)),
+            other => AutomationKind::from_name(other).map(AutomationFilter::Kind),
+        }
+    }
+
+    /// Whether a project has any automation of this kind or category
+    pub fn matches(&self, project: &Project) -> bool {
+        project.automation.iter().any(|config| match self {
+            AutomationFilter::Kind(kind) => config.kind == *kind,
+            AutomationFilter::Category(category) => config.kind.category() == *category,
+        })
+    }
+}
+
+/// Number of projects pinning a tool to one version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolchainCount {
+    pub tool: String,
+    pub version: String,
+    pub projects: usize,
+}
+
+/// State of a git repository captured during a scan
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitMetadata {
+    /// Checked-out branch; `None` when HEAD is detached
+    pub branch: Option<String>,
+    /// Full hash of the HEAD commit; `None` before the first commit
+    pub head_commit: Option<String>,
+    /// Commit time of HEAD
+    pub head_date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Full hash of the first commit on HEAD's first-parent line, shared by
+    /// every clone of the repository
+    #[serde(default)]
+    pub root_commit: Option<String>,
+    /// Configured remotes, in the order git lists them
+    pub remotes: Vec<GitRemote>,
+    /// Upstream of the checked-out branch, e.g. `origin/main`
+    pub upstream: Option<String>,
+    /// Commits on the branch that are not on its upstream
+    pub ahead: usize,
+    /// Commits on the upstream that are not on the branch
+    pub behind: usize,
+    /// Tracked files with staged or unstaged changes
+    pub dirty_count: usize,
+    /// Untracked files; an untracked directory counts once
+    pub untracked_count: usize,
+    /// Number of stash entries
+    pub stash_count: usize,
+    /// Whether the directory is a linked worktree of another repository
+    pub is_worktree: bool,
+    /// Whether the directory is a submodule checkout
+    pub is_submodule: bool,
+}
+
+impl GitMetadata {
+    /// Whether the working tree has uncommitted or untracked changes
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_count > 0 || self.untracked_count > 0
+    }
+
+    /// First 7 characters of the HEAD commit hash
+    pub fn short_commit(&self) -> Option<&str> {
+        self.head_commit
+            .as_deref()
+            .map(|commit| &commit[..commit.len().min(7)])
+    }
+
+    /// Branch and short commit, e.g. `main@1a2b3c4`
+    ///
+    /// A detached HEAD shows as `(detached)` and an unborn branch has no commit.
+    pub fn head_label(&self) -> String {
+        let branch = self.branch.as_deref().unwrap_or("(detached)");
+        match self.short_commit() {
+            Some(commit) => format!("{}@{}", branch, commit),
+            None => branch.to_string(),
+        }
+    }
+
+    /// Short description of the working tree, e.g. `2 dirty, ↑1 ↓3, 1 stash`
+    pub fn status_summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.dirty_count > 0 {
+            parts.push(format!("{} dirty", self.dirty_count));
+        }
+        if self.untracked_count > 0 {
+            parts.push(format!("{} untracked", self.untracked_count));
+        }
+        if self.ahead > 0 || self.behind > 0 {
+            parts.push(format!("↑{} ↓{}", self.ahead, self.behind));
+        }
+        if self.stash_count > 0 {
+            let noun = if self.stash_count == 1 {
+                "stash"
+            } else {
+                "stashes"
+            };
+            parts.push(format!("{} {}", self.stash_count, noun));
+        }
+        if parts.is_empty() {
+            "clean".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Space used by a project on disk, by apparent file size
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Every file under the project, including version control data
+    pub total_bytes: u64,
+    /// Files outside build artifact directories and `.git`
+    pub source_bytes: u64,
+    /// Build output and installed dependencies, which can be regenerated
+    pub artifact_bytes: u64,
+    /// Artifact directories found, relative to the project, largest first
+    #[serde(default)]
+    pub artifact_dirs: Vec<ArtifactDir>,
+    /// When the project was measured, which may be before its last scan
+    pub measured_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Lines of source code in a project, per language
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CodeStats {
+    /// One entry per language found, most code first
+    #[serde(default)]
+    pub languages: Vec<LanguageStats>,
+    /// When the lines were counted, which may be before the project's last scan
+    pub measured_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CodeStats {
+    /// Files and lines summed over every language
+    pub fn total(&self) -> LanguageStats {
+        let mut total = LanguageStats {
+            language: "Total".to_string(),
+            ..LanguageStats::default()
+        };
+        for language in &self.languages {
+            total.add(language);
+        }
+        total
+    }
+
+    /// The language with the most lines of code, ignoring prose and data
+    /// formats that hold no code lines
+    pub fn primary_language(&self) -> Option<&str> {
+        self.languages
+            .iter()
+            .filter(|language| language.code > 0)
+            .max_by(|a, b| {
+                a.code
+                    .cmp(&b.code)
+                    .then_with(|| b.language.cmp(&a.language))
+            })
+            .map(|language| language.language.as_str())
+    }
+}
+
+/// Files and lines of one language
+///
+/// Every line is exactly one of code, comment or blank; a line holding both
+/// code and a comment counts as code.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: u64,
+    pub code: u64,
+    pub comments: u64,
+    pub blanks: u64,
+}
+
+impl LanguageStats {
+    /// All lines, whatever they hold
+    pub fn lines(&self) -> u64 {
+        self.code + self.comments + self.blanks
+    }
+
+    /// Add another count to this one, keeping this language name
+    pub fn add(&mut self, other: &LanguageStats) {
+        self.files += other.files;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+    }
+}
+
+/// Lines of one language across the catalog
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LanguageCount {
+    /// Projects containing at least one file of the language
+    pub projects: usize,
+    #[serde(flatten)]
+    pub stats: LanguageStats,
+}
+
+/// A build artifact or dependency directory inside a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArtifactDir {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// Package registry a dependency is resolved from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyEcosystem {
+    /// crates.io, from `Cargo.lock`
+    Cargo,
+    /// The npm registry, from `package-lock.json`, `yarn.lock` or `pnpm-lock.yaml`
+    Npm,
+    /// PyPI, from `poetry.lock`
+    PyPI,
+    /// RubyGems, from `Gemfile.lock`
+    RubyGems,
+    /// Go modules, from `go.sum`
+    Go,
+}
+
+impl DependencyEcosystem {
+    pub const ALL: [DependencyEcosystem; 5] = [
+        DependencyEcosystem::Cargo,
+        DependencyEcosystem::Npm,
+        DependencyEcosystem::PyPI,
+        DependencyEcosystem::RubyGems,
+        DependencyEcosystem::Go,
+    ];
+
+    /// Stable lowercase identifier, also used for storage
+    pub fn id(&self) -> &'static str {
+        match self {
+            DependencyEcosystem::Cargo => "cargo",
+            DependencyEcosystem::Npm => "npm",
+            DependencyEcosystem::PyPI => "pypi",
+            DependencyEcosystem::RubyGems => "rubygems",
+            DependencyEcosystem::Go => "go",
+        }
+    }
+
+    /// Parse an identifier or a common alias such as `crates`, `pip` or `gem`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cargo" | "crates" | "crate" | "rust" => Some(DependencyEcosystem::Cargo),
+            "npm" | "node" | "yarn" | "pnpm" => Some(DependencyEcosystem::Npm),
+            "pypi" | "pip" | "python" | "poetry" => Some(DependencyEcosystem::PyPI),
+            "rubygems" | "gem" | "gems" | "ruby" => Some(DependencyEcosystem::RubyGems),
+            "go" | "golang" => Some(DependencyEcosystem::Go),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DependencyEcosystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A package version resolved in a project's lockfile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dependency {
+    pub ecosystem: DependencyEcosystem,
+    pub name: String,
+    pub version: String,
+}
+
+/// A project that resolves a given package
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Dependent {
+    /// Path of the project
+    pub path: PathBuf,
+    /// The package as resolved in that project
+    pub dependency: Dependency,
+}
+
+/// The versions of one package resolved across the catalog
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionSpread {
+    pub ecosystem: DependencyEcosystem,
+    pub name: String,
+    /// Each resolved version with the projects that use it, ordered by version string
+    pub versions: Vec<(String, Vec<PathBuf>)>,
+}
+
+/// A locked dependency matched by a security advisory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditFinding {
+    /// Path of the affected project
+    pub path: PathBuf,
+    /// The vulnerable package as resolved in that project
+    pub dependency: Dependency,
+    /// Advisory identifier, such as `RUSTSEC-2021-0078` or `GHSA-...`
+    pub advisory_id: String,
+    /// Other identifiers of the same advisory, usually CVE numbers
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub url: Option<String>,
+    /// Versions or version requirements that contain the fix; empty when none is released
+    pub fixed_versions: Vec<String>,
+}
+
+/// A path a project was cataloged at before it moved
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectAlias {
+    /// The former path
+    pub path: PathBuf,
+    /// Where the project is cataloged now
+    pub project_path: PathBuf,
+    /// When the scan noticed the move
+    pub moved_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// How recently a project shows signs of work
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Staleness {
+    /// Worked on within the stale threshold
+    Active,
+    /// Idle longer than the stale threshold
+    Stale,
+    /// Idle longer than the abandoned threshold, or never seen in use
+    Abandoned,
+}
+
+impl Staleness {
+    /// Classify a project idle for `idle_days`, or with no observed activity
+    ///
+    /// Uncommitted changes keep a project from counting as abandoned, since
+    /// work that exists nowhere else deserves a look before anything is
+    /// archived or deleted.
+    ///
+    /// # Examples
+    /// ```
+    /// use dprojc_types::Staleness;
+    ///
+    /// assert_eq!(Staleness::classify(Some(10), false, 90, 365), Staleness::Active);
+    /// assert_eq!(Staleness::classify(Some(120), false, 90, 365), Staleness::Stale);
+    /// assert_eq!(Staleness::classify(None, false, 90, 365), Staleness::Abandoned);
+    /// assert_eq!(Staleness::classify(Some(800), true, 90, 365), Staleness::Stale);
+    /// ```
+    pub fn classify(
+        idle_days: Option<i64>,
+        uncommitted_changes: bool,
+        stale_after_days: u32,
+        abandoned_after_days: u32,
+    ) -> Self {
+        let staleness = match idle_days {
+            Some(days) if days < i64::from(stale_after_days) => Staleness::Active,
+            Some(days) if days < i64::from(abandoned_after_days) => Staleness::Stale,
+            _ => Staleness::Abandoned,
+        };
+        if uncommitted_changes {
+            staleness.min(Staleness::Stale)
+        } else {
+            staleness
+        }
+    }
+}
+
+impl std::fmt::Display for Staleness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Staleness::Active => "active",
+            Staleness::Stale => "stale",
+            Staleness::Abandoned => "abandoned",
+        })
+    }
+}
+
+/// Signs of work on a project observed on this machine
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivitySignals {
+    /// Commit time of HEAD as of the last scan
+    pub last_commit: Option<chrono::DateTime<chrono::Utc>>,
+    /// Newest file modification outside version control and build artifacts
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+    /// Last visit recorded through the shell integration
+    pub last_accessed: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the working tree had uncommitted changes as of the last scan
+    pub uncommitted_changes: bool,
+}
+
+impl ActivitySignals {
+    /// The most recent of the commit, modification and access times
+    pub fn last_activity(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        [self.last_commit, self.last_modified, self.last_accessed]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+}
+
+/// A project classified by how long it has been idle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StaleProject {
+    pub path: PathBuf,
+    pub staleness: Staleness,
+    /// Whole days since the last activity; `None` when none was observed
+    pub idle_days: Option<i64>,
+    #[serde(flatten)]
+    pub signals: ActivitySignals,
+}
+
+/// What two copies of a project were found to share
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMatch {
+    /// A remote URL, normalized so SSH and HTTPS forms compare equal
+    RemoteUrl(String),
+    /// The root commit of the repository
+    RootCommit(String),
+    /// Manifest name and version, for projects outside git
+    Manifest { name: String, version: String },
+}
+
+impl std::fmt::Display for DuplicateMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuplicateMatch::RemoteUrl(url) => write!(f, "remote {}", url),
+            DuplicateMatch::RootCommit(commit) => {
+                write!(f, "root commit {}", &commit[..commit.len().min(7)])
+            }
+            DuplicateMatch::Manifest { name, version } => write!(f, "{} {}", name, version),
+        }
+    }
+}
+
+/// One copy of a project that exists in several places
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateCopy {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub head_commit: Option<String>,
+    /// HEAD commit time, or the newest file modification outside git
+    pub updated: Option<chrono::DateTime<chrono::Utc>>,
+    /// Tracked files with staged or unstaged changes, and untracked files
+    pub uncommitted_changes: usize,
+    /// Commits on the branch that are not on its upstream
+    pub unpushed_commits: usize,
+    pub stashes: usize,
+    /// Current frecency score from the shell integration
+    pub frecency: f64,
+    /// Whether this copy was updated most recently in its group
+    pub newest: bool,
+    /// Whether this copy is the one visited most in its group
+    pub most_frecent: bool,
+}
+
+impl DuplicateCopy {
+    /// Whether deleting this copy would lose work that exists nowhere else
+    pub fn has_local_changes(&self) -> bool {
+        self.uncommitted_changes > 0 || self.unpushed_commits > 0 || self.stashes > 0
+    }
+}
+
+/// Copies of the same project found in different places
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateGroup {
+    /// Everything the copies were matched on, sorted
+    pub matched_by: Vec<DuplicateMatch>,
+    /// The copies, ordered by path
+    pub copies: Vec<DuplicateCopy>,
+}
+
+/// A named git remote
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitRemote {
+    pub name: String,
+    pub url: String,
+}
+
+/// Types of projects that can be detected
+///
+/// Serialized as a plain string: the variant name for built-in types and the
+/// registry name for [`ProjectType::Custom`] types, so stored values stay valid
+/// map keys and existing databases keep deserializing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(from = "String", into = "String")]
 pub enum ProjectType {
     Git,
     NodeJs,
@@ -25,21 +868,53 @@ pub enum ProjectType {
     Go,
     Java,
     Nix,
+    Deno,
+    Php,
+    Cpp,
+    DotNet,
+    Gradle,
+    Elixir,
+    Haskell,
+    Swift,
+    Dart,
+    Zig,
+    Terraform,
+    DockerCompose,
+    #[default]
     Unknown,
+    /// A user-defined type from the configured project type registry
+    Custom(String),
 }
 
 impl std::fmt::Display for ProjectType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ProjectType::Git => write!(f, "Git"),
             ProjectType::NodeJs => write!(f, "Node.js"),
-            ProjectType::Ruby => write!(f, "Ruby"),
-            ProjectType::Rust => write!(f, "Rust"),
-            ProjectType::Python => write!(f, "Python"),
-            ProjectType::Go => write!(f, "Go"),
-            ProjectType::Java => write!(f, "Java"),
-            ProjectType::Nix => write!(f, "Nix"),
-            ProjectType::Unknown => write!(f, "Unknown"),
+            ProjectType::Php => write!(f, "PHP"),
+            ProjectType::Cpp => write!(f, "C/C++"),
+            ProjectType::DotNet => write!(f, ".NET"),
+            ProjectType::DockerCompose => write!(f, "Docker Compose"),
+            other => write!(f, "{}", other.id()),
+        }
+    }
+}
+
+impl From<String> for ProjectType {
+    fn from(name: String) -> Self {
+        ProjectType::BUILTIN
+            .iter()
+            .chain([&ProjectType::Unknown])
+            .find(|builtin| builtin.id() == name)
+            .cloned()
+            .unwrap_or(ProjectType::Custom(name))
+    }
+}
+
+impl From<ProjectType> for String {
+    fn from(project_type: ProjectType) -> Self {
+        match project_type {
+            ProjectType::Custom(name) => name,
+            builtin => builtin.id().to_string(),
         }
     }
 }
@@ -57,29 +932,116 @@ pub enum ProjectIndicator {
     GoMod,
     PomXml,
     DevenvNix,
+    SetupPy,
+    FlakeNix,
+    DenoJson,
+    ComposerJson,
+    CMakeLists,
+    MesonBuild,
+    Makefile,
+    DotNetProject,
+    DotNetSolution,
+    GradleBuild,
+    MixExs,
+    StackYaml,
+    Cabal,
+    PackageSwift,
+    PubspecYaml,
+    BuildZig,
+    TerraformConfig,
+    ComposeFile,
     Custom(String),
 }
 
 impl std::fmt::Display for ProjectIndicator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ProjectIndicator::GitDirectory => write!(f, ".git"),
-            ProjectIndicator::PackageJson => write!(f, "package.json"),
-            ProjectIndicator::Gemfile => write!(f, "Gemfile"),
-            ProjectIndicator::Gemspec => write!(f, ".gemspec"),
-            ProjectIndicator::CargoToml => write!(f, "Cargo.toml"),
-            ProjectIndicator::PyprojectToml => write!(f, "pyproject.toml"),
-            ProjectIndicator::RequirementsTxt => write!(f, "requirements.txt"),
-            ProjectIndicator::GoMod => write!(f, "go.mod"),
-            ProjectIndicator::PomXml => write!(f, "pom.xml"),
-            ProjectIndicator::DevenvNix => write!(f, "devenv.nix"),
             ProjectIndicator::Custom(name) => write!(f, "{}", name),
+            builtin => write!(f, "{}", builtin.patterns()[0]),
         }
     }
 }
 
+/// A configurable rule that marks a directory as a project
+///
+/// Matching directories are tagged with `ProjectIndicator::Custom(name)`.
+///
+/// ```yaml
+/// - name: dotnet
+///   glob: "*.csproj"
+/// - name: poetry
+///   file_contains:
+///     file: pyproject.toml
+///     contains: "[tool.poetry]"
+/// - name: ansible-role
+///   directory_shape: [tasks/main.yml, meta/]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IndicatorRule {
+    /// Name recorded as the indicator when the rule matches
+    pub name: String,
+    /// What the directory has to contain
+    #[serde(flatten)]
+    pub kind: IndicatorRuleKind,
+}
+
+/// The condition checked by an [`IndicatorRule`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndicatorRuleKind {
+    /// An entry whose name matches the glob exists in the directory
+    Glob(String),
+    /// A file exists in the directory and its contents include `contains`
+    FileContains { file: String, contains: String },
+    /// Every listed relative path exists; a trailing `/` requires a directory
+    DirectoryShape(Vec<String>),
+}
+
+/// Priority given to a user-defined project type that does not set one
+///
+/// Sits just above [`ProjectType::Git`] so a matching custom type wins over a
+/// bare repository but not over a language ecosystem.
+pub const DEFAULT_CUSTOM_TYPE_PRIORITY: i32 = 2;
+
+fn default_custom_type_priority() -> i32 {
+    DEFAULT_CUSTOM_TYPE_PRIORITY
+}
+
+/// A user-defined project type in the configured type registry
+///
+/// A directory has this type when any of its indicators or rules match and no
+/// other matching type has a higher priority. Built-in types rank from 10
+/// (Rust) down to 1 (Git); a custom type wins ties with a built-in one.
+///
+/// ```yaml
+/// project_types:
+///   - name: terraform
+///     priority: 20
+///     indicators: ["*.tf", .terraform.lock.hcl]
+///   - name: helm
+///     indicators: [Chart.yaml]
+///   - name: ansible-role
+///     rules:
+///       - name: ansible-role
+///         directory_shape: [tasks/main.yml, meta/]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectTypeDefinition {
+    /// Name shown in listings and accepted by `list --project-type`
+    pub name: String,
+    /// Indicator names, globs or relative paths that identify this type
+    #[serde(default)]
+    pub indicators: Vec<String>,
+    /// Indicator rules that identify this type
+    #[serde(default)]
+    pub rules: Vec<IndicatorRule>,
+    /// Precedence over other matching types (higher wins)
+    #[serde(default = "default_custom_type_priority")]
+    pub priority: i32,
+}
+
 /// Result of a directory scan
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScanResult {
     /// Root path that was scanned
     pub root_path: PathBuf,
@@ -93,6 +1055,23 @@ pub struct ScanResult {
     pub dirs_scanned: usize,
     /// Scan duration in milliseconds
     pub scan_duration_ms: u64,
+    /// Whether the scan was cancelled before it finished, leaving the
+    /// results partial
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// How far a scan in progress has come
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanProgress {
+    /// Directory the scan visited most recently
+    pub current_path: PathBuf,
+    /// Directories visited so far
+    pub dirs_scanned: usize,
+    /// Projects found so far
+    pub projects_found: usize,
+    /// Errors encountered so far
+    pub errors: usize,
 }
 
 /// Errors that can occur during scanning
@@ -135,17 +1114,85 @@ impl std::fmt::Display for ScanErrorType {
     }
 }
 
+/// A directory a scan walked, remembered so a rescan can tell whether its
+/// entries changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryState {
+    pub path: PathBuf,
+    /// Modification time in nanoseconds since the Unix epoch; `None` when it
+    /// could not be read or was too close to the scan to be trusted
+    pub mtime_ns: Option<i64>,
+    /// Pruned by the exclude patterns, so nothing below it was walked
+    pub excluded: bool,
+}
+
+/// The directories below a root as its last incremental scan saw them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryCache {
+    /// The scan settings the walk depended on; a cache recorded with other
+    /// settings is not reused
+    pub fingerprint: String,
+    pub directories: Vec<DirectoryState>,
+}
+
+/// Result of a rescan that only listed directories whose entries changed
+#[derive(Debug, Clone)]
+pub struct IncrementalScanResult {
+    /// Every project below the root
+    pub scan: ScanResult,
+    /// Cataloged projects below the root that no longer exist
+    pub removed: Vec<PathBuf>,
+    /// Directories whose entries had not changed, so only those holding a
+    /// project were listed again
+    pub unchanged_dirs: usize,
+    /// The directories as this scan saw them, for the next rescan
+    pub cache: DirectoryCache,
+}
+
 /// Configuration for the scanner
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScanConfig {
     /// Maximum depth to scan
     pub max_depth: Option<usize>,
     /// Patterns to exclude from scanning
     pub exclude_patterns: Vec<String>,
-    /// Additional project indicators to check
+    /// Additional project indicators to check (names, globs or relative paths)
     pub project_indicators: Vec<String>,
+    /// Glob, file-contents and directory-shape indicator rules
+    pub indicator_rules: Vec<IndicatorRule>,
+    /// User-defined project types, checked alongside the built-in ones
+    pub project_types: Vec<ProjectTypeDefinition>,
     /// Whether to follow symbolic links
     pub follow_symlinks: bool,
+    /// Whether to honor `.gitignore`, `.ignore`, `.git/info/exclude` and the
+    /// global git excludes file while walking
+    pub respect_gitignore: bool,
+    /// Whether to honor catalog-specific `.dprojcignore` files while walking
+    pub respect_dprojcignore: bool,
+    /// Number of walker threads used within a single scan root (0 = one per CPU)
+    pub threads: usize,
+    /// Whether to read branch, commit and working tree state from git repositories
+    pub git_metadata: bool,
+    /// Whether to measure total, source and build artifact sizes of each project
+    pub disk_usage: bool,
+    /// Whether to count lines of code per language in each project
+    pub code_stats: bool,
+    /// Local clone of a security advisory database used by `audit`
+    pub advisory_db: Option<PathBuf>,
+    /// Days without activity after which `stale` reports a project as stale
+    pub stale_after_days: u32,
+    /// Days without activity after which `stale` reports a project as abandoned
+    pub abandoned_after_days: u32,
+    /// Roots `watch` follows when none are given on the command line
+    pub watch_roots: Vec<PathBuf>,
+    /// Quiet time in milliseconds `watch` waits for before applying changes
+    pub watch_debounce_ms: u64,
+    /// Most directories `watch` registers with the file system; roots that
+    /// do not fit are rescanned periodically instead
+    pub max_watches: usize,
+    /// Minutes between rescans of roots `watch` cannot follow through events
+    pub rescan_interval_minutes: u32,
 }
 
 impl Default for ScanConfig {
@@ -161,68 +1208,359 @@ impl Default for ScanConfig {
                 "build".to_string(),
                 "dist".to_string(),
             ],
-            project_indicators: vec![
-                ".git".to_string(),
-                "package.json".to_string(),
-                "Gemfile".to_string(),
-                ".gemspec".to_string(),
-                "Cargo.toml".to_string(),
-                "pyproject.toml".to_string(),
-                "requirements.txt".to_string(),
-                "go.mod".to_string(),
-                "pom.xml".to_string(),
-                "devenv.nix".to_string(),
-            ],
+            project_indicators: ProjectIndicator::builtin_patterns(),
+            indicator_rules: Vec::new(),
+            project_types: Vec::new(),
             follow_symlinks: false,
+            respect_gitignore: true,
+            respect_dprojcignore: true,
+            threads: 0,
+            git_metadata: true,
+            disk_usage: false,
+            code_stats: false,
+            advisory_db: None,
+            stale_after_days: 90,
+            abandoned_after_days: 365,
+            watch_roots: Vec::new(),
+            watch_debounce_ms: 500,
+            max_watches: 8192,
+            rescan_interval_minutes: 60,
         }
     }
 }
 
+impl ScanConfig {
+    /// Describe the settings that decide which directories a scan walks and
+    /// which of them are projects
+    ///
+    /// No other setting takes part, so changing one keeps directory caches
+    /// valid.
+    pub fn walk_fingerprint(&self) -> String {
+        #[derive(Serialize)]
+        struct WalkSettings<'a> {
+            max_depth: Option<usize>,
+            exclude_patterns: &'a [String],
+            project_indicators: &'a [String],
+            indicator_rules: &'a [IndicatorRule],
+            project_types: &'a [ProjectTypeDefinition],
+            follow_symlinks: bool,
+            respect_gitignore: bool,
+            respect_dprojcignore: bool,
+        }
+        serde_json::to_string(&WalkSettings {
+            max_depth: self.max_depth,
+            exclude_patterns: &self.exclude_patterns,
+            project_indicators: &self.project_indicators,
+            indicator_rules: &self.indicator_rules,
+            project_types: &self.project_types,
+            follow_symlinks: self.follow_symlinks,
+            respect_gitignore: self.respect_gitignore,
+            respect_dprojcignore: self.respect_dprojcignore,
+        })
+        .unwrap_or_default()
+    }
+}
+
 impl ProjectType {
-    /// Determine project type from indicators
-    pub fn from_indicators(indicators: &[ProjectIndicator]) -> Self {
-        if indicators.contains(&ProjectIndicator::CargoToml) {
-            ProjectType::Rust
-        } else if indicators.contains(&ProjectIndicator::PackageJson) {
-            ProjectType::NodeJs
-        } else if indicators.contains(&ProjectIndicator::Gemfile)
-            || indicators.contains(&ProjectIndicator::Gemspec)
-        {
+    /// Built-in types in detection precedence order
+    ///
+    /// This table is the single source of built-in detection: the scanner, the
+    /// docs generator and the default indicator list all derive from it. The
+    /// first type whose indicators are present becomes a project's primary type.
+    /// New ecosystems go after the existing language ones, so a directory with
+    /// files of both keeps its type; a bare repository and development
+    /// environments come last.
+    pub const BUILTIN: &'static [ProjectType] = &[
+        ProjectType::Rust,
+        ProjectType::NodeJs,
+        ProjectType::Ruby,
+        ProjectType::Python,
+        ProjectType::Go,
+        ProjectType::Java,
+        ProjectType::Deno,
+        ProjectType::Gradle,
+        ProjectType::DotNet,
+        ProjectType::Php,
+        ProjectType::Elixir,
+        ProjectType::Haskell,
+        ProjectType::Swift,
+        ProjectType::Dart,
+        ProjectType::Zig,
+        ProjectType::Cpp,
+        ProjectType::Terraform,
+        ProjectType::DockerCompose,
+        ProjectType::Git,
+        ProjectType::Nix,
+    ];
+
+    /// Stable identifier used when serializing
+    fn id(&self) -> &str {
+        match self {
+            ProjectType::Git => "Git",
+            ProjectType::NodeJs => "NodeJs",
+            ProjectType::Ruby => "Ruby",
+            ProjectType::Rust => "Rust",
+            ProjectType::Python => "Python",
+            ProjectType::Go => "Go",
+            ProjectType::Java => "Java",
+            ProjectType::Nix => "Nix",
+            ProjectType::Deno => "Deno",
+            ProjectType::Php => "Php",
+            ProjectType::Cpp => "Cpp",
+            ProjectType::DotNet => "DotNet",
+            ProjectType::Gradle => "Gradle",
+            ProjectType::Elixir => "Elixir",
+            ProjectType::Haskell => "Haskell",
+            ProjectType::Swift => "Swift",
+            ProjectType::Dart => "Dart",
+            ProjectType::Zig => "Zig",
+            ProjectType::Terraform => "Terraform",
+            ProjectType::DockerCompose => "DockerCompose",
+            ProjectType::Unknown => "Unknown",
+            ProjectType::Custom(name) => name,
+        }
+    }
+
+    /// Indicators that identify a built-in type
+    pub fn indicators(&self) -> &'static [ProjectIndicator] {
+        match self {
+            ProjectType::Git => &[ProjectIndicator::GitDirectory],
+            ProjectType::NodeJs => &[ProjectIndicator::PackageJson],
+            ProjectType::Ruby => &[ProjectIndicator::Gemfile, ProjectIndicator::Gemspec],
+            ProjectType::Rust => &[ProjectIndicator::CargoToml],
+            ProjectType::Python => &[
+                ProjectIndicator::PyprojectToml,
+                ProjectIndicator::RequirementsTxt,
+                ProjectIndicator::SetupPy,
+            ],
+            ProjectType::Go => &[ProjectIndicator::GoMod],
+            ProjectType::Java => &[ProjectIndicator::PomXml],
+            ProjectType::Nix => &[ProjectIndicator::DevenvNix, ProjectIndicator::FlakeNix],
+            ProjectType::Deno => &[ProjectIndicator::DenoJson],
+            ProjectType::Php => &[ProjectIndicator::ComposerJson],
+            ProjectType::Cpp => &[
+                ProjectIndicator::CMakeLists,
+                ProjectIndicator::MesonBuild,
+                ProjectIndicator::Makefile,
+            ],
+            ProjectType::DotNet => &[
+                ProjectIndicator::DotNetProject,
+                ProjectIndicator::DotNetSolution,
+            ],
+            ProjectType::Gradle => &[ProjectIndicator::GradleBuild],
+            ProjectType::Elixir => &[ProjectIndicator::MixExs],
+            ProjectType::Haskell => &[ProjectIndicator::StackYaml, ProjectIndicator::Cabal],
+            ProjectType::Swift => &[ProjectIndicator::PackageSwift],
+            ProjectType::Dart => &[ProjectIndicator::PubspecYaml],
+            ProjectType::Zig => &[ProjectIndicator::BuildZig],
+            ProjectType::Terraform => &[ProjectIndicator::TerraformConfig],
+            ProjectType::DockerCompose => &[ProjectIndicator::ComposeFile],
+            ProjectType::Unknown | ProjectType::Custom(_) => &[],
+        }
+    }
+
+    /// Language label used in generated documentation
+    pub fn language(&self) -> Option<&'static str> {
+        match self {
+            ProjectType::NodeJs => Some("JavaScript/TypeScript"),
+            ProjectType::Deno => Some("TypeScript"),
+            ProjectType::Ruby => Some("Ruby"),
+            ProjectType::Rust => Some("Rust"),
+            ProjectType::Python => Some("Python"),
+            ProjectType::Go => Some("Go"),
+            ProjectType::Java => Some("Java"),
+            ProjectType::Gradle => Some("Java/Kotlin"),
+            ProjectType::DotNet => Some("C#/F#"),
+            ProjectType::Php => Some("PHP"),
+            ProjectType::Elixir => Some("Elixir"),
+            ProjectType::Haskell => Some("Haskell"),
+            ProjectType::Swift => Some("Swift"),
+            ProjectType::Dart => Some("Dart"),
+            ProjectType::Zig => Some("Zig"),
+            ProjectType::Cpp => Some("C/C++"),
+            ProjectType::Terraform => Some("HCL"),
+            ProjectType::Nix => Some("Nix"),
+            ProjectType::DockerCompose
+            | ProjectType::Git
+            | ProjectType::Unknown
+            | ProjectType::Custom(_) => None,
+        }
+    }
+
+    /// Ranking used when sorting by type and when weighing user-defined types
+    /// (higher numbers = higher priority)
+    pub fn builtin_priority(&self) -> i32 {
+        match self {
+            ProjectType::Rust => 10,
+            ProjectType::NodeJs | ProjectType::Deno => 9,
+            ProjectType::Python => 8,
+            ProjectType::Go => 7,
+            ProjectType::Java | ProjectType::Gradle | ProjectType::DotNet => 6,
             ProjectType::Ruby
-        } else if indicators.contains(&ProjectIndicator::PyprojectToml)
-            || indicators.contains(&ProjectIndicator::RequirementsTxt)
-        {
-            ProjectType::Python
-        } else if indicators.contains(&ProjectIndicator::GoMod) {
-            ProjectType::Go
-        } else if indicators.contains(&ProjectIndicator::PomXml) {
-            ProjectType::Java
-        } else if indicators.contains(&ProjectIndicator::GitDirectory) {
-            ProjectType::Git
-        } else if indicators.contains(&ProjectIndicator::DevenvNix) {
-            ProjectType::Nix
-        } else {
-            ProjectType::Unknown
+            | ProjectType::Php
+            | ProjectType::Elixir
+            | ProjectType::Haskell
+            | ProjectType::Swift
+            | ProjectType::Dart
+            | ProjectType::Zig => 5,
+            ProjectType::Cpp | ProjectType::Nix => 4,
+            ProjectType::Terraform | ProjectType::DockerCompose => 3,
+            ProjectType::Custom(_) => DEFAULT_CUSTOM_TYPE_PRIORITY,
+            ProjectType::Git => 1,
+            ProjectType::Unknown => 0,
         }
     }
+
+    /// Look up a built-in type by a case-insensitive name as typed on the command line
+    pub fn from_builtin_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        let alias = match name.as_str() {
+            "node" => Some(ProjectType::NodeJs),
+            "c" | "c++" | "cpp" => Some(ProjectType::Cpp),
+            "dotnet" | "csharp" | "c#" => Some(ProjectType::DotNet),
+            "kotlin" => Some(ProjectType::Gradle),
+            "flutter" => Some(ProjectType::Dart),
+            "compose" | "docker-compose" => Some(ProjectType::DockerCompose),
+            _ => None,
+        };
+        alias.or_else(|| {
+            ProjectType::BUILTIN
+                .iter()
+                .chain([&ProjectType::Unknown])
+                .find(|builtin| {
+                    builtin.id().to_lowercase() == name
+                        || builtin.to_string().to_lowercase() == name
+                })
+                .cloned()
+        })
+    }
+
+    /// Every built-in type whose indicators are present, in precedence order
+    pub fn builtins_from_indicators(
+        indicators: &[ProjectIndicator],
+    ) -> impl Iterator<Item = &'static ProjectType> + '_ {
+        ProjectType::BUILTIN
+            .iter()
+            .filter(|builtin| builtin.indicators().iter().any(|i| indicators.contains(i)))
+    }
+
+    /// Every built-in ecosystem present in the indicators, in precedence order
+    ///
+    /// A `.git` directory alone is not an ecosystem, so [`ProjectType::Git`]
+    /// never appears here.
+    pub fn ecosystems_from_indicators(indicators: &[ProjectIndicator]) -> Vec<Self> {
+        ProjectType::builtins_from_indicators(indicators)
+            .filter(|builtin| **builtin != ProjectType::Git)
+            .cloned()
+            .collect()
+    }
+
+    /// Determine project type from indicators
+    pub fn from_indicators(indicators: &[ProjectIndicator]) -> Self {
+        ProjectType::builtins_from_indicators(indicators)
+            .next()
+            .cloned()
+            .unwrap_or(ProjectType::Unknown)
+    }
 }
 
 impl ProjectIndicator {
-    /// Convert a file/directory name to an indicator
+    /// Every built-in indicator, in the order used for the default indicator list
+    pub const BUILTIN: &'static [ProjectIndicator] = &[
+        ProjectIndicator::GitDirectory,
+        ProjectIndicator::PackageJson,
+        ProjectIndicator::Gemfile,
+        ProjectIndicator::Gemspec,
+        ProjectIndicator::CargoToml,
+        ProjectIndicator::PyprojectToml,
+        ProjectIndicator::RequirementsTxt,
+        ProjectIndicator::GoMod,
+        ProjectIndicator::PomXml,
+        ProjectIndicator::DevenvNix,
+        ProjectIndicator::SetupPy,
+        ProjectIndicator::FlakeNix,
+        ProjectIndicator::DenoJson,
+        ProjectIndicator::ComposerJson,
+        ProjectIndicator::CMakeLists,
+        ProjectIndicator::MesonBuild,
+        ProjectIndicator::Makefile,
+        ProjectIndicator::DotNetProject,
+        ProjectIndicator::DotNetSolution,
+        ProjectIndicator::GradleBuild,
+        ProjectIndicator::MixExs,
+        ProjectIndicator::StackYaml,
+        ProjectIndicator::Cabal,
+        ProjectIndicator::PackageSwift,
+        ProjectIndicator::PubspecYaml,
+        ProjectIndicator::BuildZig,
+        ProjectIndicator::TerraformConfig,
+        ProjectIndicator::ComposeFile,
+    ];
+
+    /// Names or globs that produce this indicator; the first one is canonical
+    pub fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            ProjectIndicator::GitDirectory => &[".git"],
+            ProjectIndicator::PackageJson => &["package.json"],
+            ProjectIndicator::Gemfile => &["Gemfile"],
+            ProjectIndicator::Gemspec => &["*.gemspec"],
+            ProjectIndicator::CargoToml => &["Cargo.toml"],
+            ProjectIndicator::PyprojectToml => &["pyproject.toml"],
+            ProjectIndicator::RequirementsTxt => &["requirements.txt"],
+            ProjectIndicator::GoMod => &["go.mod"],
+            ProjectIndicator::PomXml => &["pom.xml"],
+            ProjectIndicator::DevenvNix => &["devenv.nix"],
+            ProjectIndicator::SetupPy => &["setup.py"],
+            ProjectIndicator::FlakeNix => &["flake.nix"],
+            ProjectIndicator::DenoJson => &["deno.json", "deno.jsonc"],
+            ProjectIndicator::ComposerJson => &["composer.json"],
+            ProjectIndicator::CMakeLists => &["CMakeLists.txt"],
+            ProjectIndicator::MesonBuild => &["meson.build"],
+            ProjectIndicator::Makefile => &["Makefile", "makefile", "GNUmakefile"],
+            ProjectIndicator::DotNetProject => &["*.csproj", "*.fsproj", "*.vbproj"],
+            ProjectIndicator::DotNetSolution => &["*.sln"],
+            ProjectIndicator::GradleBuild => &[
+                "build.gradle",
+                "build.gradle.kts",
+                "settings.gradle",
+                "settings.gradle.kts",
+            ],
+            ProjectIndicator::MixExs => &["mix.exs"],
+            ProjectIndicator::StackYaml => &["stack.yaml"],
+            ProjectIndicator::Cabal => &["*.cabal", "cabal.project"],
+            ProjectIndicator::PackageSwift => &["Package.swift"],
+            ProjectIndicator::PubspecYaml => &["pubspec.yaml"],
+            ProjectIndicator::BuildZig => &["build.zig"],
+            ProjectIndicator::TerraformConfig => &["*.tf", ".terraform.lock.hcl"],
+            ProjectIndicator::ComposeFile => &[
+                "compose.yaml",
+                "compose.yml",
+                "docker-compose.yaml",
+                "docker-compose.yml",
+            ],
+            ProjectIndicator::Custom(_) => &[],
+        }
+    }
+
+    /// Every pattern of every built-in indicator, used as the default indicator list
+    pub fn builtin_patterns() -> Vec<String> {
+        ProjectIndicator::BUILTIN
+            .iter()
+            .flat_map(|indicator| indicator.patterns())
+            .map(|pattern| pattern.to_string())
+            .collect()
+    }
+
+    /// Convert a file/directory name or indicator pattern to an indicator
     pub fn from_path_name(name: &str) -> Option<Self> {
-        match name {
-            ".git" => Some(ProjectIndicator::GitDirectory),
-            "package.json" => Some(ProjectIndicator::PackageJson),
-            "Gemfile" => Some(ProjectIndicator::Gemfile),
-            ".gemspec" => Some(ProjectIndicator::Gemspec),
-            "Cargo.toml" => Some(ProjectIndicator::CargoToml),
-            "pyproject.toml" => Some(ProjectIndicator::PyprojectToml),
-            "requirements.txt" => Some(ProjectIndicator::RequirementsTxt),
-            "go.mod" => Some(ProjectIndicator::GoMod),
-            "pom.xml" => Some(ProjectIndicator::PomXml),
-            "devenv.nix" => Some(ProjectIndicator::DevenvNix),
-            _ => None,
+        // ".gemspec" is the legacy spelling of the "*.gemspec" glob
+        if name == ".gemspec" {
+            return Some(ProjectIndicator::Gemspec);
         }
+        ProjectIndicator::BUILTIN
+            .iter()
+            .find(|indicator| indicator.patterns().contains(&name))
+            .cloned()
     }
 }
 
@@ -238,6 +1576,116 @@ pub struct ScanResultSummary {
     pub excluded_dirs_count: usize,
 }
 
+/// A project as one scan recorded it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScannedProject {
+    pub path: PathBuf,
+    pub project_type: ProjectType,
+    pub indicators: Vec<ProjectIndicator>,
+}
+
+/// A project found by both scans whose type or indicators differ
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectChange {
+    pub path: PathBuf,
+    pub old_type: ProjectType,
+    pub new_type: ProjectType,
+    /// Indicators only the later scan found
+    pub added_indicators: Vec<ProjectIndicator>,
+    /// Indicators only the earlier scan found
+    pub removed_indicators: Vec<ProjectIndicator>,
+}
+
+/// Differences between the projects recorded by two scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDiff {
+    pub from: ScanResultSummary,
+    pub to: ScanResultSummary,
+    /// Projects only the later scan found
+    pub added: Vec<ScannedProject>,
+    /// Projects only the earlier scan found
+    pub removed: Vec<ScannedProject>,
+    pub changed: Vec<ProjectChange>,
+}
+
+impl ScanDiff {
+    /// Compare the projects recorded by two scans, matched by path
+    pub fn between(
+        from: ScanResultSummary,
+        to: ScanResultSummary,
+        before: Vec<ScannedProject>,
+        after: Vec<ScannedProject>,
+    ) -> Self {
+        let mut before: std::collections::HashMap<PathBuf, ScannedProject> = before
+            .into_iter()
+            .map(|project| (project.path.clone(), project))
+            .collect();
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for project in after {
+            let Some(old) = before.remove(&project.path) else {
+                added.push(project);
+                continue;
+            };
+            let added_indicators: Vec<_> = project
+                .indicators
+                .iter()
+                .filter(|indicator| !old.indicators.contains(indicator))
+                .cloned()
+                .collect();
+            let removed_indicators: Vec<_> = old
+                .indicators
+                .iter()
+                .filter(|indicator| !project.indicators.contains(indicator))
+                .cloned()
+                .collect();
+            if old.project_type != project.project_type
+                || !added_indicators.is_empty()
+                || !removed_indicators.is_empty()
+            {
+                changed.push(ProjectChange {
+                    path: project.path,
+                    old_type: old.project_type,
+                    new_type: project.project_type,
+                    added_indicators,
+                    removed_indicators,
+                });
+            }
+        }
+        let mut removed: Vec<_> = before.into_values().collect();
+        removed.sort_by(|a, b| a.path.cmp(&b.path));
+        added.sort_by(|a, b| a.path.cmp(&b.path));
+        changed.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            from,
+            to,
+            added,
+            removed,
+            changed,
+        }
+    }
+
+    /// Whether both scans recorded the same projects
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A change `watch` made to the catalog
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogChange {
+    /// A project appeared
+    Added(PathBuf),
+    /// A cataloged project was detected again after its files changed
+    Updated(PathBuf),
+    /// A project disappeared
+    Removed(PathBuf),
+    /// A root was scanned in full, finding this many projects
+    Rescanned { root: PathBuf, projects: usize },
+}
+
 /// Scan statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanStatistics {
@@ -260,7 +1708,18 @@ pub struct ReportData {
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct StatsData {
     pub statistics: ScanStatistics,
+    /// Projects per primary type
     pub project_counts: std::collections::HashMap<ProjectType, usize>,
+    /// Projects containing each ecosystem, as primary type or secondary
+    #[serde(default)]
+    pub ecosystem_counts: std::collections::HashMap<ProjectType, usize>,
+    /// Projects per pinned tool version, ordered by tool and version
+    #[serde(default)]
+    pub toolchain_counts: Vec<ToolchainCount>,
+    /// Lines of code per language over projects counted in a deep scan, most
+    /// code first
+    #[serde(default)]
+    pub language_counts: Vec<LanguageCount>,
 }
 
 #[cfg(test)]
@@ -363,6 +1822,10 @@ mod tests {
             ProjectIndicator::from_path_name(".gemspec"),
             Some(ProjectIndicator::Gemspec)
         );
+        assert_eq!(
+            ProjectIndicator::from_path_name("*.gemspec"),
+            Some(ProjectIndicator::Gemspec)
+        );
         assert_eq!(
             ProjectIndicator::from_path_name("Cargo.toml"),
             Some(ProjectIndicator::CargoToml)
@@ -415,7 +1878,7 @@ mod tests {
             .project_indicators
             .contains(&"package.json".to_string()));
         assert!(config.project_indicators.contains(&"Gemfile".to_string()));
-        assert!(config.project_indicators.contains(&".gemspec".to_string()));
+        assert!(config.project_indicators.contains(&"*.gemspec".to_string()));
         assert!(config
             .project_indicators
             .contains(&"Cargo.toml".to_string()));
@@ -430,7 +1893,14 @@ mod tests {
         assert!(config
             .project_indicators
             .contains(&"requirements.txt".to_string()));
-        assert_eq!(config.project_indicators.len(), 10);
+        assert!(config.project_indicators.contains(&"*.csproj".to_string()));
+        assert!(config
+            .project_indicators
+            .contains(&"docker-compose.yml".to_string()));
+        assert_eq!(
+            config.project_indicators,
+            ProjectIndicator::builtin_patterns()
+        );
 
         assert!(!config.follow_symlinks);
     }
@@ -442,6 +1912,7 @@ mod tests {
             project_type: ProjectType::Rust,
             indicators: vec![ProjectIndicator::CargoToml, ProjectIndicator::GitDirectory],
             last_scanned: chrono::Utc::now(),
+            ..Default::default()
         };
 
         let serialized = serde_json::to_string(&project).unwrap();
@@ -466,6 +1937,7 @@ mod tests {
             }],
             dirs_scanned: 42,
             scan_duration_ms: 1000,
+            ..Default::default()
         };
 
         let serialized = serde_json::to_string(&scan_result).unwrap();
@@ -494,7 +1966,33 @@ mod tests {
             max_depth: Some(5),
             exclude_patterns: vec!["test".to_string()],
             project_indicators: vec!["indicator".to_string()],
+            indicator_rules: vec![IndicatorRule {
+                name: "poetry".to_string(),
+                kind: IndicatorRuleKind::FileContains {
+                    file: "pyproject.toml".to_string(),
+                    contains: "[tool.poetry]".to_string(),
+                },
+            }],
+            project_types: vec![ProjectTypeDefinition {
+                name: "helm".to_string(),
+                indicators: vec!["Chart.yaml".to_string()],
+                rules: Vec::new(),
+                priority: 15,
+            }],
             follow_symlinks: true,
+            respect_gitignore: false,
+            respect_dprojcignore: false,
+            threads: 4,
+            git_metadata: false,
+            disk_usage: true,
+            code_stats: true,
+            advisory_db: Some(PathBuf::from("/srv/advisory-db")),
+            stale_after_days: 30,
+            abandoned_after_days: 180,
+            watch_roots: vec![PathBuf::from("/src")],
+            watch_debounce_ms: 250,
+            max_watches: 1000,
+            rescan_interval_minutes: 15,
         };
 
         let serialized = serde_json::to_string(&config).unwrap();
@@ -503,7 +2001,122 @@ mod tests {
         assert_eq!(deserialized.max_depth, config.max_depth);
         assert_eq!(deserialized.exclude_patterns, config.exclude_patterns);
         assert_eq!(deserialized.project_indicators, config.project_indicators);
+        assert_eq!(deserialized.indicator_rules, config.indicator_rules);
+        assert_eq!(deserialized.project_types, config.project_types);
         assert_eq!(deserialized.follow_symlinks, config.follow_symlinks);
+        assert_eq!(deserialized.respect_gitignore, config.respect_gitignore);
+        assert_eq!(
+            deserialized.respect_dprojcignore,
+            config.respect_dprojcignore
+        );
+        assert_eq!(deserialized.threads, config.threads);
+        assert_eq!(deserialized.git_metadata, config.git_metadata);
+        assert_eq!(deserialized.disk_usage, config.disk_usage);
+        assert_eq!(deserialized.code_stats, config.code_stats);
+        assert_eq!(deserialized.advisory_db, config.advisory_db);
+        assert_eq!(deserialized.stale_after_days, config.stale_after_days);
+        assert_eq!(
+            deserialized.abandoned_after_days,
+            config.abandoned_after_days
+        );
+        assert_eq!(deserialized.watch_roots, config.watch_roots);
+        assert_eq!(deserialized.watch_debounce_ms, config.watch_debounce_ms);
+        assert_eq!(deserialized.max_watches, config.max_watches);
+        assert_eq!(
+            deserialized.rescan_interval_minutes,
+            config.rescan_interval_minutes
+        );
+
+        // Fields missing from older serialized configs fall back to defaults
+        let legacy: ScanConfig = serde_json::from_str(r#"{"max_depth": 3}"#).unwrap();
+        assert_eq!(legacy.max_depth, Some(3));
+        assert!(legacy.respect_gitignore);
+        assert!(legacy.respect_dprojcignore);
+        assert!(legacy.git_metadata);
+        assert!(!legacy.disk_usage);
+        assert!(!legacy.code_stats);
+        assert_eq!(legacy.advisory_db, None);
+        assert_eq!(legacy.stale_after_days, 90);
+        assert_eq!(legacy.abandoned_after_days, 365);
+        assert!(legacy.watch_roots.is_empty());
+        assert_eq!(legacy.watch_debounce_ms, 500);
+        assert_eq!(legacy.max_watches, 8192);
+        assert_eq!(legacy.rescan_interval_minutes, 60);
+    }
+
+    #[test]
+    fn test_indicator_rule_format() {
+        let rules: Vec<IndicatorRule> = serde_json::from_str(
+            r#"[
+                {"name": "dotnet", "glob": "*.csproj"},
+                {"name": "poetry", "file_contains": {"file": "pyproject.toml", "contains": "[tool.poetry]"}},
+                {"name": "ansible-role", "directory_shape": ["tasks/main.yml", "meta/"]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(rules[0].name, "dotnet");
+        assert_eq!(
+            rules[0].kind,
+            IndicatorRuleKind::Glob("*.csproj".to_string())
+        );
+        assert_eq!(
+            rules[1].kind,
+            IndicatorRuleKind::FileContains {
+                file: "pyproject.toml".to_string(),
+                contains: "[tool.poetry]".to_string(),
+            }
+        );
+        assert_eq!(
+            rules[2].kind,
+            IndicatorRuleKind::DirectoryShape(vec![
+                "tasks/main.yml".to_string(),
+                "meta/".to_string()
+            ])
+        );
+
+        let round_trip: Vec<IndicatorRule> =
+            serde_json::from_str(&serde_json::to_string(&rules).unwrap()).unwrap();
+        assert_eq!(round_trip, rules);
+    }
+
+    #[test]
+    fn test_project_type_string_format() {
+        // Built-in types keep the variant names already stored in databases
+        assert_eq!(
+            serde_json::to_string(&ProjectType::NodeJs).unwrap(),
+            "\"NodeJs\""
+        );
+        let node: ProjectType = serde_json::from_str("\"NodeJs\"").unwrap();
+        assert_eq!(node, ProjectType::NodeJs);
+
+        let terraform = ProjectType::Custom("terraform".to_string());
+        assert_eq!(serde_json::to_string(&terraform).unwrap(), "\"terraform\"");
+        let parsed: ProjectType = serde_json::from_str("\"terraform\"").unwrap();
+        assert_eq!(parsed, terraform);
+        assert_eq!(terraform.to_string(), "terraform");
+
+        // Custom types work as map keys, e.g. in project counts
+        let mut counts = std::collections::HashMap::new();
+        counts.insert(terraform.clone(), 2usize);
+        let json = serde_json::to_string(&counts).unwrap();
+        assert_eq!(json, r#"{"terraform":2}"#);
+
+        assert_eq!(
+            ProjectType::from_builtin_name("Node"),
+            Some(ProjectType::NodeJs)
+        );
+        assert_eq!(ProjectType::from_builtin_name("helm"), None);
+    }
+
+    #[test]
+    fn test_project_type_definition_format() {
+        let definition: ProjectTypeDefinition =
+            serde_json::from_str(r#"{"name": "helm", "indicators": ["Chart.yaml"]}"#).unwrap();
+        assert_eq!(definition.name, "helm");
+        assert_eq!(definition.indicators, vec!["Chart.yaml"]);
+        assert!(definition.rules.is_empty());
+        assert_eq!(definition.priority, DEFAULT_CUSTOM_TYPE_PRIORITY);
     }
 
     #[test]
@@ -560,6 +2173,7 @@ mod tests {
             exclude_patterns: vec![],
             project_indicators: vec![],
             follow_symlinks: false,
+            ..Default::default()
         };
         assert_eq!(config.max_depth, None);
 
@@ -641,7 +2255,236 @@ mod tests {
             ProjectType::Java,
             ProjectType::Nix,
             ProjectType::Unknown,
+            ProjectType::Custom("terraform".to_string()),
+        ];
+        assert_eq!(types.len(), 10);
+
+        // Every built-in type is detectable and round-trips through its name
+        for builtin in ProjectType::BUILTIN {
+            assert!(!builtin.indicators().is_empty(), "{builtin:?}");
+            assert_eq!(ProjectType::from(String::from(builtin.clone())), *builtin);
+            assert_eq!(
+                ProjectType::from_builtin_name(&builtin.to_string()),
+                Some(builtin.clone())
+            );
+        }
+        for indicator in ProjectIndicator::BUILTIN {
+            assert!(ProjectType::BUILTIN
+                .iter()
+                .any(|builtin| builtin.indicators().contains(indicator)));
+            for pattern in indicator.patterns() {
+                assert_eq!(
+                    ProjectIndicator::from_path_name(pattern),
+                    Some(indicator.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_dependency_ecosystem_names() {
+        for ecosystem in DependencyEcosystem::ALL {
+            assert_eq!(
+                DependencyEcosystem::from_name(ecosystem.id()),
+                Some(ecosystem)
+            );
+            let json = serde_json::to_string(&ecosystem).unwrap();
+            assert_eq!(json, format!("\"{}\"", ecosystem.id()));
+        }
+        assert_eq!(
+            DependencyEcosystem::from_name("Crates"),
+            Some(DependencyEcosystem::Cargo)
+        );
+        assert_eq!(
+            DependencyEcosystem::from_name("yarn"),
+            Some(DependencyEcosystem::Npm)
+        );
+        assert_eq!(DependencyEcosystem::from_name("maven"), None);
+    }
+
+    #[test]
+    fn test_git_metadata_display() {
+        let mut git = GitMetadata {
+            branch: Some("main".to_string()),
+            head_commit: Some("1a2b3c4d5e6f".to_string()),
+            ..GitMetadata::default()
+        };
+        assert_eq!(git.head_label(), "main@1a2b3c4");
+        assert_eq!(git.status_summary(), "clean");
+        assert!(!git.is_dirty());
+
+        git.branch = None;
+        git.dirty_count = 2;
+        git.behind = 3;
+        git.stash_count = 2;
+        assert_eq!(git.head_label(), "(detached)@1a2b3c4");
+        assert_eq!(git.status_summary(), "2 dirty, ↑0 ↓3, 2 stashes");
+        assert!(git.is_dirty());
+    }
+
+    #[test]
+    fn test_walk_fingerprint() {
+        let config = ScanConfig::default();
+        let threaded = ScanConfig {
+            threads: 8,
+            stale_after_days: 30,
+            git_metadata: false,
+            code_stats: true,
+            ..ScanConfig::default()
+        };
+        assert_eq!(config.walk_fingerprint(), threaded.walk_fingerprint());
+
+        let deeper = ScanConfig {
+            max_depth: Some(20),
+            ..ScanConfig::default()
+        };
+        assert_ne!(config.walk_fingerprint(), deeper.walk_fingerprint());
+    }
+
+    #[test]
+    fn test_scan_diff() {
+        let summary = |id| ScanResultSummary {
+            id,
+            scan_timestamp: chrono::Utc::now(),
+            root_path: PathBuf::from("/src"),
+            dirs_scanned: 10,
+            scan_duration_ms: 5,
+            error_count: 0,
+            excluded_dirs_count: 0,
+        };
+        let scanned = |path: &str, project_type, indicators: &[ProjectIndicator]| ScannedProject {
+            path: PathBuf::from(path),
+            project_type,
+            indicators: indicators.to_vec(),
+        };
+        let before = vec![
+            scanned(
+                "/src/app",
+                ProjectType::NodeJs,
+                &[ProjectIndicator::PackageJson],
+            ),
+            scanned("/src/old", ProjectType::Go, &[ProjectIndicator::GoMod]),
+            scanned(
+                "/src/same",
+                ProjectType::Rust,
+                &[ProjectIndicator::CargoToml],
+            ),
+        ];
+        let after = vec![
+            scanned(
+                "/src/same",
+                ProjectType::Rust,
+                &[ProjectIndicator::CargoToml],
+            ),
+            scanned(
+                "/src/app",
+                ProjectType::Rust,
+                &[ProjectIndicator::CargoToml, ProjectIndicator::GitDirectory],
+            ),
+            scanned(
+                "/src/new",
+                ProjectType::Python,
+                &[ProjectIndicator::PyprojectToml],
+            ),
         ];
-        assert_eq!(types.len(), 9);
+
+        let diff = ScanDiff::between(summary(1), summary(2), before, after);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, PathBuf::from("/src/new"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, PathBuf::from("/src/old"));
+        assert_eq!(
+            diff.changed,
+            vec![ProjectChange {
+                path: PathBuf::from("/src/app"),
+                old_type: ProjectType::NodeJs,
+                new_type: ProjectType::Rust,
+                added_indicators: vec![ProjectIndicator::CargoToml, ProjectIndicator::GitDirectory],
+                removed_indicators: vec![ProjectIndicator::PackageJson],
+            }]
+        );
+
+        let same = vec![scanned("/src/same", ProjectType::Rust, &[])];
+        assert!(ScanDiff::between(summary(1), summary(2), same.clone(), same).is_empty());
+    }
+
+    #[test]
+    fn test_extended_ecosystem_detection() {
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::DotNetProject]),
+            ProjectType::DotNet
+        );
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::GradleBuild]),
+            ProjectType::Gradle
+        );
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::Makefile]),
+            ProjectType::Cpp
+        );
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::SetupPy]),
+            ProjectType::Python
+        );
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::FlakeNix]),
+            ProjectType::Nix
+        );
+
+        // A package.json keeps a project Node.js, as before Deno was detected
+        assert_eq!(
+            ProjectType::from_indicators(&[
+                ProjectIndicator::PackageJson,
+                ProjectIndicator::DenoJson
+            ]),
+            ProjectType::NodeJs
+        );
+        assert_eq!(
+            ProjectType::from_indicators(&[ProjectIndicator::DenoJson]),
+            ProjectType::Deno
+        );
+
+        // Infrastructure files only win when no language ecosystem is present
+        assert_eq!(
+            ProjectType::from_indicators(&[
+                ProjectIndicator::ComposeFile,
+                ProjectIndicator::GitDirectory
+            ]),
+            ProjectType::DockerCompose
+        );
+        assert_eq!(
+            ProjectType::ecosystems_from_indicators(&[
+                ProjectIndicator::ComposeFile,
+                ProjectIndicator::TerraformConfig,
+                ProjectIndicator::GoMod,
+                ProjectIndicator::GitDirectory,
+            ]),
+            vec![
+                ProjectType::Go,
+                ProjectType::Terraform,
+                ProjectType::DockerCompose
+            ]
+        );
+
+        assert_eq!(
+            ProjectIndicator::from_path_name("build.gradle.kts"),
+            Some(ProjectIndicator::GradleBuild)
+        );
+        assert_eq!(
+            ProjectIndicator::from_path_name("*.fsproj"),
+            Some(ProjectIndicator::DotNetProject)
+        );
+        assert_eq!(ProjectIndicator::ComposeFile.to_string(), "compose.yaml");
+        assert_eq!(
+            ProjectType::from_builtin_name("c#"),
+            Some(ProjectType::DotNet)
+        );
+        assert_eq!(
+            ProjectType::from_builtin_name("docker compose"),
+            Some(ProjectType::DockerCompose)
+        );
+        assert_eq!(ProjectType::DotNet.language(), Some("C#/F#"));
+        assert_eq!(ProjectType::DockerCompose.language(), None);
     }
 }
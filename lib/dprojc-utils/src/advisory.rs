@@ -0,0 +1,760 @@
+//! Matching locked dependencies against a local security advisory database
+//!
+//! The database is a directory on disk, so an audit never touches the network.
+//! Two layouts are understood and may share one tree:
+//!
+//! - a clone of the RustSec `advisory-db`, where every `RUSTSEC-*.md` file opens
+//!   with a TOML front matter block (the bare `.toml` advisories of older clones
+//!   are read too)
+//! - OSV JSON records, one per file or an array per file, such as an extracted
+//!   per-ecosystem `all.zip` export
+//!
+//! Withdrawn and informational advisories are skipped, and so are OSV packages
+//! from ecosystems the catalog does not read lockfiles for. Hidden directories,
+//! including `.git`, are not walked.
+
+use dprojc_types::{AuditFinding, Dependency, DependencyEcosystem};
+use ignore::WalkBuilder;
+use semver::{Version, VersionReq};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A security advisory for one package
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub url: Option<String>,
+    pub ecosystem: DependencyEcosystem,
+    pub package: String,
+    affected: Affected,
+}
+
+/// How an advisory describes the affected versions
+#[derive(Debug, Clone)]
+enum Affected {
+    /// RustSec: every version outside the patched and unaffected requirements
+    Requirements {
+        patched: Vec<(String, VersionReq)>,
+        unaffected: Vec<VersionReq>,
+    },
+    /// OSV: introduced/fixed/last_affected event ranges plus explicit versions
+    Ranges {
+        ranges: Vec<Vec<Event>>,
+        versions: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Introduced(String),
+    Fixed(String),
+    LastAffected(String),
+}
+
+impl Event {
+    fn version(&self) -> &str {
+        match self {
+            Event::Introduced(v) | Event::Fixed(v) | Event::LastAffected(v) => v,
+        }
+    }
+}
+
+impl Advisory {
+    /// Whether `version` of the package falls in the affected range
+    pub fn affects(&self, version: &str) -> bool {
+        match &self.affected {
+            Affected::Requirements {
+                patched,
+                unaffected,
+            } => {
+                let Ok(version) = Version::parse(version) else {
+                    return false;
+                };
+                !patched.iter().any(|(_, req)| req.matches(&version))
+                    && !unaffected.iter().any(|req| req.matches(&version))
+            }
+            Affected::Ranges { ranges, versions } => {
+                versions.iter().any(|v| v == version)
+                    || ranges
+                        .iter()
+                        .any(|events| range_affects(self.ecosystem, events, version))
+            }
+        }
+    }
+
+    /// Versions or requirements that contain the fix
+    pub fn fixed_versions(&self) -> Vec<String> {
+        match &self.affected {
+            Affected::Requirements { patched, .. } => {
+                patched.iter().map(|(text, _)| text.clone()).collect()
+            }
+            Affected::Ranges { ranges, .. } => ranges
+                .iter()
+                .flatten()
+                .filter_map(|event| match event {
+                    Event::Fixed(v) => Some(v.clone()),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether two advisories describe the same issue, e.g. a RustSec advisory
+    /// and its OSV export
+    fn same_issue(&self, other: &Advisory) -> bool {
+        let ids = |a: &Advisory| std::iter::once(a.id.clone()).chain(a.aliases.clone());
+        ids(self).any(|id| ids(other).any(|other_id| other_id == id))
+    }
+}
+
+/// Advisories loaded from a local database, keyed by ecosystem and package
+#[derive(Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: HashMap<(DependencyEcosystem, String), Vec<Advisory>>,
+}
+
+impl AdvisoryDatabase {
+    /// Load every advisory found under `path`
+    ///
+    /// Files that are not advisories, or cannot be parsed, are skipped so a
+    /// partly updated clone still loads. When the same advisory appears twice,
+    /// the first file in path order wins.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Advisory database not found: {}",
+                path.display()
+            ));
+        }
+
+        let mut database = Self::default();
+        let walker = WalkBuilder::new(path)
+            .standard_filters(false)
+            .hidden(true)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker.flatten() {
+            let file = entry.path();
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let Ok(contents) = fs::read_to_string(file) else {
+                continue;
+            };
+            let advisories = match file.extension().and_then(|e| e.to_str()) {
+                Some("md") => parse_rustsec_markdown(&contents).into_iter().collect(),
+                Some("toml") => toml::from_str(&contents)
+                    .ok()
+                    .and_then(|value| parse_rustsec(&value, None))
+                    .into_iter()
+                    .collect(),
+                Some("json") => parse_osv_file(&contents),
+                _ => Vec::new(),
+            };
+            for advisory in advisories {
+                database.insert(advisory);
+            }
+        }
+        Ok(database)
+    }
+
+    fn insert(&mut self, advisory: Advisory) {
+        let key = (
+            advisory.ecosystem,
+            package_key(advisory.ecosystem, &advisory.package),
+        );
+        let advisories = self.advisories.entry(key).or_default();
+        if !advisories.iter().any(|a| a.same_issue(&advisory)) {
+            advisories.push(advisory);
+        }
+    }
+
+    /// Number of advisories loaded
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories that affect the resolved version of a dependency
+    pub fn matching(&self, dependency: &Dependency) -> Vec<&Advisory> {
+        let key = (
+            dependency.ecosystem,
+            package_key(dependency.ecosystem, &dependency.name),
+        );
+        self.advisories
+            .get(&key)
+            .map(|advisories| {
+                advisories
+                    .iter()
+                    .filter(|a| a.affects(&dependency.version))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check the dependencies of the project at `path`
+    pub fn audit(&self, path: &Path, dependencies: &[Dependency]) -> Vec<AuditFinding> {
+        dependencies
+            .iter()
+            .flat_map(|dependency| {
+                self.matching(dependency)
+                    .into_iter()
+                    .map(|advisory| AuditFinding {
+                        path: path.to_path_buf(),
+                        dependency: dependency.clone(),
+                        advisory_id: advisory.id.clone(),
+                        aliases: advisory.aliases.clone(),
+                        title: advisory.title.clone(),
+                        url: advisory.url.clone(),
+                        fixed_versions: advisory.fixed_versions(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// PyPI treats case, `-`, `_` and `.` in names as equivalent
+fn package_key(ecosystem: DependencyEcosystem, name: &str) -> String {
+    match ecosystem {
+        DependencyEcosystem::PyPI => name
+            .to_ascii_lowercase()
+            .split(['-', '_', '.'])
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-"),
+        _ => name.to_string(),
+    }
+}
+
+/// Parse a RustSec markdown advisory: a fenced TOML block followed by a
+/// `# Title` heading and the description
+fn parse_rustsec_markdown(contents: &str) -> Option<Advisory> {
+    let rest = contents.trim_start().strip_prefix("```toml")?;
+    let (front_matter, body) = rest.split_once("```")?;
+    let value: toml::Value = toml::from_str(front_matter).ok()?;
+    let title = body
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string());
+    parse_rustsec(&value, title)
+}
+
+fn parse_rustsec(value: &toml::Value, title: Option<String>) -> Option<Advisory> {
+    let advisory = value.get("advisory")?;
+    let text = |table: &toml::Value, key: &str| {
+        table.get(key).and_then(|v| v.as_str()).map(str::to_string)
+    };
+    let strings = |table: Option<&toml::Value>, key: &str| -> Vec<String> {
+        table
+            .and_then(|t| t.get(key))
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    if advisory.get("withdrawn").is_some() || advisory.get("informational").is_some() {
+        return None;
+    }
+
+    // Older clones keep the requirements in the advisory table itself
+    let versions = value.get("versions");
+    let mut patched = strings(versions, "patched");
+    patched.extend(strings(Some(advisory), "patched_versions"));
+    let mut unaffected = strings(versions, "unaffected");
+    unaffected.extend(strings(Some(advisory), "unaffected_versions"));
+
+    let id = text(advisory, "id")?;
+    Some(Advisory {
+        title: title
+            .or_else(|| text(advisory, "title"))
+            .unwrap_or_else(|| id.clone()),
+        id,
+        aliases: strings(Some(advisory), "aliases"),
+        url: text(advisory, "url"),
+        ecosystem: DependencyEcosystem::Cargo,
+        package: text(advisory, "package")?,
+        affected: Affected::Requirements {
+            patched: patched
+                .into_iter()
+                .filter_map(|text| {
+                    let req = VersionReq::parse(&text).ok()?;
+                    Some((text, req))
+                })
+                .collect(),
+            unaffected: unaffected
+                .iter()
+                .filter_map(|text| VersionReq::parse(text).ok())
+                .collect(),
+        },
+    })
+}
+
+/// Parse a file holding one OSV record or an array of them
+fn parse_osv_file(contents: &str) -> Vec<Advisory> {
+    match serde_json::from_str::<Value>(contents) {
+        Ok(Value::Array(records)) => records.iter().flat_map(parse_osv).collect(),
+        Ok(record) => parse_osv(&record),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// One advisory per affected package of an OSV record
+fn parse_osv(record: &Value) -> Vec<Advisory> {
+    let Some(id) = record.get("id").and_then(Value::as_str) else {
+        return Vec::new();
+    };
+    if record.get("withdrawn").is_some_and(|w| !w.is_null()) {
+        return Vec::new();
+    }
+
+    let title = record
+        .get("summary")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            record
+                .get("details")
+                .and_then(Value::as_str)
+                .and_then(|details| details.lines().next())
+        })
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or(id)
+        .trim()
+        .to_string();
+    let aliases: Vec<String> = record
+        .get("aliases")
+        .and_then(Value::as_array)
+        .map(|aliases| {
+            aliases
+                .iter()
+                .filter_map(|a| a.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let references = record.get("references").and_then(Value::as_array);
+    let reference = |kind: Option<&str>| {
+        references?
+            .iter()
+            .find(|r| kind.is_none_or(|kind| r.get("type").and_then(Value::as_str) == Some(kind)))
+            .and_then(|r| r.get("url").and_then(Value::as_str))
+            .map(str::to_string)
+    };
+    let url = reference(Some("ADVISORY")).or_else(|| reference(None));
+
+    let Some(affected) = record.get("affected").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    affected
+        .iter()
+        .filter_map(|entry| {
+            let package = entry.get("package")?;
+            let ecosystem = osv_ecosystem(package.get("ecosystem")?.as_str()?)?;
+            let name = package.get("name")?.as_str()?.to_string();
+            let ranges = entry
+                .get("ranges")
+                .and_then(Value::as_array)
+                .map(|ranges| {
+                    ranges
+                        .iter()
+                        .filter(|r| r.get("type").and_then(Value::as_str) != Some("GIT"))
+                        .map(osv_events)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let versions = entry
+                .get("versions")
+                .and_then(Value::as_array)
+                .map(|versions| {
+                    versions
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(Advisory {
+                id: id.to_string(),
+                aliases: aliases.clone(),
+                title: title.clone(),
+                url: url.clone(),
+                ecosystem,
+                package: name,
+                affected: Affected::Ranges { ranges, versions },
+            })
+        })
+        .collect()
+}
+
+fn osv_events(range: &Value) -> Vec<Event> {
+    range
+        .get("events")
+        .and_then(Value::as_array)
+        .map(|events| {
+            events
+                .iter()
+                .filter_map(|event| {
+                    let field = |key| event.get(key).and_then(Value::as_str).map(str::to_string);
+                    field("introduced")
+                        .map(Event::Introduced)
+                        .or_else(|| field("fixed").map(Event::Fixed))
+                        .or_else(|| field("last_affected").map(Event::LastAffected))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Map an OSV ecosystem name, ignoring any `:release` suffix
+fn osv_ecosystem(name: &str) -> Option<DependencyEcosystem> {
+    match name.split(':').next()? {
+        "crates.io" => Some(DependencyEcosystem::Cargo),
+        "npm" => Some(DependencyEcosystem::Npm),
+        "PyPI" => Some(DependencyEcosystem::PyPI),
+        "RubyGems" => Some(DependencyEcosystem::RubyGems),
+        "Go" => Some(DependencyEcosystem::Go),
+        _ => None,
+    }
+}
+
+/// Evaluate one OSV range following the reference algorithm: walk the events
+/// in version order, entering the range at `introduced` and leaving it at
+/// `fixed` or after `last_affected`
+fn range_affects(ecosystem: DependencyEcosystem, events: &[Event], version: &str) -> bool {
+    let mut events: Vec<&Event> = events.iter().collect();
+    events.sort_by(|a, b| match (a.version(), b.version()) {
+        ("0", "0") => Ordering::Equal,
+        ("0", _) => Ordering::Less,
+        (_, "0") => Ordering::Greater,
+        (a, b) => compare_versions(ecosystem, a, b),
+    });
+
+    let mut affected = false;
+    for event in events {
+        match event {
+            Event::Introduced(v) => {
+                if v == "0" || compare_versions(ecosystem, version, v) != Ordering::Less {
+                    affected = true;
+                }
+            }
+            Event::Fixed(v) => {
+                if compare_versions(ecosystem, version, v) != Ordering::Less {
+                    affected = false;
+                }
+            }
+            Event::LastAffected(v) => {
+                if compare_versions(ecosystem, version, v) == Ordering::Greater {
+                    affected = false;
+                }
+            }
+        }
+    }
+    affected
+}
+
+/// Compare two versions, as semver where both parse and loosely otherwise
+///
+/// Go module versions carry a `v` prefix in `go.sum` but not in OSV records.
+fn compare_versions(ecosystem: DependencyEcosystem, a: &str, b: &str) -> Ordering {
+    let (a, b) = match ecosystem {
+        DependencyEcosystem::Go => (a.trim_start_matches('v'), b.trim_start_matches('v')),
+        _ => (a, b),
+    };
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => compare_loose(a, b),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part<'a> {
+    Number(u64),
+    Text(&'a str),
+}
+
+/// Split a version into runs of digits and letters, dropping separators
+fn version_parts(version: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = version.trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+    while let Some(first) = rest.chars().next() {
+        let digits = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() || c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let run = &rest[..end];
+        parts.push(match run.parse() {
+            Ok(n) if digits => Part::Number(n),
+            _ => Part::Text(run),
+        });
+        rest = rest[end..].trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+    }
+    parts
+}
+
+/// Component-wise comparison for versions that are not semver, such as PyPI
+/// and RubyGems ones
+///
+/// Missing components count as zero and letters sort before numbers, so
+/// `1.0` equals `1.0.0` and pre-releases like `1.0rc1` or `1.0.0.beta` sort
+/// below the release.
+fn compare_loose(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).unwrap_or(&Part::Number(0));
+        let y = b.get(i).unwrap_or(&Part::Number(0));
+        let ordering = match (x, y) {
+            (Part::Number(x), Part::Number(y)) => x.cmp(y),
+            (Part::Text(x), Part::Text(y)) => x.to_ascii_lowercase().cmp(&y.to_ascii_lowercase()),
+            (Part::Number(_), Part::Text(_)) => Ordering::Greater,
+            (Part::Text(_), Part::Number(_)) => Ordering::Less,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn dependency(ecosystem: DependencyEcosystem, name: &str, version: &str) -> Dependency {
+        Dependency {
+            ecosystem,
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rustsec_advisories() {
+        let temp_dir = tempdir().unwrap();
+        let crate_dir = temp_dir.path().join("crates/smallvec");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(
+            crate_dir.join("RUSTSEC-2021-0003.md"),
+            r#"```toml
+[advisory]
+id = "RUSTSEC-2021-0003"
+package = "smallvec"
+date = "2021-01-08"
+url = "https://github.com/servo/rust-smallvec/issues/252"
+aliases = ["CVE-2021-25900"]
+
+[versions]
+patched = [">= 0.6.14, < 1.0.0", ">= 1.6.1"]
+unaffected = ["< 0.6.10"]
+```
+
+# Buffer overflow in SmallVec::insert_many
+
+A bug in insert_many can overflow the buffer.
+"#,
+        )
+        .unwrap();
+        fs::write(
+            crate_dir.join("RUSTSEC-2019-0009.toml"),
+            r#"[advisory]
+id = "RUSTSEC-2019-0009"
+package = "smallvec"
+title = "Double-free and use-after-free in SmallVec::grow()"
+patched_versions = [">= 0.6.10"]
+unaffected_versions = ["< 0.6.5"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            crate_dir.join("RUSTSEC-2020-0100.md"),
+            "```toml\n[advisory]\nid = \"RUSTSEC-2020-0100\"\npackage = \"smallvec\"\n\
+             withdrawn = \"2020-12-01\"\n\n[versions]\npatched = []\n```\n\n# Withdrawn\n",
+        )
+        .unwrap();
+        // The git checkout of a clone is not read
+        fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join(".git/broken.json"), "{").unwrap();
+
+        let database = AdvisoryDatabase::load(temp_dir.path()).unwrap();
+        assert_eq!(database.len(), 2);
+
+        let matching =
+            database.matching(&dependency(DependencyEcosystem::Cargo, "smallvec", "1.6.0"));
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].id, "RUSTSEC-2021-0003");
+        assert_eq!(
+            matching[0].title,
+            "Buffer overflow in SmallVec::insert_many"
+        );
+        assert_eq!(matching[0].aliases, vec!["CVE-2021-25900"]);
+        assert_eq!(
+            matching[0].fixed_versions(),
+            vec![">= 0.6.14, < 1.0.0", ">= 1.6.1"]
+        );
+
+        let old = database.matching(&dependency(DependencyEcosystem::Cargo, "smallvec", "0.6.7"));
+        assert_eq!(old.len(), 1);
+        assert_eq!(old[0].id, "RUSTSEC-2019-0009");
+        assert_eq!(
+            database
+                .matching(&dependency(
+                    DependencyEcosystem::Cargo,
+                    "smallvec",
+                    "0.6.12"
+                ))
+                .len(),
+            1
+        );
+        assert!(database
+            .matching(&dependency(DependencyEcosystem::Cargo, "smallvec", "1.6.1"))
+            .is_empty());
+        assert!(database
+            .matching(&dependency(DependencyEcosystem::Npm, "smallvec", "1.6.0"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_osv_advisories() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("GHSA-p6mc-m468-83gw.json"),
+            r#"{
+  "id": "GHSA-p6mc-m468-83gw",
+  "summary": "Prototype Pollution in lodash",
+  "aliases": ["CVE-2020-8203"],
+  "references": [
+    {"type": "WEB", "url": "https://example.com/issue"},
+    {"type": "ADVISORY", "url": "https://nvd.nist.gov/vuln/detail/CVE-2020-8203"}
+  ],
+  "affected": [{
+    "package": {"ecosystem": "npm", "name": "lodash"},
+    "ranges": [{"type": "SEMVER", "events": [{"introduced": "3.7.0"}, {"fixed": "4.17.19"}]}]
+  }]
+}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("pypi.json"),
+            r#"[{
+  "id": "PYSEC-2021-59",
+  "details": "Jinja2 has a ReDoS in the urlize filter.\nMore text.",
+  "affected": [{
+    "package": {"ecosystem": "PyPI", "name": "Jinja2"},
+    "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "0"}, {"fixed": "2.11.3"}]}],
+    "versions": ["2.11.2"]
+  }, {
+    "package": {"ecosystem": "Debian:11", "name": "jinja2"}
+  }]
+}, {
+  "id": "GHSA-xxxx",
+  "withdrawn": "2022-01-01T00:00:00Z",
+  "affected": [{"package": {"ecosystem": "PyPI", "name": "jinja2"}, "versions": ["3.0.0"]}]
+}, {
+  "id": "GO-2022-0001",
+  "affected": [{
+    "package": {"ecosystem": "Go", "name": "golang.org/x/text"},
+    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"last_affected": "0.3.7"}]}]
+  }]
+}]"#,
+        )
+        .unwrap();
+
+        let database = AdvisoryDatabase::load(temp_dir.path()).unwrap();
+        assert_eq!(database.len(), 3);
+
+        let lodash = database.matching(&dependency(DependencyEcosystem::Npm, "lodash", "4.17.15"));
+        assert_eq!(lodash.len(), 1);
+        assert_eq!(
+            lodash[0].url.as_deref(),
+            Some("https://nvd.nist.gov/vuln/detail/CVE-2020-8203")
+        );
+        assert_eq!(lodash[0].fixed_versions(), vec!["4.17.19"]);
+        assert!(database
+            .matching(&dependency(DependencyEcosystem::Npm, "lodash", "4.17.21"))
+            .is_empty());
+        assert!(database
+            .matching(&dependency(DependencyEcosystem::Npm, "lodash", "3.6.0"))
+            .is_empty());
+
+        // PyPI names are normalized and versions compared loosely
+        let jinja = database.matching(&dependency(DependencyEcosystem::PyPI, "jinja2", "2.10rc1"));
+        assert_eq!(jinja.len(), 1);
+        assert_eq!(jinja[0].title, "Jinja2 has a ReDoS in the urlize filter.");
+        assert!(database
+            .matching(&dependency(DependencyEcosystem::PyPI, "jinja2", "3.0.0"))
+            .is_empty());
+
+        let go = |version| {
+            database
+                .matching(&dependency(
+                    DependencyEcosystem::Go,
+                    "golang.org/x/text",
+                    version,
+                ))
+                .len()
+        };
+        assert_eq!(go("v0.3.7"), 1);
+        assert_eq!(go("v0.3.8"), 0);
+    }
+
+    #[test]
+    fn test_audit_and_duplicate_advisories() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("crates/time")).unwrap();
+        fs::write(
+            temp_dir.path().join("crates/time/RUSTSEC-2020-0071.md"),
+            "```toml\n[advisory]\nid = \"RUSTSEC-2020-0071\"\npackage = \"time\"\n\n\
+             [versions]\npatched = [\">= 0.2.23\"]\n```\n\n# Potential segfault in the time crate\n",
+        )
+        .unwrap();
+        // The OSV export of the same advisory is not reported twice
+        fs::create_dir_all(temp_dir.path().join("osv")).unwrap();
+        fs::write(
+            temp_dir.path().join("osv/RUSTSEC-2020-0071.json"),
+            r#"{"id": "RUSTSEC-2020-0071", "affected": [{"package": {"ecosystem": "crates.io", "name": "time"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0.0.0-0"}, {"fixed": "0.2.23"}]}]}]}"#,
+        )
+        .unwrap();
+
+        let database = AdvisoryDatabase::load(temp_dir.path()).unwrap();
+        assert_eq!(database.len(), 1);
+
+        let project = Path::new("/projects/app");
+        let findings = database.audit(
+            project,
+            &[
+                dependency(DependencyEcosystem::Cargo, "serde", "1.0.0"),
+                dependency(DependencyEcosystem::Cargo, "time", "0.1.45"),
+            ],
+        );
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].path, project);
+        assert_eq!(findings[0].dependency.name, "time");
+        assert_eq!(findings[0].advisory_id, "RUSTSEC-2020-0071");
+        assert_eq!(findings[0].fixed_versions, vec![">= 0.2.23"]);
+
+        assert!(AdvisoryDatabase::load(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_compare_loose() {
+        assert_eq!(compare_loose("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_loose("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_loose("2.0rc1", "2.0"), Ordering::Less);
+        assert_eq!(compare_loose("1.0.0.beta", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_loose("2.0b1", "2.0rc1"), Ordering::Less);
+        assert_eq!(
+            compare_versions(DependencyEcosystem::Go, "v1.2.3", "1.2.10"),
+            Ordering::Less
+        );
+    }
+}
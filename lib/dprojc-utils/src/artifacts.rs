@@ -0,0 +1,256 @@
+//! Build artifacts and installed dependencies that can be removed from projects
+//!
+//! Each ecosystem names the directories its tools regenerate at the project
+//! root. Where a name is also common for hand-written content, the rule asks
+//! for a marker file the tool leaves inside, such as the `pyvenv.cfg` of a
+//! virtual environment, so only directories the tool created are offered.
+
+use chrono::{DateTime, Utc};
+use dprojc_types::{Project, ProjectType};
+use ignore::WalkBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory an ecosystem's tools regenerate
+#[derive(Debug, Clone)]
+pub struct ArtifactRule {
+    pub project_type: ProjectType,
+    /// Directory relative to the project root
+    pub dir: &'static str,
+    /// Entries of which at least one must exist inside the directory; empty
+    /// when the name alone is enough
+    pub markers: &'static [&'static str],
+}
+
+const fn rule(
+    project_type: ProjectType,
+    dir: &'static str,
+    markers: &'static [&'static str],
+) -> ArtifactRule {
+    ArtifactRule {
+        project_type,
+        dir,
+        markers,
+    }
+}
+
+/// Built-in artifact rules, checked against every ecosystem of a project
+pub const ARTIFACT_RULES: &[ArtifactRule] = &[
+    rule(
+        ProjectType::Rust,
+        "target",
+        &["CACHEDIR.TAG", ".rustc_info.json"],
+    ),
+    rule(ProjectType::NodeJs, "node_modules", &[]),
+    rule(ProjectType::NodeJs, ".next", &[]),
+    rule(ProjectType::NodeJs, ".nuxt", &[]),
+    rule(ProjectType::NodeJs, ".svelte-kit", &[]),
+    rule(ProjectType::NodeJs, ".parcel-cache", &[]),
+    rule(ProjectType::NodeJs, ".turbo", &[]),
+    rule(ProjectType::Python, ".venv", &["pyvenv.cfg"]),
+    rule(ProjectType::Python, "venv", &["pyvenv.cfg"]),
+    rule(ProjectType::Python, "__pycache__", &[]),
+    rule(ProjectType::Python, ".pytest_cache", &[]),
+    rule(ProjectType::Python, ".mypy_cache", &[]),
+    rule(ProjectType::Python, ".ruff_cache", &[]),
+    rule(ProjectType::Python, ".tox", &[]),
+    rule(ProjectType::Java, "target", &["classes", "maven-status"]),
+    rule(ProjectType::Gradle, "build", &["classes", "libs", "tmp"]),
+    rule(ProjectType::Gradle, ".gradle", &[]),
+    rule(ProjectType::Elixir, "_build", &[]),
+    rule(ProjectType::Elixir, "deps", &[]),
+    rule(ProjectType::Haskell, "dist-newstyle", &[]),
+    rule(ProjectType::Haskell, ".stack-work", &[]),
+    rule(ProjectType::Swift, ".build", &[]),
+    rule(ProjectType::Dart, ".dart_tool", &[]),
+    rule(ProjectType::Zig, "zig-cache", &[]),
+    rule(ProjectType::Zig, ".zig-cache", &[]),
+    rule(ProjectType::Zig, "zig-out", &[]),
+    rule(ProjectType::DotNet, "obj", &["project.assets.json"]),
+    rule(ProjectType::Terraform, ".terraform", &[]),
+];
+
+/// An artifact directory found in a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildArtifact {
+    /// The project the directory belongs to
+    pub project: PathBuf,
+    pub path: PathBuf,
+    /// Ecosystem of the rule that matched
+    pub project_type: ProjectType,
+    /// Apparent size of the files inside
+    pub bytes: u64,
+    /// Most recent modification of a file inside; `None` when there are no files
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl BuildArtifact {
+    /// Whether nothing inside was modified since `cutoff`
+    pub fn is_stale(&self, cutoff: DateTime<Utc>) -> bool {
+        self.last_modified.is_none_or(|modified| modified < cutoff)
+    }
+}
+
+/// Find the artifact directories of a project for all of its ecosystems
+///
+/// Symbolic links are never reported, so removing an artifact cannot reach
+/// outside the project.
+pub fn find_artifacts(project: &Project) -> Vec<BuildArtifact> {
+    let mut artifacts: Vec<BuildArtifact> = Vec::new();
+    for rule in ARTIFACT_RULES {
+        if !project.has_ecosystem(&rule.project_type) {
+            continue;
+        }
+        let path = project.path.join(rule.dir);
+        if artifacts.iter().any(|artifact| artifact.path == path) {
+            continue;
+        }
+        let is_dir = fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_dir());
+        if !is_dir
+            || !(rule.markers.is_empty()
+                || rule.markers.iter().any(|marker| path.join(marker).exists()))
+        {
+            continue;
+        }
+
+        let (bytes, last_modified) = inspect_dir(&path);
+        artifacts.push(BuildArtifact {
+            project: project.path.clone(),
+            path,
+            project_type: rule.project_type.clone(),
+            bytes,
+            last_modified,
+        });
+    }
+    artifacts
+}
+
+/// Total file size and newest file modification time under a directory
+fn inspect_dir(dir: &Path) -> (u64, Option<DateTime<Utc>>) {
+    let mut bytes = 0;
+    let mut newest = None;
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .follow_links(false)
+        .build();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        bytes += metadata.len();
+        if let Ok(modified) = metadata.modified() {
+            let modified = DateTime::<Utc>::from(modified);
+            if newest.is_none_or(|newest| modified > newest) {
+                newest = Some(modified);
+            }
+        }
+    }
+    (bytes, newest)
+}
+
+/// Delete an artifact directory, or move it to the desktop trash
+pub fn remove_artifact(path: &Path, to_trash: bool) -> anyhow::Result<()> {
+    if to_trash {
+        trash::delete(path)
+            .map_err(|e| anyhow::anyhow!("Failed to move {} to trash: {}", path.display(), e))
+    } else {
+        fs::remove_dir_all(path)
+            .map_err(|e| anyhow::anyhow!("Failed to remove {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, bytes: usize) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_find_artifacts() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write(root, "target/CACHEDIR.TAG", 10);
+        write(root, "target/debug/app", 90);
+        write(root, "web/node_modules/react/index.js", 5);
+        write(root, "node_modules/lodash/index.js", 50);
+        // A hand-written venv directory without pyvenv.cfg is left alone
+        write(root, "venv/notes.txt", 5);
+        write(root, ".venv/pyvenv.cfg", 20);
+        write(root, "src/main.rs", 1);
+
+        let rust_and_node = Project {
+            path: root.to_path_buf(),
+            project_type: ProjectType::Rust,
+            ecosystems: vec![ProjectType::NodeJs],
+            ..Default::default()
+        };
+        let artifacts = find_artifacts(&rust_and_node);
+        let found: Vec<_> = artifacts
+            .iter()
+            .map(|a| (a.path.strip_prefix(root).unwrap().to_path_buf(), a.bytes))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (PathBuf::from("target"), 100),
+                (PathBuf::from("node_modules"), 50)
+            ]
+        );
+        assert_eq!(artifacts[0].project_type, ProjectType::Rust);
+        assert!(artifacts[0].last_modified.is_some());
+
+        let python = Project {
+            path: root.to_path_buf(),
+            project_type: ProjectType::Python,
+            ..Default::default()
+        };
+        let artifacts = find_artifacts(&python);
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].path, root.join(".venv"));
+
+        // A target directory Cargo did not create is not a Rust artifact
+        let other = tempdir().unwrap();
+        write(other.path(), "target/notes.md", 5);
+        assert!(find_artifacts(&Project {
+            path: other.path().to_path_buf(),
+            project_type: ProjectType::Rust,
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn test_artifact_staleness() {
+        let now = Utc::now();
+        let mut artifact = BuildArtifact {
+            project: PathBuf::from("/p"),
+            path: PathBuf::from("/p/target"),
+            project_type: ProjectType::Rust,
+            bytes: 0,
+            last_modified: None,
+        };
+        assert!(artifact.is_stale(now));
+        artifact.last_modified = Some(now - chrono::Duration::days(30));
+        assert!(artifact.is_stale(now - chrono::Duration::days(14)));
+        assert!(!artifact.is_stale(now - chrono::Duration::days(60)));
+    }
+
+    #[test]
+    fn test_remove_artifact() {
+        let temp_dir = tempdir().unwrap();
+        write(temp_dir.path(), "node_modules/a/index.js", 5);
+        let path = temp_dir.path().join("node_modules");
+
+        remove_artifact(&path, false).unwrap();
+        assert!(!path.exists());
+        assert!(remove_artifact(&path, false).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! CI pipelines, container definitions and task runners configured in a project
+//!
+//! Only the locations each tool reads by default are checked: workflows in
+//! `.github/workflows`, `.gitlab-ci.yml`, `.circleci/config.yml` and a
+//! `Jenkinsfile` for CI; Dockerfiles, compose files and devcontainer
+//! definitions for containers; and the `Makefile` or `justfile` make and just
+//! would pick. Besides the file, the jobs, stages, services or targets it
+//! defines are listed where they can be read without evaluating anything.
+
+use dprojc_types::{AutomationConfig, AutomationKind};
+use std::fs;
+use std::path::Path;
+
+/// Compose file names, in the order `docker compose` looks for them
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Makefile names, in the order GNU make looks for them
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+/// Justfile names just accepts
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
+
+/// Top-level `.gitlab-ci.yml` keys that configure the pipeline rather than name a job
+const GITLAB_KEYWORDS: &[&str] = &[
+    "after_script",
+    "before_script",
+    "cache",
+    "default",
+    "image",
+    "include",
+    "services",
+    "stages",
+    "variables",
+    "workflow",
+];
+
+/// Find the automation configured at the root of a project directory
+///
+/// Results are ordered by kind and then by file name.
+pub fn read_automation(dir: &Path) -> Vec<AutomationConfig> {
+    let mut configs = Vec::new();
+
+    for name in sorted_files(&dir.join(".github").join("workflows")) {
+        if name.ends_with(".yml") || name.ends_with(".yaml") {
+            let relative = format!(".github/workflows/{}", name);
+            configs.push(config(dir, AutomationKind::GithubActions, &relative, jobs));
+        }
+    }
+    if dir.join(".gitlab-ci.yml").is_file() {
+        configs.push(config(
+            dir,
+            AutomationKind::GitlabCi,
+            ".gitlab-ci.yml",
+            gitlab_jobs,
+        ));
+    }
+    if dir.join(".circleci/config.yml").is_file() {
+        configs.push(config(
+            dir,
+            AutomationKind::CircleCi,
+            ".circleci/config.yml",
+            jobs,
+        ));
+    }
+    if dir.join("Jenkinsfile").is_file() {
+        configs.push(config(
+            dir,
+            AutomationKind::Jenkins,
+            "Jenkinsfile",
+            jenkins_stages,
+        ));
+    }
+
+    for name in sorted_files(dir) {
+        if is_dockerfile(&name) {
+            configs.push(config(
+                dir,
+                AutomationKind::Dockerfile,
+                &name,
+                docker_stages,
+            ));
+        }
+    }
+    if let Some(name) = first_file(dir, COMPOSE_FILES) {
+        configs.push(config(
+            dir,
+            AutomationKind::DockerCompose,
+            name,
+            compose_services,
+        ));
+    }
+    for name in [".devcontainer/devcontainer.json", ".devcontainer.json"] {
+        if dir.join(name).is_file() {
+            configs.push(AutomationConfig {
+                kind: AutomationKind::Devcontainer,
+                path: name.to_string(),
+                targets: Vec::new(),
+            });
+            break;
+        }
+    }
+
+    if let Some(name) = first_file(dir, MAKEFILES) {
+        configs.push(config(dir, AutomationKind::Makefile, name, make_targets));
+    }
+    if let Some(name) = first_file(dir, JUSTFILES) {
+        configs.push(config(dir, AutomationKind::Justfile, name, just_recipes));
+    }
+
+    configs
+}
+
+/// Read a configuration file and list its targets; an unreadable file has none
+fn config(
+    dir: &Path,
+    kind: AutomationKind,
+    path: &str,
+    targets: fn(&str) -> Vec<String>,
+) -> AutomationConfig {
+    AutomationConfig {
+        kind,
+        path: path.to_string(),
+        targets: fs::read_to_string(dir.join(path))
+            .map(|content| targets(&content))
+            .unwrap_or_default(),
+    }
+}
+
+/// Names of the regular files in a directory, sorted
+fn sorted_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The first of several candidate file names that exists
+fn first_file<'a>(dir: &Path, names: &[&'a str]) -> Option<&'a str> {
+    names.iter().copied().find(|name| dir.join(name).is_file())
+}
+
+/// `Dockerfile`, `Containerfile`, `Dockerfile.dev` or `dev.Dockerfile`
+fn is_dockerfile(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower == "dockerfile"
+        || lower == "containerfile"
+        || lower.starts_with("dockerfile.")
+        || lower.ends_with(".dockerfile")
+}
+
+/// Keys of a top-level YAML mapping, or of the mapping under `section`
+fn yaml_keys(content: &str, section: Option<&str>) -> Vec<String> {
+    let Ok(document) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return Vec::new();
+    };
+    let mapping = match section {
+        Some(section) => document.get(section),
+        None => Some(&document),
+    };
+    mapping
+        .and_then(|value| value.as_mapping())
+        .map(|mapping| {
+            mapping
+                .keys()
+                .filter_map(|key| key.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Job names of a GitHub Actions workflow or CircleCI configuration
+fn jobs(content: &str) -> Vec<String> {
+    yaml_keys(content, Some("jobs"))
+}
+
+/// Job names of a `.gitlab-ci.yml`, leaving out hidden `.template` jobs
+fn gitlab_jobs(content: &str) -> Vec<String> {
+    yaml_keys(content, None)
+        .into_iter()
+        .filter(|key| !key.starts_with('.') && !GITLAB_KEYWORDS.contains(&key.as_str()))
+        .collect()
+}
+
+fn compose_services(content: &str) -> Vec<String> {
+    yaml_keys(content, Some("services"))
+}
+
+/// Stage names from `stage('Build')` blocks of a Jenkinsfile
+fn jenkins_stages(content: &str) -> Vec<String> {
+    let mut stages = Vec::new();
+    for (start, _) in content.match_indices("stage(") {
+        let rest = content[start + "stage(".len()..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            continue;
+        };
+        if let Some(end) = rest[1..].find(quote) {
+            stages.push(rest[1..1 + end].to_string());
+        }
+    }
+    stages
+}
+
+/// Named build stages from `FROM image AS name` lines of a Dockerfile
+fn docker_stages(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [from, .., as_, name]
+                    if from.eq_ignore_ascii_case("from") && as_.eq_ignore_ascii_case("as") =>
+                {
+                    Some(name.to_string())
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Explicit targets of a Makefile
+///
+/// Special targets such as `.PHONY`, pattern rules and targets built from
+/// variables are left out, as are variable assignments.
+fn make_targets(content: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if names.contains('=') || rest.starts_with('=') || rest.starts_with(":=") {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if name.starts_with('.') || name.contains(['%', '$']) {
+                continue;
+            }
+            if !targets.iter().any(|target| target == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// Public recipes of a justfile, leaving out private ones starting with `_`
+fn just_recipes(content: &str) -> Vec<String> {
+    let mut recipes = Vec::new();
+    for line in content.lines() {
+        if line.starts_with(char::is_whitespace) || line.starts_with(['#', '[']) {
+            continue;
+        }
+        let Some((head, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') {
+            continue;
+        }
+        let Some(name) = head.split_whitespace().next() else {
+            continue;
+        };
+        if ["set", "alias", "export", "import", "mod"].contains(&name) {
+            continue;
+        }
+        let name = name.trim_start_matches('@');
+        if !name.is_empty()
+            && !name.starts_with('_')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            recipes.push(name.to_string());
+        }
+    }
+    recipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn summary(configs: &[AutomationConfig]) -> Vec<(AutomationKind, &str, Vec<&str>)> {
+        configs
+            .iter()
+            .map(|config| {
+                (
+                    config.kind,
+                    config.path.as_str(),
+                    config.targets.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_ci_configuration() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            ".github/workflows/ci.yml",
+            "name: CI\non: [push]\njobs:\n  test:\n    runs-on: ubuntu-latest\n  lint:\n    runs-on: ubuntu-latest\n",
+        );
+        write(root, ".github/workflows/README.md", "not a workflow");
+        write(
+            root,
+            ".gitlab-ci.yml",
+            "stages: [build, test]\n.template:\n  image: rust\nbuild:\n  stage: build\nunit:\n  stage: test\n",
+        );
+        write(
+            root,
+            ".circleci/config.yml",
+            "version: 2.1\njobs:\n  build: {}\n",
+        );
+        write(
+            root,
+            "Jenkinsfile",
+            "pipeline {\n  stages {\n    stage('Build') { }\n    stage(\"Deploy\") { }\n  }\n}\n",
+        );
+
+        assert_eq!(
+            summary(&read_automation(root)),
+            vec![
+                (
+                    AutomationKind::GithubActions,
+                    ".github/workflows/ci.yml",
+                    vec!["test", "lint"]
+                ),
+                (
+                    AutomationKind::GitlabCi,
+                    ".gitlab-ci.yml",
+                    vec!["build", "unit"]
+                ),
+                (
+                    AutomationKind::CircleCi,
+                    ".circleci/config.yml",
+                    vec!["build"]
+                ),
+                (
+                    AutomationKind::Jenkins,
+                    "Jenkinsfile",
+                    vec!["Build", "Deploy"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_container_and_task_configuration() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "Dockerfile",
+            "FROM rust:1.75 AS builder\nRUN cargo build\nFROM debian:bookworm\nCOPY --from=builder /app /app\n",
+        );
+        write(root, "Dockerfile.dev", "FROM rust:1.75\n");
+        write(
+            root,
+            "docker-compose.yml",
+            "services:\n  db:\n    image: postgres\n  app:\n    build: .\n",
+        );
+        write(root, ".devcontainer/devcontainer.json", "{}");
+        write(
+            root,
+            "Makefile",
+            "CC := gcc\n.PHONY: all test\nall: build test\nbuild test:\n\tcargo build\n%.o: %.c\n\t$(CC) -c $<\n",
+        );
+        write(
+            root,
+            "justfile",
+            "set shell := [\"bash\", \"-c\"]\nversion := \"1.0\"\n# Run the tests\ntest *args:\n  cargo test {{args}}\n@fmt:\n  cargo fmt\n_helper:\n  true\n",
+        );
+
+        assert_eq!(
+            summary(&read_automation(root)),
+            vec![
+                (AutomationKind::Dockerfile, "Dockerfile", vec!["builder"]),
+                (AutomationKind::Dockerfile, "Dockerfile.dev", vec![]),
+                (
+                    AutomationKind::DockerCompose,
+                    "docker-compose.yml",
+                    vec!["db", "app"]
+                ),
+                (
+                    AutomationKind::Devcontainer,
+                    ".devcontainer/devcontainer.json",
+                    vec![]
+                ),
+                (
+                    AutomationKind::Makefile,
+                    "Makefile",
+                    vec!["all", "build", "test"]
+                ),
+                (AutomationKind::Justfile, "justfile", vec!["test", "fmt"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_automation_without_configuration() {
+        let temp_dir = tempdir().unwrap();
+        write(temp_dir.path(), "src/main.rs", "fn main() {}");
+        assert!(read_automation(temp_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Counting lines of code per language
+//!
+//! Files are assigned a language by name or extension, and each line is
+//! classified as code, comment or blank from the language's comment syntax.
+//! String literals are not parsed, so a comment marker inside a string is
+//! taken at face value; like other line counters, the figures are an estimate
+//! meant for comparing projects rather than an exact measure.
+
+use crate::{create_walker, ExcludeMatcher};
+use dprojc_types::{CodeStats, LanguageStats, ScanConfig};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Files larger than this are generated or minified and are not counted
+const MAX_SOURCE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// How to recognize a language and its comments
+struct Syntax {
+    name: &'static str,
+    /// Lower-case extensions without the dot
+    extensions: &'static [&'static str],
+    /// Exact file names, for files without a telling extension
+    file_names: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    /// Every non-blank line is prose and counts as a comment
+    prose: bool,
+}
+
+const fn syntax(
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+) -> Syntax {
+    Syntax {
+        name,
+        extensions,
+        file_names: &[],
+        line_comments,
+        block_comments,
+        prose: false,
+    }
+}
+
+const C_STYLE: &[(&str, &str)] = &[("/*", "*/")];
+const HTML_STYLE: &[(&str, &str)] = &[("<!--", "-->")];
+
+/// Languages recognized when counting, matched in order
+const SYNTAXES: &[Syntax] = &[
+    syntax("Rust", &["rs"], &["//"], C_STYLE),
+    syntax("C", &["c", "h"], &["//"], C_STYLE),
+    syntax(
+        "C++",
+        &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        &["//"],
+        C_STYLE,
+    ),
+    syntax("C#", &["cs"], &["//"], C_STYLE),
+    syntax("Java", &["java"], &["//"], C_STYLE),
+    syntax("Kotlin", &["kt", "kts"], &["//"], C_STYLE),
+    syntax("Scala", &["scala", "sc"], &["//"], C_STYLE),
+    syntax("Swift", &["swift"], &["//"], C_STYLE),
+    syntax("Go", &["go"], &["//"], C_STYLE),
+    syntax("Dart", &["dart"], &["//"], C_STYLE),
+    syntax("Zig", &["zig"], &["//"], &[]),
+    syntax("JavaScript", &["js", "mjs", "cjs", "jsx"], &["//"], C_STYLE),
+    syntax("TypeScript", &["ts", "mts", "cts", "tsx"], &["//"], C_STYLE),
+    syntax("Python", &["py", "pyi"], &["#"], &[]),
+    Syntax {
+        file_names: &["Gemfile", "Rakefile"],
+        ..syntax(
+            "Ruby",
+            &["rb", "rake", "gemspec"],
+            &["#"],
+            &[("=begin", "=end")],
+        )
+    },
+    syntax("PHP", &["php"], &["//", "#"], C_STYLE),
+    syntax("Shell", &["sh", "bash", "zsh"], &["#"], &[]),
+    syntax("Elixir", &["ex", "exs"], &["#"], &[]),
+    syntax("Erlang", &["erl", "hrl"], &["%"], &[]),
+    syntax("Haskell", &["hs"], &["--"], &[("{-", "-}")]),
+    syntax("OCaml", &["ml", "mli"], &[], &[("(*", "*)")]),
+    syntax("Lua", &["lua"], &["--"], &[("--[[", "]]")]),
+    syntax("Perl", &["pl", "pm"], &["#"], &[]),
+    syntax("R", &["r"], &["#"], &[]),
+    syntax("Julia", &["jl"], &["#"], &[("#=", "=#")]),
+    syntax("Clojure", &["clj", "cljs", "cljc"], &[";"], &[]),
+    syntax("Nix", &["nix"], &["#"], C_STYLE),
+    syntax("Terraform", &["tf", "tfvars"], &["#", "//"], C_STYLE),
+    syntax("SQL", &["sql"], &["--"], C_STYLE),
+    syntax("HTML", &["html", "htm"], &[], HTML_STYLE),
+    syntax("Vue", &["vue"], &["//"], &[("<!--", "-->"), ("/*", "*/")]),
+    syntax(
+        "Svelte",
+        &["svelte"],
+        &["//"],
+        &[("<!--", "-->"), ("/*", "*/")],
+    ),
+    syntax("CSS", &["css"], &[], C_STYLE),
+    syntax("SCSS", &["scss", "sass", "less"], &["//"], C_STYLE),
+    syntax("TOML", &["toml"], &["#"], &[]),
+    syntax("YAML", &["yaml", "yml"], &["#"], &[]),
+    syntax("JSON", &["json"], &[], &[]),
+    syntax("XML", &["xml"], &[], HTML_STYLE),
+    Syntax {
+        file_names: &["Makefile", "makefile", "GNUmakefile"],
+        ..syntax("Makefile", &["mk"], &["#"], &[])
+    },
+    Syntax {
+        file_names: &["Dockerfile", "Containerfile"],
+        ..syntax("Dockerfile", &["dockerfile"], &["#"], &[])
+    },
+    Syntax {
+        file_names: &["CMakeLists.txt"],
+        ..syntax("CMake", &["cmake"], &["#"], &[])
+    },
+    Syntax {
+        prose: true,
+        ..syntax("Markdown", &["md", "markdown"], &[], &[])
+    },
+];
+
+/// The language of a file, from its name or else its extension
+fn syntax_for(path: &Path) -> Option<&'static Syntax> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(syntax) = SYNTAXES.iter().find(|s| s.file_names.contains(&name)) {
+        return Some(syntax);
+    }
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    SYNTAXES
+        .iter()
+        .find(|s| s.extensions.contains(&extension.as_str()))
+}
+
+/// Count the code, comment and blank lines of a file's text
+fn count_lines(text: &str, syntax: &Syntax) -> LanguageStats {
+    let mut stats = LanguageStats {
+        language: syntax.name.to_string(),
+        files: 1,
+        ..LanguageStats::default()
+    };
+    // End marker of the block comment the previous line left open
+    let mut open_block: Option<&str> = None;
+
+    for line in text.lines() {
+        let mut rest = line.trim();
+        if rest.is_empty() {
+            stats.blanks += 1;
+            continue;
+        }
+        if syntax.prose {
+            stats.comments += 1;
+            continue;
+        }
+
+        let mut has_code = false;
+        loop {
+            if let Some(end) = open_block {
+                match rest.find(end) {
+                    Some(index) => {
+                        rest = &rest[index + end.len()..];
+                        open_block = None;
+                    }
+                    None => break,
+                }
+                continue;
+            }
+
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            // Block markers first, so Lua's `--[[` is not read as a `--` line comment
+            if let Some((start, end)) = syntax
+                .block_comments
+                .iter()
+                .find(|(start, _)| rest.starts_with(start))
+            {
+                rest = &rest[start.len()..];
+                open_block = Some(end);
+                continue;
+            }
+            if syntax
+                .line_comments
+                .iter()
+                .any(|marker| rest.starts_with(marker))
+            {
+                break;
+            }
+
+            // Code runs until a block comment opens later on the line; a line
+            // comment after code ends the line either way
+            has_code = true;
+            let line_comment = syntax
+                .line_comments
+                .iter()
+                .filter_map(|marker| rest.find(marker))
+                .min()
+                .unwrap_or(rest.len());
+            let next_block = syntax
+                .block_comments
+                .iter()
+                .filter_map(|(start, end)| Some((rest.find(start)?, *start, *end)))
+                .filter(|(index, _, _)| *index <= line_comment)
+                .min_by_key(|(index, _, _)| *index);
+            match next_block {
+                Some((index, start, end)) => {
+                    rest = &rest[index + start.len()..];
+                    open_block = Some(end);
+                }
+                None => break,
+            }
+        }
+
+        if has_code {
+            stats.code += 1;
+        } else {
+            stats.comments += 1;
+        }
+    }
+    stats
+}
+
+/// Count lines of code per language under a project directory
+///
+/// The walk honors the exclude patterns and, as configured, the ignore files
+/// that apply to the project scan, and skips hidden entries. Files that are too
+/// large, binary or unreadable are left out rather than failing the count.
+pub fn count_code(dir: &Path, config: &ScanConfig, excludes: &ExcludeMatcher) -> CodeStats {
+    let excludes = excludes.clone();
+    let walker = create_walker(dir, config)
+        .max_depth(None)
+        .hidden(true)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            entry.depth() == 0 || !excludes.is_excluded(entry.path(), is_dir)
+        })
+        .build();
+
+    let mut languages: HashMap<&'static str, LanguageStats> = HashMap::new();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Some(syntax) = syntax_for(entry.path()) else {
+            continue;
+        };
+        if entry
+            .metadata()
+            .map_or(true, |metadata| metadata.len() > MAX_SOURCE_FILE_BYTES)
+        {
+            continue;
+        }
+        let Ok(bytes) = fs::read(entry.path()) else {
+            continue;
+        };
+        if bytes.contains(&0) {
+            continue;
+        }
+        let counts = count_lines(&String::from_utf8_lossy(&bytes), syntax);
+        languages
+            .entry(syntax.name)
+            .or_insert_with(|| LanguageStats {
+                language: syntax.name.to_string(),
+                ..LanguageStats::default()
+            })
+            .add(&counts);
+    }
+
+    let mut languages: Vec<LanguageStats> = languages.into_values().collect();
+    languages.sort_by(|a, b| {
+        b.code
+            .cmp(&a.code)
+            .then_with(|| b.lines().cmp(&a.lines()))
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    CodeStats {
+        languages,
+        measured_at: chrono::Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn lines(text: &str, extension: &str) -> (u64, u64, u64) {
+        let syntax = syntax_for(Path::new(&format!("file.{}", extension))).unwrap();
+        let stats = count_lines(text, syntax);
+        (stats.code, stats.comments, stats.blanks)
+    }
+
+    #[test]
+    fn test_count_lines() {
+        let rust = "//! Crate docs\n\nfn main() {\n    /* start\n       still comment */\n    let x = 1; // trailing\n    let y = /* inline */ 2;\n}\n";
+        assert_eq!(lines(rust, "rs"), (4, 3, 1));
+        // A block marker inside a trailing line comment opens nothing
+        assert_eq!(
+            lines("let a = 1; // see /* here\nlet b = 2;\n", "rs"),
+            (2, 0, 0)
+        );
+
+        let python = "#!/usr/bin/env python\nimport os\n\n# comment\nprint(os.name)\n";
+        assert_eq!(lines(python, "py"), (2, 2, 1));
+
+        let lua = "--[[ block\ncomment ]]\nlocal x = 1 -- note\n";
+        assert_eq!(lines(lua, "lua"), (1, 2, 0));
+
+        assert_eq!(lines("# Title\n\nSome prose.\n", "md"), (0, 2, 1));
+        assert!(syntax_for(Path::new("image.png")).is_none());
+        assert_eq!(syntax_for(Path::new("Makefile")).unwrap().name, "Makefile");
+    }
+
+    #[test]
+    fn test_count_code() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("src/main.rs", "fn main() {\n    // hello\n}\n");
+        write("src/lib.rs", "\npub fn f() {}\n");
+        write("scripts/build.py", "print('hi')\n");
+        write("node_modules/dep/index.js", "module.exports = 1;\n");
+        write("generated/big.js", "let x = 1;\n");
+        write(".hidden/tool.rs", "fn hidden() {}\n");
+        write("assets/logo.rs", "fn\0binary() {}\n");
+        write(".gitignore", "generated/\n");
+        fs::create_dir(root.join(".git")).unwrap();
+
+        let config = ScanConfig::default();
+        let excludes = ExcludeMatcher::new(root, &config.exclude_patterns).unwrap();
+        let stats = count_code(root, &config, &excludes);
+
+        let found: Vec<_> = stats
+            .languages
+            .iter()
+            .map(|l| (l.language.as_str(), l.files, l.code, l.comments, l.blanks))
+            .collect();
+        assert_eq!(found, vec![("Rust", 2, 3, 1, 1), ("Python", 1, 1, 0, 0)]);
+        assert_eq!(stats.total().lines(), 6);
+        assert_eq!(stats.primary_language(), Some("Rust"));
+    }
+}
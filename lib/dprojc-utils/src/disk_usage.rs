@@ -0,0 +1,153 @@
+//! Measuring the space a project takes on disk
+//!
+//! Sizes are apparent file sizes, summed without following symbolic links. A
+//! directory named like a build artifact directory counts as one at any depth,
+//! so the `target/` of a nested crate or the `__pycache__` next to every Python
+//! module is reclaimable too, while anything inside it is counted only once.
+
+use dprojc_types::{ArtifactDir, DiskUsage};
+use ignore::WalkBuilder;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Directory names holding build output or installed dependencies
+pub const ARTIFACT_DIR_NAMES: &[&str] = &[
+    "target",
+    "node_modules",
+    ".venv",
+    "build",
+    "dist",
+    "__pycache__",
+];
+
+/// Where a file under the project belongs
+enum Area {
+    Source,
+    /// Version control data, counted in the total only
+    Vcs,
+    /// Inside the artifact directory at this relative path
+    Artifact(PathBuf),
+}
+
+/// Classify a file by the directories on its path relative to the project
+fn area(relative: &Path) -> Area {
+    let mut prefix = PathBuf::new();
+    let Some(parent) = relative.parent() else {
+        return Area::Source;
+    };
+    for component in parent.components() {
+        prefix.push(component);
+        let name = component.as_os_str();
+        if name == ".git" {
+            return Area::Vcs;
+        }
+        if ARTIFACT_DIR_NAMES.iter().any(|dir| name == *dir) {
+            return Area::Artifact(prefix);
+        }
+    }
+    Area::Source
+}
+
+/// Measure the total, source and build artifact size of a project directory
+///
+/// Files that cannot be read are left out rather than failing the measurement.
+pub fn measure_disk_usage(dir: &Path) -> DiskUsage {
+    let mut usage = DiskUsage {
+        measured_at: chrono::Utc::now(),
+        ..DiskUsage::default()
+    };
+    let mut artifacts: HashMap<PathBuf, u64> = HashMap::new();
+
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .follow_links(false)
+        .build();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(dir) else {
+            continue;
+        };
+        let bytes = metadata.len();
+        usage.total_bytes += bytes;
+        match area(relative) {
+            Area::Source => usage.source_bytes += bytes,
+            Area::Vcs => {}
+            Area::Artifact(path) => {
+                usage.artifact_bytes += bytes;
+                *artifacts.entry(path).or_default() += bytes;
+            }
+        }
+    }
+
+    usage.artifact_dirs = artifacts
+        .into_iter()
+        .map(|(path, bytes)| ArtifactDir { path, bytes })
+        .collect();
+    usage
+        .artifact_dirs
+        .sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, bytes: usize) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_measure_disk_usage() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write(root, "Cargo.toml", 100);
+        write(root, "src/main.rs", 400);
+        write(root, ".git/objects/pack", 1_000);
+        write(root, "target/debug/app", 5_000);
+        write(root, "target/debug/build/out.o", 2_000);
+        write(root, "web/node_modules/react/index.js", 3_000);
+        write(root, "web/node_modules/dist/bundle.js", 500);
+        write(root, "tools/__pycache__/gen.pyc", 50);
+        write(root, "tools/gen.py", 25);
+
+        let usage = measure_disk_usage(root);
+        assert_eq!(usage.total_bytes, 12_075);
+        assert_eq!(usage.source_bytes, 525);
+        assert_eq!(usage.artifact_bytes, 10_550);
+        assert_eq!(
+            usage.artifact_dirs,
+            vec![
+                ArtifactDir {
+                    path: PathBuf::from("target"),
+                    bytes: 7_000,
+                },
+                ArtifactDir {
+                    path: PathBuf::from("web/node_modules"),
+                    bytes: 3_500,
+                },
+                ArtifactDir {
+                    path: PathBuf::from("tools/__pycache__"),
+                    bytes: 50,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_measure_disk_usage_empty_project() {
+        let temp_dir = tempdir().unwrap();
+        let usage = measure_disk_usage(temp_dir.path());
+        assert_eq!(usage.total_bytes, 0);
+        assert!(usage.artifact_dirs.is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+//! Finding projects cloned or copied to more than one place
+//!
+//! Git repositories are matched on their normalized remote URLs and on their
+//! root commit, so a clone whose remote was removed still pairs with the
+//! others. Matches are transitive: a fork that keeps the original as its
+//! `upstream` remote joins the group of the original. Projects outside git are
+//! matched on manifest name and version, except workspace members and projects
+//! inside a cataloged repository, which are compared through their root.
+//! Linked worktrees share a repository with its main checkout by design and are
+//! left out.
+
+use crate::last_source_modification;
+use dprojc_types::{DuplicateCopy, DuplicateGroup, DuplicateMatch, Project};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Reduce a git remote URL to `host/path` so equivalent forms compare equal
+///
+/// The scheme, user name, SSH port, trailing slash and `.git` suffix are
+/// dropped and the host is lower-cased. Local paths and `file://` URLs are
+/// only trimmed.
+///
+/// # Examples
+/// ```
+/// use dprojc_utils::normalize_remote_url;
+///
+/// assert_eq!(normalize_remote_url("git@github.com:acme/app.git"), "github.com/acme/app");
+/// assert_eq!(normalize_remote_url("https://GitHub.com/acme/app/"), "github.com/acme/app");
+/// assert_eq!(normalize_remote_url("ssh://git@github.com:22/acme/app"), "github.com/acme/app");
+/// assert_eq!(normalize_remote_url("/srv/git/app.git"), "/srv/git/app.git");
+/// ```
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme.eq_ignore_ascii_case("file") {
+            return url.trim_end_matches('/').to_string();
+        }
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let host = if scheme.starts_with("http") {
+            host
+        } else {
+            host.split_once(':').map_or(host, |(host, _)| host)
+        };
+        (host, path)
+    } else {
+        // scp-like syntax, `user@host:path`; a colon after a slash or a
+        // single-letter drive means a local path
+        match url.split_once(':') {
+            Some((authority, path)) if authority.len() > 1 && !authority.contains('/') => {
+                let host = authority
+                    .rsplit_once('@')
+                    .map_or(authority, |(_, host)| host);
+                (host, path)
+            }
+            _ => return url.trim_end_matches('/').to_string(),
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    format!(
+        "{}/{}",
+        host.to_ascii_lowercase(),
+        path.trim_end_matches('/')
+    )
+}
+
+/// What a project can be matched on, empty when it is not compared
+fn match_keys(project: &Project, repositories: &HashSet<&Path>) -> Vec<DuplicateMatch> {
+    if let Some(git) = &project.git {
+        if git.is_worktree {
+            return Vec::new();
+        }
+        let mut keys: Vec<DuplicateMatch> = git
+            .remotes
+            .iter()
+            .map(|remote| DuplicateMatch::RemoteUrl(normalize_remote_url(&remote.url)))
+            .collect();
+        keys.extend(git.root_commit.clone().map(DuplicateMatch::RootCommit));
+        return keys;
+    }
+
+    let inside_repository = project
+        .path
+        .ancestors()
+        .skip(1)
+        .any(|ancestor| repositories.contains(ancestor));
+    if project.parent.is_some() || inside_repository {
+        return Vec::new();
+    }
+    project
+        .manifest
+        .as_ref()
+        .and_then(|manifest| {
+            Some(DuplicateMatch::Manifest {
+                name: manifest.name.clone()?,
+                version: manifest.version.clone()?,
+            })
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Root of `index` in a union-find forest, compressing the path on the way
+fn find(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = index;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+    root
+}
+
+/// Group the projects that are copies of each other, ordered by first path
+///
+/// `frecency` holds the current frecency score of visited projects. Files are
+/// only read for copies outside git, to find when they were last modified.
+pub fn find_duplicates(
+    projects: &[Project],
+    frecency: &HashMap<PathBuf, f64>,
+) -> Vec<DuplicateGroup> {
+    let repositories: HashSet<&Path> = projects
+        .iter()
+        .filter(|project| project.git.is_some())
+        .map(|project| project.path.as_path())
+        .collect();
+
+    let mut holders: BTreeMap<DuplicateMatch, Vec<usize>> = BTreeMap::new();
+    for (index, project) in projects.iter().enumerate() {
+        for key in match_keys(project, &repositories) {
+            let indices = holders.entry(key).or_default();
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..projects.len()).collect();
+    for indices in holders.values() {
+        for &index in &indices[1..] {
+            let (a, b) = (find(&mut parents, indices[0]), find(&mut parents, index));
+            parents[b] = a;
+        }
+    }
+
+    let mut groups: HashMap<usize, (Vec<DuplicateMatch>, Vec<usize>)> = HashMap::new();
+    for (key, indices) in holders {
+        if indices.len() < 2 {
+            continue;
+        }
+        let root = find(&mut parents, indices[0]);
+        let (keys, members) = groups.entry(root).or_default();
+        keys.push(key);
+        for index in indices {
+            if !members.contains(&index) {
+                members.push(index);
+            }
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = groups
+        .into_values()
+        .map(|(matched_by, members)| {
+            let mut copies: Vec<DuplicateCopy> = members
+                .into_iter()
+                .map(|index| duplicate_copy(&projects[index], frecency))
+                .collect();
+            copies.sort_by(|a, b| a.path.cmp(&b.path));
+            mark_group(&mut copies);
+            DuplicateGroup { matched_by, copies }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.copies[0].path.cmp(&b.copies[0].path));
+    groups
+}
+
+fn duplicate_copy(project: &Project, frecency: &HashMap<PathBuf, f64>) -> DuplicateCopy {
+    let git = project.git.as_ref();
+    DuplicateCopy {
+        path: project.path.clone(),
+        branch: git.and_then(|git| git.branch.clone()),
+        head_commit: git.and_then(|git| git.head_commit.clone()),
+        updated: match git {
+            Some(git) => git.head_date,
+            None => last_source_modification(&project.path),
+        },
+        uncommitted_changes: git.map_or(0, |git| git.dirty_count + git.untracked_count),
+        unpushed_commits: git.map_or(0, |git| git.ahead),
+        stashes: git.map_or(0, |git| git.stash_count),
+        frecency: frecency.get(&project.path).copied().unwrap_or(0.0),
+        newest: false,
+        most_frecent: false,
+    }
+}
+
+/// Flag the newest and the most frecent copy
+///
+/// Nothing is flagged when the top value is shared, such as by clones that
+/// are checked out at the same commit.
+fn mark_group(copies: &mut [DuplicateCopy]) {
+    /// Index of the only copy holding the largest value
+    fn single_max<T: PartialOrd>(values: impl Iterator<Item = Option<T>>) -> Option<usize> {
+        let mut best: Option<(usize, T)> = None;
+        let mut tied = false;
+        for (index, value) in values.enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            match &best {
+                Some((_, top)) if value < *top => {}
+                Some((_, top)) if value == *top => tied = true,
+                _ => {
+                    best = Some((index, value));
+                    tied = false;
+                }
+            }
+        }
+        best.filter(|_| !tied).map(|(index, _)| index)
+    }
+
+    if let Some(index) = single_max(copies.iter().map(|copy| copy.updated)) {
+        copies[index].newest = true;
+    }
+    let frecency = copies
+        .iter()
+        .map(|copy| Some(copy.frecency).filter(|score| *score > 0.0));
+    if let Some(index) = single_max(frecency) {
+        copies[index].most_frecent = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dprojc_types::{GitMetadata, GitRemote, ProjectManifest};
+
+    fn clone(url: Option<&str>, root: &str, days_ago: i64) -> GitMetadata {
+        GitMetadata {
+            branch: Some("main".to_string()),
+            head_date: Some(chrono::Utc::now() - chrono::Duration::days(days_ago)),
+            root_commit: Some(root.to_string()),
+            remotes: url
+                .map(|url| GitRemote {
+                    name: "origin".to_string(),
+                    url: url.to_string(),
+                })
+                .into_iter()
+                .collect(),
+            ..GitMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_remote_url() {
+        for url in [
+            "https://github.com/acme/app.git",
+            "http://user@github.com/acme/app",
+            "git@github.com:acme/app.git",
+            "ssh://git@github.com/acme/app.git/",
+            "git://github.com/acme/app",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/acme/app", "{}", url);
+        }
+        assert_eq!(
+            normalize_remote_url("https://git.example.com:8443/app"),
+            "git.example.com:8443/app"
+        );
+        assert_eq!(normalize_remote_url("C:/repos/app"), "C:/repos/app");
+        assert_eq!(normalize_remote_url("file:///srv/app/"), "file:///srv/app");
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let mut dirty = clone(Some("git@github.com:acme/app.git"), "r1", 10);
+        dirty.dirty_count = 2;
+        let local = GitMetadata {
+            is_worktree: true,
+            ..clone(None, "r1", 0)
+        };
+        let manifest = ProjectManifest {
+            source: "package.json".to_string(),
+            name: Some("site".to_string()),
+            version: Some("1.0.0".to_string()),
+            ..ProjectManifest::default()
+        };
+        let mut projects: Vec<Project> = [
+            ("/a/app", Some(dirty)),
+            (
+                "/b/app",
+                Some(clone(Some("https://github.com/acme/app"), "r1", 2)),
+            ),
+            // No remote, matched by the root commit alone
+            ("/c/app", Some(clone(None, "r1", 30))),
+            ("/d/app-worktree", Some(local)),
+            (
+                "/e/other",
+                Some(clone(Some("https://github.com/acme/other"), "r2", 1)),
+            ),
+            ("/a/site", None),
+            ("/b/site", None),
+            // Inside a repository that is compared as a whole
+            ("/a/app/site", None),
+        ]
+        .into_iter()
+        .map(|(path, git)| Project {
+            path: PathBuf::from(path),
+            git,
+            ..Default::default()
+        })
+        .collect();
+        for index in [5, 6, 7] {
+            projects[index].manifest = Some(manifest.clone());
+        }
+        let frecency = HashMap::from([
+            (PathBuf::from("/c/app"), 4.0),
+            (PathBuf::from("/a/app"), 1.5),
+        ]);
+
+        let groups = find_duplicates(&projects, &frecency);
+        assert_eq!(groups.len(), 2);
+
+        let app = &groups[0];
+        assert_eq!(
+            app.matched_by,
+            vec![
+                DuplicateMatch::RemoteUrl("github.com/acme/app".to_string()),
+                DuplicateMatch::RootCommit("r1".to_string()),
+            ]
+        );
+        let paths: Vec<_> = app
+            .copies
+            .iter()
+            .map(|copy| copy.path.to_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["/a/app", "/b/app", "/c/app"]);
+        assert!(app.copies[0].has_local_changes());
+        assert!(app.copies[1].newest);
+        assert!(app.copies[2].most_frecent);
+        assert_eq!(app.copies.iter().filter(|copy| copy.newest).count(), 1);
+
+        let site = &groups[1];
+        assert_eq!(site.matched_by[0].to_string(), "site 1.0.0");
+        assert_eq!(site.copies.len(), 2);
+        assert!(site
+            .copies
+            .iter()
+            .all(|copy| !copy.newest && !copy.most_frecent));
+    }
+}
@@ -0,0 +1,82 @@
+//! Identifying a project independently of where it lives
+//!
+//! A repository is identified by its root commit together with its `origin`
+//! remote, or its first remote when there is no `origin`. Root commits alone
+//! are shared by every repository started from the same template, and
+//! manifest names by unrelated projects, so neither is enough on its own.
+//! Copies of a repository share its identity as well, so the catalog only
+//! treats a project as moved when the path it was recorded at no longer
+//! exists.
+
+use crate::normalize_remote_url;
+use dprojc_types::Project;
+
+/// Key that stays the same when a project directory is moved or renamed
+///
+/// Returns `None` for projects outside git, for linked worktrees and for
+/// repositories without commits or remotes.
+pub fn project_identity(project: &Project) -> Option<String> {
+    let git = project.git.as_ref().filter(|git| !git.is_worktree)?;
+    let root = git.root_commit.as_deref()?;
+    let remote = git
+        .remotes
+        .iter()
+        .find(|remote| remote.name == "origin")
+        .or(git.remotes.first())?;
+    Some(format!(
+        "git:{}@{}",
+        root,
+        normalize_remote_url(&remote.url)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dprojc_types::{GitMetadata, GitRemote, ProjectManifest, ProjectType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_project_identity() {
+        let mut project = Project {
+            path: PathBuf::from("/src/app"),
+            project_type: ProjectType::Rust,
+            ..Default::default()
+        };
+        assert_eq!(project_identity(&project), None);
+
+        project.manifest = Some(ProjectManifest {
+            source: "Cargo.toml".to_string(),
+            name: Some("app".to_string()),
+            ..ProjectManifest::default()
+        });
+        assert_eq!(project_identity(&project), None);
+
+        let remote = |name: &str, url: &str| GitRemote {
+            name: name.to_string(),
+            url: url.to_string(),
+        };
+        let mut git = GitMetadata {
+            root_commit: Some("abc123".to_string()),
+            remotes: vec![
+                remote("fork", "git@github.com:me/app.git"),
+                remote("origin", "https://github.com/acme/app"),
+            ],
+            ..GitMetadata::default()
+        };
+        project.git = Some(git.clone());
+        assert_eq!(
+            project_identity(&project).as_deref(),
+            Some("git:abc123@github.com/acme/app")
+        );
+
+        let mut worktree = git.clone();
+        worktree.is_worktree = true;
+        project.git = Some(worktree);
+        assert_eq!(project_identity(&project), None);
+
+        git.remotes.clear();
+        project.git = Some(git);
+        assert_eq!(project_identity(&project), None);
+    }
+}
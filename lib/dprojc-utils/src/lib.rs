@@ -3,16 +3,104 @@
 //! This crate provides various utility functions used throughout the project,
 //! including path manipulation, validation, formatting, and scanning helpers.
 
-use dprojc_types::{ProjectIndicator, ScanConfig};
-use regex::Regex;
-use std::collections::HashSet;
+use dprojc_types::{
+    IndicatorRule, IndicatorRuleKind, ProjectIndicator, ProjectType, ProjectTypeDefinition,
+    ScanConfig, DEFAULT_CUSTOM_TYPE_PRIORITY,
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{DirEntry, WalkBuilder};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
-use walkdir::{DirEntry, WalkDir};
+
+mod advisory;
+mod artifacts;
+mod automation;
+mod code_stats;
+mod disk_usage;
+mod duplicates;
+mod identity;
+mod license;
+mod lockfile;
+mod manifest;
+mod staleness;
+mod toolchain;
+mod workspace;
+
+pub use advisory::{Advisory, AdvisoryDatabase};
+pub use artifacts::{find_artifacts, remove_artifact, ArtifactRule, BuildArtifact, ARTIFACT_RULES};
+pub use automation::read_automation;
+pub use code_stats::count_code;
+pub use disk_usage::{measure_disk_usage, ARTIFACT_DIR_NAMES};
+pub use duplicates::{find_duplicates, normalize_remote_url};
+pub use identity::project_identity;
+pub use license::{
+    canonical_expression, identify_license_text, read_licenses, MIN_LICENSE_CONFIDENCE,
+};
+pub use lockfile::read_lockfiles;
+pub use manifest::read_manifest;
+pub use staleness::{assess_projects, assess_staleness, last_source_modification};
+pub use toolchain::read_toolchains;
+pub use workspace::{
+    assign_workspace_parents, collapse_workspace_members, group_workspace_members,
+    workspace_depths, WorkspaceManifest,
+};
+
+/// Name of the catalog-specific ignore file honored during scans
+///
+/// `.dprojcignore` files use gitignore syntax and apply to the directory they
+/// live in and everything below it, just like `.gitignore`.
+pub const DPROJC_IGNORE_FILENAME: &str = ".dprojcignore";
+
+/// Compiled matcher for `ScanConfig::exclude_patterns`
+///
+/// Patterns use gitignore syntax: a bare name such as `node_modules` or `*.tmp`
+/// matches at any depth, a leading `/` anchors the pattern to the scan root,
+/// `**` spans directories and a leading `!` re-includes a previously excluded
+/// path. The patterns are compiled once and can then be checked against every
+/// directory visited during a scan.
+///
+/// # Examples
+/// ```
+/// use dprojc_utils::ExcludeMatcher;
+/// use std::path::Path;
+///
+/// let patterns = vec!["node_modules".to_string(), "/build".to_string()];
+/// let matcher = ExcludeMatcher::new(Path::new("/work"), &patterns).unwrap();
+/// assert!(matcher.is_excluded(Path::new("/work/app/node_modules"), true));
+/// assert!(matcher.is_excluded(Path::new("/work/build"), true));
+/// assert!(!matcher.is_excluded(Path::new("/work/app/build"), true));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExcludeMatcher {
+    matcher: Gitignore,
+}
+
+impl ExcludeMatcher {
+    /// Compile exclude patterns relative to the given scan root
+    pub fn new(root: &Path, patterns: &[String]) -> anyhow::Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        }
+        let matcher = builder.build()?;
+        Ok(Self { matcher })
+    }
+
+    /// Check whether a path (absolute under the root, or relative to it) is excluded
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.matcher.matched(path, is_dir).is_ignore()
+    }
+}
 
 /// Check if a directory should be excluded based on patterns
 ///
-/// This function supports both exact matches and glob patterns with wildcards (* and ?).
-/// Patterns are matched against directory names, and regex special characters are escaped.
+/// Patterns are matched against the directory name using the same gitignore
+/// syntax as [`ExcludeMatcher`]. This compiles the patterns on every call, so
+/// prefer building an [`ExcludeMatcher`] once when checking many directories.
 ///
 /// # Examples
 /// ```
@@ -24,35 +112,434 @@ use walkdir::{DirEntry, WalkDir};
 /// assert!(!should_exclude_dir("src", &patterns));
 /// ```
 pub fn should_exclude_dir(dir_name: &str, exclude_patterns: &[String]) -> bool {
-    exclude_patterns.iter().any(|pattern| {
-        // Handle exact matches first
-        if dir_name == pattern {
-            return true;
-        }
+    ExcludeMatcher::new(Path::new(""), exclude_patterns)
+        .map(|matcher| matcher.is_excluded(Path::new(dir_name), true))
+        .unwrap_or(false)
+}
 
-        // Handle glob patterns
-        if pattern.contains('*') || pattern.contains('?') {
-            // Convert glob pattern to regex by escaping everything first, then converting wildcards
-            let escaped = regex::escape(pattern);
-            let regex_pattern = escaped
-                .replace(r"\*", ".*") // Convert escaped * back to .*
-                .replace(r"\?", "."); // Convert escaped ? to .
+/// Largest prefix of a file read when evaluating a `file_contains` rule
+const MAX_CONTAINS_READ: u64 = 1024 * 1024;
 
-            if let Ok(regex) = Regex::new(&format!("^{}$", regex_pattern)) {
-                regex.is_match(dir_name)
+/// Extensions of the C and C++ sources a `Makefile` needs next to it to count
+const CPP_SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx", "h", "hh", "hpp", "hxx"];
+
+/// Compiled set of project indicators
+///
+/// Indicators are plain file or directory names (`Cargo.toml`, `.git`), glob
+/// patterns matched against the names in a directory (`*.gemspec`, `*.csproj`),
+/// or relative paths containing a `/` (`.github/workflows`). Names and globs are
+/// matched against a single listing of the directory, so checking a directory
+/// costs one `readdir` no matter how many indicators are configured; only
+/// nested paths need an extra `stat` each. A caller that already lists the
+/// directory, such as the scanner's walk, keeps the entries
+/// [`IndicatorMatcher::is_relevant_name`] accepts and hands them to
+/// [`IndicatorMatcher::find_in_listing`] instead.
+///
+/// [`IndicatorRule`]s add glob, file-contents and directory-shape checks on top.
+/// Their top-level entries are resolved from the same listing, so a
+/// `file_contains` rule only opens its file when the file is actually present.
+///
+/// A `Makefile` builds all kinds of projects, so it only counts as an indicator
+/// next to C/C++ sources or a CMake or Meson build.
+///
+/// # Examples
+/// ```
+/// use dprojc_types::ProjectIndicator;
+/// use dprojc_utils::IndicatorMatcher;
+/// use std::fs;
+/// use tempfile::tempdir;
+///
+/// let temp_dir = tempdir().unwrap();
+/// fs::write(temp_dir.path().join("mygem.gemspec"), "").unwrap();
+///
+/// let matcher = IndicatorMatcher::new(&["Gemfile".to_string(), "*.gemspec".to_string()]).unwrap();
+/// let found = matcher.find_in_dir(temp_dir.path()).unwrap();
+/// assert_eq!(found, vec![ProjectIndicator::Gemspec]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndicatorMatcher {
+    /// Indicator reported for each configured entry, in configuration order
+    indicators: Vec<ProjectIndicator>,
+    /// Exact names mapped to their index in `indicators`
+    names: HashMap<OsString, usize>,
+    /// Compiled globs; match `i` belongs to `indicators[glob_targets[i]]`
+    globs: GlobSet,
+    glob_targets: Vec<usize>,
+    /// Relative paths that have to be checked individually
+    nested: Vec<(String, usize)>,
+    /// `file_contains` rules as (file, needle, index)
+    contents: Vec<(String, String, usize)>,
+    /// `directory_shape` rules as (required entries, index)
+    shapes: Vec<(Vec<String>, usize)>,
+    /// Every literal top-level name a listing has to keep for the checks above
+    relevant: HashSet<OsString>,
+    /// Index of the `Makefile` indicator, which needs supporting evidence
+    makefile: Option<usize>,
+}
+
+impl IndicatorMatcher {
+    /// Compile a list of indicator names, globs and relative paths
+    pub fn new(indicators: &[String]) -> anyhow::Result<Self> {
+        Self::with_rules(indicators, &[])
+    }
+
+    /// Compile indicator names together with configured indicator rules
+    pub fn with_rules(indicators: &[String], rules: &[IndicatorRule]) -> anyhow::Result<Self> {
+        let mut matcher = Self {
+            indicators: Vec::new(),
+            names: HashMap::new(),
+            globs: GlobSet::empty(),
+            glob_targets: Vec::new(),
+            nested: Vec::new(),
+            contents: Vec::new(),
+            shapes: Vec::new(),
+            relevant: HashSet::new(),
+            makefile: None,
+        };
+        let mut globs = GlobSetBuilder::new();
+        let mut seen = HashSet::new();
+
+        for indicator in indicators {
+            let pattern = normalize_indicator_pattern(indicator);
+            if !seen.insert(pattern) {
+                continue;
+            }
+
+            // Several patterns can stand for one indicator, e.g. `*.csproj` and `*.fsproj`
+            let index = matcher.index_for(indicator_for_pattern(indicator));
+
+            if pattern.contains('/') {
+                matcher.nested.push((pattern.to_string(), index));
+            } else if is_glob_pattern(pattern) {
+                globs.add(compile_indicator_glob(pattern)?);
+                matcher.glob_targets.push(index);
             } else {
-                false
+                matcher.names.insert(OsString::from(pattern), index);
+            }
+        }
+
+        for rule in rules {
+            validate_indicator_rule(rule)?;
+            // Rules sharing a name are alternatives for the same indicator
+            let index = matcher.index_for(ProjectIndicator::Custom(rule.name.clone()));
+
+            match &rule.kind {
+                IndicatorRuleKind::Glob(pattern) => {
+                    globs.add(compile_indicator_glob(pattern)?);
+                    matcher.glob_targets.push(index);
+                }
+                IndicatorRuleKind::FileContains { file, contains } => {
+                    matcher
+                        .contents
+                        .push((file.clone(), contains.clone(), index));
+                }
+                IndicatorRuleKind::DirectoryShape(entries) => {
+                    matcher.shapes.push((entries.clone(), index));
+                }
+            }
+        }
+
+        matcher.globs = globs.build()?;
+
+        // Nested paths and rule entries are found through their first component
+        let entries: Vec<OsString> = matcher
+            .nested
+            .iter()
+            .map(|(path, _)| path)
+            .chain(matcher.contents.iter().map(|(file, _, _)| file))
+            .chain(matcher.shapes.iter().flat_map(|(entries, _)| entries))
+            .filter_map(|path| path.split('/').next())
+            .map(OsString::from)
+            .collect();
+        matcher.relevant = matcher.names.keys().cloned().chain(entries).collect();
+        matcher.makefile = matcher
+            .indicators
+            .iter()
+            .position(|i| *i == ProjectIndicator::Makefile);
+        Ok(matcher)
+    }
+
+    /// Compile the indicators and rules of a scan configuration, including
+    /// those contributed by user-defined project types
+    pub fn from_config(config: &ScanConfig) -> anyhow::Result<Self> {
+        let indicators: Vec<String> = config
+            .project_indicators
+            .iter()
+            .chain(config.project_types.iter().flat_map(|t| &t.indicators))
+            .cloned()
+            .collect();
+        let rules: Vec<IndicatorRule> = config
+            .indicator_rules
+            .iter()
+            .chain(config.project_types.iter().flat_map(|t| &t.rules))
+            .cloned()
+            .collect();
+        Self::with_rules(&indicators, &rules)
+    }
+
+    fn index_for(&mut self, indicator: ProjectIndicator) -> usize {
+        match self.indicators.iter().position(|i| *i == indicator) {
+            Some(index) => index,
+            None => {
+                self.indicators.push(indicator);
+                self.indicators.len() - 1
             }
+        }
+    }
+
+    /// Match indicators against the entry names of a directory listing
+    ///
+    /// Only names and globs can be decided from names alone; nested paths and
+    /// rules that inspect files need [`IndicatorMatcher::find_in_dir`].
+    pub fn match_names<I, S>(&self, names: I) -> Vec<ProjectIndicator>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let names: Vec<S> = names.into_iter().collect();
+        let mut found = vec![false; self.indicators.len()];
+        self.mark_names(&names, &mut found);
+        self.drop_unsupported_makefile(&names, &mut found);
+        self.collect(found)
+    }
+
+    /// Whether an entry name can take part in a match, so a listing handed
+    /// to [`IndicatorMatcher::find_in_listing`] only needs to keep these
+    pub fn is_relevant_name(&self, name: &OsStr) -> bool {
+        self.relevant.contains(name)
+            || (!self.glob_targets.is_empty() && self.globs.is_match(name))
+            || (self.makefile.is_some() && is_cpp_source(name))
+    }
+
+    /// Find all indicators present in a directory with a single listing
+    pub fn find_in_dir(&self, path: &Path) -> std::io::Result<Vec<ProjectIndicator>> {
+        let needs_listing = !self.names.is_empty()
+            || !self.glob_targets.is_empty()
+            || !self.contents.is_empty()
+            || !self.shapes.is_empty();
+        let listing: HashMap<OsString, bool> = if needs_listing {
+            std::fs::read_dir(path)?
+                .filter_map(Result::ok)
+                .map(|entry| {
+                    let is_dir = match entry.file_type() {
+                        Ok(ft) if ft.is_symlink() => entry.path().is_dir(),
+                        Ok(ft) => ft.is_dir(),
+                        Err(_) => false,
+                    };
+                    (entry.file_name(), is_dir)
+                })
+                .collect()
         } else {
-            false
+            HashMap::new()
+        };
+        Ok(self.find_in_listing(path, &listing))
+    }
+
+    /// Find all indicators present in a directory whose entries were already
+    /// listed, as names mapped to whether the entry is a directory
+    ///
+    /// The listing may leave out names [`IndicatorMatcher::is_relevant_name`]
+    /// rejects. Nested paths are still checked with a `stat` each.
+    pub fn find_in_listing(
+        &self,
+        path: &Path,
+        listing: &HashMap<OsString, bool>,
+    ) -> Vec<ProjectIndicator> {
+        let mut found = vec![false; self.indicators.len()];
+        self.mark_names(listing.keys(), &mut found);
+
+        // Some(is_dir) when a relative entry exists below `path`
+        let lookup = |relative: &str| -> Option<bool> {
+            let relative = relative.trim_end_matches('/');
+            if relative.contains('/') {
+                std::fs::metadata(path.join(relative))
+                    .ok()
+                    .map(|m| m.is_dir())
+            } else {
+                listing.get(OsStr::new(relative)).copied()
+            }
+        };
+
+        for (nested, index) in &self.nested {
+            if lookup(nested).is_some() {
+                found[*index] = true;
+            }
+        }
+
+        for (file, needle, index) in &self.contents {
+            if !found[*index]
+                && lookup(file) == Some(false)
+                && file_contains(&path.join(file), needle)
+            {
+                found[*index] = true;
+            }
+        }
+
+        for (entries, index) in &self.shapes {
+            if !found[*index]
+                && entries.iter().all(|entry| match lookup(entry) {
+                    Some(is_dir) => is_dir || !entry.ends_with('/'),
+                    None => false,
+                })
+            {
+                found[*index] = true;
+            }
         }
-    })
+
+        self.drop_unsupported_makefile(listing.keys(), &mut found);
+        self.collect(found)
+    }
+
+    /// Unflag a found `Makefile` unless C/C++ sources or a CMake or Meson
+    /// build are next to it
+    fn drop_unsupported_makefile<I, S>(&self, names: I, found: &mut [bool])
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let Some(makefile) = self.makefile.filter(|&index| found[index]) else {
+            return;
+        };
+        let build_found = self
+            .indicators
+            .iter()
+            .zip(found.iter())
+            .any(|(indicator, found)| {
+                *found
+                    && matches!(
+                        indicator,
+                        ProjectIndicator::CMakeLists | ProjectIndicator::MesonBuild
+                    )
+            });
+        if !build_found && !names.into_iter().any(|name| is_cpp_source(name.as_ref())) {
+            found[makefile] = false;
+        }
+    }
+
+    /// Flag every name or glob indicator matched by the given names, stopping
+    /// early once all of them have been seen
+    fn mark_names<I, S>(&self, names: I, found: &mut [bool])
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut remaining = self.names.len() + self.glob_targets.len();
+
+        for name in names {
+            let name = name.as_ref();
+            let glob_matches = if self.glob_targets.is_empty() {
+                Vec::new()
+            } else {
+                self.globs.matches(name)
+            };
+            let matched = self
+                .names
+                .get(name)
+                .copied()
+                .into_iter()
+                .chain(glob_matches.into_iter().map(|i| self.glob_targets[i]));
+
+            for index in matched {
+                if !found[index] {
+                    found[index] = true;
+                    remaining = remaining.saturating_sub(1);
+                }
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+
+    fn collect(&self, found: Vec<bool>) -> Vec<ProjectIndicator> {
+        self.indicators
+            .iter()
+            .zip(found)
+            .filter(|(_, found)| *found)
+            .map(|(indicator, _)| indicator.clone())
+            .collect()
+    }
+}
+
+/// Whether an entry name is a C or C++ source or header
+fn is_cpp_source(name: &OsStr) -> bool {
+    has_extension(Path::new(name), CPP_SOURCE_EXTENSIONS)
+}
+
+/// Older configurations spell the gemspec glob as a literal ".gemspec"
+fn normalize_indicator_pattern(pattern: &str) -> &str {
+    if pattern == ".gemspec" {
+        "*.gemspec"
+    } else {
+        pattern
+    }
+}
+
+/// The indicator reported when a configured name, glob or path matches
+fn indicator_for_pattern(pattern: &str) -> ProjectIndicator {
+    ProjectIndicator::from_path_name(normalize_indicator_pattern(pattern))
+        .unwrap_or_else(|| ProjectIndicator::Custom(pattern.to_string()))
+}
+
+/// Check whether an indicator or pattern contains glob metacharacters
+fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{'])
+}
+
+fn compile_indicator_glob(pattern: &str) -> anyhow::Result<Glob> {
+    Glob::new(pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid project indicator '{}': {}", pattern, e))
+}
+
+/// Check that an indicator rule is complete
+fn validate_indicator_rule(rule: &IndicatorRule) -> anyhow::Result<()> {
+    if rule.name.trim().is_empty() {
+        return Err(anyhow::anyhow!("Indicator rule name cannot be empty"));
+    }
+    let complete = match &rule.kind {
+        IndicatorRuleKind::Glob(pattern) => !pattern.trim().is_empty(),
+        IndicatorRuleKind::FileContains { file, contains } => {
+            !file.trim().is_empty() && !contains.is_empty()
+        }
+        IndicatorRuleKind::DirectoryShape(entries) => {
+            !entries.is_empty() && entries.iter().all(|e| !e.trim().is_empty())
+        }
+    };
+    if !complete {
+        return Err(anyhow::anyhow!(
+            "Indicator rule '{}' has an empty pattern, file or entry",
+            rule.name
+        ));
+    }
+    Ok(())
+}
+
+/// Check whether the start of a file contains the given text
+fn file_contains(path: &Path, needle: &str) -> bool {
+    use std::io::Read;
+
+    let mut contents = Vec::new();
+    match std::fs::File::open(path) {
+        Ok(file) => {
+            if file
+                .take(MAX_CONTAINS_READ)
+                .read_to_end(&mut contents)
+                .is_err()
+            {
+                return false;
+            }
+        }
+        Err(_) => return false,
+    }
+    String::from_utf8_lossy(&contents).contains(needle)
 }
 
 /// Check if a path contains a project indicator
 ///
 /// Scans the given path for files/directories that indicate the presence of a software project.
-/// Returns a vector of all matching project indicators found.
+/// Returns a vector of all matching project indicators found. Indicators may be globs such as
+/// `*.gemspec`; see [`IndicatorMatcher`], which should be preferred when checking many
+/// directories against the same indicator list.
 ///
 /// # Examples
 /// ```
@@ -69,20 +556,9 @@ pub fn should_exclude_dir(dir_name: &str, exclude_patterns: &[String]) -> bool {
 /// assert!(!found.is_empty());
 /// ```
 pub fn has_project_indicator(path: &Path, indicators: &[String]) -> Vec<ProjectIndicator> {
-    let mut found_indicators = Vec::new();
-
-    for indicator in indicators {
-        let indicator_path = path.join(indicator);
-        if indicator_path.exists() {
-            if let Some(indicator_type) = ProjectIndicator::from_path_name(indicator) {
-                found_indicators.push(indicator_type);
-            } else {
-                found_indicators.push(ProjectIndicator::Custom(indicator.clone()));
-            }
-        }
-    }
-
-    found_indicators
+    IndicatorMatcher::new(indicators)
+        .and_then(|matcher| Ok(matcher.find_in_dir(path)?))
+        .unwrap_or_default()
 }
 
 /// Normalize a path to absolute path, resolving relative components
@@ -98,7 +574,7 @@ pub fn normalize_path(path: &Path) -> anyhow::Result<PathBuf> {
 }
 
 /// Check if a directory entry should be skipped during walk
-pub fn should_skip_entry(entry: &DirEntry, config: &ScanConfig) -> bool {
+pub fn should_skip_entry(entry: &DirEntry, config: &ScanConfig, excludes: &ExcludeMatcher) -> bool {
     let path = entry.path();
 
     // Skip if it's not a directory
@@ -114,10 +590,8 @@ pub fn should_skip_entry(entry: &DirEntry, config: &ScanConfig) -> bool {
     }
 
     // Check exclude patterns
-    if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-        if should_exclude_dir(dir_name, &config.exclude_patterns) {
-            return true;
-        }
+    if excludes.is_excluded(path, true) {
+        return true;
     }
 
     // Skip hidden directories unless they are project indicators
@@ -130,12 +604,28 @@ pub fn should_skip_entry(entry: &DirEntry, config: &ScanConfig) -> bool {
     false
 }
 
-/// Create a WalkDir iterator with proper configuration
-pub fn create_walker(root: &Path, config: &ScanConfig) -> WalkDir {
-    let mut walker = WalkDir::new(root).follow_links(config.follow_symlinks);
-
-    if let Some(max_depth) = config.max_depth {
-        walker = walker.max_depth(max_depth);
+/// Create a directory walker with proper configuration
+///
+/// The walker honors `.gitignore`, `.ignore`, `.git/info/exclude` and the
+/// global git excludes file when `respect_gitignore` is set, and
+/// `.dprojcignore` files when `respect_dprojcignore` is set. As with git,
+/// `.gitignore` rules only apply inside a git repository. Hidden directories
+/// are not filtered here; callers decide how to treat them.
+pub fn create_walker(root: &Path, config: &ScanConfig) -> WalkBuilder {
+    let mut walker = WalkBuilder::new(root);
+    walker
+        .standard_filters(false)
+        .hidden(false)
+        .parents(true)
+        .follow_links(config.follow_symlinks)
+        .max_depth(config.max_depth)
+        .ignore(config.respect_gitignore)
+        .git_ignore(config.respect_gitignore)
+        .git_global(config.respect_gitignore)
+        .git_exclude(config.respect_gitignore);
+
+    if config.respect_dprojcignore {
+        walker.add_custom_ignore_filename(DPROJC_IGNORE_FILENAME);
     }
 
     walker
@@ -242,22 +732,9 @@ pub fn has_extension(path: &Path, extensions: &[&str]) -> bool {
     }
 }
 
-/// Get all project indicators as a HashSet for fast lookup
+/// Get the patterns of all built-in project indicators as a HashSet for fast lookup
 pub fn get_project_indicators_set() -> HashSet<String> {
-    [
-        ".git",
-        "package.json",
-        "Gemfile",
-        ".gemspec",
-        "Cargo.toml",
-        "pyproject.toml",
-        "go.mod",
-        "pom.xml",
-        "devenv.nix",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect()
+    ProjectIndicator::builtin_patterns().into_iter().collect()
 }
 
 /// Validate scan configuration for common issues
@@ -274,16 +751,31 @@ pub fn validate_scan_config(config: &ScanConfig) -> anyhow::Result<()> {
         if pattern.trim().is_empty() {
             return Err(anyhow::anyhow!("Exclude pattern cannot be whitespace-only"));
         }
-        if pattern.contains('*') || pattern.contains('?') {
-            // Test if the glob pattern can be converted to a valid regex
-            let escaped = regex::escape(pattern);
-            let regex_pattern = escaped.replace(r"\*", ".*").replace(r"\?", ".");
-            if Regex::new(&format!("^{}$", regex_pattern)).is_err() {
-                return Err(anyhow::anyhow!("Invalid exclude pattern: {}", pattern));
-            }
+    }
+
+    // Make sure every pattern compiles as a gitignore-style glob
+    ExcludeMatcher::new(Path::new(""), &config.exclude_patterns)?;
+
+    let mut type_names = HashSet::new();
+    for definition in &config.project_types {
+        let name = definition.name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Project type name cannot be empty"));
+        }
+        if !type_names.insert(name.to_lowercase()) {
+            return Err(anyhow::anyhow!("Project type '{}' is defined twice", name));
+        }
+        if definition.indicators.is_empty() && definition.rules.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Project type '{}' needs at least one indicator or rule",
+                name
+            ));
         }
     }
 
+    // Make sure globbed project indicators and indicator rules compile too
+    IndicatorMatcher::from_config(config)?;
+
     Ok(())
 }
 
@@ -324,21 +816,117 @@ pub fn is_hidden_path(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// Get the project type priority for sorting (higher numbers = higher priority)
-pub fn get_project_type_priority(project_type: &dprojc_types::ProjectType) -> i32 {
+/// Built-in types replaced by a user-defined type of the same name
+///
+/// A definition named like a built-in type, e.g. `terraform`, shadows it: the
+/// built-in type is no longer detected and the definition decides instead.
+pub fn shadowed_builtin_types(project_types: &[ProjectTypeDefinition]) -> Vec<ProjectType> {
+    project_types
+        .iter()
+        .filter_map(|definition| ProjectType::from_builtin_name(definition.name.trim()))
+        .collect()
+}
+
+/// Pick the project type for a set of found indicators
+///
+/// Built-in types are ranked by [`get_project_type_priority`]; a user-defined
+/// type matches when any of its indicators or rules was found and takes over
+/// when its priority is at least that of the built-in match. Among matching
+/// custom types the highest priority wins, then the first defined. Built-in
+/// types a definition shadows are skipped.
+pub fn classify_project(
+    indicators: &[ProjectIndicator],
+    project_types: &[ProjectTypeDefinition],
+) -> ProjectType {
+    let shadowed = shadowed_builtin_types(project_types);
+    let builtin = ProjectType::builtins_from_indicators(indicators)
+        .find(|builtin| !shadowed.contains(builtin))
+        .cloned()
+        .unwrap_or(ProjectType::Unknown);
+
+    let mut best: Option<&ProjectTypeDefinition> = None;
+    for definition in project_types {
+        if definition_matches(definition, indicators)
+            && best.is_none_or(|b| definition.priority > b.priority)
+        {
+            best = Some(definition);
+        }
+    }
+
+    match best {
+        Some(definition) if definition.priority >= get_project_type_priority(&builtin) => {
+            ProjectType::Custom(definition.name.clone())
+        }
+        _ => builtin,
+    }
+}
+
+/// Find the secondary ecosystems of a project with the given primary type
+///
+/// Returns every other built-in ecosystem that is not shadowed and every
+/// matching user-defined type, highest priority first. A bare `.git` directory
+/// is not an ecosystem.
+pub fn detect_ecosystems(
+    indicators: &[ProjectIndicator],
+    project_types: &[ProjectTypeDefinition],
+    primary: &ProjectType,
+) -> Vec<ProjectType> {
+    let shadowed = shadowed_builtin_types(project_types);
+    let mut ecosystems: Vec<ProjectType> = ProjectType::ecosystems_from_indicators(indicators)
+        .into_iter()
+        .filter(|ecosystem| !shadowed.contains(ecosystem))
+        .chain(
+            project_types
+                .iter()
+                .filter(|definition| definition_matches(definition, indicators))
+                .map(|definition| ProjectType::Custom(definition.name.clone())),
+        )
+        .filter(|ecosystem| ecosystem != primary)
+        .collect();
+    // Stable, so equal priorities keep precedence and definition order
+    ecosystems.sort_by_key(|ecosystem| -project_type_priority(ecosystem, project_types));
+    ecosystems
+}
+
+/// Whether any indicator or rule of a user-defined type was found
+fn definition_matches(definition: &ProjectTypeDefinition, indicators: &[ProjectIndicator]) -> bool {
+    definition
+        .indicators
+        .iter()
+        .map(|pattern| indicator_for_pattern(pattern))
+        .chain(
+            definition
+                .rules
+                .iter()
+                .map(|rule| ProjectIndicator::Custom(rule.name.clone())),
+        )
+        .any(|indicator| indicators.contains(&indicator))
+}
+
+/// Get the priority of a project type, taking user-defined priorities into account
+pub fn project_type_priority(
+    project_type: &ProjectType,
+    project_types: &[ProjectTypeDefinition],
+) -> i32 {
     match project_type {
-        dprojc_types::ProjectType::Rust => 10,
-        dprojc_types::ProjectType::NodeJs => 9,
-        dprojc_types::ProjectType::Python => 8,
-        dprojc_types::ProjectType::Go => 7,
-        dprojc_types::ProjectType::Java => 6,
-        dprojc_types::ProjectType::Ruby => 5,
-        dprojc_types::ProjectType::Nix => 4,
-        dprojc_types::ProjectType::Git => 1,
-        dprojc_types::ProjectType::Unknown => 0,
+        ProjectType::Custom(name) => project_types
+            .iter()
+            .find(|definition| definition.name == *name)
+            .map_or(DEFAULT_CUSTOM_TYPE_PRIORITY, |definition| {
+                definition.priority
+            }),
+        builtin => get_project_type_priority(builtin),
     }
 }
 
+/// Get the project type priority for sorting (higher numbers = higher priority)
+///
+/// Custom types report the default custom priority; use
+/// [`project_type_priority`] when the type registry is available.
+pub fn get_project_type_priority(project_type: &dprojc_types::ProjectType) -> i32 {
+    project_type.builtin_priority()
+}
+
 /// Sanitize a string for use in file paths
 pub fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -452,6 +1040,168 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_has_project_indicator_glob() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("widgets.gemspec"), "").unwrap();
+        fs::write(temp_dir.path().join("App.fsproj"), "").unwrap();
+        fs::write(temp_dir.path().join("tool.nimble"), "").unwrap();
+
+        let indicators = vec![
+            "Gemfile".to_string(),
+            "*.gemspec".to_string(),
+            "*.csproj".to_string(),
+            "*.fsproj".to_string(),
+            "*.nimble".to_string(),
+        ];
+        let found = has_project_indicator(temp_dir.path(), &indicators);
+        assert_eq!(
+            found,
+            vec![
+                ProjectIndicator::Gemspec,
+                ProjectIndicator::DotNetProject,
+                ProjectIndicator::Custom("*.nimble".to_string())
+            ]
+        );
+
+        // The legacy ".gemspec" spelling is treated as the glob
+        let found = has_project_indicator(temp_dir.path(), &[".gemspec".to_string()]);
+        assert_eq!(found, vec![ProjectIndicator::Gemspec]);
+    }
+
+    #[test]
+    fn test_indicator_matcher() {
+        let matcher = IndicatorMatcher::new(&[
+            ".git".to_string(),
+            "package.json".to_string(),
+            "*.gemspec".to_string(),
+            ".github/workflows".to_string(),
+        ])
+        .unwrap();
+
+        // Results follow configuration order and each indicator is reported once
+        let found = matcher.match_names(["b.gemspec", "package.json", "a.gemspec", ".git"]);
+        assert_eq!(
+            found,
+            vec![
+                ProjectIndicator::GitDirectory,
+                ProjectIndicator::PackageJson,
+                ProjectIndicator::Gemspec
+            ]
+        );
+        assert!(matcher.match_names(["README.md", "gemspec"]).is_empty());
+
+        // Nested paths are checked against the directory itself
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".github/workflows")).unwrap();
+        let found = matcher.find_in_dir(temp_dir.path()).unwrap();
+        assert_eq!(
+            found,
+            vec![ProjectIndicator::Custom(".github/workflows".to_string())]
+        );
+
+        assert!(IndicatorMatcher::new(&["[unclosed".to_string()]).is_err());
+        assert!(matcher
+            .find_in_dir(&temp_dir.path().join("missing"))
+            .is_err());
+
+        // A Makefile only counts next to C/C++ sources or a CMake or Meson build
+        let builtin = IndicatorMatcher::new(&ProjectIndicator::builtin_patterns()).unwrap();
+        assert!(builtin.match_names(["Makefile", "README.md"]).is_empty());
+        assert_eq!(
+            builtin.match_names(["Makefile", "Cargo.toml"]),
+            vec![ProjectIndicator::CargoToml]
+        );
+        assert_eq!(
+            builtin.match_names(["Makefile", "main.c"]),
+            vec![ProjectIndicator::Makefile]
+        );
+        assert_eq!(
+            builtin.match_names(["Makefile", "meson.build"]),
+            vec![ProjectIndicator::MesonBuild, ProjectIndicator::Makefile]
+        );
+        assert!(builtin.is_relevant_name(OsStr::new("util.hpp")));
+    }
+
+    #[test]
+    fn test_indicator_rules() {
+        let rules = vec![
+            IndicatorRule {
+                name: "poetry".to_string(),
+                kind: IndicatorRuleKind::FileContains {
+                    file: "pyproject.toml".to_string(),
+                    contains: "[tool.poetry]".to_string(),
+                },
+            },
+            IndicatorRule {
+                name: "terraform".to_string(),
+                kind: IndicatorRuleKind::Glob("*.tf".to_string()),
+            },
+            IndicatorRule {
+                name: "rails".to_string(),
+                kind: IndicatorRuleKind::DirectoryShape(vec![
+                    "Gemfile".to_string(),
+                    "config/routes.rb".to_string(),
+                    "app/".to_string(),
+                ]),
+            },
+        ];
+        let matcher = IndicatorMatcher::with_rules(&[], &rules).unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("pyproject.toml"), "[project]\nname = \"x\"\n").unwrap();
+        fs::write(dir.join("main.tf"), "").unwrap();
+        fs::write(dir.join("Gemfile"), "").unwrap();
+        fs::create_dir_all(dir.join("config")).unwrap();
+        fs::write(dir.join("config/routes.rb"), "").unwrap();
+        // A file named like a required directory does not satisfy the shape
+        fs::write(dir.join("app"), "").unwrap();
+
+        assert_eq!(
+            matcher.find_in_dir(dir).unwrap(),
+            vec![ProjectIndicator::Custom("terraform".to_string())]
+        );
+
+        fs::write(dir.join("pyproject.toml"), "[tool.poetry]\nname = \"x\"\n").unwrap();
+        fs::remove_file(dir.join("app")).unwrap();
+        fs::create_dir(dir.join("app")).unwrap();
+        assert_eq!(
+            matcher.find_in_dir(dir).unwrap(),
+            vec![
+                ProjectIndicator::Custom("poetry".to_string()),
+                ProjectIndicator::Custom("terraform".to_string()),
+                ProjectIndicator::Custom("rails".to_string()),
+            ]
+        );
+
+        // A listing trimmed to the relevant names decides the same
+        fs::write(dir.join("README.md"), "").unwrap();
+        let listing: HashMap<OsString, bool> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| matcher.is_relevant_name(&entry.file_name()))
+            .map(|entry| (entry.file_name(), entry.file_type().unwrap().is_dir()))
+            .collect();
+        assert!(!listing.contains_key(OsStr::new("README.md")));
+        assert_eq!(listing.len(), 5);
+        assert_eq!(
+            matcher.find_in_listing(dir, &listing),
+            matcher.find_in_dir(dir).unwrap()
+        );
+
+        let empty_name = IndicatorRule {
+            name: String::new(),
+            kind: IndicatorRuleKind::Glob("*.tf".to_string()),
+        };
+        assert!(IndicatorMatcher::with_rules(&[], &[empty_name]).is_err());
+        let empty_shape = IndicatorRule {
+            name: "nothing".to_string(),
+            kind: IndicatorRuleKind::DirectoryShape(Vec::new()),
+        };
+        assert!(IndicatorMatcher::with_rules(&[], &[empty_shape]).is_err());
+    }
+
     #[test]
     fn test_normalize_path() {
         let temp_dir = tempdir().unwrap();
@@ -543,7 +1293,8 @@ mod tests {
         assert!(indicators.contains(".git"));
         assert!(indicators.contains("package.json"));
         assert!(indicators.contains("Cargo.toml"));
-        assert_eq!(indicators.len(), 9);
+        assert!(indicators.contains("*.csproj"));
+        assert_eq!(indicators.len(), ProjectIndicator::builtin_patterns().len());
     }
 
     #[test]
@@ -615,6 +1366,121 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_project() {
+        let terraform = ProjectTypeDefinition {
+            name: "terraform".to_string(),
+            indicators: vec!["*.tf".to_string()],
+            rules: Vec::new(),
+            priority: DEFAULT_CUSTOM_TYPE_PRIORITY,
+        };
+        let helm = ProjectTypeDefinition {
+            name: "helm".to_string(),
+            indicators: vec!["Chart.yaml".to_string()],
+            rules: Vec::new(),
+            priority: 12,
+        };
+        let types = vec![terraform, helm];
+        let tf = ProjectIndicator::TerraformConfig;
+        let chart = ProjectIndicator::Custom("Chart.yaml".to_string());
+
+        // A custom type outranks a bare repository by default
+        assert_eq!(
+            classify_project(&[ProjectIndicator::GitDirectory, tf.clone()], &types),
+            ProjectType::Custom("terraform".to_string())
+        );
+        // ...but not a language ecosystem unless its priority is higher
+        assert_eq!(
+            classify_project(&[ProjectIndicator::PackageJson, tf.clone()], &types),
+            ProjectType::NodeJs
+        );
+        assert_eq!(
+            classify_project(&[ProjectIndicator::CargoToml, tf.clone(), chart], &types),
+            ProjectType::Custom("helm".to_string())
+        );
+        assert_eq!(
+            classify_project(&[ProjectIndicator::CargoToml], &types),
+            ProjectType::Rust
+        );
+        // The definition shadows the built-in type of the same name
+        assert_eq!(
+            classify_project(&[ProjectIndicator::GitDirectory, tf.clone()], &[]),
+            ProjectType::Terraform
+        );
+        assert_eq!(shadowed_builtin_types(&types), vec![ProjectType::Terraform]);
+
+        assert_eq!(
+            project_type_priority(&ProjectType::Custom("helm".to_string()), &types),
+            12
+        );
+        assert_eq!(
+            project_type_priority(&ProjectType::Custom("gone".to_string()), &types),
+            DEFAULT_CUSTOM_TYPE_PRIORITY
+        );
+        assert_eq!(project_type_priority(&ProjectType::Rust, &types), 10);
+    }
+
+    #[test]
+    fn test_detect_ecosystems() {
+        let types = vec![ProjectTypeDefinition {
+            name: "terraform".to_string(),
+            indicators: vec!["*.tf".to_string()],
+            rules: Vec::new(),
+            priority: DEFAULT_CUSTOM_TYPE_PRIORITY,
+        }];
+        let indicators = [
+            ProjectIndicator::GitDirectory,
+            ProjectIndicator::PackageJson,
+            ProjectIndicator::CargoToml,
+            ProjectIndicator::TerraformConfig,
+        ];
+
+        let primary = classify_project(&indicators, &types);
+        assert_eq!(primary, ProjectType::Rust);
+        assert_eq!(
+            detect_ecosystems(&indicators, &types, &primary),
+            vec![
+                ProjectType::NodeJs,
+                ProjectType::Custom("terraform".to_string())
+            ]
+        );
+        assert_eq!(
+            detect_ecosystems(&indicators, &[], &primary),
+            vec![ProjectType::NodeJs, ProjectType::Terraform]
+        );
+
+        // A plain repository has no secondary ecosystems
+        let git_only = [ProjectIndicator::GitDirectory];
+        assert!(detect_ecosystems(&git_only, &types, &ProjectType::Git).is_empty());
+    }
+
+    #[test]
+    fn test_validate_project_types() {
+        let definition = |name: &str| ProjectTypeDefinition {
+            name: name.to_string(),
+            indicators: vec!["Chart.yaml".to_string()],
+            rules: Vec::new(),
+            priority: DEFAULT_CUSTOM_TYPE_PRIORITY,
+        };
+        let config_with = |project_types| ScanConfig {
+            project_types,
+            ..Default::default()
+        };
+
+        assert!(validate_scan_config(&config_with(vec![definition("helm")])).is_ok());
+        assert!(validate_scan_config(&config_with(vec![definition("")])).is_err());
+        // A type named like a built-in one shadows it
+        assert!(validate_scan_config(&config_with(vec![definition("Rust")])).is_ok());
+        assert!(
+            validate_scan_config(&config_with(vec![definition("helm"), definition("Helm")]))
+                .is_err()
+        );
+
+        let mut empty = definition("empty");
+        empty.indicators.clear();
+        assert!(validate_scan_config(&config_with(vec![empty])).is_err());
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("normal_file"), "normal_file");
@@ -675,15 +1541,18 @@ mod tests {
     fn test_should_skip_entry() {
         let temp_dir = tempdir().unwrap();
         let config = ScanConfig::default();
+        let excludes = ExcludeMatcher::new(temp_dir.path(), &config.exclude_patterns).unwrap();
 
         // Test with a subdirectory (not root)
         let sub_dir = temp_dir.path().join("subdir");
         fs::create_dir(&sub_dir).unwrap();
-        let walker = walkdir::WalkDir::new(temp_dir.path());
+        let walker = WalkBuilder::new(temp_dir.path())
+            .standard_filters(false)
+            .build();
         for entry in walker {
             let entry = entry.unwrap();
             if entry.path() == sub_dir {
-                assert!(!should_skip_entry(&entry, &config));
+                assert!(!should_skip_entry(&entry, &config, &excludes));
                 break;
             }
         }
@@ -691,11 +1560,13 @@ mod tests {
         // Test with excluded directory
         let excluded_dir = temp_dir.path().join("node_modules");
         fs::create_dir(&excluded_dir).unwrap();
-        let walker = walkdir::WalkDir::new(temp_dir.path());
+        let walker = WalkBuilder::new(temp_dir.path())
+            .standard_filters(false)
+            .build();
         for entry in walker {
             let entry = entry.unwrap();
             if entry.path() == excluded_dir {
-                assert!(should_skip_entry(&entry, &config));
+                assert!(should_skip_entry(&entry, &config, &excludes));
                 break;
             }
         }
@@ -711,14 +1582,17 @@ mod tests {
             max_depth: Some(1),
             ..Default::default()
         };
+        let excludes = ExcludeMatcher::new(temp_dir.path(), &config.exclude_patterns).unwrap();
 
         // Walk from temp_dir, so level2 should be at depth 2
-        let walker = walkdir::WalkDir::new(temp_dir.path());
+        let walker = WalkBuilder::new(temp_dir.path())
+            .standard_filters(false)
+            .build();
         for entry in walker {
             let entry = entry.unwrap();
             if entry.path() == deep_dir {
                 assert_eq!(entry.depth(), 2);
-                assert!(should_skip_entry(&entry, &config));
+                assert!(should_skip_entry(&entry, &config, &excludes));
                 break;
             }
         }
@@ -730,7 +1604,72 @@ mod tests {
         let config = ScanConfig::default();
         let walker = create_walker(temp_dir.path(), &config);
         // Just test that it creates a walker without panicking
-        let _count = walker.into_iter().count();
+        let _count = walker.build().count();
+    }
+
+    #[test]
+    fn test_create_walker_honors_ignore_files() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        fs::write(root.join(DPROJC_IGNORE_FILENAME), "archive/\n").unwrap();
+        for dir in ["generated", "archive", "src"] {
+            fs::create_dir(root.join(dir)).unwrap();
+        }
+
+        let visited = |config: &ScanConfig| -> Vec<PathBuf> {
+            create_walker(root, config)
+                .build()
+                .filter_map(Result::ok)
+                .map(|entry| entry.into_path())
+                .collect()
+        };
+
+        let all = visited(&ScanConfig::default());
+        assert!(all.contains(&root.join("src")));
+        assert!(!all.contains(&root.join("generated")));
+        assert!(!all.contains(&root.join("archive")));
+
+        let config = ScanConfig {
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        let all = visited(&config);
+        assert!(all.contains(&root.join("generated")));
+        assert!(!all.contains(&root.join("archive")));
+
+        let config = ScanConfig {
+            respect_dprojcignore: false,
+            ..Default::default()
+        };
+        let all = visited(&config);
+        assert!(!all.contains(&root.join("generated")));
+        assert!(all.contains(&root.join("archive")));
+    }
+
+    #[test]
+    fn test_exclude_matcher_gitignore_semantics() {
+        let root = Path::new("/scan");
+        let patterns = vec![
+            "build".to_string(),
+            "!keep/build".to_string(),
+            "/dist".to_string(),
+            "docs/**/generated".to_string(),
+        ];
+        let matcher = ExcludeMatcher::new(root, &patterns).unwrap();
+
+        // Bare names match at any depth
+        assert!(matcher.is_excluded(Path::new("/scan/build"), true));
+        assert!(matcher.is_excluded(Path::new("/scan/a/b/build"), true));
+        // Negation re-includes a path
+        assert!(!matcher.is_excluded(Path::new("/scan/keep/build"), true));
+        // Anchored patterns only match at the root
+        assert!(matcher.is_excluded(Path::new("/scan/dist"), true));
+        assert!(!matcher.is_excluded(Path::new("/scan/app/dist"), true));
+        // `**` spans intermediate directories
+        assert!(matcher.is_excluded(Path::new("/scan/docs/api/v1/generated"), true));
+        assert!(!matcher.is_excluded(Path::new("/scan/src/generated"), true));
     }
 
     #[test]
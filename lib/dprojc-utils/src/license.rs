@@ -0,0 +1,269 @@
+//! License detection from manifests and license files
+//!
+//! The license field of the project's manifest is validated as an SPDX
+//! expression, accepting the loose forms crates.io and npm allow such as
+//! `MIT/Apache-2.0` or `Apache 2`. Files named `LICENSE*`, `LICENCE*`,
+//! `COPYING*` or `UNLICENSE` at the project root are identified by comparing
+//! their words with the license texts of the SPDX license list bundled in the
+//! binary, so no network access is needed.
+
+use dprojc_types::{LicenseDetection, ProjectManifest};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Similarity in percent a license file needs to be identified as a license
+pub const MIN_LICENSE_CONFIDENCE: u8 = 80;
+
+/// License files larger than this are not license texts
+const MAX_LICENSE_FILE_BYTES: u64 = 256 * 1024;
+
+/// Upper-case file name prefixes of license files
+const LICENSE_FILE_PREFIXES: &[&str] = &["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+
+/// Find the licenses of a project directory
+///
+/// The manifest's declared license comes first, followed by one entry per
+/// license file in name order. Unrecognized values and texts are kept with an
+/// `spdx` of `None` so they can be reviewed.
+pub fn read_licenses(dir: &Path, manifest: Option<&ProjectManifest>) -> Vec<LicenseDetection> {
+    let mut licenses = Vec::new();
+
+    if let Some(manifest) = manifest {
+        if let Some(declared) = &manifest.license {
+            let spdx = canonical_expression(declared);
+            licenses.push(LicenseDetection {
+                source: manifest.source.clone(),
+                declared: Some(declared.clone()),
+                confidence: if spdx.is_some() { 100 } else { 0 },
+                spdx,
+            });
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return licenses;
+    };
+    let mut files: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_ok_and(|t| t.is_file())
+                && entry
+                    .metadata()
+                    .is_ok_and(|metadata| metadata.len() <= MAX_LICENSE_FILE_BYTES)
+        })
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+            let upper = name.to_ascii_uppercase();
+            LICENSE_FILE_PREFIXES
+                .iter()
+                .any(|prefix| upper.starts_with(prefix))
+        })
+        .collect();
+    files.sort();
+
+    for name in files {
+        let Ok(text) = fs::read_to_string(dir.join(&name)) else {
+            continue;
+        };
+        let (spdx, confidence) = match identify_license_text(&text) {
+            Some((id, confidence)) if confidence >= MIN_LICENSE_CONFIDENCE => {
+                (Some(id), confidence)
+            }
+            Some((_, confidence)) => (None, confidence),
+            None => (None, 0),
+        };
+        licenses.push(LicenseDetection {
+            source: name,
+            declared: None,
+            spdx,
+            confidence,
+        });
+    }
+
+    licenses
+}
+
+/// The canonical form of an SPDX expression written loosely, if it is valid
+///
+/// # Examples
+/// ```
+/// use dprojc_utils::canonical_expression;
+///
+/// assert_eq!(canonical_expression("MIT/Apache-2.0").as_deref(), Some("MIT OR Apache-2.0"));
+/// assert_eq!(canonical_expression("ISC").as_deref(), Some("ISC"));
+/// assert_eq!(canonical_expression("SEE LICENSE IN LICENSE.txt"), None);
+/// ```
+pub fn canonical_expression(value: &str) -> Option<String> {
+    let value = value.trim();
+    let canonical = match spdx::Expression::canonicalize(value) {
+        Ok(Some(canonical)) => canonical,
+        Ok(None) => value.to_string(),
+        Err(_) => return None,
+    };
+    spdx::Expression::parse(&canonical).ok()?;
+    Some(canonical)
+}
+
+/// Identify the license a text most resembles, with the similarity in percent
+///
+/// A `SPDX-License-Identifier:` tag or a text consisting only of a valid
+/// expression is taken at its word. Otherwise word pairs are compared with
+/// every non-deprecated license text; texts identical across a family, such as
+/// `GPL-3.0-only` and `GPL-3.0-or-later`, resolve to the first in list order.
+/// Returns `None` for a text without words.
+pub fn identify_license_text(text: &str) -> Option<(String, u8)> {
+    if let Some(tag) = text
+        .lines()
+        .find_map(|line| line.split_once("SPDX-License-Identifier:"))
+        .and_then(|(_, expression)| canonical_expression(expression))
+    {
+        return Some((tag, 100));
+    }
+    let trimmed = text.trim();
+    if !trimmed.contains('\n') {
+        if let Some(expression) = canonical_expression(trimmed) {
+            return Some((expression, 100));
+        }
+    }
+
+    let pairs = word_pairs(text);
+    if pairs.is_empty() {
+        return None;
+    }
+    let mut best: Option<(&str, f64)> = None;
+    for (id, license_pairs) in license_corpus() {
+        // The Dice coefficient cannot reach the threshold when one text has
+        // far more word pairs than the other
+        let (small, large) = if pairs.len() < license_pairs.len() {
+            (pairs.len(), license_pairs.len())
+        } else {
+            (license_pairs.len(), pairs.len())
+        };
+        if (2 * small) as f64 / ((small + large) as f64) < 0.5 {
+            continue;
+        }
+        let shared = pairs.intersection(license_pairs).count();
+        let score = (2 * shared) as f64 / (pairs.len() + license_pairs.len()) as f64;
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((id, score));
+        }
+    }
+    best.map(|(id, score)| (id.to_string(), (score * 100.0).round() as u8))
+}
+
+/// Word pairs of every non-deprecated license in the SPDX list, built once
+fn license_corpus() -> &'static [(&'static str, HashSet<u64>)] {
+    static CORPUS: OnceLock<Vec<(&'static str, HashSet<u64>)>> = OnceLock::new();
+    CORPUS.get_or_init(|| {
+        spdx::identifiers::LICENSES
+            .iter()
+            .filter_map(|(name, _, _)| spdx::license_id(name))
+            .filter(|license| !license.is_deprecated())
+            .map(|license| (license.name, word_pairs(license.text())))
+            .filter(|(_, pairs)| !pairs.is_empty())
+            .collect()
+    })
+}
+
+/// Hashes of adjacent lower-case word pairs, ignoring punctuation and layout
+fn word_pairs(text: &str) -> HashSet<u64> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .map(|pair| {
+            let mut hasher = DefaultHasher::new();
+            pair.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_identify_license_texts() {
+        let mit = spdx::license_id("MIT")
+            .unwrap()
+            .text()
+            .replace("<year> <copyright holders>", "2024 Example Corp");
+        assert_eq!(
+            identify_license_text(&mit).map(|(id, _)| id).as_deref(),
+            Some("MIT")
+        );
+
+        let apache = spdx::license_id("Apache-2.0").unwrap().text();
+        let (id, confidence) = identify_license_text(apache).unwrap();
+        assert_eq!(id, "Apache-2.0");
+        assert!(confidence >= 95);
+
+        let bsd3 = spdx::license_id("BSD-3-Clause").unwrap().text();
+        assert_eq!(identify_license_text(bsd3).unwrap().0, "BSD-3-Clause");
+
+        assert_eq!(
+            identify_license_text("// SPDX-License-Identifier: MPL-2.0\n"),
+            Some(("MPL-2.0".to_string(), 100))
+        );
+        assert_eq!(identify_license_text("   \n"), None);
+    }
+
+    #[test]
+    fn test_read_licenses() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("LICENSE-MIT"),
+            spdx::license_id("MIT").unwrap().text(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("LICENSE-APACHE"),
+            spdx::license_id("Apache-2.0").unwrap().text(),
+        )
+        .unwrap();
+        fs::write(dir.join("COPYING"), "All rights reserved. Ask us first.").unwrap();
+        fs::write(dir.join("README.md"), "# Readme").unwrap();
+
+        let manifest = ProjectManifest {
+            source: "Cargo.toml".to_string(),
+            license: Some("MIT/Apache-2.0".to_string()),
+            ..ProjectManifest::default()
+        };
+        let licenses = read_licenses(dir, Some(&manifest));
+        let found: Vec<_> = licenses
+            .iter()
+            .map(|l| (l.source.as_str(), l.spdx.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Cargo.toml", Some("MIT OR Apache-2.0")),
+                ("COPYING", None),
+                ("LICENSE-APACHE", Some("Apache-2.0")),
+                ("LICENSE-MIT", Some("MIT")),
+            ]
+        );
+        assert_eq!(licenses[0].declared.as_deref(), Some("MIT/Apache-2.0"));
+
+        let unknown = ProjectManifest {
+            source: "package.json".to_string(),
+            license: Some("SEE LICENSE IN EULA".to_string()),
+            ..ProjectManifest::default()
+        };
+        let empty = tempdir().unwrap();
+        let licenses = read_licenses(empty.path(), Some(&unknown));
+        assert_eq!(licenses.len(), 1);
+        assert_eq!(licenses[0].spdx, None);
+        assert!(read_licenses(empty.path(), None).is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+//! Resolved dependencies from lockfiles
+//!
+//! Lockfiles record the exact version of every package a project resolves,
+//! including transitive ones. Packages that live inside the project itself,
+//! such as workspace members, path dependencies and linked packages, are left
+//! out so only packages fetched from a registry or repository remain.
+
+use dprojc_types::{Dependency, DependencyEcosystem};
+use std::fs;
+use std::path::Path;
+
+type LockfileReader = fn(&str) -> Vec<(String, String)>;
+
+/// Lockfile names with their ecosystem and parser
+const LOCKFILES: &[(&str, DependencyEcosystem, LockfileReader)] = &[
+    ("Cargo.lock", DependencyEcosystem::Cargo, parse_cargo_lock),
+    (
+        "package-lock.json",
+        DependencyEcosystem::Npm,
+        parse_package_lock,
+    ),
+    ("yarn.lock", DependencyEcosystem::Npm, parse_yarn_lock),
+    ("pnpm-lock.yaml", DependencyEcosystem::Npm, parse_pnpm_lock),
+    ("poetry.lock", DependencyEcosystem::PyPI, parse_poetry_lock),
+    (
+        "Gemfile.lock",
+        DependencyEcosystem::RubyGems,
+        parse_gemfile_lock,
+    ),
+    ("go.sum", DependencyEcosystem::Go, parse_go_sum),
+];
+
+/// Read the dependencies resolved by every lockfile in a project directory
+///
+/// The result is sorted and free of duplicates. Lockfiles that are missing or
+/// cannot be parsed contribute nothing.
+pub fn read_lockfiles(dir: &Path) -> Vec<Dependency> {
+    let mut dependencies: Vec<Dependency> = LOCKFILES
+        .iter()
+        .filter_map(|(file, ecosystem, parse)| {
+            let content = fs::read_to_string(dir.join(file)).ok()?;
+            Some(
+                parse(&content)
+                    .into_iter()
+                    .map(|(name, version)| Dependency {
+                        ecosystem: *ecosystem,
+                        name,
+                        version,
+                    }),
+            )
+        })
+        .flatten()
+        .collect();
+    dependencies.sort();
+    dependencies.dedup();
+    dependencies
+}
+
+/// `[[package]]` entries; those without a `source` are workspace or path crates
+fn parse_cargo_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    value
+        .get("package")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|package| package.get("source").is_some())
+        .filter_map(|package| {
+            Some((
+                package.get("name")?.as_str()?.to_string(),
+                package.get("version")?.as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// The `packages` map of lockfile v2/v3, or the nested `dependencies` of v1
+fn parse_package_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    if let Some(packages) = value.get("packages").and_then(|v| v.as_object()) {
+        return packages
+            .iter()
+            .filter(|(_, package)| package.get("link").and_then(|v| v.as_bool()) != Some(true))
+            .filter_map(|(key, package)| {
+                // Keys are install paths such as `node_modules/a/node_modules/b`
+                let (_, name) = key.rsplit_once("node_modules/")?;
+                let version = package.get("version")?.as_str()?;
+                Some((name.to_string(), version.to_string()))
+            })
+            .collect();
+    }
+
+    fn collect_v1(dependencies: &serde_json::Value, found: &mut Vec<(String, String)>) {
+        let Some(dependencies) = dependencies.as_object() else {
+            return;
+        };
+        for (name, package) in dependencies {
+            if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+                // Local packages record a `file:` reference instead of a version
+                if !version.starts_with("file:") {
+                    found.push((name.clone(), version.to_string()));
+                }
+            }
+            if let Some(nested) = package.get("dependencies") {
+                collect_v1(nested, found);
+            }
+        }
+    }
+    let mut found = Vec::new();
+    if let Some(dependencies) = value.get("dependencies") {
+        collect_v1(dependencies, &mut found);
+    }
+    found
+}
+
+/// Entries of both the classic and the Berry format
+///
+/// An entry starts with an unindented line of comma-separated specifiers such
+/// as `"@babel/core@^7.0.0", "@babel/core@^7.1.0":` and has an indented
+/// `version "7.1.2"` (classic) or `version: 7.1.2` (Berry) line.
+fn parse_yarn_lock(content: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut name: Option<String> = None;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            let specifier = line
+                .trim_end_matches(':')
+                .split(',')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches('"');
+            // Berry records workspace packages with a `@workspace:` range
+            name = if specifier.contains("@workspace:") || specifier.contains("@link:") {
+                None
+            } else {
+                package_name(specifier).map(str::to_string)
+            };
+            continue;
+        }
+        let Some(current) = &name else {
+            continue;
+        };
+        let line = line.trim();
+        if let Some(version) = line
+            .strip_prefix("version ")
+            .or_else(|| line.strip_prefix("version: "))
+        {
+            found.push((
+                current.clone(),
+                version.trim().trim_matches('"').to_string(),
+            ));
+            name = None;
+        }
+    }
+    found
+}
+
+/// The package name of a `name@range` specifier, allowing a leading `@scope/`
+fn package_name(specifier: &str) -> Option<&str> {
+    let at = specifier[1.min(specifier.len())..].find('@')? + 1;
+    Some(&specifier[..at])
+}
+
+/// Keys of the `packages` map across lockfile versions
+///
+/// Version 5 uses `/name/1.0.0_peer`, version 6 uses `/name@1.0.0(peer)` and
+/// version 9 uses `name@1.0.0(peer)`, with `@scope/name` for scoped packages.
+fn parse_pnpm_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return Vec::new();
+    };
+    value
+        .get("packages")
+        .and_then(|v| v.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, _)| {
+            let key = key.as_str()?.trim_start_matches('/');
+            let key = key.split('(').next()?;
+            let name_start = if key.starts_with('@') {
+                key.find('/')? + 1
+            } else {
+                0
+            };
+            let rest = &key[name_start..];
+            let (name, version) = match (rest.find('@'), rest.find('/')) {
+                (Some(at), slash) if slash.is_none_or(|slash| at < slash) => {
+                    (&key[..name_start + at], &rest[at + 1..])
+                }
+                (_, Some(slash)) => (
+                    &key[..name_start + slash],
+                    rest[slash + 1..].split('_').next()?,
+                ),
+                _ => return None,
+            };
+            // Local packages resolve to `link:` or `file:` references
+            if version.is_empty() || version.contains(':') {
+                return None;
+            }
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// `[[package]]` entries; directory and editable installs are skipped
+fn parse_poetry_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    value
+        .get("package")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|package| {
+            let source_type = package
+                .get("source")
+                .and_then(|source| source.get("type"))
+                .and_then(|v| v.as_str());
+            !matches!(source_type, Some("directory" | "file"))
+        })
+        .filter_map(|package| {
+            Some((
+                package.get("name")?.as_str()?.to_string(),
+                package.get("version")?.as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Top-level `specs:` entries of the `GEM` and `GIT` sections
+///
+/// Entries are indented four spaces as `name (version)`, and the version may
+/// carry a platform suffix such as `-x86_64-linux`. Lines indented further
+/// are the requirements of the entry above. `PATH` sections hold local gems.
+fn parse_gemfile_lock(content: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut in_remote_section = false;
+    for line in content.lines() {
+        if !line.starts_with(' ') {
+            in_remote_section = matches!(line.trim(), "GEM" | "GIT");
+            continue;
+        }
+        if !in_remote_section || !line.starts_with("    ") || line.starts_with("     ") {
+            continue;
+        }
+        let Some((name, version)) = line.trim().split_once(" (") else {
+            continue;
+        };
+        found.push((name.to_string(), version.trim_end_matches(')').to_string()));
+    }
+    found
+}
+
+/// Modules with a content hash; `/go.mod`-only lines are modules whose source
+/// was never needed for the build
+fn parse_go_sum(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let module = fields.next()?;
+            let version = fields.next()?;
+            (!version.ends_with("/go.mod")).then(|| (module.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_cargo_and_poetry_locks() {
+        let cargo = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        assert_eq!(parse_cargo_lock(cargo), pairs(&[("serde", "1.0.200")]));
+
+        let poetry = r#"
+[[package]]
+name = "requests"
+version = "2.31.0"
+
+[[package]]
+name = "internal"
+version = "0.1.0"
+
+[package.source]
+type = "directory"
+url = "../internal"
+"#;
+        assert_eq!(parse_poetry_lock(poetry), pairs(&[("requests", "2.31.0")]));
+    }
+
+    #[test]
+    fn test_parse_package_lock_versions() {
+        let v3 = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": {"name": "app"},
+                "node_modules/lodash": {"version": "4.17.21"},
+                "node_modules/@types/node": {"version": "20.1.0"},
+                "node_modules/a/node_modules/lodash": {"version": "3.10.1"},
+                "node_modules/local": {"resolved": "packages/local", "link": true}
+            }
+        }"#;
+        let mut found = parse_package_lock(v3);
+        found.sort();
+        assert_eq!(
+            found,
+            pairs(&[
+                ("@types/node", "20.1.0"),
+                ("lodash", "3.10.1"),
+                ("lodash", "4.17.21")
+            ])
+        );
+
+        let v1 = r#"{
+            "lockfileVersion": 1,
+            "dependencies": {
+                "a": {"version": "1.0.0", "dependencies": {"b": {"version": "2.0.0"}}},
+                "local": {"version": "file:../local"}
+            }
+        }"#;
+        assert_eq!(
+            parse_package_lock(v1),
+            pairs(&[("a", "1.0.0"), ("b", "2.0.0")])
+        );
+    }
+
+    #[test]
+    fn test_parse_yarn_lock_formats() {
+        let classic = r#"# yarn lockfile v1
+
+"@babel/core@^7.0.0", "@babel/core@^7.1.0":
+  version "7.1.2"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.1.2.tgz"
+
+lodash@^4.17.0:
+  version "4.17.21"
+"#;
+        assert_eq!(
+            parse_yarn_lock(classic),
+            pairs(&[("@babel/core", "7.1.2"), ("lodash", "4.17.21")])
+        );
+
+        let berry = r#"__metadata:
+  version: 6
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+
+"react@npm:^18.2.0":
+  version: 18.2.0
+"#;
+        assert_eq!(parse_yarn_lock(berry), pairs(&[("react", "18.2.0")]));
+    }
+
+    #[test]
+    fn test_parse_pnpm_lock_versions() {
+        let lock = r#"
+lockfileVersion: '6.0'
+packages:
+  /lodash@4.17.21:
+    resolution: {integrity: sha512-x}
+  /@types/react@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-y}
+  /left-pad/1.3.0_react@18.2.0:
+    resolution: {integrity: sha512-z}
+  /@scope/pkg/2.0.0:
+    resolution: {integrity: sha512-w}
+"#;
+        let mut found = parse_pnpm_lock(lock);
+        found.sort();
+        assert_eq!(
+            found,
+            pairs(&[
+                ("@scope/pkg", "2.0.0"),
+                ("@types/react", "18.2.0"),
+                ("left-pad", "1.3.0"),
+                ("lodash", "4.17.21"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_gemfile_lock_and_go_sum() {
+        let gemfile = "PATH
+  remote: .
+  specs:
+    mygem (0.1.0)
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.15.0-x86_64-linux)
+      racc (~> 1.4)
+    racc (1.7.1)
+
+PLATFORMS
+  ruby
+";
+        assert_eq!(
+            parse_gemfile_lock(gemfile),
+            pairs(&[("nokogiri", "1.15.0-x86_64-linux"), ("racc", "1.7.1")])
+        );
+
+        let go_sum = "github.com/pkg/errors v0.9.1 h1:abc=
+github.com/pkg/errors v0.9.1/go.mod h1:def=
+golang.org/x/sys v0.1.0/go.mod h1:ghi=
+";
+        assert_eq!(
+            parse_go_sum(go_sum),
+            pairs(&[("github.com/pkg/errors", "v0.9.1")])
+        );
+    }
+
+    #[test]
+    fn test_read_lockfiles() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("yarn.lock"),
+            "lodash@^4.0.0:\n  version \"4.17.21\"\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("package-lock.json"),
+            r#"{"packages": {"node_modules/lodash": {"version": "4.17.21"}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("go.sum"),
+            "example.com/mod v1.0.0 h1:abc=\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Cargo.lock"), "not toml [[").unwrap();
+
+        assert_eq!(
+            read_lockfiles(temp_dir.path()),
+            vec![
+                Dependency {
+                    ecosystem: DependencyEcosystem::Npm,
+                    name: "lodash".to_string(),
+                    version: "4.17.21".to_string(),
+                },
+                Dependency {
+                    ecosystem: DependencyEcosystem::Go,
+                    name: "example.com/mod".to_string(),
+                    version: "v1.0.0".to_string(),
+                },
+            ]
+        );
+    }
+}
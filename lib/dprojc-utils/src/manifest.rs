@@ -0,0 +1,572 @@
+//! Package metadata from project manifests
+//!
+//! The declared name, version, description, authors, license and homepage are
+//! read from `Cargo.toml`, `package.json`, `pyproject.toml`, `go.mod`,
+//! `*.gemspec`, `pom.xml` and `composer.json`. Only manifests whose indicator
+//! was detected are opened, and values that are not plain literals, such as
+//! `version.workspace = true` or `spec.version = Foo::VERSION`, are skipped.
+
+use dprojc_types::{ProjectIndicator, ProjectManifest, ProjectType};
+use std::fs;
+use std::path::Path;
+
+type ManifestReader = fn(&Path) -> Option<ProjectManifest>;
+
+/// Manifest readers, in the order they are tried after the primary type's own
+const READERS: &[(ProjectIndicator, ManifestReader)] = &[
+    (ProjectIndicator::CargoToml, read_cargo),
+    (ProjectIndicator::PackageJson, read_package_json),
+    (ProjectIndicator::PyprojectToml, read_pyproject),
+    (ProjectIndicator::ComposerJson, read_composer_json),
+    (ProjectIndicator::PomXml, read_pom),
+    (ProjectIndicator::Gemspec, read_gemspec),
+    (ProjectIndicator::GoMod, read_go_mod),
+];
+
+/// Read the package metadata declared in a project directory
+///
+/// The manifest of the primary project type is preferred, so a Rust crate that
+/// also has a `package.json` for tooling reports its crate name. Otherwise the
+/// first detected manifest with any metadata wins. Returns `None` when no
+/// manifest yields anything.
+pub fn read_manifest(
+    dir: &Path,
+    project_type: &ProjectType,
+    indicators: &[ProjectIndicator],
+) -> Option<ProjectManifest> {
+    let primary = project_type.indicators();
+    let (preferred, others): (Vec<_>, Vec<_>) = READERS
+        .iter()
+        .filter(|(indicator, _)| indicators.contains(indicator))
+        .partition(|(indicator, _)| primary.contains(indicator));
+
+    preferred
+        .into_iter()
+        .chain(others)
+        .filter_map(|(_, read)| read(dir))
+        .find(|manifest| !manifest.is_empty())
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn toml_string(table: &toml::Table, key: &str) -> Option<String> {
+    table.get(key)?.as_str().map(str::to_string)
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(str::to_string)
+}
+
+/// Format a person as `Name <email>`, or whichever part is present
+fn person(name: Option<&str>, email: Option<&str>) -> Option<String> {
+    match (name, email) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (Some(name), None) => Some(name.to_string()),
+        (None, Some(email)) => Some(email.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// A person given as a string or as an object with `name` and `email` keys
+fn json_person(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(person) => Some(person.clone()),
+        serde_json::Value::Object(object) => person(
+            object.get("name").and_then(|v| v.as_str()),
+            object.get("email").and_then(|v| v.as_str()),
+        ),
+        _ => None,
+    }
+}
+
+fn read_cargo(dir: &Path) -> Option<ProjectManifest> {
+    let value = read_toml(&dir.join("Cargo.toml"))?;
+    let package = value.get("package")?.as_table()?;
+    Some(ProjectManifest {
+        source: "Cargo.toml".to_string(),
+        name: toml_string(package, "name"),
+        version: toml_string(package, "version"),
+        description: toml_string(package, "description"),
+        authors: package
+            .get("authors")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        license: toml_string(package, "license"),
+        homepage: toml_string(package, "homepage"),
+    })
+}
+
+fn read_package_json(dir: &Path) -> Option<ProjectManifest> {
+    let value = read_json(&dir.join("package.json"))?;
+    let contributors = value
+        .get("contributors")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten();
+    Some(ProjectManifest {
+        source: "package.json".to_string(),
+        name: json_string(&value, "name"),
+        version: json_string(&value, "version"),
+        description: json_string(&value, "description"),
+        authors: value
+            .get("author")
+            .into_iter()
+            .chain(contributors)
+            .filter_map(json_person)
+            .collect(),
+        // Older packages use `{ "type": "MIT" }`
+        license: match value.get("license") {
+            Some(serde_json::Value::Object(license)) => license
+                .get("type")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            Some(license) => license.as_str().map(str::to_string),
+            None => None,
+        },
+        homepage: json_string(&value, "homepage"),
+    })
+}
+
+fn read_pyproject(dir: &Path) -> Option<ProjectManifest> {
+    let value = read_toml(&dir.join("pyproject.toml"))?;
+    let mut manifest = ProjectManifest {
+        source: "pyproject.toml".to_string(),
+        ..ProjectManifest::default()
+    };
+
+    // PEP 621 metadata
+    if let Some(project) = value.get("project").and_then(|v| v.as_table()) {
+        manifest.name = toml_string(project, "name");
+        manifest.version = toml_string(project, "version");
+        manifest.description = toml_string(project, "description");
+        manifest.authors = project
+            .get("authors")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|author| {
+                person(
+                    author.get("name").and_then(|v| v.as_str()),
+                    author.get("email").and_then(|v| v.as_str()),
+                )
+            })
+            .collect();
+        manifest.license = match project.get("license") {
+            Some(toml::Value::Table(license)) => toml_string(license, "text"),
+            Some(license) => license.as_str().map(str::to_string),
+            None => None,
+        };
+        manifest.homepage = project
+            .get("urls")
+            .and_then(|v| v.as_table())
+            .and_then(|urls| {
+                ["Homepage", "homepage", "Home"]
+                    .iter()
+                    .find_map(|key| toml_string(urls, key))
+            });
+    }
+
+    // Poetry keeps the same fields under `[tool.poetry]`
+    if let Some(poetry) = value
+        .get("tool")
+        .and_then(|v| v.get("poetry"))
+        .and_then(|v| v.as_table())
+    {
+        manifest.name = manifest.name.or_else(|| toml_string(poetry, "name"));
+        manifest.version = manifest.version.or_else(|| toml_string(poetry, "version"));
+        manifest.description = manifest
+            .description
+            .or_else(|| toml_string(poetry, "description"));
+        if manifest.authors.is_empty() {
+            manifest.authors = poetry
+                .get("authors")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+        }
+        manifest.license = manifest.license.or_else(|| toml_string(poetry, "license"));
+        manifest.homepage = manifest
+            .homepage
+            .or_else(|| toml_string(poetry, "homepage"));
+    }
+
+    Some(manifest)
+}
+
+fn read_composer_json(dir: &Path) -> Option<ProjectManifest> {
+    let value = read_json(&dir.join("composer.json"))?;
+    Some(ProjectManifest {
+        source: "composer.json".to_string(),
+        name: json_string(&value, "name"),
+        version: json_string(&value, "version"),
+        description: json_string(&value, "description"),
+        authors: value
+            .get("authors")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(json_person)
+            .collect(),
+        // A list of licenses means the user may choose any of them
+        license: match value.get("license") {
+            Some(serde_json::Value::Array(licenses)) => {
+                let licenses: Vec<&str> = licenses.iter().filter_map(|v| v.as_str()).collect();
+                (!licenses.is_empty()).then(|| licenses.join(" OR "))
+            }
+            Some(license) => license.as_str().map(str::to_string),
+            None => None,
+        },
+        homepage: json_string(&value, "homepage"),
+    })
+}
+
+fn read_pom(dir: &Path) -> Option<ProjectManifest> {
+    let content = fs::read_to_string(dir.join("pom.xml")).ok()?;
+    let document = roxmltree::Document::parse(&content).ok()?;
+    let project = document.root_element();
+
+    let names = |list: &str, item: &str| -> Vec<String> {
+        pom_child(project, list)
+            .into_iter()
+            .flat_map(|list| list.children())
+            .filter(|node| node.is_element() && node.tag_name().name() == item)
+            .filter_map(|node| pom_text(node, "name"))
+            .collect()
+    };
+
+    // A module inherits its version from the parent POM when it omits one
+    let version = pom_text(project, "version")
+        .or_else(|| pom_child(project, "parent").and_then(|parent| pom_text(parent, "version")));
+    let licenses = names("licenses", "license");
+
+    Some(ProjectManifest {
+        source: "pom.xml".to_string(),
+        name: pom_text(project, "artifactId"),
+        version,
+        description: pom_text(project, "description"),
+        authors: names("developers", "developer"),
+        license: (!licenses.is_empty()).then(|| licenses.join(" OR ")),
+        homepage: pom_text(project, "url"),
+    })
+}
+
+/// First child element with a local name, so the POM namespace does not matter
+fn pom_child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Trimmed text of a child element, if it has any
+fn pom_text(node: roxmltree::Node<'_, '_>, name: &str) -> Option<String> {
+    pom_child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn read_gemspec(dir: &Path) -> Option<ProjectManifest> {
+    let mut gemspecs: Vec<_> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gemspec"))
+        .collect();
+    gemspecs.sort();
+    let path = gemspecs.into_iter().next()?;
+    let content = fs::read_to_string(&path).ok()?;
+
+    let mut manifest = ProjectManifest {
+        source: path.file_name()?.to_string_lossy().into_owned(),
+        ..ProjectManifest::default()
+    };
+    let mut summary = None;
+    let mut description = None;
+
+    // Assignments look like `spec.name = "foo"` or `s.authors = ["A", "B"]`
+    for line in content.lines() {
+        let Some((target, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some((_, attribute)) = target.trim().rsplit_once('.') else {
+            continue;
+        };
+        let strings = ruby_strings(value.trim());
+        let first = strings.first().cloned();
+        match attribute {
+            "name" => manifest.name = first,
+            "version" => manifest.version = first,
+            "summary" => summary = first,
+            "description" => description = first,
+            "homepage" => manifest.homepage = first,
+            "authors" | "author" => manifest.authors = strings,
+            "license" | "licenses" if !strings.is_empty() => {
+                manifest.license = Some(strings.join(" OR "))
+            }
+            _ => {}
+        }
+    }
+    manifest.description = summary.or(description);
+
+    Some(manifest)
+}
+
+/// String literals in a Ruby expression, or nothing if it is not built only from literals
+fn ruby_strings(expression: &str) -> Vec<String> {
+    let expression = expression.trim_end_matches(".freeze").trim();
+    let inner = match expression.strip_prefix('[') {
+        Some(list) => match list.strip_suffix(']') {
+            Some(inner) => inner,
+            None => return Vec::new(),
+        },
+        None => expression,
+    };
+
+    let mut strings = Vec::new();
+    for item in inner
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let quote = match item.chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Vec::new(),
+        };
+        match item[1..].strip_suffix(quote) {
+            Some(literal) if !literal.contains(quote) => strings.push(literal.to_string()),
+            _ => return Vec::new(),
+        }
+    }
+    strings
+}
+
+fn read_go_mod(dir: &Path) -> Option<ProjectManifest> {
+    let content = fs::read_to_string(dir.join("go.mod")).ok()?;
+    let module = content.lines().find_map(|line| {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        line.strip_prefix("module ")
+            .map(|module| module.trim().trim_matches('"').to_string())
+    })?;
+    Some(ProjectManifest {
+        source: "go.mod".to_string(),
+        name: Some(module),
+        ..ProjectManifest::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn read_only(dir: &Path, indicator: ProjectIndicator) -> ProjectManifest {
+        read_manifest(dir, &ProjectType::Unknown, &[indicator]).unwrap()
+    }
+
+    #[test]
+    fn test_read_cargo_manifest() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "widget"
+version.workspace = true
+description = "Makes widgets"
+authors = ["Ada <ada@example.com>"]
+license = "MIT OR Apache-2.0"
+homepage = "https://widget.example.com"
+"#,
+        )
+        .unwrap();
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::CargoToml);
+        assert_eq!(manifest.source, "Cargo.toml");
+        assert_eq!(manifest.name.as_deref(), Some("widget"));
+        assert_eq!(manifest.version, None);
+        assert_eq!(manifest.description.as_deref(), Some("Makes widgets"));
+        assert_eq!(manifest.authors, vec!["Ada <ada@example.com>"]);
+        assert_eq!(manifest.license.as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(
+            manifest.homepage.as_deref(),
+            Some("https://widget.example.com")
+        );
+    }
+
+    #[test]
+    fn test_read_package_json_and_composer_json() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{
+                "name": "@acme/ui",
+                "version": "2.1.0",
+                "author": {"name": "Grace", "email": "grace@example.com"},
+                "contributors": ["Linus"],
+                "license": {"type": "ISC"}
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("composer.json"),
+            r#"{
+                "name": "acme/api",
+                "description": "API client",
+                "authors": [{"name": "Rasmus"}],
+                "license": ["MIT", "GPL-3.0-or-later"]
+            }"#,
+        )
+        .unwrap();
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::PackageJson);
+        assert_eq!(manifest.name.as_deref(), Some("@acme/ui"));
+        assert_eq!(manifest.version.as_deref(), Some("2.1.0"));
+        assert_eq!(manifest.authors, vec!["Grace <grace@example.com>", "Linus"]);
+        assert_eq!(manifest.license.as_deref(), Some("ISC"));
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::ComposerJson);
+        assert_eq!(manifest.name.as_deref(), Some("acme/api"));
+        assert_eq!(manifest.authors, vec!["Rasmus"]);
+        assert_eq!(manifest.license.as_deref(), Some("MIT OR GPL-3.0-or-later"));
+    }
+
+    #[test]
+    fn test_read_pyproject() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "spam"
+version = "0.3.0"
+authors = [{ name = "Guido", email = "guido@example.com" }, { email = "bot@example.com" }]
+license = { text = "BSD-3-Clause" }
+
+[project.urls]
+Homepage = "https://spam.example.com"
+
+[tool.poetry]
+description = "Spam and eggs"
+"#,
+        )
+        .unwrap();
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::PyprojectToml);
+        assert_eq!(manifest.name.as_deref(), Some("spam"));
+        assert_eq!(manifest.version.as_deref(), Some("0.3.0"));
+        assert_eq!(manifest.description.as_deref(), Some("Spam and eggs"));
+        assert_eq!(
+            manifest.authors,
+            vec!["Guido <guido@example.com>", "bot@example.com"]
+        );
+        assert_eq!(manifest.license.as_deref(), Some("BSD-3-Clause"));
+        assert_eq!(
+            manifest.homepage.as_deref(),
+            Some("https://spam.example.com")
+        );
+    }
+
+    #[test]
+    fn test_read_pom() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("pom.xml"),
+            r#"<?xml version="1.0"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <parent><groupId>com.acme</groupId><version>1.4.0</version></parent>
+  <artifactId>billing</artifactId>
+  <description>Billing service</description>
+  <url>https://acme.example.com/billing</url>
+  <licenses><license><name>Apache-2.0</name></license></licenses>
+  <developers><developer><name>Barbara</name></developer></developers>
+</project>"#,
+        )
+        .unwrap();
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::PomXml);
+        assert_eq!(manifest.name.as_deref(), Some("billing"));
+        assert_eq!(manifest.version.as_deref(), Some("1.4.0"));
+        assert_eq!(manifest.description.as_deref(), Some("Billing service"));
+        assert_eq!(manifest.authors, vec!["Barbara"]);
+        assert_eq!(manifest.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            manifest.homepage.as_deref(),
+            Some("https://acme.example.com/billing")
+        );
+    }
+
+    #[test]
+    fn test_read_gemspec_and_go_mod() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("ruby_gem.gemspec"),
+            r#"
+Gem::Specification.new do |spec|
+  spec.name          = "ruby_gem"
+  spec.version       = RubyGem::VERSION
+  spec.authors       = ["Matz", 'DHH']
+  spec.summary       = "A gem".freeze
+  spec.description   = "A longer description"
+  spec.license       = "MIT"
+end
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("go.mod"),
+            "module github.com/acme/tool // tool\n\ngo 1.22\n",
+        )
+        .unwrap();
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::Gemspec);
+        assert_eq!(manifest.source, "ruby_gem.gemspec");
+        assert_eq!(manifest.name.as_deref(), Some("ruby_gem"));
+        assert_eq!(manifest.version, None);
+        assert_eq!(manifest.authors, vec!["Matz", "DHH"]);
+        assert_eq!(manifest.description.as_deref(), Some("A gem"));
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+
+        let manifest = read_only(temp_dir.path(), ProjectIndicator::GoMod);
+        assert_eq!(manifest.name.as_deref(), Some("github.com/acme/tool"));
+    }
+
+    #[test]
+    fn test_read_manifest_prefers_primary_type() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"core\"\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("package.json"), r#"{"name": "tools"}"#).unwrap();
+        let indicators = [ProjectIndicator::PackageJson, ProjectIndicator::CargoToml];
+
+        let manifest = read_manifest(temp_dir.path(), &ProjectType::NodeJs, &indicators).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("tools"));
+        let manifest = read_manifest(temp_dir.path(), &ProjectType::Rust, &indicators).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("core"));
+
+        // Undetected manifests are never opened, and empty ones are skipped
+        assert_eq!(
+            read_manifest(temp_dir.path(), &ProjectType::Rust, &[]),
+            None
+        );
+        fs::write(temp_dir.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+        let manifest = read_manifest(temp_dir.path(), &ProjectType::Rust, &indicators).unwrap();
+        assert_eq!(manifest.source, "package.json");
+    }
+}
@@ -0,0 +1,193 @@
+//! Classifying projects by how long they have been idle
+//!
+//! Only signals observable on this machine are used: the HEAD commit time and
+//! working tree state recorded by the last scan, the newest file modification
+//! found now, and visits recorded through the shell integration. Files under
+//! `.git` and build artifact directories are ignored, since a build or a fetch
+//! touches them without anyone working on the project.
+
+use crate::ARTIFACT_DIR_NAMES;
+use chrono::{DateTime, Utc};
+use dprojc_types::{ActivitySignals, Project, ScanConfig, StaleProject, Staleness};
+use ignore::WalkBuilder;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Newest modification time of the files of a project directory
+///
+/// Returns `None` when the directory is missing or has no readable files
+/// outside version control and build artifacts.
+pub fn last_source_modification(dir: &Path) -> Option<DateTime<Utc>> {
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .follow_links(false)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let name = entry.file_name();
+            !(is_dir
+                && entry.depth() > 0
+                && (name == ".git" || ARTIFACT_DIR_NAMES.iter().any(|dir| name == *dir)))
+        })
+        .build();
+    walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .map(DateTime::<Utc>::from)
+        .max()
+}
+
+/// Gather the activity signals of a project and classify it
+pub fn assess_staleness(
+    project: &Project,
+    last_accessed: Option<DateTime<Utc>>,
+    config: &ScanConfig,
+    now: DateTime<Utc>,
+) -> StaleProject {
+    let signals = ActivitySignals {
+        last_commit: project.git.as_ref().and_then(|git| git.head_date),
+        last_modified: last_source_modification(&project.path),
+        last_accessed,
+        uncommitted_changes: project.git.as_ref().is_some_and(|git| git.is_dirty()),
+    };
+    let idle_days = signals
+        .last_activity()
+        .map(|last| (now - last).num_days().max(0));
+    StaleProject {
+        path: project.path.clone(),
+        staleness: Staleness::classify(
+            idle_days,
+            signals.uncommitted_changes,
+            config.stale_after_days,
+            config.abandoned_after_days,
+        ),
+        idle_days,
+        signals,
+    }
+}
+
+/// Classify every project, longest idle first
+///
+/// Projects without any observed activity come first, then by idle days and
+/// path.
+pub fn assess_projects(
+    projects: &[Project],
+    last_accessed: &HashMap<PathBuf, DateTime<Utc>>,
+    config: &ScanConfig,
+    now: DateTime<Utc>,
+) -> Vec<StaleProject> {
+    let mut assessed: Vec<StaleProject> = projects
+        .iter()
+        .map(|project| {
+            let accessed = last_accessed.get(&project.path).copied();
+            assess_staleness(project, accessed, config, now)
+        })
+        .collect();
+    assessed.sort_by(|a, b| {
+        let idle = |project: &StaleProject| project.idle_days.unwrap_or(i64::MAX);
+        idle(b).cmp(&idle(a)).then_with(|| a.path.cmp(&b.path))
+    });
+    assessed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use dprojc_types::GitMetadata;
+    use std::fs::{self, File};
+    use std::time::SystemTime;
+    use tempfile::tempdir;
+
+    fn write_modified(root: &Path, path: &str, modified: DateTime<Utc>) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "x").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::from(modified))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_last_source_modification() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let now = Utc::now();
+        let source = now - Duration::days(200);
+        write_modified(root, "src/main.rs", source);
+        write_modified(root, "Cargo.toml", now - Duration::days(400));
+        for artifact in [
+            "target/debug/app",
+            ".git/FETCH_HEAD",
+            "web/node_modules/react/index.js",
+        ] {
+            write_modified(root, artifact, now - Duration::days(1));
+        }
+
+        let newest = last_source_modification(root).unwrap();
+        assert_eq!(newest.timestamp(), source.timestamp());
+        assert_eq!(last_source_modification(&root.join("missing")), None);
+    }
+
+    #[test]
+    fn test_assess_projects() {
+        let config = ScanConfig::default();
+        let now = Utc::now();
+        let active = tempdir().unwrap();
+        let stale = tempdir().unwrap();
+        let abandoned = tempdir().unwrap();
+        let dirty = tempdir().unwrap();
+        for dir in [&active, &stale, &abandoned, &dirty] {
+            write_modified(dir.path(), "README.md", now - Duration::days(500));
+        }
+
+        let committed = GitMetadata {
+            head_date: Some(now - Duration::days(120)),
+            ..GitMetadata::default()
+        };
+        let uncommitted = GitMetadata {
+            dirty_count: 2,
+            ..GitMetadata::default()
+        };
+        let projects = vec![
+            Project {
+                path: active.path().to_path_buf(),
+                ..Default::default()
+            },
+            Project {
+                path: stale.path().to_path_buf(),
+                git: Some(committed),
+                ..Default::default()
+            },
+            Project {
+                path: abandoned.path().to_path_buf(),
+                ..Default::default()
+            },
+            Project {
+                path: dirty.path().to_path_buf(),
+                git: Some(uncommitted),
+                ..Default::default()
+            },
+        ];
+        let last_accessed = HashMap::from([(active.path().to_path_buf(), now - Duration::days(3))]);
+
+        let assessed = assess_projects(&projects, &last_accessed, &config, now);
+        let found: Vec<_> = assessed
+            .iter()
+            .map(|p| (p.path.as_path(), p.staleness, p.idle_days))
+            .collect();
+        let mut idle = vec![
+            (abandoned.path(), Staleness::Abandoned, Some(500)),
+            (dirty.path(), Staleness::Stale, Some(500)),
+        ];
+        idle.sort_by_key(|(path, _, _)| *path);
+        idle.push((stale.path(), Staleness::Stale, Some(120)));
+        idle.push((active.path(), Staleness::Active, Some(3)));
+        assert_eq!(found, idle);
+        assert!(assessed[3].signals.last_accessed.is_some());
+        assert!(assessed.iter().any(|p| p.signals.uncommitted_changes));
+    }
+}
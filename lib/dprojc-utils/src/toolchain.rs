@@ -0,0 +1,348 @@
+//! Runtime and tool versions pinned by files in a project
+//!
+//! Version managers and build tools each keep the pinned version in their own
+//! file: `rust-toolchain.toml` for rustup, `.nvmrc` for nvm, `.tool-versions`
+//! for asdf and mise, the `go` directive of `go.mod`, `engines` in
+//! `package.json`, and package attributes or `nixpkgs` inputs in Nix files.
+//! Every pin is kept with the file it came from, so conflicting pins stay
+//! visible.
+
+use dprojc_types::ToolchainPin;
+use std::fs;
+use std::path::Path;
+
+type PinReader = fn(&str) -> Vec<(String, String)>;
+
+/// Pin files at the project root with their parser, in the order they are read
+const PIN_FILES: &[(&str, PinReader)] = &[
+    ("rust-toolchain.toml", parse_rust_toolchain),
+    ("rust-toolchain", parse_rust_toolchain),
+    (".nvmrc", parse_node_version),
+    (".node-version", parse_node_version),
+    (".python-version", parse_python_version),
+    (".ruby-version", parse_ruby_version),
+    (".tool-versions", parse_tool_versions),
+    ("go.mod", parse_go_mod),
+    ("package.json", parse_package_engines),
+    ("devenv.nix", parse_nix),
+    ("flake.nix", parse_nix),
+];
+
+/// Read the toolchain versions pinned at the root of a project directory
+///
+/// Files that are missing or cannot be parsed are skipped. A file naming the
+/// same tool and version twice yields one pin.
+pub fn read_toolchains(dir: &Path) -> Vec<ToolchainPin> {
+    let mut pins: Vec<ToolchainPin> = Vec::new();
+    for (file, parse) in PIN_FILES {
+        let Ok(content) = fs::read_to_string(dir.join(file)) else {
+            continue;
+        };
+        for (tool, version) in parse(&content) {
+            let pin = ToolchainPin {
+                tool: ToolchainPin::canonical_tool(&tool),
+                version,
+                source: file.to_string(),
+            };
+            if !pins.contains(&pin) {
+                pins.push(pin);
+            }
+        }
+    }
+    pins
+}
+
+/// Drop a `v` written before a version number, as in `v18.17.0`
+fn strip_v(version: &str) -> String {
+    match version.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest.to_string(),
+        _ => version.to_string(),
+    }
+}
+
+/// The first non-comment line of a file holding just a version
+fn single_version(tool: &str, content: &str) -> Vec<(String, String)> {
+    let Some(line) = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+    else {
+        return Vec::new();
+    };
+    // rbenv and chruby accept an engine prefix such as `ruby-3.2.2`
+    let version = line
+        .strip_prefix(&format!("{}-", tool))
+        .unwrap_or(line)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    vec![(tool.to_string(), strip_v(version))]
+}
+
+fn parse_node_version(content: &str) -> Vec<(String, String)> {
+    single_version("node", content)
+}
+
+fn parse_python_version(content: &str) -> Vec<(String, String)> {
+    single_version("python", content)
+}
+
+fn parse_ruby_version(content: &str) -> Vec<(String, String)> {
+    single_version("ruby", content)
+}
+
+/// The channel of a `rust-toolchain.toml`, or of a legacy one-line `rust-toolchain`
+fn parse_rust_toolchain(content: &str) -> Vec<(String, String)> {
+    match content.parse::<toml::Table>() {
+        Ok(table) => table
+            .get("toolchain")
+            .and_then(|toolchain| toolchain.get("channel"))
+            .and_then(|channel| channel.as_str())
+            .map(|channel| vec![("rust".to_string(), channel.to_string())])
+            .unwrap_or_default(),
+        Err(_) => single_version("rust", content),
+    }
+}
+
+/// One pin per `tool version...` line of an asdf or mise `.tool-versions`
+///
+/// The first version listed is the one selected; later ones are fallbacks.
+fn parse_tool_versions(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let tool = fields.next()?;
+            let version = fields.next()?;
+            Some((tool.to_string(), strip_v(version)))
+        })
+        .collect()
+}
+
+/// The minimum Go version from the `go` directive of `go.mod`
+fn parse_go_mod(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .find_map(|line| {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            let version = line.strip_prefix("go ")?.trim();
+            Some(vec![("go".to_string(), version.to_string())])
+        })
+        .unwrap_or_default()
+}
+
+/// The version ranges in the `engines` object of `package.json`
+fn parse_package_engines(content: &str) -> Vec<(String, String)> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(engines) = package.get("engines").and_then(|e| e.as_object()) else {
+        return Vec::new();
+    };
+    engines
+        .iter()
+        .filter_map(|(tool, range)| Some((tool.clone(), range.as_str()?.trim().to_string())))
+        .collect()
+}
+
+/// Nixpkgs attribute prefixes that carry a version, with the tool they provide
+///
+/// For packed attributes the first digit is the major version and the rest the
+/// minor one, as in `python311`; others separate components with `_`, as in
+/// `go_1_21`.
+const NIX_PACKAGES: &[(&str, &str, bool)] = &[
+    ("nodejs", "node", false),
+    ("python", "python", true),
+    ("php", "php", true),
+    ("ruby", "ruby", false),
+    ("go", "go", false),
+    ("jdk", "java", false),
+    ("openjdk", "java", false),
+    ("erlang", "erlang", false),
+    ("elixir", "elixir", false),
+    ("zig", "zig", false),
+];
+
+/// Pins from a `devenv.nix` or `flake.nix`
+///
+/// Versioned package attributes such as `pkgs.nodejs_18` or `pkgs.python311`,
+/// devenv `languages.<name>.version` settings and the branch of a `nixpkgs`
+/// input URL are recognized. Unversioned attributes follow whatever nixpkgs
+/// provides, so they are not pins.
+fn parse_nix(content: &str) -> Vec<(String, String)> {
+    let mut pins = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+
+        for (start, _) in line.match_indices("pkgs.") {
+            let attribute: String = line[start + "pkgs.".len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+                .collect();
+            if let Some(pin) = nix_package_pin(&attribute) {
+                pins.push(pin);
+            }
+        }
+
+        if let Some(rest) = line.trim().strip_prefix("languages.") {
+            if let Some((language, value)) = rest.split_once(".version") {
+                if let Some(version) = nix_string(value) {
+                    pins.push((language.to_string(), version));
+                }
+            }
+        }
+
+        if let Some((_, value)) = line.split_once("nixpkgs.url") {
+            if let Some(reference) = nix_string(value)
+                .as_deref()
+                .and_then(|url| url.strip_prefix("github:NixOS/nixpkgs/"))
+            {
+                pins.push(("nixpkgs".to_string(), reference.to_string()));
+            }
+        }
+    }
+    pins
+}
+
+/// The string assigned by `= "value"`
+fn nix_string(assignment: &str) -> Option<String> {
+    let value = assignment.trim_start().strip_prefix('=')?.trim_start();
+    let value = value.strip_prefix('"')?;
+    Some(value[..value.find('"')?].to_string())
+}
+
+/// The tool and version of a versioned nixpkgs attribute such as `nodejs_18`
+fn nix_package_pin(attribute: &str) -> Option<(String, String)> {
+    let (prefix, tool, packed) = NIX_PACKAGES.iter().find(|(prefix, _, _)| {
+        attribute.strip_prefix(prefix).is_some_and(|rest| {
+            rest.starts_with(|c: char| c.is_ascii_digit() || c == '_' || c == '-')
+        })
+    })?;
+    let numbers: Vec<&str> = attribute[prefix.len()..]
+        .split(['_', '-'])
+        .filter(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    let version = match numbers.as_slice() {
+        [] => return None,
+        [digits] if *packed && digits.len() > 1 => format!("{}.{}", &digits[..1], &digits[1..]),
+        parts => parts.join("."),
+    };
+    Some((tool.to_string(), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn pins(dir: &Path) -> Vec<(String, String, String)> {
+        read_toolchains(dir)
+            .into_iter()
+            .map(|pin| (pin.tool, pin.version, pin.source))
+            .collect()
+    }
+
+    fn pin(tool: &str, version: &str, source: &str) -> (String, String, String) {
+        (tool.to_string(), version.to_string(), source.to_string())
+    }
+
+    #[test]
+    fn test_read_version_files() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"1.75.0\"\ncomponents = [\"clippy\"]\n",
+        )
+        .unwrap();
+        fs::write(dir.join(".nvmrc"), "v16.20.1\n").unwrap();
+        fs::write(dir.join(".python-version"), "3.11.4\n3.10.12\n").unwrap();
+        fs::write(dir.join(".ruby-version"), "ruby-3.2.2\n").unwrap();
+        fs::write(
+            dir.join(".tool-versions"),
+            "# runtimes\nnodejs 16.20.1\ngolang 1.21.5 1.20.0\n",
+        )
+        .unwrap();
+        fs::write(dir.join("go.mod"), "module example.com/app\n\ngo 1.21\n").unwrap();
+        fs::write(
+            dir.join("package.json"),
+            r#"{"name": "app", "engines": {"node": ">=16 <19", "npm": "^8"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pins(dir),
+            vec![
+                pin("rust", "1.75.0", "rust-toolchain.toml"),
+                pin("node", "16.20.1", ".nvmrc"),
+                pin("python", "3.11.4", ".python-version"),
+                pin("ruby", "3.2.2", ".ruby-version"),
+                pin("node", "16.20.1", ".tool-versions"),
+                pin("go", "1.21.5", ".tool-versions"),
+                pin("go", "1.21", "go.mod"),
+                pin("node", ">=16 <19", "package.json"),
+                pin("npm", "^8", "package.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_legacy_rust_toolchain() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("rust-toolchain"),
+            "nightly-2024-01-01\n",
+        )
+        .unwrap();
+        assert_eq!(
+            pins(temp_dir.path()),
+            vec![pin("rust", "nightly-2024-01-01", "rust-toolchain")]
+        );
+    }
+
+    #[test]
+    fn test_read_nix_pins() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("devenv.nix"),
+            r#"{ pkgs, ... }: {
+  packages = [ pkgs.git pkgs.nodejs_18 pkgs.python311 ];
+  languages.go.package = pkgs.go_1_21;
+  languages.rust.version = "1.74.0";
+  # pkgs.nodejs_14 is no longer used
+}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("flake.nix"),
+            r#"{
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixos-23.11";
+  outputs = { self, nixpkgs }: { };
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pins(dir),
+            vec![
+                pin("node", "18", "devenv.nix"),
+                pin("python", "3.11", "devenv.nix"),
+                pin("go", "1.21", "devenv.nix"),
+                pin("rust", "1.74.0", "devenv.nix"),
+                pin("nixpkgs", "nixos-23.11", "flake.nix"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_toolchains_without_pins() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("package.json"), r#"{"name": "app"}"#).unwrap();
+        fs::write(temp_dir.path().join(".nvmrc"), "\n").unwrap();
+        assert!(read_toolchains(temp_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,484 @@
+//! Workspace and monorepo membership
+//!
+//! A workspace root lists its members in a manifest: `[workspace] members` in
+//! `Cargo.toml`, `workspaces` in `package.json`, `packages` in
+//! `pnpm-workspace.yaml` or `use` directives in `go.work`. Member entries are
+//! paths or globs relative to the root, and a project matching one is recorded
+//! as a child of the nearest enclosing workspace.
+
+use dprojc_types::Project;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Member patterns declared by the workspace manifests of a directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceManifest {
+    /// Paths or globs, relative to the workspace root, that select members
+    pub members: Vec<String>,
+    /// Paths or globs, relative to the workspace root, that are never members
+    pub exclude: Vec<String>,
+}
+
+impl WorkspaceManifest {
+    /// Read the workspace manifests in a directory
+    ///
+    /// Patterns from every manifest present are combined, so a repository that
+    /// is both a Cargo and an npm workspace groups the members of both.
+    /// Manifests that are missing or fail to parse contribute nothing, and
+    /// `None` is returned when no members are declared at all.
+    pub fn read(dir: &Path) -> Option<Self> {
+        let mut manifest = WorkspaceManifest::default();
+        manifest.read_cargo(&dir.join("Cargo.toml"));
+        manifest.read_package_json(&dir.join("package.json"));
+        manifest.read_pnpm(&dir.join("pnpm-workspace.yaml"));
+        manifest.read_go_work(&dir.join("go.work"));
+        (!manifest.members.is_empty()).then_some(manifest)
+    }
+
+    fn read_cargo(&mut self, path: &Path) {
+        let Some(value) = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| content.parse::<toml::Table>().ok())
+        else {
+            return;
+        };
+        let Some(workspace) = value.get("workspace") else {
+            return;
+        };
+        let strings = |key: &str| -> Vec<String> {
+            workspace
+                .get(key)
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        };
+        self.members.extend(strings("members"));
+        self.exclude.extend(strings("exclude"));
+    }
+
+    fn read_package_json(&mut self, path: &Path) {
+        let Some(value) = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            return;
+        };
+        // Either an array of patterns or Yarn's `{ "packages": [...] }` form
+        let workspaces = match value.get("workspaces") {
+            Some(serde_json::Value::Object(object)) => object.get("packages"),
+            other => other,
+        };
+        let patterns = workspaces
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str());
+        self.add_patterns(patterns);
+    }
+
+    fn read_pnpm(&mut self, path: &Path) {
+        let Some(value) = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+        else {
+            return;
+        };
+        let patterns = value
+            .get("packages")
+            .and_then(|v| v.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str());
+        self.add_patterns(patterns);
+    }
+
+    fn read_go_work(&mut self, path: &Path) {
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+        let mut in_block = false;
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            if in_block {
+                if line == ")" {
+                    in_block = false;
+                } else if !line.is_empty() {
+                    self.members.push(line.trim_matches('"').to_string());
+                }
+            } else if let Some(rest) = line.strip_prefix("use") {
+                let rest = rest.trim();
+                if rest == "(" {
+                    in_block = true;
+                } else if !rest.is_empty() && line.starts_with("use ") {
+                    self.members.push(rest.trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+
+    /// Add patterns where a leading `!` excludes instead of includes
+    fn add_patterns<'a>(&mut self, patterns: impl Iterator<Item = &'a str>) {
+        for pattern in patterns {
+            match pattern.strip_prefix('!') {
+                Some(excluded) => self.exclude.push(excluded.to_string()),
+                None => self.members.push(pattern.to_string()),
+            }
+        }
+    }
+
+    /// Compile the patterns for matching paths below `root`
+    fn matcher(&self, root: &Path) -> WorkspaceMatcher {
+        WorkspaceMatcher {
+            members: compile_member_globs(root, &self.members),
+            exclude: compile_member_globs(root, &self.exclude),
+        }
+    }
+}
+
+/// Compiled member patterns of one workspace
+struct WorkspaceMatcher {
+    members: GlobSet,
+    exclude: GlobSet,
+}
+
+impl WorkspaceMatcher {
+    fn is_member(&self, root: &Path, path: &Path) -> bool {
+        match path.strip_prefix(root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => {
+                self.members.is_match(relative) && !self.exclude.is_match(relative)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compile member patterns relative to `root`, skipping invalid ones and those
+/// that point outside of it
+fn compile_member_globs(root: &Path, patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let Some(relative) = normalize_member_pattern(root, pattern) else {
+            continue;
+        };
+        // `*` stays within one path component, as in Cargo, npm and pnpm
+        if let Ok(glob) = GlobBuilder::new(&relative).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+/// Resolve `.` and `..` in a member pattern and make it relative to `root`
+fn normalize_member_pattern(root: &Path, pattern: &str) -> Option<String> {
+    let mut resolved = root.to_path_buf();
+    for component in Path::new(pattern.trim()).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(part) => resolved.push(part),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    let relative = resolved.strip_prefix(root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Record each project's nearest enclosing workspace in [`Project::parent`]
+///
+/// Only workspaces among `projects` are considered, so a member scanned
+/// without its workspace root gets no parent.
+///
+/// # Examples
+/// ```
+/// use dprojc_types::{Project, ProjectType};
+/// use dprojc_utils::assign_workspace_parents;
+/// use std::fs;
+/// use tempfile::tempdir;
+///
+/// let temp_dir = tempdir().unwrap();
+/// let root = temp_dir.path().to_path_buf();
+/// fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+///
+/// let project = |path: std::path::PathBuf| Project {
+///     path,
+///     project_type: ProjectType::Rust,
+///     ..Default::default()
+/// };
+/// let mut projects = vec![project(root.clone()), project(root.join("crates/core"))];
+/// assign_workspace_parents(&mut projects);
+/// assert_eq!(projects[0].parent, None);
+/// assert_eq!(projects[1].parent, Some(root));
+/// ```
+pub fn assign_workspace_parents(projects: &mut [Project]) {
+    let workspaces: Vec<(PathBuf, WorkspaceMatcher)> = projects
+        .iter()
+        .filter_map(|project| {
+            WorkspaceManifest::read(&project.path)
+                .map(|manifest| (project.path.clone(), manifest.matcher(&project.path)))
+        })
+        .collect();
+
+    for project in projects.iter_mut() {
+        project.parent = workspaces
+            .iter()
+            .filter(|(root, matcher)| matcher.is_member(root, &project.path))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(root, _)| root.clone());
+    }
+}
+
+/// Reorder projects so members directly follow their workspace
+///
+/// Workspaces and standalone projects keep their relative order, and members
+/// whose workspace is not in the list are treated as standalone.
+pub fn group_workspace_members(projects: &mut Vec<Project>) {
+    let paths: HashSet<PathBuf> = projects.iter().map(|p| p.path.clone()).collect();
+    let mut children: HashMap<PathBuf, Vec<Project>> = HashMap::new();
+    let mut roots = Vec::new();
+    for project in projects.drain(..) {
+        match project
+            .parent
+            .clone()
+            .filter(|parent| paths.contains(parent))
+        {
+            Some(parent) => children.entry(parent).or_default().push(project),
+            None => roots.push(project),
+        }
+    }
+
+    fn push_with_members(
+        project: Project,
+        children: &mut HashMap<PathBuf, Vec<Project>>,
+        out: &mut Vec<Project>,
+    ) {
+        let members = children.remove(&project.path).unwrap_or_default();
+        out.push(project);
+        for member in members {
+            push_with_members(member, children, out);
+        }
+    }
+
+    for project in roots {
+        push_with_members(project, &mut children, projects);
+    }
+    // Parent cycles cannot come from a scan, but keep every project regardless
+    projects.extend(children.into_values().flatten());
+}
+
+/// Number of workspaces between each project and the top of its group
+///
+/// Standalone projects and workspace roots are at depth 0, their members at 1,
+/// and so on. Only parents present in `projects` count.
+pub fn workspace_depths(projects: &[Project]) -> Vec<usize> {
+    let parents: HashMap<&Path, &Path> = projects
+        .iter()
+        .filter_map(|p| p.parent.as_deref().map(|parent| (p.path.as_path(), parent)))
+        .collect();
+    let present: HashSet<&Path> = projects.iter().map(|p| p.path.as_path()).collect();
+
+    projects
+        .iter()
+        .map(|project| {
+            let mut depth = 0;
+            let mut current = project.path.as_path();
+            while let Some(parent) = parents.get(current).filter(|p| present.contains(**p)) {
+                depth += 1;
+                current = parent;
+                if depth > projects.len() {
+                    break;
+                }
+            }
+            depth
+        })
+        .collect()
+}
+
+/// Hide workspace members, returning how many were folded into each workspace
+///
+/// Members of nested workspaces are counted towards the outermost workspace
+/// that remains visible.
+pub fn collapse_workspace_members(projects: &mut Vec<Project>) -> HashMap<PathBuf, usize> {
+    group_workspace_members(projects);
+    let depths = workspace_depths(projects);
+
+    let mut counts = HashMap::new();
+    let mut visible = Vec::with_capacity(projects.len());
+    for (project, depth) in projects.drain(..).zip(depths) {
+        if depth == 0 {
+            visible.push(project);
+        } else if let Some(root) = visible.last() {
+            *counts.entry(root.path.clone()).or_insert(0) += 1;
+        }
+    }
+    *projects = visible;
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_workspace_manifests() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        assert_eq!(WorkspaceManifest::read(root), None);
+
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/legacy\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": {"packages": ["packages/*"]}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'apps/*'\n  - '!apps/sandbox'\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("go.work"),
+            "go 1.22\n\nuse (\n\t./svc/api // api service\n\t\"./svc/worker\"\n)\nuse ./lib\n",
+        )
+        .unwrap();
+
+        let manifest = WorkspaceManifest::read(root).unwrap();
+        assert_eq!(
+            manifest.members,
+            vec![
+                "crates/*",
+                "tools/cli",
+                "packages/*",
+                "apps/*",
+                "./svc/api",
+                "./svc/worker",
+                "./lib"
+            ]
+        );
+        assert_eq!(manifest.exclude, vec!["crates/legacy", "apps/sandbox"]);
+    }
+
+    #[test]
+    fn test_read_package_json_workspace_array() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "mono", "workspaces": ["packages/*", "docs"]}"#,
+        )
+        .unwrap();
+        let manifest = WorkspaceManifest::read(temp_dir.path()).unwrap();
+        assert_eq!(manifest.members, vec!["packages/*", "docs"]);
+
+        // A plain package is not a workspace
+        fs::write(temp_dir.path().join("package.json"), r#"{"name": "app"}"#).unwrap();
+        assert_eq!(WorkspaceManifest::read(temp_dir.path()), None);
+    }
+
+    #[test]
+    fn test_assign_workspace_parents() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/legacy\"]\n",
+        )
+        .unwrap();
+        let web = root.join("web");
+        fs::create_dir(&web).unwrap();
+        fs::write(
+            web.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/*'\n",
+        )
+        .unwrap();
+
+        let mut projects: Vec<Project> = [
+            (root.clone(), None),
+            (root.join("crates/core"), None),
+            (root.join("crates/core/fixtures/sample"), None),
+            (root.join("crates/legacy"), None),
+            (web.clone(), None),
+            (web.join("packages/ui"), None),
+            (root.join("scripts"), Some(root.clone())),
+        ]
+        .into_iter()
+        .map(|(path, parent)| Project {
+            path,
+            parent,
+            ..Default::default()
+        })
+        .collect();
+        assign_workspace_parents(&mut projects);
+
+        let parents: Vec<_> = projects.iter().map(|p| p.parent.clone()).collect();
+        assert_eq!(
+            parents,
+            vec![
+                None,
+                Some(root.clone()),
+                None, // `*` does not cross directories
+                None, // excluded
+                None,
+                Some(web.clone()),
+                None, // stale parents are cleared
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_and_collapse_workspace_members() {
+        let root = PathBuf::from("/work/mono");
+        let nested = root.join("apps/web");
+        let mut projects: Vec<Project> = [
+            (root.clone(), None),
+            (PathBuf::from("/work/mono-docs"), None),
+            (root.join("apps/api"), Some(root.clone())),
+            (nested.clone(), Some(root.clone())),
+            (nested.join("packages/ui"), Some(nested.clone())),
+            (
+                PathBuf::from("/work/other/member"),
+                Some("/work/other".into()),
+            ),
+        ]
+        .into_iter()
+        .map(|(path, parent)| Project {
+            path,
+            parent,
+            ..Default::default()
+        })
+        .collect();
+
+        group_workspace_members(&mut projects);
+        let order: Vec<_> = projects.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(
+            order,
+            vec![
+                root.clone(),
+                root.join("apps/api"),
+                nested.clone(),
+                nested.join("packages/ui"),
+                PathBuf::from("/work/mono-docs"),
+                PathBuf::from("/work/other/member"),
+            ]
+        );
+        assert_eq!(workspace_depths(&projects), vec![0, 1, 1, 2, 0, 0]);
+
+        let counts = collapse_workspace_members(&mut projects);
+        assert_eq!(projects.len(), 3);
+        assert_eq!(counts.get(&root), Some(&3));
+        assert_eq!(counts.len(), 1);
+    }
+}